@@ -0,0 +1,35 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_c_header();
+}
+
+// Keeps include/coast_to_coast.h in sync with src/capi.rs when building with `--features capi`.
+#[cfg(feature = "capi")]
+fn generate_c_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is set by cargo");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("cbindgen.toml is valid");
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("C header generation failed")
+        .write(&mut header);
+    // Every excluded item leaves an empty line behind; runs of them become one.
+    let mut trimmed = String::new();
+    for line in String::from_utf8(header).expect("cbindgen writes UTF-8").lines() {
+        if line.is_empty() && trimmed.ends_with("\n\n") {
+            continue;
+        }
+        trimmed.push_str(line);
+        trimmed.push('\n');
+    }
+    // Written only when it changes, so the header's timestamp doesn't trigger rebuilds.
+    let path = format!("{}/include/coast_to_coast.h", crate_dir);
+    if std::fs::read_to_string(&path).ok().as_deref() != Some(trimmed.as_str()) {
+        std::fs::write(&path, trimmed).expect("include/coast_to_coast.h is writable");
+    }
+}
@@ -0,0 +1,28 @@
+// Arbitrary text fed to the record parser, as from a pasted or downloaded move list. Whatever
+// parses must verify or be refused without panicking, and whatever replays must write a record
+// that reads back to the same game.
+//
+//   cargo fuzz run records
+#![no_main]
+
+use coast_to_coast::record::{verify_record, GameRecord};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(record) = GameRecord::parse(text) else {
+        return;
+    };
+    let verified = verify_record(&record);
+    let Ok(game) = record.replay() else {
+        assert!(verified.is_err());
+        return;
+    };
+
+    let written = GameRecord::from_game(&game, &record.metadata).to_text();
+    let reread = GameRecord::parse(&written).expect("a written record parses").replay().expect("and replays");
+    assert_eq!(reread.board.cells, game.board.cells);
+    assert_eq!(reread.history, game.history);
+});
@@ -0,0 +1,136 @@
+#ifndef COAST_TO_COAST_H
+#define COAST_TO_COAST_H
+
+/* Generated by cbindgen from src/capi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Colors as returned by [`hex_winner`] and [`hex_current_player`].
+ */
+#define HEX_NONE 0
+
+#define HEX_RED 1
+
+#define HEX_BLUE 2
+
+/**
+ * Returned by [`hex_cell`] for a permanently blocked cell.
+ */
+#define HEX_BLOCKED 3
+
+/**
+ * Result codes returned by the mutating functions.
+ */
+typedef enum HexStatus {
+  HexStatus_Ok = 0,
+  /**
+   * [`hex_genmove`] answered a pending pie-rule decision by swapping.
+   */
+  HexStatus_Swapped = 1,
+  /**
+   * [`hex_genmove`] answered a pending pie-rule decision by declining the swap.
+   */
+  HexStatus_Declined = 2,
+  HexStatus_NullGame = -1,
+  HexStatus_OutOfBounds = -2,
+  HexStatus_Occupied = -3,
+  /**
+   * The game is over or adjourned, or a pie-rule decision is pending (or not pending, for
+   * [`hex_decide_pie_rule`]).
+   */
+  HexStatus_WrongState = -4,
+  HexStatus_NoMove = -5,
+} HexStatus;
+
+/**
+ * Opaque game handle.
+ */
+typedef struct HexGame HexGame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a game on a `size`×`size` board. Returns null if `size` is not positive or
+ * above `MAX_BOARD_SIZE` (64).
+ */
+struct HexGame *hex_new_game(int32_t size);
+
+/**
+ * # Safety
+ * `game` must be null or a handle from [`hex_new_game`] that was not freed yet.
+ */
+void hex_free_game(struct HexGame *game);
+
+/**
+ * Places a stone for the side to move at axial `(q, r)`.
+ *
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+enum HexStatus hex_play(struct HexGame *game, int32_t q, int32_t r);
+
+/**
+ * Answers the pie-rule question after the first move.
+ *
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+enum HexStatus hex_decide_pie_rule(struct HexGame *game, bool swap);
+
+/**
+ * Lets the engine pick and play a move for the side to move, writing it to `q`/`r`
+ * (either may be null). If a pie-rule decision is pending, the engine makes it instead and
+ * returns [`HexStatus::Swapped`], reporting the cell Blue's stone now sits on, or
+ * [`HexStatus::Declined`], leaving `q`/`r` untouched.
+ *
+ * # Safety
+ * `game` must be null or a live handle; `q` and `r` must be null or valid for writes.
+ */
+enum HexStatus hex_genmove(struct HexGame *game, int32_t *q, int32_t *r);
+
+/**
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+bool hex_game_over(const struct HexGame *game);
+
+/**
+ * Returns [`HEX_RED`], [`HEX_BLUE`], or [`HEX_NONE`] while the game is running.
+ *
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+int32_t hex_winner(const struct HexGame *game);
+
+/**
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+int32_t hex_current_player(const struct HexGame *game);
+
+/**
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+bool hex_waiting_for_pie_rule(const struct HexGame *game);
+
+/**
+ * Returns [`HEX_RED`], [`HEX_BLUE`], [`HEX_BLOCKED`] or [`HEX_NONE`] for the cell, or -1 if
+ * out of bounds.
+ *
+ * # Safety
+ * `game` must be null or a live handle from [`hex_new_game`].
+ */
+int32_t hex_cell(const struct HexGame *game, int32_t q, int32_t r);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COAST_TO_COAST_H */
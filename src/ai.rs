@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::board::{CellState, Hex};
+use crate::eval;
+use crate::game::Game;
+
+/// A player that picks moves without any UI, used by simulations and computer opponents.
+pub trait AiPlayer {
+    /// Picks a cell to play for `game.current_player`, or `None` if it cannot find one.
+    fn choose_move(&mut self, game: &Game) -> Option<Hex>;
+
+    /// Called when the game waits for the second player's pie-rule choice.
+    /// Returns `true` to swap. Defaults to never swapping.
+    fn decide_pie_rule(&mut self, _game: &Game) -> bool {
+        false
+    }
+
+    /// How the last decision's search went, for engines that search. Defaults to `None`.
+    fn telemetry(&self) -> Option<SearchTelemetry> {
+        None
+    }
+}
+
+/// What a searching engine reports about one decision.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchTelemetry {
+    /// Positions searched: playouts for MCTS, nodes for alpha-beta.
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// Deepest ply reached in the tree, or the last finished iteration's depth.
+    pub depth: u32,
+    /// Whether the search started from work done before the move, such as a kept tree.
+    pub ponder_hit: bool,
+    /// Share of transposition table probes that found an entry, for engines with a table.
+    pub table_hit_rate: Option<f32>,
+}
+
+impl SearchTelemetry {
+    pub fn nodes_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.nodes as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A move that can't wait: a cell that wins on the spot for the player to move, or else one
+/// that takes the cell the opponent would win on. `None` when neither side is a stone from
+/// winning. Several opponent threats mean the game is lost anyway; the first is returned.
+pub fn urgent_move(game: &Game) -> Option<Hex> {
+    let player = game.current_player;
+    let opponent = match player {
+        CellState::Red => CellState::Blue,
+        _ => CellState::Red,
+    };
+    let moves: Vec<Hex> = game.legal_moves().collect();
+    moves
+        .iter()
+        .find(|hex| game.would_win(**hex, player))
+        .or_else(|| moves.iter().find(|hex| game.would_win(**hex, opponent)))
+        .copied()
+}
+
+/// Plays uniformly random empty cells and swaps with a fixed probability.
+pub struct RandomPlayer {
+    rng: StdRng,
+    swap_probability: f64,
+}
+
+impl RandomPlayer {
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    /// Same seed, same game: useful for reproducible experiments.
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(rng: StdRng) -> Self {
+        Self { rng, swap_probability: 0.5 }
+    }
+
+    pub fn with_swap_probability(mut self, swap_probability: f64) -> Self {
+        self.swap_probability = swap_probability.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Default for RandomPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AiPlayer for RandomPlayer {
+    fn choose_move(&mut self, game: &Game) -> Option<Hex> {
+        let moves: Vec<Hex> = game.legal_moves().collect();
+        moves.choose(&mut self.rng).copied()
+    }
+
+    fn decide_pie_rule(&mut self, _game: &Game) -> bool {
+        self.rng.gen_bool(self.swap_probability)
+    }
+}
+
+/// Plays the move [`eval::rank_moves`] rates best, a one-ply search over the connection
+/// distances. Stronger than [`RandomPlayer`] but easy to beat.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyPlayer;
+
+impl AiPlayer for GreedyPlayer {
+    fn choose_move(&mut self, game: &Game) -> Option<Hex> {
+        urgent_move(game).or_else(|| eval::rank_moves(game).first().map(|(hex, _)| *hex))
+    }
+
+    /// Swaps when the opening stone leaves Red ahead.
+    fn decide_pie_rule(&mut self, game: &Game) -> bool {
+        eval::evaluate(game) > 0.5
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_player_picks_empty_cell() {
+        let mut game = Game::with_size(2);
+        game.board.set_cell(Hex { q: 0, r: 0 }, CellState::Red);
+        game.board.set_cell(Hex { q: 1, r: 0 }, CellState::Blue);
+        game.board.set_cell(Hex { q: 0, r: 1 }, CellState::Red);
+
+        let mut player = RandomPlayer::seeded(7);
+        assert_eq!(player.choose_move(&game), Some(Hex { q: 1, r: 1 }));
+    }
+
+    #[test]
+    fn test_random_player_full_board() {
+        let mut game = Game::with_size(1);
+        game.board.set_cell(Hex { q: 0, r: 0 }, CellState::Red);
+
+        let mut player = RandomPlayer::seeded(7);
+        assert_eq!(player.choose_move(&game), None);
+    }
+
+    #[test]
+    fn test_seeded_players_are_deterministic() {
+        let game = Game::with_size(5);
+        let mut a = RandomPlayer::seeded(42);
+        let mut b = RandomPlayer::seeded(42);
+        for _ in 0..10 {
+            assert_eq!(a.choose_move(&game), b.choose_move(&game));
+        }
+    }
+
+    #[test]
+    fn test_swap_probability_extremes() {
+        let game = Game::new();
+        let mut always = RandomPlayer::seeded(1).with_swap_probability(1.0);
+        let mut never = RandomPlayer::seeded(1).with_swap_probability(0.0);
+        assert!(always.decide_pie_rule(&game));
+        assert!(!never.decide_pie_rule(&game));
+    }
+
+    #[test]
+    fn test_urgent_moves() {
+        // Red a1 and b1 with c1 to complete on a 3x3 board.
+        let mut game = Game::with_size(3).with_pie_rule(false);
+        for mv in ["a1", "a3", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let c1 = Hex { q: 2, r: 0 };
+        let before = game.board.clone();
+        assert!(game.would_win(c1, CellState::Red));
+        assert!(!game.would_win(c1, CellState::Blue));
+        assert!(!game.would_win(Hex { q: 2, r: 2 }, CellState::Red));
+        assert!(game.board.shares_cells(&before));
+
+        // Blue has to block; Red, to move after another Blue stone, takes the win.
+        assert_eq!(urgent_move(&game), Some(c1));
+        assert_eq!(GreedyPlayer.choose_move(&game), Some(c1));
+        game.play_move("c3".parse().unwrap()).unwrap();
+        assert_eq!(urgent_move(&game), Some(c1));
+        assert_eq!(urgent_move(&Game::with_size(3)), None);
+    }
+
+    #[test]
+    fn test_greedy_player_takes_and_swaps_the_center() {
+        let mut game = Game::with_size(5);
+        let mut player = GreedyPlayer;
+        let center = Hex { q: 2, r: 2 };
+        assert_eq!(player.choose_move(&game), Some(center));
+        game.handle_click(center);
+        assert!(player.decide_pie_rule(&game));
+    }
+}
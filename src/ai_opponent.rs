@@ -0,0 +1,334 @@
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use coast_to_coast::ai::{AiPlayer, GreedyPlayer, RandomPlayer, SearchTelemetry};
+use coast_to_coast::alphabeta::AlphaBetaPlayer;
+use coast_to_coast::board::Hex;
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::presets::Opponent;
+use coast_to_coast::mcts::{BridgePlayout, MctsPlayer, PatternPlayout, PlayoutPolicy, SearchSnapshot, UniformPlayout};
+use coast_to_coast::transposition::{Replacement, TranspositionTable};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, Language};
+use crate::motion;
+
+// How computer opponents behave in the UI.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AiConfig {
+    // Replies never come sooner than this after the user's move, so instant answers don't jar.
+    pub min_response_delay_secs: f32,
+    pub playout: PlayoutChoice,
+    // Memory for the transposition table the search engines share.
+    pub table_memory_mb: usize,
+    pub table_replacement: ReplacementChoice,
+    // Whether the hard engine carries its search tree over to its next move, and how big
+    // the tree may grow.
+    pub reuse_tree: bool,
+    pub tree_memory_mb: usize,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            min_response_delay_secs: 0.6,
+            playout: PlayoutChoice::Bridge,
+            table_memory_mb: 32,
+            table_replacement: ReplacementChoice::DepthPreferred,
+            reuse_tree: true,
+            tree_memory_mb: 128,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplacementChoice {
+    Always,
+    DepthPreferred,
+}
+
+impl ReplacementChoice {
+    pub const ALL: [ReplacementChoice; 2] = [ReplacementChoice::Always, ReplacementChoice::DepthPreferred];
+
+    fn policy(self) -> Replacement {
+        match self {
+            ReplacementChoice::Always => Replacement::Always,
+            ReplacementChoice::DepthPreferred => Replacement::DepthPreferred,
+        }
+    }
+
+    pub fn name(self, language: Language) -> &'static str {
+        match self {
+            ReplacementChoice::Always => tr(language, "table.always"),
+            ReplacementChoice::DepthPreferred => tr(language, "table.depth_preferred"),
+        }
+    }
+}
+
+// What every engine the app creates shares: the transposition table, and the channel feeding
+// the search inspector.
+#[derive(Clone)]
+pub struct EngineShared {
+    pub table: Arc<TranspositionTable>,
+    pub snapshots: SyncSender<SearchSnapshot>,
+}
+
+impl EngineShared {
+    pub fn new(config: &AiConfig, snapshots: SyncSender<SearchSnapshot>) -> Self {
+        Self { table: table_for(config), snapshots }
+    }
+
+    // Swaps in a fresh table once its settings change. Engines mid-search finish with the old one.
+    pub fn sync(&mut self, config: &AiConfig) {
+        let (memory_bytes, replacement) = (config.table_memory_mb << 20, config.table_replacement.policy());
+        if self.table.memory_bytes() != memory_bytes || self.table.replacement() != replacement {
+            self.table = table_for(config);
+        }
+    }
+}
+
+fn table_for(config: &AiConfig) -> Arc<TranspositionTable> {
+    Arc::new(TranspositionTable::with_memory(config.table_memory_mb << 20, config.table_replacement.policy()))
+}
+
+// How the hard engine's random playouts pick their moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayoutChoice {
+    Uniform,
+    Pattern,
+    Bridge,
+}
+
+impl PlayoutChoice {
+    pub const ALL: [PlayoutChoice; 3] = [PlayoutChoice::Uniform, PlayoutChoice::Pattern, PlayoutChoice::Bridge];
+
+    fn policy(self) -> Box<dyn PlayoutPolicy> {
+        match self {
+            PlayoutChoice::Uniform => Box::new(UniformPlayout),
+            PlayoutChoice::Pattern => Box::new(PatternPlayout),
+            PlayoutChoice::Bridge => Box::new(BridgePlayout),
+        }
+    }
+
+    pub fn name(self, language: Language) -> &'static str {
+        match self {
+            PlayoutChoice::Uniform => tr(language, "playout.uniform"),
+            PlayoutChoice::Pattern => tr(language, "playout.pattern"),
+            PlayoutChoice::Bridge => tr(language, "playout.bridge"),
+        }
+    }
+}
+
+// Playouts per decision for the hard engine; under a second on a standard board.
+const HARD_ITERATIONS: usize = 10_000;
+// The classic engine deepens until this runs out, or it solves the position.
+const CLASSIC_TIME_LIMIT: Duration = Duration::from_secs(1);
+const CLASSIC_MAX_DEPTH: u32 = 64;
+
+// A computer reply waiting for the minimum response delay to pass.
+#[derive(Clone, Copy, Debug)]
+pub struct PendingReply {
+    due: Instant,
+}
+
+impl PendingReply {
+    pub fn new(config: &AiConfig, now: Instant) -> Self {
+        Self::after(config.min_response_delay_secs, now)
+    }
+
+    pub fn after(delay_secs: f32, now: Instant) -> Self {
+        Self { due: now + Duration::from_secs_f32(delay_secs.max(0.0)) }
+    }
+
+    // Time left before the reply may be played, or None once it is due.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        Some(self.due.saturating_duration_since(now)).filter(|remaining| !remaining.is_zero())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    // Alpha-beta instead of MCTS: deterministic, and strongest on small boards.
+    Classic,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Classic];
+
+    fn engine(self, ai: &AiConfig, shared: &EngineShared) -> Engine {
+        match self {
+            Difficulty::Easy => Box::new(RandomPlayer::new()),
+            Difficulty::Medium => Box::new(GreedyPlayer),
+            Difficulty::Hard => Box::new(
+                MctsPlayer::new(HARD_ITERATIONS, ai.playout.policy())
+                    .with_snapshots(shared.snapshots.clone())
+                    .with_memory_limit(ai.tree_memory_mb << 20)
+                    .with_tree_reuse(ai.reuse_tree),
+            ),
+            Difficulty::Classic => Box::new(
+                AlphaBetaPlayer::new(CLASSIC_MAX_DEPTH)
+                    .with_table(shared.table.clone())
+                    .with_time_limit(CLASSIC_TIME_LIMIT),
+            ),
+        }
+    }
+
+    pub fn name(self, language: Language) -> &'static str {
+        match self {
+            Difficulty::Easy => tr(language, "ai.easy"),
+            Difficulty::Medium => tr(language, "ai.medium"),
+            Difficulty::Hard => tr(language, "ai.hard"),
+            Difficulty::Classic => tr(language, "ai.classic"),
+        }
+    }
+}
+
+impl From<Opponent> for Difficulty {
+    fn from(opponent: Opponent) -> Self {
+        match opponent {
+            Opponent::Easy => Difficulty::Easy,
+            Opponent::Medium => Difficulty::Medium,
+            Opponent::Hard => Difficulty::Hard,
+            Opponent::Classic => Difficulty::Classic,
+        }
+    }
+}
+
+type Engine = Box<dyn AiPlayer + Send>;
+// What the thinking thread hands back: the engine itself, its decision and how the search went.
+type Answer = (Engine, Decision, Option<SearchTelemetry>);
+
+pub enum Decision {
+    Move(Option<Hex>),
+    PieRule(bool),
+}
+
+// A computer player that thinks on its own thread, so the UI keeps running meanwhile. Each
+// decision is handed back no sooner than the reply it was asked with allows.
+pub struct BackgroundEngine {
+    difficulty: Difficulty,
+    ai: AiConfig,
+    shared: EngineShared,
+    // None while the engine is away thinking.
+    engine: Option<Engine>,
+    thinking: Option<(Receiver<Answer>, PendingReply)>,
+    telemetry: Option<SearchTelemetry>,
+}
+
+impl BackgroundEngine {
+    pub fn new(difficulty: Difficulty, ai: &AiConfig, shared: &EngineShared) -> Self {
+        Self {
+            difficulty,
+            ai: ai.clone(),
+            shared: shared.clone(),
+            engine: Some(difficulty.engine(ai, shared)),
+            thinking: None,
+            telemetry: None,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // What the search behind the last decision reported, for engines that search.
+    pub fn telemetry(&self) -> Option<SearchTelemetry> {
+        self.telemetry
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.thinking.is_some()
+    }
+
+    // Starts deciding for the player to move in `game`, unless already thinking.
+    pub fn think(&mut self, ctx: &egui::Context, game: &Game, reply: PendingReply) {
+        let Some(mut engine) = self.engine.take() else {
+            return;
+        };
+        let game = game.clone();
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let decision = match game.state {
+                GameState::WaitingForPieRuleChoice => Decision::PieRule(engine.decide_pie_rule(&game)),
+                _ => Decision::Move(engine.choose_move(&game)),
+            };
+            let telemetry = engine.telemetry();
+            // The receiver is gone if the decision was cancelled meanwhile.
+            let _ = sender.send((engine, decision, telemetry));
+            ctx.request_repaint();
+        });
+        self.thinking = Some((receiver, reply));
+    }
+
+    // The decision, once it is ready and its reply is due.
+    pub fn poll(&mut self, ctx: &egui::Context, now: Instant) -> Option<Decision> {
+        let (receiver, reply) = self.thinking.as_ref()?;
+        if let Some(remaining) = reply.remaining(now) {
+            ctx.request_repaint_after(remaining);
+            return None;
+        }
+        match receiver.try_recv() {
+            Ok((engine, decision, telemetry)) => {
+                self.engine = Some(engine);
+                self.telemetry = telemetry;
+                self.thinking = None;
+                Some(decision)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                tracing::warn!(difficulty = ?self.difficulty, "engine thread died");
+                self.cancel();
+                None
+            }
+        }
+    }
+
+    // Forgets the decision in progress; a fresh engine takes over from the one still thinking.
+    pub fn cancel(&mut self) {
+        if self.thinking.take().is_some() {
+            self.engine = Some(self.difficulty.engine(&self.ai, &self.shared));
+        }
+    }
+}
+
+// A spinner (see `motion::busy`) and "Thinking…" over the middle of `rect` while the engine is searching.
+pub fn thinking_overlay(ctx: &egui::Context, rect: egui::Rect, language: Language) {
+    egui::Area::new(egui::Id::new("ai_thinking"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(rect.center())
+        .pivot(egui::Align2::CENTER_CENTER)
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    motion::busy(ui);
+                    ui.label(tr(language, "ai.thinking"));
+                });
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_reply_waits_for_delay() {
+        let start = Instant::now();
+        let reply = PendingReply::new(&AiConfig { min_response_delay_secs: 1.5, ..AiConfig::default() }, start);
+        assert_eq!(reply.remaining(start), Some(Duration::from_millis(1_500)));
+        assert_eq!(reply.remaining(start + Duration::from_secs(1)), Some(Duration::from_millis(500)));
+        assert_eq!(reply.remaining(start + Duration::from_secs(2)), None);
+
+        let instant = PendingReply::new(&AiConfig { min_response_delay_secs: 0.0, ..AiConfig::default() }, start);
+        assert_eq!(instant.remaining(start), None);
+    }
+}
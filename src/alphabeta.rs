@@ -0,0 +1,335 @@
+//! A classical alpha-beta player: iterative deepening over the connection-distance evaluation.
+//!
+//! Each iteration searches one ply deeper than the last, ordering moves by the previous
+//! iteration's best move from the [`TranspositionTable`], then killer moves, then the history
+//! heuristic. There is no randomness, so a fresh player always answers a position the same
+//! way, which makes it handy for regression tests. It is strong on small boards and shallow
+//! on large ones. Turns are assumed to be one stone each. The root's moves leave out the
+//! [`inferior`] cells.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::ai::{AiPlayer, SearchTelemetry};
+use crate::board::{Board, CellState, Hex};
+use crate::eval;
+use crate::game::{Game, GameState};
+use crate::inferior;
+use crate::transposition::{zobrist, zobrist_key, zobrist_side_key, Bound, Replacement, TableEntry, TranspositionTable};
+
+/// A won position scores this, less one for every ply it takes, so quicker wins rank higher.
+pub const WIN_SCORE: f32 = 100.0;
+
+const OWN_TABLE_BYTES: usize = 4 << 20;
+
+pub struct AlphaBetaPlayer {
+    max_depth: u32,
+    time_limit: Option<Duration>,
+    table: Arc<TranspositionTable>,
+    // Two moves per ply that last caused a cutoff there.
+    killers: Vec<[Option<Hex>; 2]>,
+    // Cutoffs caused by each cell, weighted by depth.
+    history: HashMap<Hex, u32>,
+    deadline: Option<Instant>,
+    aborted: bool,
+    // Counters for the search in progress.
+    nodes: u64,
+    probes: u64,
+    hits: u64,
+    telemetry: Option<SearchTelemetry>,
+}
+
+impl AlphaBetaPlayer {
+    /// Searches up to `max_depth` plies with a table of its own.
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            max_depth: max_depth.max(1),
+            time_limit: None,
+            table: Arc::new(TranspositionTable::with_memory(OWN_TABLE_BYTES, Replacement::DepthPreferred)),
+            killers: Vec::new(),
+            history: HashMap::new(),
+            deadline: None,
+            aborted: false,
+            nodes: 0,
+            probes: 0,
+            hits: 0,
+            telemetry: None,
+        }
+    }
+
+    /// Shares `table` with other engines. Answers then depend on what the table holds.
+    pub fn with_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Stops deepening once `limit` has passed and plays the last finished iteration's move.
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// The best move for the player to move and its score from their side: `WIN_SCORE`
+    /// minus plies for a forced win, the negative for a forced loss, otherwise between -1
+    /// and 1. None without legal moves.
+    pub fn search(&mut self, game: &Game) -> Option<(Hex, f32)> {
+        let player = game.current_player;
+        let mut board = game.board.clone();
+        let hash = zobrist(&board, player);
+        self.table.new_search();
+        self.killers.clear();
+        self.history.clear();
+        let started = Instant::now();
+        self.deadline = self.time_limit.map(|limit| started + limit);
+        self.aborted = false;
+        (self.nodes, self.probes, self.hits) = (0, 0, 0);
+
+        let candidates: HashSet<Hex> = inferior::candidate_moves(&board, player).into_iter().collect();
+
+        let mut best = None;
+        let mut finished_depth = 0;
+        for depth in 1..=self.max_depth {
+            let Some(result) = self.search_root(&mut board, hash, player, depth, &candidates) else {
+                break;
+            };
+            tracing::trace!(depth, best = ?result, "alpha-beta iteration finished");
+            best = Some(result);
+            finished_depth = depth;
+            if result.1.abs() > WIN_SCORE / 2.0 {
+                break;
+            }
+        }
+        self.telemetry = Some(SearchTelemetry {
+            nodes: self.nodes,
+            elapsed: started.elapsed(),
+            depth: finished_depth,
+            ponder_hit: false,
+            table_hit_rate: Some(self.hits as f32 / self.probes.max(1) as f32),
+        });
+        best
+    }
+
+    // One iteration over the `candidates` at the root and every move below it; None if it ran out
+    // of time or there is nothing to play.
+    fn search_root(
+        &mut self,
+        board: &mut Board,
+        hash: u64,
+        player: CellState,
+        depth: u32,
+        candidates: &HashSet<Hex>,
+    ) -> Option<(Hex, f32)> {
+        let tt_move = self.probe(hash).and_then(|entry| entry.best);
+        let mut alpha = -f32::INFINITY;
+        let mut best = None;
+        let moves = self.ordered_moves(board, tt_move, 0);
+        for hex in moves.into_iter().filter(|hex| candidates.contains(hex)) {
+            let score = self.score_move(board, hash, player, hex, depth, 0, -f32::INFINITY, -alpha);
+            if self.aborted {
+                return None;
+            }
+            if score > alpha {
+                alpha = score;
+                best = Some((hex, score));
+            }
+        }
+        let (hex, score) = best?;
+        self.table.store(hash, TableEntry { depth, score, bound: Bound::Exact, best: Some(hex) });
+        best
+    }
+
+    // Plays `hex` for `player`, scores the result from `player`'s side and takes it back.
+    #[allow(clippy::too_many_arguments)]
+    fn score_move(
+        &mut self,
+        board: &mut Board,
+        hash: u64,
+        player: CellState,
+        hex: Hex,
+        depth: u32,
+        ply: usize,
+        alpha: f32,
+        beta: f32,
+    ) -> f32 {
+        board.set_cell(hex, player);
+        let score = if board.connects(player, &board.shape().goal_sides(player)) {
+            WIN_SCORE - ply as f32
+        } else {
+            let next = hash ^ zobrist_key(hex, player) ^ zobrist_side_key();
+            -self.negamax(board, next, opponent(player), depth - 1, ply + 1, alpha, beta)
+        };
+        board.set_cell(hex, CellState::Empty);
+        score
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        board: &mut Board,
+        hash: u64,
+        player: CellState,
+        depth: u32,
+        ply: usize,
+        mut alpha: f32,
+        mut beta: f32,
+    ) -> f32 {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.aborted = true;
+            return 0.0;
+        }
+        self.nodes += 1;
+        let stored = self.probe(hash);
+        if let Some(entry) = stored.filter(|entry| entry.depth >= depth) {
+            match entry.bound {
+                Bound::Exact => return entry.score,
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+        if depth == 0 {
+            let red = eval::evaluate_board(board, player) * 2.0 - 1.0;
+            return if player == CellState::Red { red } else { -red };
+        }
+
+        let original_alpha = alpha;
+        let mut best = (-f32::INFINITY, None);
+        for hex in self.ordered_moves(board, stored.and_then(|entry| entry.best), ply) {
+            let score = self.score_move(board, hash, player, hex, depth, ply, -beta, -alpha);
+            if self.aborted {
+                return 0.0;
+            }
+            if score > best.0 {
+                best = (score, Some(hex));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                self.record_cutoff(hex, depth, ply);
+                break;
+            }
+        }
+        let (score, hex) = best;
+        if hex.is_none() {
+            // A full board always has a winner, found when its last stone was played.
+            return 0.0;
+        }
+        let bound = if score <= original_alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(hash, TableEntry { depth, score, bound, best: hex });
+        score
+    }
+
+    fn probe(&mut self, hash: u64) -> Option<TableEntry> {
+        let entry = self.table.probe(hash);
+        self.probes += 1;
+        self.hits += u64::from(entry.is_some());
+        entry
+    }
+
+    fn record_cutoff(&mut self, hex: Hex, depth: u32, ply: usize) {
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; 2]);
+        }
+        let killers = &mut self.killers[ply];
+        if killers[0] != Some(hex) {
+            killers[1] = killers[0];
+            killers[0] = Some(hex);
+        }
+        *self.history.entry(hex).or_default() += depth * depth;
+    }
+
+    // Table move, then killers, then history, then cells nearer the center.
+    fn ordered_moves(&self, board: &Board, tt_move: Option<Hex>, ply: usize) -> Vec<Hex> {
+        let killers = self.killers.get(ply).copied().unwrap_or_default();
+        let twice_center = board.size - 1;
+        let mut moves = board.empty_cells();
+        moves.sort_by_key(|hex| {
+            let rank = if Some(*hex) == tt_move {
+                0
+            } else if killers.contains(&Some(*hex)) {
+                1
+            } else {
+                2
+            };
+            let (dq, dr) = (2 * hex.q - twice_center, 2 * hex.r - twice_center);
+            let off_center = dq.abs() + dr.abs() + (dq + dr).abs();
+            (rank, std::cmp::Reverse(self.history.get(hex).copied().unwrap_or(0)), off_center)
+        });
+        moves
+    }
+}
+
+fn opponent(player: CellState) -> CellState {
+    match player {
+        CellState::Red => CellState::Blue,
+        _ => CellState::Red,
+    }
+}
+
+impl AiPlayer for AlphaBetaPlayer {
+    fn choose_move(&mut self, game: &Game) -> Option<Hex> {
+        if game.state != GameState::InProgress {
+            return None;
+        }
+        self.search(game).map(|(hex, _)| hex)
+    }
+
+    /// Swaps when even the best reply to the opening stone scores below even.
+    fn decide_pie_rule(&mut self, game: &Game) -> bool {
+        self.search(game).is_some_and(|(_, score)| score < 0.0)
+    }
+
+    fn telemetry(&self) -> Option<SearchTelemetry> {
+        self.telemetry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_wins_and_blocks() {
+        // Red a1, b1 with c1 to complete on a 3x3 board.
+        let mut game = Game::with_size(3);
+        for mv in ["a1", "a3", "b1", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let (hex, score) = AlphaBetaPlayer::new(4).search(&game).unwrap();
+        assert_eq!((hex, score), (Hex { q: 2, r: 0 }, WIN_SCORE));
+
+        // Blue to move must take c1 or lose at once.
+        let mut blocking = Game::with_size(3);
+        for mv in ["a1", "a3", "b1"] {
+            blocking.play_move(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(AlphaBetaPlayer::new(2).choose_move(&blocking), Some(Hex { q: 2, r: 0 }));
+    }
+
+    #[test]
+    fn test_deterministic_and_solves_small_boards() {
+        // The first player wins Hex; on 3x3 the center wins by force.
+        let game = Game::with_size(3);
+        let first = AlphaBetaPlayer::new(9).search(&game).unwrap();
+        assert_eq!(first.0, Hex { q: 1, r: 1 });
+        assert!(first.1 > WIN_SCORE / 2.0, "{:?}", first);
+        let mut again = AlphaBetaPlayer::new(9);
+        assert_eq!(again.search(&game), Some(first));
+        let telemetry = again.telemetry().unwrap();
+        assert!(telemetry.nodes > 0 && telemetry.depth > 0);
+        assert!(telemetry.table_hit_rate.is_some_and(|rate| rate > 0.0));
+
+        let shared = Arc::new(TranspositionTable::with_memory(1 << 20, Replacement::DepthPreferred));
+        let mut player = AlphaBetaPlayer::new(9).with_table(shared.clone());
+        assert_eq!(player.search(&game), Some(first));
+        assert!(shared.stats().filled > 0);
+    }
+}
@@ -0,0 +1,284 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::game::{Game, GameState};
+use eframe::egui;
+use ureq::serde_json::{json, Value};
+
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+// Longest body accepted, plenty for a move.
+const MAX_BODY: usize = 4096;
+// How long a client waits for the app to answer, e.g. while a modal dialog is up.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The address to serve on from the command line: `--serve-api` for the default, or
+// `--serve-api=HOST:PORT`. None without the flag.
+pub fn address_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter().find_map(|arg| match arg.strip_prefix("--serve-api")? {
+        "" => Some(DEFAULT_ADDRESS.to_string()),
+        rest => rest.strip_prefix('=').map(str::to_string),
+    })
+}
+
+// What a client asked for:
+//
+//   GET  /position   the board, whose turn it is and the moves so far, as JSON
+//   POST /move       plays the move in the body, e.g. "c3", "swap" or "pass"
+//   POST /new-game   starts a game as configured in the settings
+//   GET  /sgf        the game as SGF
+//   POST /command    runs the command line in the body, as typed in the app after `:`
+//   GET  /overlay     a page showing the live board, for an OBS browser source
+//   GET  /overlay.svg the board as SVG, which the overlay page reloads every second
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApiRequest {
+    Position,
+    Move(String),
+    NewGame,
+    Sgf,
+    Command(String),
+    Overlay,
+}
+
+// Transparent so only the board shows over the stream. The next image is swapped in once it
+// has loaded, so the board doesn't flicker.
+const OVERLAY_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Hex board</title>
+<style>
+html, body { margin: 0; height: 100%; background: transparent; overflow: hidden; }
+img { width: 100%; height: 100%; object-fit: contain; }
+</style>
+</head>
+<body>
+<img id="board" src="/overlay.svg" alt="">
+<script>
+const board = document.getElementById("board");
+setInterval(() => {
+  const next = new Image();
+  next.onload = () => { board.src = next.src; };
+  next.src = "/overlay.svg?t=" + Date.now();
+}, 1000);
+</script>
+</body>
+</html>
+"#;
+
+pub struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    pub fn json(value: Value) -> Self {
+        Self { status: 200, content_type: "application/json", body: value.to_string() }
+    }
+
+    pub fn sgf(sgf: String) -> Self {
+        Self { status: 200, content_type: "application/x-go-sgf", body: sgf }
+    }
+
+    fn html(html: &str) -> Self {
+        Self { status: 200, content_type: "text/html; charset=utf-8", body: html.to_string() }
+    }
+
+    pub fn svg(svg: String) -> Self {
+        Self { status: 200, content_type: "image/svg+xml", body: svg }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self { status, content_type: "application/json", body: json!({ "error": message }).to_string() }
+    }
+}
+
+// A request waiting for the app, which owns the game, to answer it.
+pub struct ApiCall {
+    pub request: ApiRequest,
+    reply: Sender<Response>,
+}
+
+impl ApiCall {
+    pub fn reply(self, response: Response) {
+        let _ = self.reply.send(response);
+    }
+}
+
+// A small HTTP API onto the game on screen, for external tools, stream overlays and bridges to
+// physical boards. Connections are read on background threads; requests reach the app
+// through `poll` and are answered on the UI thread between frames.
+pub struct ApiServer {
+    pub address: SocketAddr,
+    calls: Receiver<ApiCall>,
+}
+
+impl ApiServer {
+    pub fn start(ctx: &egui::Context, address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let (sender, calls) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (sender, ctx) = (sender.clone(), ctx.clone());
+                thread::spawn(move || {
+                    if let Err(e) = serve(stream, &sender, &ctx) {
+                        tracing::debug!(error = %e, "API connection failed");
+                    }
+                });
+            }
+        });
+        tracing::info!(%address, "serving the API");
+        Ok(Self { address, calls })
+    }
+
+    pub fn poll(&self) -> Vec<ApiCall> {
+        self.calls.try_iter().collect()
+    }
+}
+
+fn serve(stream: TcpStream, calls: &Sender<ApiCall>, ctx: &egui::Context) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; length.min(MAX_BODY)];
+    reader.read_exact(&mut body)?;
+
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let response = match route(method, path, &String::from_utf8_lossy(&body)) {
+        Ok(request) => {
+            let (reply, answer) = mpsc::channel();
+            let _ = calls.send(ApiCall { request, reply });
+            ctx.request_repaint();
+            answer.recv_timeout(ANSWER_TIMEOUT).unwrap_or_else(|_| Response::error(503, "the app did not answer"))
+        }
+        Err(response) => response,
+    };
+    write_response(stream, &response)
+}
+
+// A request for the app to answer, or the response when the server can answer by itself.
+fn route(method: &str, path: &str, body: &str) -> Result<ApiRequest, Response> {
+    let path = path.split('?').next().unwrap_or(path).trim_end_matches('/');
+    match (method, path) {
+        ("GET", "/position") => Ok(ApiRequest::Position),
+        ("POST", "/move") => Ok(ApiRequest::Move(body.trim().to_string())),
+        ("POST", "/new-game") => Ok(ApiRequest::NewGame),
+        ("GET", "/sgf") => Ok(ApiRequest::Sgf),
+        ("GET", "/overlay") => Err(Response::html(OVERLAY_PAGE)),
+        ("POST", "/command") => Ok(ApiRequest::Command(body.trim().to_string())),
+        ("GET", "/overlay.svg") => Ok(ApiRequest::Overlay),
+        (_, "/position" | "/move" | "/new-game" | "/sgf" | "/command" | "/overlay" | "/overlay.svg") => {
+            Err(Response::error(405, "method not allowed"))
+        }
+        _ => Err(Response::error(404, "not found")),
+    }
+}
+
+fn write_response(mut stream: TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Service Unavailable",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+// The position as the API reports it. `board` has one string per row, top to bottom, with
+// "R", "B", "." and "#" (blocked) for each cell.
+pub fn position(game: &Game) -> Value {
+    let player = |player: CellState| match player {
+        CellState::Red => Value::from("red"),
+        CellState::Blue => Value::from("blue"),
+        CellState::Empty | CellState::Blocked => Value::Null,
+    };
+    let (state, winner) = match game.state {
+        GameState::InProgress => ("in_progress", None),
+        GameState::WaitingForPieRuleChoice => ("pie_rule", None),
+        GameState::Finished { winner } => ("finished", Some(winner)),
+        GameState::Resigned { winner } => ("resigned", Some(winner)),
+        GameState::Drawn => ("drawn", None),
+        GameState::Adjourned { .. } => ("adjourned", None),
+    };
+    let board: Vec<String> = (0..game.board.size)
+        .map(|r| {
+            (0..game.board.size)
+                .map(|q| match game.board.get_cell(&Hex { q, r }) {
+                    Some(CellState::Red) => 'R',
+                    Some(CellState::Blue) => 'B',
+                    Some(CellState::Blocked) => '#',
+                    Some(CellState::Empty) => '.',
+                    None => ' ',
+                })
+                .collect()
+        })
+        .collect();
+    json!({
+        "size": game.board.size,
+        "state": state,
+        "to_move": player(game.current_player),
+        "winner": winner.map_or(Value::Null, player),
+        "moves": game.history.iter().map(|mv| mv.to_string()).collect::<Vec<_>>(),
+        "board": board,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_and_position() {
+        let args = |args: &[&str]| address_from_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&["hex"]), None);
+        assert_eq!(args(&["hex", "--serve-api"]).as_deref(), Some(DEFAULT_ADDRESS));
+        assert_eq!(args(&["hex", "--serve-api=0.0.0.0:9000"]).as_deref(), Some("0.0.0.0:9000"));
+
+        assert_eq!(route("POST", "/move/", " c3\n").ok(), Some(ApiRequest::Move("c3".to_string())));
+        assert_eq!(route("GET", "/sgf?x=1", "").ok(), Some(ApiRequest::Sgf));
+        assert_eq!(route("GET", "/move", "").err().map(|response| response.status), Some(405));
+        assert_eq!(route("GET", "/", "").err().map(|response| response.status), Some(404));
+        let overlay = route("GET", "/overlay", "").err().unwrap();
+        assert!(overlay.content_type.starts_with("text/html") && overlay.body.contains("/overlay.svg"));
+
+        let mut game = Game::with_size(3).with_pie_rule(false);
+        game.play_move("b2".parse().unwrap()).unwrap();
+        let position = position(&game);
+        assert_eq!(position["board"], json!(["...", ".R.", "..."]));
+        assert_eq!((position["to_move"].as_str(), position["state"].as_str()), (Some("blue"), Some("in_progress")));
+        assert_eq!(position["moves"], json!(["b2"]));
+    }
+}
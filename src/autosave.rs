@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use coast_to_coast::journal::{self, Entry, Journal};
+use coast_to_coast::record::{GameRecord, GameResult};
+
+use crate::events::GameEvent;
+
+// Journals the game on screen action by action, so a crash or power loss between autosaves
+// loses nothing. A journal left behind is folded into the autosave at the next start.
+#[derive(Clone, Default)]
+pub struct AutosaveJournal {
+    data_dir: Option<PathBuf>,
+    journal: Rc<RefCell<Option<Journal>>>,
+}
+
+impl AutosaveJournal {
+    // Call before the saves are listed, so they see the game a crash interrupted.
+    pub fn open(data_dir: Option<&Path>) -> Self {
+        if let Some(data_dir) = data_dir {
+            match journal::compact(data_dir) {
+                Ok(true) => tracing::info!("recovered the game in progress from its journal"),
+                Ok(false) => {}
+                Err(e) => tracing::warn!(error = %e, "could not recover the journal"),
+            }
+        }
+        Self { data_dir: data_dir.map(Path::to_path_buf), journal: Rc::default() }
+    }
+
+    pub fn handle(&self, event: &GameEvent, record: &GameRecord) {
+        let entry = match event {
+            GameEvent::GameStarted { .. } => return self.restart(record),
+            GameEvent::MovePlayed { mv, .. } => Entry::Move(*mv, record.move_times.last().copied().flatten()),
+            GameEvent::TakenBack { moves } => Entry::TakeBack(*moves),
+            GameEvent::GameFinished { winner } => Entry::Result(GameResult::Win(*winner)),
+            GameEvent::ResultAgreed { result } => Entry::Result(*result),
+        };
+        let mut journal = self.journal.borrow_mut();
+        let Some(open) = journal.as_mut() else {
+            drop(journal);
+            // Nothing to append to, e.g. after a failed write; the record has it all.
+            return self.restart(record);
+        };
+        if let Err(e) = open.append(&entry) {
+            tracing::warn!(error = %e, "journal write failed");
+            *journal = None;
+        }
+    }
+
+    // Starts over from `record`, which compacts the journal once the autosave has it.
+    pub fn restart(&self, record: &GameRecord) {
+        let Some(data_dir) = self.data_dir.as_deref() else {
+            return;
+        };
+        let journal = Journal::start(data_dir, record);
+        if let Err(e) = &journal {
+            tracing::warn!(error = %e, "could not start the journal");
+        }
+        *self.journal.borrow_mut() = journal.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::board::CellState;
+    use coast_to_coast::game::Game;
+    use coast_to_coast::record::GameMetadata;
+    use coast_to_coast::saves;
+
+    #[test]
+    fn test_journal_follows_events() {
+        let dir = std::env::temp_dir().join(format!("hex-autosave-journal-test-{}", std::process::id()));
+        let journal = AutosaveJournal::open(Some(&dir));
+        let mut game = Game::with_size(3).with_pie_rule(false);
+        let record = |game: &Game| GameRecord::from_game(game, &GameMetadata::default());
+        journal.handle(&GameEvent::GameStarted { size: 3, moves: 0, opponent: None }, &record(&game));
+        for mv in ["a1", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+            let event = GameEvent::MovePlayed { player: CellState::Red, mv: mv.parse().unwrap(), move_number: 1 };
+            journal.handle(&event, &record(&game));
+        }
+        journal.handle(&GameEvent::TakenBack { moves: 1 }, &record(&game));
+        drop(journal);
+
+        // As if the app had been killed: the next start recovers the game.
+        AutosaveJournal::open(Some(&dir));
+        let saved = saves::list(&dir);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].record.moves, vec!["a1".parse().unwrap()]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
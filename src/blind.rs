@@ -0,0 +1,87 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use coast_to_coast::board::{Board, CellState, Hex};
+use serde::{Deserialize, Serialize};
+
+// How stones look once blind mode hides them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlindStyle {
+    // Drawn as empty cells.
+    #[default]
+    Hidden,
+    // Drawn as grey markers that show a cell is taken but not by whom.
+    Neutral,
+}
+
+// Remembers when each stone went down so it can be hidden `delay` later. Stones from a loaded
+// game have no time and are hidden straight away.
+#[derive(Default)]
+pub struct BlindTracker {
+    placed: HashMap<Hex, Instant>,
+}
+
+impl BlindTracker {
+    pub fn reset(&mut self) {
+        self.placed.clear();
+    }
+
+    pub fn placed(&mut self, hex: Hex, now: Instant) {
+        self.placed.insert(hex, now);
+    }
+
+    // Stones to hide now, and how long until the next visible stone should disappear.
+    pub fn hidden(&self, board: &Board, delay: Duration, now: Instant) -> (HashSet<Hex>, Option<Duration>) {
+        let mut hidden = HashSet::new();
+        let mut next_change: Option<Duration> = None;
+        for (hex, state) in board.cells.iter() {
+            // Holes are part of the board, not stones to remember.
+            if !matches!(state, CellState::Red | CellState::Blue) {
+                continue;
+            }
+            let shown_for = self.placed.get(hex).map(|placed| now.saturating_duration_since(*placed));
+            match shown_for {
+                Some(shown_for) if shown_for < delay => {
+                    let remaining = delay - shown_for;
+                    next_change = Some(next_change.map_or(remaining, |next| next.min(remaining)));
+                }
+                _ => {
+                    hidden.insert(*hex);
+                }
+            }
+        }
+        (hidden, next_change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stones_hide_after_delay() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let delay = Duration::from_secs(3);
+        let mut board = Board::new(3);
+        let (old, new, loaded) = (Hex { q: 0, r: 0 }, Hex { q: 1, r: 1 }, Hex { q: 2, r: 2 });
+        let mut tracker = BlindTracker::default();
+        for (hex, secs) in [(old, 0), (new, 2)] {
+            board.set_cell(hex, CellState::Red);
+            tracker.placed(hex, at(secs));
+        }
+        board.set_cell(loaded, CellState::Blue);
+
+        let (hidden, next) = tracker.hidden(&board, delay, at(1));
+        assert_eq!(hidden, HashSet::from([loaded]));
+        assert_eq!(next, Some(Duration::from_secs(2)));
+
+        let (hidden, next) = tracker.hidden(&board, delay, at(4));
+        assert_eq!(hidden, HashSet::from([loaded, old]));
+        assert_eq!(next, Some(Duration::from_secs(1)));
+
+        let (hidden, next) = tracker.hidden(&board, delay, at(5));
+        assert_eq!(hidden.len(), 3);
+        assert_eq!(next, None);
+    }
+}
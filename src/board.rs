@@ -1,10 +1,17 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use crate::shape::{BoardShape, Rhombus};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CellState {
     Empty,
     Red,
     Blue,
+    Blocked, // A permanent hole for puzzles and variants: nobody can play there or pass through
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -24,22 +31,106 @@ impl Hex {
             Hex { q: self.q + 1, r: self.r - 1 },
         ]
     }
+
+    // Letters run along Blue's edge (q), numbers along Red's edge (r), e.g. q=2, r=4 is "c5".
+    pub fn notation(&self) -> String {
+        format!("{}{}", crate::notation::column_name(self.q), self.r + 1)
+    }
 }
 
+// Cloning a board is a cheap snapshot: clones share their cells until one of them changes a
+// cell, which copies them for that board alone. Engines can keep or hand off positions freely.
+#[derive(Clone)]
 pub struct Board {
-    pub cells: HashMap<Hex, CellState>,
-    pub size: i32,
+    pub cells: Arc<HashMap<Hex, CellState>>,
+    pub size: i32, // Every cell has q and r below this; the side length for the Hex rhombus
+    shape: Arc<dyn BoardShape>,
 }
 
 impl Board {
     pub fn new(size: i32) -> Self {
-        let mut cells = HashMap::new();
-        for q in 0..size {
-            for r in 0..size {
-                cells.insert(Hex { q, r }, CellState::Empty);
+        Self::with_shape(Rhombus { size })
+    }
+
+    pub fn with_shape(shape: impl BoardShape + 'static) -> Self {
+        let cells = Arc::new(shape.cells().into_iter().map(|hex| (hex, CellState::Empty)).collect());
+        Board { cells, size: shape.extent(), shape: Arc::new(shape) }
+    }
+
+    pub fn shape(&self) -> &dyn BoardShape {
+        self.shape.as_ref()
+    }
+
+    // Whether this is the plain Hex board: every cell of a `size` by `size` rhombus, with its
+    // sides and goals. Fast paths for Hex alone check this first.
+    pub fn is_rhombus(&self) -> bool {
+        let rhombus = Rhombus { size: self.size };
+        let shape = self.shape();
+        self.cells.len() == (self.size * self.size) as usize
+            && shape.side_count() == 4
+            && [CellState::Red, CellState::Blue]
+                .iter()
+                .all(|player| shape.goal_sides(*player) == rhombus.goal_sides(*player))
+            && self.cells.keys().all(|hex| (0..4).all(|side| shape.on_side(*hex, side) == rhombus.on_side(*hex, side)))
+    }
+
+    // Whether one chain of `player`'s stones touches every side in `sides`.
+    pub fn connects(&self, player: CellState, sides: &[usize]) -> bool {
+        self.connecting_chain(player, sides).is_some()
+    }
+
+    // The stones of a chain of `player`'s that touches every side in `sides`, if there is one.
+    pub fn connecting_chain(&self, player: CellState, sides: &[usize]) -> Option<Vec<Hex>> {
+        if sides.is_empty() {
+            return None;
+        }
+        let mut visited = HashSet::new();
+        for (start, state) in self.cells.iter() {
+            if *state != player || !visited.insert(*start) {
+                continue;
+            }
+            if let Some(chain) = self.chain_from(*start, player, sides, &mut visited) {
+                return Some(chain);
             }
         }
-        Board { cells, size }
+        None
+    }
+
+    // Whether a `player` stone on the empty `hex` would join a chain touching every side in
+    // `sides`. Only walks the chain through `hex` and leaves the board as it is, so a search can
+    // ask it of every empty cell.
+    pub fn would_connect(&self, hex: Hex, player: CellState, sides: &[usize]) -> bool {
+        !sides.is_empty()
+            && self.is_valid_move(&hex)
+            && self.chain_from(hex, player, sides, &mut HashSet::from([hex])).is_some()
+    }
+
+    // Walks the chain of `player`'s stones through `start`, which is taken to be theirs, and
+    // returns it if it touches every side in `sides`. Marks what it walks in `visited`.
+    fn chain_from(&self, start: Hex, player: CellState, sides: &[usize], visited: &mut HashSet<Hex>) -> Option<Vec<Hex>> {
+        let mut touched = vec![false; sides.len()];
+        let mut chain = vec![start];
+        let mut queue = VecDeque::from([start]);
+        while let Some(hex) = queue.pop_front() {
+            for (i, side) in sides.iter().enumerate() {
+                touched[i] |= self.shape.on_side(hex, *side);
+            }
+            for neighbor in hex.get_neighbors() {
+                if self.cells.get(&neighbor) == Some(&player) && visited.insert(neighbor) {
+                    chain.push(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        touched.iter().all(|t| *t).then_some(chain)
+    }
+
+    // The player with a chain joining their goal sides, whoever is to move. Positions reached
+    // through play have at most one; one set up by hand with two reports Red.
+    pub fn winner(&self) -> Option<CellState> {
+        [CellState::Red, CellState::Blue]
+            .into_iter()
+            .find(|player| self.connects(*player, &self.shape.goal_sides(*player)))
     }
 
     pub fn get_cell(&self, hex: &Hex) -> Option<&CellState> {
@@ -47,7 +138,12 @@ impl Board {
     }
 
     pub fn set_cell(&mut self, hex: Hex, state: CellState) {
-        self.cells.insert(hex, state);
+        Arc::make_mut(&mut self.cells).insert(hex, state);
+    }
+
+    // Whether `other` is a snapshot of this board that neither has changed since.
+    pub fn shares_cells(&self, other: &Board) -> bool {
+        Arc::ptr_eq(&self.cells, &other.cells)
     }
 
     pub fn place_piece(&mut self, hex: Hex, state: CellState) -> Result<(), &str> {
@@ -70,6 +166,83 @@ impl Board {
             false
         }
     }
+
+    // Sorted row by row so callers (seeded AIs, bindings) see a stable order.
+    pub fn empty_cells(&self) -> Vec<Hex> {
+        let mut empty: Vec<Hex> = self
+            .cells
+            .iter()
+            .filter(|(_, state)| **state == CellState::Empty)
+            .map(|(hex, _)| *hex)
+            .collect();
+        empty.sort_by_key(|hex| (hex.r, hex.q));
+        empty
+    }
+
+    // Blocked cells row by row, e.g. for writing a puzzle's layout into a record.
+    pub fn blocked_cells(&self) -> Vec<Hex> {
+        let mut blocked: Vec<Hex> = self
+            .cells
+            .iter()
+            .filter(|(_, state)| **state == CellState::Blocked)
+            .map(|(hex, _)| *hex)
+            .collect();
+        blocked.sort_by_key(|hex| (hex.r, hex.q));
+        blocked
+    }
+
+    // The cells that differ in `other`, with their state there, row by row. Applying the result
+    // to this board with `apply_diff` reproduces `other`, so a position far away in a game can be
+    // reached by touching only what changed. Both boards should share a shape.
+    pub fn diff(&self, other: &Board) -> Vec<(Hex, CellState)> {
+        let mut changes: Vec<(Hex, CellState)> = other
+            .cells
+            .iter()
+            .filter(|(hex, state)| self.cells.get(hex) != Some(state))
+            .map(|(hex, state)| (*hex, *state))
+            .collect();
+        changes.sort_by_key(|(hex, _)| (hex.r, hex.q));
+        changes
+    }
+
+    pub fn apply_diff(&mut self, changes: &[(Hex, CellState)]) {
+        for (hex, state) in changes {
+            self.set_cell(*hex, *state);
+        }
+    }
+
+    // Identifies a position within one run of the program, e.g. to tell whether a cached
+    // rendering is stale. Not stable across builds, so don't persist it.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.size.hash(&mut hasher);
+        for r in 0..self.size {
+            for q in 0..self.size {
+                self.get_cell(&Hex { q, r }).hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+// Rows are indented one step per row so the rhombus reads like a Hex diagram. Coordinates
+// that are not part of the board's shape are left blank.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..self.size {
+            let row: Vec<&str> = (0..self.size)
+                .map(|q| match self.get_cell(&Hex { q, r }) {
+                    Some(CellState::Red) => "R",
+                    Some(CellState::Blue) => "B",
+                    Some(CellState::Empty) => ".",
+                    Some(CellState::Blocked) => "#",
+                    None => " ",
+                })
+                .collect();
+            writeln!(f, "{}{}", " ".repeat(r as usize), row.join(" "))?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -108,6 +281,67 @@ mod tests {
         assert_eq!(neighbors, expected_neighbors);
     }
 
+    #[test]
+    fn test_position_hash() {
+        let mut a = Board::new(3);
+        let mut b = Board::new(3);
+        assert_eq!(a.position_hash(), b.position_hash());
+        assert_ne!(a.position_hash(), Board::new(4).position_hash());
+
+        a.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        assert_ne!(a.position_hash(), b.position_hash());
+        b.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        assert_eq!(a.position_hash(), b.position_hash());
+    }
+
+    #[test]
+    fn test_diff_round_trip() {
+        let mut before = Board::new(4);
+        before.set_cell(Hex { q: 0, r: 0 }, CellState::Red);
+        before.set_cell(Hex { q: 3, r: 1 }, CellState::Blue);
+        let mut after = before.clone();
+        after.set_cell(Hex { q: 0, r: 0 }, CellState::Empty);
+        after.set_cell(Hex { q: 2, r: 2 }, CellState::Red);
+        after.set_cell(Hex { q: 1, r: 2 }, CellState::Blue);
+
+        let forward = before.diff(&after);
+        assert_eq!(
+            forward,
+            vec![
+                (Hex { q: 0, r: 0 }, CellState::Empty),
+                (Hex { q: 1, r: 2 }, CellState::Blue),
+                (Hex { q: 2, r: 2 }, CellState::Red),
+            ]
+        );
+        let mut board = before.clone();
+        board.apply_diff(&forward);
+        assert_eq!(board.cells, after.cells);
+        board.apply_diff(&after.diff(&before));
+        assert_eq!(board.cells, before.cells);
+        assert!(board.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_snapshots_copy_on_write() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        let snapshot = board.clone();
+        assert!(snapshot.shares_cells(&board));
+
+        board.set_cell(Hex { q: 0, r: 0 }, CellState::Blue);
+        assert!(!snapshot.shares_cells(&board));
+        assert_eq!(snapshot.get_cell(&Hex { q: 0, r: 0 }), Some(&CellState::Empty));
+        assert_eq!(snapshot.get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Red));
+        assert_eq!(board.get_cell(&Hex { q: 0, r: 0 }), Some(&CellState::Blue));
+    }
+
+    #[test]
+    fn test_hex_notation() {
+        assert_eq!(Hex { q: 0, r: 0 }.notation(), "a1");
+        assert_eq!(Hex { q: 2, r: 4 }.notation(), "c5");
+        assert_eq!(Hex { q: 10, r: 10 }.notation(), "k11");
+    }
+
     #[test]
     fn test_place_piece() {
         let mut board = Board::new(2);
@@ -164,4 +398,63 @@ mod tests {
         assert!(!board.cells.contains_key(&Hex { q: size, r: size -1 }));
         assert!(!board.cells.contains_key(&Hex { q: size -1, r: size }));
     }
+
+    #[test]
+    fn test_empty_cells() {
+        let mut board = Board::new(2);
+        board.set_cell(Hex { q: 1, r: 0 }, CellState::Red);
+        assert_eq!(
+            board.empty_cells(),
+            vec![Hex { q: 0, r: 0 }, Hex { q: 0, r: 1 }, Hex { q: 1, r: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_connects() {
+        // Red's sides are q = 0 and q = 2 on the rhombus.
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 0, r: 2 }, CellState::Red);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        assert!(!board.connects(CellState::Red, &[0, 1]));
+        board.set_cell(Hex { q: 2, r: 0 }, CellState::Red);
+        assert!(board.connects(CellState::Red, &[0, 1]));
+        assert!(!board.connects(CellState::Blue, &[2, 3]));
+
+        // A Y win on a triangle touches all three sides.
+        let mut board = Board::with_shape(crate::shape::Triangle { side: 3 });
+        assert_eq!(board.size, 3);
+        assert_eq!(board.to_string(), ". . .\n . .  \n  .    \n");
+        for hex in [Hex { q: 0, r: 0 }, Hex { q: 1, r: 0 }] {
+            board.set_cell(hex, CellState::Blue);
+        }
+        assert!(!board.connects(CellState::Blue, &[0, 1, 2]));
+        board.set_cell(Hex { q: 2, r: 0 }, CellState::Blue);
+        assert!(board.connects(CellState::Blue, &[0, 1, 2]));
+    }
+
+    #[test]
+    fn test_blocked_cells() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Blocked);
+        board.set_cell(Hex { q: 0, r: 1 }, CellState::Blocked);
+        assert_eq!(board.blocked_cells(), vec![Hex { q: 0, r: 1 }, Hex { q: 1, r: 1 }]);
+        assert!(!board.is_valid_move(&Hex { q: 1, r: 1 }));
+        assert!(board.place_piece(Hex { q: 1, r: 1 }, CellState::Red).is_err());
+        assert_eq!(board.empty_cells().len(), 7);
+        assert_eq!(board.to_string(), ". . .\n # # .\n  . . .\n");
+
+        // A hole cannot be part of a chain.
+        for hex in [Hex { q: 0, r: 2 }, Hex { q: 2, r: 0 }] {
+            board.set_cell(hex, CellState::Red);
+        }
+        assert!(!board.connects(CellState::Red, &[0, 1]));
+    }
+
+    #[test]
+    fn test_display_board() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 0, r: 0 }, CellState::Red);
+        board.set_cell(Hex { q: 2, r: 1 }, CellState::Blue);
+        assert_eq!(board.to_string(), "R . .\n . . B\n  . . .\n");
+    }
 }
@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+
+use coast_to_coast::board::Hex;
+use coast_to_coast::notation::Move;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+// Where a physical board is connected and how its sensors are laid out. Sensor boards number
+// their cells from whichever corner they were wired from, so the coordinates can be mirrored
+// and swapped to match the board on screen.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BridgeConfig {
+    // The serial or USB device, e.g. /dev/ttyUSB0 or COM3, set to the board's speed beforehand
+    // (e.g. with stty); USB boards that show up as a CDC device need no setup.
+    pub device: String,
+    // The sensor's x counts rows and y columns.
+    pub transpose: bool,
+    pub mirror_x: bool,
+    pub mirror_y: bool,
+}
+
+impl BridgeConfig {
+    // The cell under sensor (x, y) on a `size` board; x and y count from 0 along the columns
+    // and rows before the config's swaps.
+    pub fn map(&self, x: i32, y: i32, size: i32) -> Hex {
+        let (x, y) = if self.transpose { (y, x) } else { (x, y) };
+        let x = if self.mirror_x { size - 1 - x } else { x };
+        let y = if self.mirror_y { size - 1 - y } else { y };
+        Hex { q: x, r: y }
+    }
+}
+
+// One line from the board. The protocol is a line per stone or action:
+//
+//   3,5      a stone placed on sensor x = 3, y = 5, counting from 0
+//   c4       a move in the app's notation; "swap" and "pass" too
+//   # ...    a comment, e.g. the board's greeting, ignored along with blank lines
+#[derive(Clone, Debug, PartialEq)]
+pub enum BridgeInput {
+    Sensor(i32, i32),
+    Move(Move),
+    // A line that is neither, reported rather than guessed at.
+    Unreadable(String),
+    // The device closed or failed; the bridge stops.
+    Closed(Option<String>),
+}
+
+pub fn parse_line(line: &str) -> Option<BridgeInput> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    if let Some((x, y)) = line.split_once(',') {
+        return Some(match (x.trim().parse(), y.trim().parse()) {
+            (Ok(x), Ok(y)) => BridgeInput::Sensor(x, y),
+            _ => BridgeInput::Unreadable(line.to_string()),
+        });
+    }
+    Some(line.parse().map_or_else(|_| BridgeInput::Unreadable(line.to_string()), BridgeInput::Move))
+}
+
+// Reads moves from a physical board on a background thread while connected. The app takes
+// them through `poll` and plays them the way it plays clicks.
+#[derive(Default)]
+pub struct BoardBridge {
+    pending: Option<Receiver<BridgeInput>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl BoardBridge {
+    pub fn connected(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn connect(&mut self, ctx: &egui::Context, device: &str) -> std::io::Result<()> {
+        self.disconnect();
+        let file = File::open(device.trim())?;
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(file).lines() {
+                if stopped.load(Ordering::Relaxed) {
+                    return;
+                }
+                let input = match line {
+                    Ok(line) => parse_line(&line),
+                    Err(e) => Some(BridgeInput::Closed(Some(e.to_string()))),
+                };
+                let closed = matches!(input, Some(BridgeInput::Closed(_)));
+                if let Some(input) = input {
+                    if sender.send(input).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                }
+                if closed {
+                    return;
+                }
+            }
+            let _ = sender.send(BridgeInput::Closed(None));
+            ctx.request_repaint();
+        });
+        tracing::info!(device, "physical board connected");
+        self.pending = Some(receiver);
+        self.stop = stop;
+        Ok(())
+    }
+
+    // The reader notices on the board's next line; nothing it reads after now is played.
+    pub fn disconnect(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.pending = None;
+    }
+
+    pub fn poll(&mut self) -> Vec<BridgeInput> {
+        let inputs: Vec<BridgeInput> = self.pending.as_ref().map(|receiver| receiver.try_iter().collect()).unwrap_or_default();
+        if inputs.iter().any(|input| matches!(input, BridgeInput::Closed(_))) {
+            self.pending = None;
+        }
+        inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_and_sensor_mapping() {
+        assert_eq!(parse_line(" 3, 5\r\n"), Some(BridgeInput::Sensor(3, 5)));
+        assert_eq!(parse_line("c4"), Some(BridgeInput::Move(Move::Place(Hex { q: 2, r: 3 }))));
+        assert_eq!(parse_line("SWAP"), Some(BridgeInput::Move(Move::Swap)));
+        assert_eq!(parse_line("# DGT-style board v2"), None);
+        assert_eq!(parse_line(""), None);
+        assert_eq!(parse_line("x,1"), Some(BridgeInput::Unreadable("x,1".to_string())));
+
+        let config = BridgeConfig { transpose: true, mirror_x: true, ..Default::default() };
+        assert_eq!(config.map(0, 0, 11), Hex { q: 10, r: 0 });
+        assert_eq!(config.map(1, 3, 11), Hex { q: 7, r: 1 });
+        assert_eq!(BridgeConfig::default().map(1, 3, 11), Hex { q: 1, r: 3 });
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use coast_to_coast::bookmarks::{self, Bookmark};
+use coast_to_coast::record::GameRecord;
+use eframe::egui;
+
+use crate::i18n::{tr, tr_args, Language};
+
+pub enum BookmarkClick {
+    Open,
+    Delete,
+}
+
+// The Bookmarks window: names the position on the board, with a note, and lists the positions
+// bookmarked so far to go back to. The games browser lists them as well.
+#[derive(Default)]
+pub struct BookmarkPanel {
+    pub open: bool,
+    name: String,
+    note: String,
+    bookmarks: Vec<Bookmark>,
+    error: Option<String>,
+}
+
+impl BookmarkPanel {
+    pub fn toggle(&mut self, data_dir: Option<&Path>) {
+        self.open = !self.open;
+        if self.open {
+            self.bookmarks = data_dir.map(bookmarks::list).unwrap_or_default();
+        }
+    }
+
+    // `position` gives the record of what the board shows, a look back included. Returns the
+    // bookmark clicked.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        language: Language,
+        data_dir: Option<&Path>,
+        position: impl FnOnce() -> GameRecord,
+    ) -> Option<Bookmark> {
+        let mut chosen = None;
+        let mut open = self.open;
+        egui::Window::new(tr(language, "bookmarks.title")).open(&mut open).default_width(320.0).show(ctx, |ui| {
+            let Some(data_dir) = data_dir else {
+                ui.label(tr(language, "bookmarks.no_data_dir"));
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(tr(language, "bookmarks.name"));
+                ui.text_edit_singleline(&mut self.name);
+            });
+            ui.label(tr(language, "bookmarks.note"));
+            ui.add(egui::TextEdit::multiline(&mut self.note).desired_rows(2).desired_width(f32::INFINITY));
+            let name = self.name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new(tr(language, "bookmarks.add"))).clicked() {
+                match bookmarks::save(data_dir, name, &self.note, &position()) {
+                    Ok(bookmark) => {
+                        tracing::info!(path = %bookmark.path.display(), "position bookmarked");
+                        self.bookmarks.insert(0, bookmark);
+                        self.name.clear();
+                        self.note.clear();
+                        self.error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "failed to save bookmark");
+                        self.error = Some(tr_args(language, "bookmarks.error", &[("error", &e.to_string())]));
+                    }
+                }
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            ui.separator();
+
+            if self.bookmarks.is_empty() {
+                ui.label(tr(language, "bookmarks.empty"));
+            }
+            let mut deleted = None;
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, bookmark) in self.bookmarks.iter().enumerate() {
+                    match show_bookmark(ui, language, bookmark) {
+                        Some(BookmarkClick::Open) => chosen = Some(bookmark.clone()),
+                        Some(BookmarkClick::Delete) => deleted = Some(index),
+                        None => {}
+                    }
+                }
+            });
+            if let Some(index) = deleted {
+                let bookmark = self.bookmarks.remove(index);
+                if let Err(e) = bookmarks::delete(&bookmark.path) {
+                    tracing::warn!(path = %bookmark.path.display(), error = %e, "failed to delete bookmark");
+                }
+            }
+        });
+        self.open = open;
+        chosen
+    }
+}
+
+// A bookmark's row, here and in the games browser: its name, which opens it, the board and move
+// count, and its note below.
+pub fn show_bookmark(ui: &mut egui::Ui, language: Language, bookmark: &Bookmark) -> Option<BookmarkClick> {
+    let mut click = None;
+    let record = &bookmark.record;
+    ui.horizontal(|ui| {
+        if ui.link(&bookmark.name).on_hover_text(tr(language, "bookmarks.open_hint")).clicked() {
+            click = Some(BookmarkClick::Open);
+        }
+        let moves = tr_args(language, "bookmarks.moves", &[("moves", &record.moves.len().to_string())]);
+        ui.weak(format!("{}×{} · {}", record.size, record.size, moves));
+        if ui.small_button(tr(language, "browser.delete")).clicked() {
+            click = Some(BookmarkClick::Delete);
+        }
+    });
+    if !bookmark.note.is_empty() {
+        ui.label(&bookmark.note);
+    }
+    click
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+    use coast_to_coast::game::Game;
+    use coast_to_coast::record::GameMetadata;
+
+    #[test]
+    fn test_bookmark_the_position_and_open_it() {
+        let dir = std::env::temp_dir().join(format!("hex-bookmark-panel-test-{}", std::process::id()));
+        let mut game = Game::with_size(4);
+        game.play_move("b2".parse().unwrap()).unwrap();
+        let record = GameRecord::from_game(&game, &GameMetadata::default());
+
+        let mut panel = BookmarkPanel::default();
+        panel.toggle(Some(&dir));
+        panel.name = "Centre".to_string();
+        let mut chosen = None;
+        let mut harness = UiHarness::new(800.0, 600.0);
+        let mut frame = |ui: &mut egui::Ui| {
+            if let Some(bookmark) = panel.show(ui.ctx(), Language::English, Some(&dir), || record.clone()) {
+                chosen = Some(bookmark);
+            }
+        };
+        // A new window is laid out unseen first.
+        harness.run(&mut frame);
+        harness.run(&mut frame);
+        harness.click("Bookmark this position", &mut frame);
+        harness.click("Centre", &mut frame);
+
+        let saved = bookmarks::list(&dir);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(chosen, Some(saved[0].clone()));
+        assert_eq!(saved[0].record.moves, game.history);
+        assert!(panel.name.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,109 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::record::GameRecord;
+
+// Bookmarked positions live in `<data_dir>/bookmarks/`, each a text record of the moves up to
+// the position with its name and note in two extra tags, which `GameRecord::parse` skips.
+pub const BOOKMARKS_DIR: &str = "bookmarks";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub path: PathBuf,
+    pub name: String,
+    pub note: String,
+    pub record: GameRecord,
+    pub created: u64,
+}
+
+// Writes a new bookmark. Tag values are a line each, so a note's line breaks become spaces.
+pub fn save(data_dir: &Path, name: &str, note: &str, record: &GameRecord) -> io::Result<Bookmark> {
+    let dir = data_dir.join(BOOKMARKS_DIR);
+    fs::create_dir_all(&dir)?;
+    let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let mut path = dir.join(format!("bookmark-{}.txt", created));
+    let mut suffix = 1;
+    while path.exists() {
+        suffix += 1;
+        path = dir.join(format!("bookmark-{}-{}.txt", created, suffix));
+    }
+    let name = one_line(name);
+    let note = one_line(note);
+    let text = format!("[Bookmark \"{}\"]\n[Note \"{}\"]\n[Created \"{}\"]\n{}", name, note, created, record.to_text());
+    fs::write(&path, text)?;
+    Ok(Bookmark { path, name, note, record: record.clone(), created })
+}
+
+pub fn delete(path: &Path) -> io::Result<()> {
+    fs::remove_file(path)
+}
+
+// All readable bookmarks, newest first. Files that fail to parse are skipped.
+pub fn list(data_dir: &Path) -> Vec<Bookmark> {
+    let Ok(entries) = fs::read_dir(data_dir.join(BOOKMARKS_DIR)) else {
+        return Vec::new();
+    };
+    let mut bookmarks: Vec<Bookmark> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter_map(|path| {
+            let text = fs::read_to_string(&path).ok()?;
+            let record = match GameRecord::parse(&text) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipping unreadable bookmark");
+                    return None;
+                }
+            };
+            let name = tag(&text, "Bookmark").unwrap_or_default().to_string();
+            let note = tag(&text, "Note").unwrap_or_default().to_string();
+            let created = tag(&text, "Created").and_then(|created| created.parse().ok()).unwrap_or(0);
+            Some(Bookmark { path, name, note, record, created })
+        })
+        .collect();
+    bookmarks.sort_by_key(|bookmark| std::cmp::Reverse(bookmark.created));
+    bookmarks
+}
+
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The value of the first `[tag "value"]` line for `tag`.
+fn tag<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    text.lines().find_map(|line| {
+        let value = line.trim().strip_prefix('[')?.strip_prefix(tag)?.strip_prefix(" \"")?;
+        value.strip_suffix("\"]")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::record::GameMetadata;
+
+    #[test]
+    fn test_save_list_and_delete() {
+        let dir = std::env::temp_dir().join(format!("hex-bookmarks-test-{}", std::process::id()));
+        let mut game = Game::with_size(3);
+        game.play_move("b2".parse().unwrap()).unwrap();
+        let record = GameRecord::from_game(&game, &GameMetadata::default());
+
+        let saved = save(&dir, "Centre \"opening\"", "Strong\nfor Red", &record).unwrap();
+        assert_eq!(saved.note, "Strong for Red");
+        fs::write(dir.join(BOOKMARKS_DIR).join("broken.txt"), "[Size 3]").unwrap();
+
+        let bookmarks = list(&dir);
+        assert_eq!(bookmarks, vec![saved.clone()]);
+        assert_eq!(bookmarks[0].name, "Centre \"opening\"");
+        assert_eq!(bookmarks[0].record.replay().unwrap().history, game.history);
+
+        delete(&saved.path).unwrap();
+        assert!(list(&dir).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use coast_to_coast::bookmarks::BOOKMARKS_DIR;
+use coast_to_coast::saves::SAVES_DIR;
+use coast_to_coast::templates::TEMPLATES_DIR;
+use eframe::egui;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+
+use crate::cloud_sync::SyncConfig;
+use crate::i18n::{tr, tr_args, Language};
+use crate::move_list::EXPORT_DIR;
+use crate::settings::Settings;
+
+// Bumped whenever a bundle's layout changes in a way older versions can't read.
+const SCHEMA_VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.txt";
+const MANIFEST_TITLE: &str = "coast-to-coast data bundle";
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug)]
+pub enum BundleError {
+    Io(io::Error),
+    // Not a zip file, or one this reader can't handle.
+    Zip(&'static str),
+    NotABundle,
+    NewerSchema(u32),
+    Settings(String),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(e) => write!(f, "{}", e),
+            BundleError::Zip(reason) => write!(f, "unreadable zip file: {}", reason),
+            BundleError::NotABundle => write!(f, "not a data bundle"),
+            BundleError::NewerSchema(schema) => write!(
+                f,
+                "made by a newer version (schema {}, this version reads up to {})",
+                schema, SCHEMA_VERSION
+            ),
+            BundleError::Settings(e) => write!(f, "unreadable settings: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for BundleError {
+    fn from(e: io::Error) -> Self {
+        BundleError::Io(e)
+    }
+}
+
+// Writes the settings, saved games with their notes, bookmarks and the user's edge templates to
+// `<data_dir>/exports/hex-data-<timestamp>.zip`. The sync password is left out.
+pub fn export(data_dir: &Path, settings: &Settings) -> Result<PathBuf, BundleError> {
+    let settings = Settings { sync: SyncConfig { password: String::new(), ..settings.sync.clone() }, ..settings.clone() };
+    let mut files = vec![(
+        SETTINGS_FILE.to_string(),
+        ureq::serde_json::to_vec_pretty(&settings).map_err(|e| BundleError::Settings(e.to_string()))?,
+        SystemTime::now(),
+    )];
+    for dir in [SAVES_DIR, BOOKMARKS_DIR, TEMPLATES_DIR] {
+        let Ok(entries) = fs::read_dir(data_dir.join(dir)) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| Some(entry.ok()?.path())).collect();
+        paths.sort();
+        // Saved games' notes are the Markdown files beside them.
+        for path in paths.into_iter().filter(|path| path.extension().is_some_and(|ext| ext == "txt" || ext == "md")) {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let modified = fs::metadata(&path)?.modified().unwrap_or(UNIX_EPOCH);
+            files.push((format!("{}/{}", dir, name), fs::read(&path)?, modified));
+        }
+    }
+
+    // The manifest keeps each file's modification time, as saved games are listed by it.
+    let mut manifest = format!("{}\nschema {}\n", MANIFEST_TITLE, SCHEMA_VERSION);
+    for (name, _, modified) in &files {
+        let seconds = modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        manifest.push_str(&format!("modified {} {}\n", seconds, name));
+    }
+    files.insert(0, (MANIFEST.to_string(), manifest.into_bytes(), SystemTime::now()));
+
+    let dir = data_dir.join(EXPORT_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let path = dir.join(format!("hex-data-{}.zip", timestamp));
+    let entries: Vec<(&str, &[u8])> = files.iter().map(|(name, contents, _)| (name.as_str(), contents.as_slice())).collect();
+    fs::write(&path, write_zip(&entries)?)?;
+    Ok(path)
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub files: usize,
+    // Settings from the bundle, to replace all but this device's sync configuration.
+    pub settings: Option<Box<Settings>>,
+}
+
+// Copies a bundle's saved games, bookmarks and templates into `data_dir`. A file that would replace a
+// different one already there is imported under a new name instead, so nothing is lost.
+pub fn import(data_dir: &Path, zip: &[u8]) -> Result<ImportReport, BundleError> {
+    let entries = read_zip(zip)?;
+    let manifest = entries
+        .iter()
+        .find(|(name, _)| name == MANIFEST)
+        .and_then(|(_, contents)| std::str::from_utf8(contents).ok())
+        .filter(|manifest| manifest.lines().next() == Some(MANIFEST_TITLE))
+        .ok_or(BundleError::NotABundle)?;
+    let mut schema = None;
+    let mut modified = Vec::new();
+    for line in manifest.lines().skip(1) {
+        if let Some(version) = line.strip_prefix("schema ") {
+            schema = version.trim().parse().ok();
+        } else if let Some((seconds, name)) = line.strip_prefix("modified ").and_then(|rest| rest.split_once(' ')) {
+            if let Ok(seconds) = seconds.parse() {
+                modified.push((name.to_string(), UNIX_EPOCH + Duration::from_secs(seconds)));
+            }
+        }
+    }
+    match schema {
+        None => return Err(BundleError::NotABundle),
+        Some(schema) if schema > SCHEMA_VERSION => return Err(BundleError::NewerSchema(schema)),
+        Some(_) => {}
+    }
+
+    let mut report = ImportReport::default();
+    for (name, contents) in &entries {
+        if name == SETTINGS_FILE {
+            let settings = ureq::serde_json::from_slice(contents).map_err(|e| BundleError::Settings(e.to_string()))?;
+            report.settings = Some(Box::new(settings));
+            continue;
+        }
+        // Only plain files in the known directories; anything else is skipped.
+        let Some((dir, file)) = name.split_once('/') else {
+            continue;
+        };
+        if ![SAVES_DIR, BOOKMARKS_DIR, TEMPLATES_DIR].contains(&dir) || file.contains(['/', '\\']) || file.starts_with('.') {
+            continue;
+        }
+        fs::create_dir_all(data_dir.join(dir))?;
+        let Some(path) = free_path(&data_dir.join(dir), file, contents) else {
+            continue;
+        };
+        fs::write(&path, contents)?;
+        if let Some((_, time)) = modified.iter().find(|(modified, _)| modified == name) {
+            fs::File::options().append(true).open(&path)?.set_modified(*time)?;
+        }
+        report.files += 1;
+    }
+    Ok(report)
+}
+
+// Where to put `file`: its own name unless a different file has it, then the first free
+// "-imported" name. None when an identical copy is already there.
+fn free_path(dir: &Path, file: &str, contents: &[u8]) -> Option<PathBuf> {
+    let (stem, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+    (0..)
+        .map(|n| match n {
+            0 => dir.join(file),
+            1 => dir.join(format!("{}-imported.{}", stem, extension)),
+            n => dir.join(format!("{}-imported-{}.{}", stem, n, extension)),
+        })
+        .find_map(|path| match fs::read(&path) {
+            Ok(existing) if existing == contents => Some(None),
+            Ok(_) => None,
+            Err(_) => Some(Some(path)),
+        })
+        .flatten()
+}
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+// Names are UTF-8.
+const UTF8_FLAG: u16 = 0x0800;
+
+// A zip archive of deflated files. Entry dates are left at 1980-01-01; the manifest has the
+// real ones.
+fn write_zip(entries: &[(&str, &[u8])]) -> io::Result<Vec<u8>> {
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for (name, contents) in entries {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(contents)?;
+        let compressed = encoder.finish()?;
+        let offset = zip.len() as u32;
+        let mut header = Vec::new();
+        for value in [20, UTF8_FLAG, DEFLATED, 0, 0x21] {
+            header.extend(u16::to_le_bytes(value));
+        }
+        for value in [crc32fast::hash(contents), compressed.len() as u32, contents.len() as u32] {
+            header.extend(u32::to_le_bytes(value));
+        }
+        header.extend(u16::to_le_bytes(name.len() as u16));
+        header.extend(u16::to_le_bytes(0));
+
+        zip.extend(u32::to_le_bytes(LOCAL_HEADER));
+        zip.extend(&header);
+        zip.extend(name.as_bytes());
+        zip.extend(&compressed);
+
+        central.extend(u32::to_le_bytes(CENTRAL_HEADER));
+        central.extend(u16::to_le_bytes(20));
+        central.extend(&header);
+        // Comment length, disk, internal and external attributes, then the local header.
+        for value in [0u16, 0, 0, 0, 0] {
+            central.extend(u16::to_le_bytes(value));
+        }
+        central.extend(u32::to_le_bytes(offset));
+        central.extend(name.as_bytes());
+    }
+    let central_offset = zip.len() as u32;
+    zip.extend(&central);
+    zip.extend(u32::to_le_bytes(END_OF_CENTRAL_DIRECTORY));
+    for value in [0, 0, entries.len() as u16, entries.len() as u16] {
+        zip.extend(u16::to_le_bytes(value));
+    }
+    zip.extend(u32::to_le_bytes(central.len() as u32));
+    zip.extend(u32::to_le_bytes(central_offset));
+    zip.extend(u16::to_le_bytes(0));
+    Ok(zip)
+}
+
+// Every file in a zip archive, stored or deflated, by name.
+fn read_zip(zip: &[u8]) -> Result<Vec<(String, Vec<u8>)>, BundleError> {
+    let u16_at = |at: usize| zip.get(at..at + 2).map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize);
+    let u32_at = |at: usize| {
+        zip.get(at..at + 4).map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+    let end = (0..zip.len().saturating_sub(21))
+        .rev()
+        .find(|at| u32_at(*at) == Some(END_OF_CENTRAL_DIRECTORY as usize))
+        .ok_or(BundleError::Zip("no central directory"))?;
+    let count = u16_at(end + 10).ok_or(BundleError::Zip("truncated"))?;
+    let mut at = u32_at(end + 16).ok_or(BundleError::Zip("truncated"))?;
+    let mut files = Vec::with_capacity(count);
+    for _ in 0..count {
+        if u32_at(at) != Some(CENTRAL_HEADER as usize) {
+            return Err(BundleError::Zip("bad central directory"));
+        }
+        let field = |offset: usize, size: usize| {
+            if size == 2 { u16_at(at + offset) } else { u32_at(at + offset) }.ok_or(BundleError::Zip("truncated"))
+        };
+        let (method, crc, compressed_size, size) = (field(10, 2)?, field(16, 4)?, field(20, 4)?, field(24, 4)?);
+        let (name_length, extra_length, comment_length) = (field(28, 2)?, field(30, 2)?, field(32, 2)?);
+        let local = field(42, 4)?;
+        let name = zip.get(at + 46..at + 46 + name_length).ok_or(BundleError::Zip("truncated"))?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + name_length + extra_length + comment_length;
+
+        let data_start = local
+            + 30
+            + u16_at(local + 26).ok_or(BundleError::Zip("truncated"))?
+            + u16_at(local + 28).ok_or(BundleError::Zip("truncated"))?;
+        let data = zip.get(data_start..data_start + compressed_size).ok_or(BundleError::Zip("truncated"))?;
+        let contents = match method as u16 {
+            STORED => data.to_vec(),
+            DEFLATED => {
+                let mut contents = Vec::with_capacity(size);
+                DeflateDecoder::new(data).read_to_end(&mut contents)?;
+                contents
+            }
+            _ => return Err(BundleError::Zip("unsupported compression")),
+        };
+        if contents.len() != size || crc32fast::hash(&contents) as usize != crc {
+            return Err(BundleError::Zip("checksum mismatch"));
+        }
+        if !name.ends_with('/') {
+            files.push((name, contents));
+        }
+    }
+    Ok(files)
+}
+
+// Asks for a bundle to import, by path or by dropping it on the window.
+#[derive(Default)]
+pub struct ImportDataDialog {
+    pub open: bool,
+    path: String,
+    error: Option<String>,
+}
+
+impl ImportDataDialog {
+    pub fn show(&mut self, ctx: &egui::Context, language: Language, data_dir: &Path) -> Option<ImportReport> {
+        if !self.open {
+            return None;
+        }
+        let mut import_path = ctx.input(|i| i.raw.dropped_files.iter().find_map(|file| file.path.clone()));
+        let mut open = self.open;
+        egui::Window::new(tr(language, "bundle.import_title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr(language, "bundle.import_hint"));
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.path);
+                    let button = egui::Button::new(tr(language, "bundle.import"));
+                    if ui.add_enabled(!self.path.trim().is_empty(), button).clicked() {
+                        import_path = Some(PathBuf::from(self.path.trim()));
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        self.open = open;
+        let path = import_path?;
+        match fs::read(&path).map_err(BundleError::from).and_then(|zip| import(data_dir, &zip)) {
+            Ok(report) => {
+                tracing::info!(path = %path.display(), files = report.files, "data imported");
+                *self = Self::default();
+                Some(report)
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "data import failed");
+                self.error = Some(tr_args(language, "bundle.import_error", &[("error", &e.to_string())]));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_and_import_round_trip() {
+        let dir = std::env::temp_dir().join(format!("hex-bundle-test-{}", std::process::id()));
+        let (from, to) = (dir.join("from"), dir.join("to"));
+        fs::create_dir_all(from.join(SAVES_DIR)).unwrap();
+        fs::create_dir_all(to.join(SAVES_DIR)).unwrap();
+        fs::write(from.join(SAVES_DIR).join("game-1.txt"), "one").unwrap();
+        fs::write(from.join(SAVES_DIR).join("game-2.txt"), "two").unwrap();
+        fs::write(to.join(SAVES_DIR).join("game-1.txt"), "one").unwrap();
+        fs::write(to.join(SAVES_DIR).join("game-2.txt"), "other").unwrap();
+        let settings = Settings { board_size: 13, ..Default::default() };
+
+        let zip = fs::read(export(&from, &settings).unwrap()).unwrap();
+        let report = import(&to, &zip).unwrap();
+        // game-1 is already there; game-2 clashes with a different game and gets a new name.
+        assert_eq!(report.files, 1);
+        assert_eq!(report.settings.map(|settings| settings.board_size), Some(13));
+        assert_eq!(fs::read_to_string(to.join(SAVES_DIR).join("game-2-imported.txt")).unwrap(), "two");
+
+        let newer = write_zip(&[(MANIFEST, format!("{}\nschema 99\n", MANIFEST_TITLE).as_bytes())]).unwrap();
+        assert!(matches!(import(&to, &newer), Err(BundleError::NewerSchema(99))));
+        let unrelated = write_zip(&[("notes.txt", b"hello".as_slice())]).unwrap();
+        assert!(matches!(import(&to, &unrelated), Err(BundleError::NotABundle)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
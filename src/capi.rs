@@ -0,0 +1,283 @@
+//! C API (`capi` feature) for embedding the rules engine and AI in non-Rust hosts.
+//!
+//! Building with `--features capi` regenerates `include/coast_to_coast.h` via cbindgen.
+//! Games are opaque handles created by [`hex_new_game`] and released by [`hex_free_game`].
+
+use std::ptr;
+
+use crate::ai::{AiPlayer, RandomPlayer};
+use crate::board::{CellState, Hex};
+use crate::game::{Game, GameState, MAX_BOARD_SIZE};
+
+/// Opaque game handle.
+pub struct HexGame {
+    game: Game,
+    ai: RandomPlayer,
+}
+
+/// Result codes returned by the mutating functions.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum HexStatus {
+    Ok = 0,
+    /// [`hex_genmove`] answered a pending pie-rule decision by swapping.
+    Swapped = 1,
+    /// [`hex_genmove`] answered a pending pie-rule decision by declining the swap.
+    Declined = 2,
+    NullGame = -1,
+    OutOfBounds = -2,
+    Occupied = -3,
+    /// The game is over or adjourned, or a pie-rule decision is pending (or not pending, for
+    /// [`hex_decide_pie_rule`]).
+    WrongState = -4,
+    NoMove = -5,
+}
+
+/// Colors as returned by [`hex_winner`] and [`hex_current_player`].
+pub const HEX_NONE: i32 = 0;
+pub const HEX_RED: i32 = 1;
+pub const HEX_BLUE: i32 = 2;
+/// Returned by [`hex_cell`] for a permanently blocked cell.
+pub const HEX_BLOCKED: i32 = 3;
+
+fn color_code(state: CellState) -> i32 {
+    match state {
+        CellState::Empty => HEX_NONE,
+        CellState::Red => HEX_RED,
+        CellState::Blue => HEX_BLUE,
+        CellState::Blocked => HEX_BLOCKED,
+    }
+}
+
+/// Creates a game on a `size`×`size` board. Returns null if `size` is not positive or
+/// above `MAX_BOARD_SIZE` (64).
+#[no_mangle]
+pub extern "C" fn hex_new_game(size: i32) -> *mut HexGame {
+    if size <= 0 || size > MAX_BOARD_SIZE {
+        return ptr::null_mut();
+    }
+    Box::into_raw(Box::new(HexGame { game: Game::with_size(size), ai: RandomPlayer::new() }))
+}
+
+/// # Safety
+/// `game` must be null or a handle from [`hex_new_game`] that was not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn hex_free_game(game: *mut HexGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Places a stone for the side to move at axial `(q, r)`.
+///
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_play(game: *mut HexGame, q: i32, r: i32) -> HexStatus {
+    let Some(handle) = game.as_mut() else {
+        return HexStatus::NullGame;
+    };
+    play(&mut handle.game, Hex { q, r })
+}
+
+fn play(game: &mut Game, hex: Hex) -> HexStatus {
+    if game.state != GameState::InProgress {
+        return HexStatus::WrongState;
+    }
+    match game.board.get_cell(&hex) {
+        None => HexStatus::OutOfBounds,
+        Some(CellState::Empty) => {
+            game.handle_click(hex);
+            HexStatus::Ok
+        }
+        Some(_) => HexStatus::Occupied,
+    }
+}
+
+/// Answers the pie-rule question after the first move.
+///
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_decide_pie_rule(game: *mut HexGame, swap: bool) -> HexStatus {
+    let Some(handle) = game.as_mut() else {
+        return HexStatus::NullGame;
+    };
+    if handle.game.state != GameState::WaitingForPieRuleChoice {
+        return HexStatus::WrongState;
+    }
+    handle.game.handle_pie_rule_decision(swap);
+    HexStatus::Ok
+}
+
+/// Lets the engine pick and play a move for the side to move, writing it to `q`/`r`
+/// (either may be null). If a pie-rule decision is pending, the engine makes it instead and
+/// returns [`HexStatus::Swapped`], reporting the cell Blue's stone now sits on, or
+/// [`HexStatus::Declined`], leaving `q`/`r` untouched.
+///
+/// # Safety
+/// `game` must be null or a live handle; `q` and `r` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn hex_genmove(game: *mut HexGame, q: *mut i32, r: *mut i32) -> HexStatus {
+    let Some(handle) = game.as_mut() else {
+        return HexStatus::NullGame;
+    };
+    let (hex, status) = match handle.game.state {
+        GameState::Finished { .. } | GameState::Drawn | GameState::Resigned { .. } | GameState::Adjourned { .. } => {
+            return HexStatus::WrongState
+        }
+        GameState::WaitingForPieRuleChoice => {
+            let swap = handle.ai.decide_pie_rule(&handle.game);
+            // Where the swap leaves Blue's stone, worked out while that cell is still empty.
+            let swapped = handle.game.first_player_move.map(|first| handle.game.swapped_cell(first));
+            handle.game.handle_pie_rule_decision(swap);
+            if !swap {
+                return HexStatus::Declined;
+            }
+            match swapped {
+                Some(hex) => (hex, HexStatus::Swapped),
+                None => return HexStatus::NoMove,
+            }
+        }
+        GameState::InProgress => {
+            let Some(hex) = handle.ai.choose_move(&handle.game) else {
+                return HexStatus::NoMove;
+            };
+            let status = play(&mut handle.game, hex);
+            if status != HexStatus::Ok {
+                return status;
+            }
+            (hex, status)
+        }
+    };
+    if let Some(q) = q.as_mut() {
+        *q = hex.q;
+    }
+    if let Some(r) = r.as_mut() {
+        *r = hex.r;
+    }
+    status
+}
+
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_game_over(game: *const HexGame) -> bool {
+    game.as_ref()
+        .is_some_and(|handle| matches!(handle.game.state, GameState::Finished { .. }))
+}
+
+/// Returns [`HEX_RED`], [`HEX_BLUE`], or [`HEX_NONE`] while the game is running.
+///
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_winner(game: *const HexGame) -> i32 {
+    match game.as_ref().map(|handle| handle.game.state) {
+        Some(GameState::Finished { winner }) => color_code(winner),
+        _ => HEX_NONE,
+    }
+}
+
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_current_player(game: *const HexGame) -> i32 {
+    game.as_ref().map_or(HEX_NONE, |handle| color_code(handle.game.current_player))
+}
+
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_waiting_for_pie_rule(game: *const HexGame) -> bool {
+    game.as_ref()
+        .is_some_and(|handle| handle.game.state == GameState::WaitingForPieRuleChoice)
+}
+
+/// Returns [`HEX_RED`], [`HEX_BLUE`], [`HEX_BLOCKED`] or [`HEX_NONE`] for the cell, or -1 if
+/// out of bounds.
+///
+/// # Safety
+/// `game` must be null or a live handle from [`hex_new_game`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_cell(game: *const HexGame, q: i32, r: i32) -> i32 {
+    game.as_ref()
+        .and_then(|handle| handle.game.board.get_cell(&Hex { q, r }).copied())
+        .map_or(-1, color_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_and_query() {
+        unsafe {
+            let game = hex_new_game(3);
+            assert!(!game.is_null());
+            assert_eq!(hex_current_player(game), HEX_RED);
+            assert_eq!(hex_play(game, 0, 0), HexStatus::Ok);
+            assert!(hex_waiting_for_pie_rule(game));
+            assert_eq!(hex_play(game, 1, 1), HexStatus::WrongState);
+            assert_eq!(hex_decide_pie_rule(game, false), HexStatus::Ok);
+            assert_eq!(hex_play(game, 0, 0), HexStatus::Occupied);
+            assert_eq!(hex_play(game, 5, 5), HexStatus::OutOfBounds);
+            assert_eq!(hex_cell(game, 0, 0), HEX_RED);
+            assert_eq!(hex_cell(game, 5, 5), -1);
+            hex_free_game(game);
+        }
+    }
+
+    #[test]
+    fn test_genmove_plays_to_the_end() {
+        unsafe {
+            let game = hex_new_game(4);
+            let (mut q, mut r) = (-1, -1);
+            while !hex_game_over(game) {
+                let status = hex_genmove(game, &mut q, &mut r);
+                assert!(matches!(status, HexStatus::Ok | HexStatus::Swapped | HexStatus::Declined));
+                assert!((0..4).contains(&q) && (0..4).contains(&r));
+            }
+            assert_ne!(hex_winner(game), HEX_NONE);
+            assert_eq!(hex_genmove(game, ptr::null_mut(), ptr::null_mut()), HexStatus::WrongState);
+            hex_free_game(game);
+        }
+    }
+
+    #[test]
+    fn test_genmove_reports_the_pie_choice() {
+        for (swap_probability, expected) in [(1.0, HexStatus::Swapped), (0.0, HexStatus::Declined)] {
+            let game = hex_new_game(3);
+            unsafe {
+                (*game).ai = RandomPlayer::seeded(1).with_swap_probability(swap_probability);
+                assert_eq!(hex_play(game, 1, 0), HexStatus::Ok);
+                let (mut q, mut r) = (-1, -1);
+                assert_eq!(hex_genmove(game, &mut q, &mut r), expected);
+                assert!(!hex_waiting_for_pie_rule(game));
+                if expected == HexStatus::Swapped {
+                    // The opening stone is mirrored onto (r, q) as Blue's, and Red is to move.
+                    assert_eq!((q, r), (0, 1));
+                    assert_eq!(hex_cell(game, 0, 1), HEX_BLUE);
+                    assert_eq!(hex_cell(game, 1, 0), HEX_NONE);
+                    assert_eq!(hex_current_player(game), HEX_RED);
+                } else {
+                    assert_eq!((q, r), (-1, -1));
+                    assert_eq!(hex_current_player(game), HEX_BLUE);
+                }
+                hex_free_game(game);
+            }
+        }
+    }
+
+    #[test]
+    fn test_null_and_invalid_handles() {
+        unsafe {
+            assert!(hex_new_game(0).is_null());
+            assert!(hex_new_game(MAX_BOARD_SIZE + 1).is_null());
+            assert_eq!(hex_play(ptr::null_mut(), 0, 0), HexStatus::NullGame);
+            assert!(!hex_game_over(ptr::null()));
+            assert_eq!(hex_winner(ptr::null()), HEX_NONE);
+            hex_free_game(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::time::{Duration, Instant};
+
+use coast_to_coast::board::CellState;
+
+// Counts up the thinking time each side has used. There are no time limits; this is what the
+// presentation mode shows as its big clock. It also times every move, the pie rule decision
+// included.
+#[derive(Clone, Debug, Default)]
+pub struct GameClock {
+    red: Duration,
+    blue: Duration,
+    running: Option<(CellState, Instant)>,
+    move_times: Vec<Option<Duration>>,
+    move_started: Option<Instant>,
+    paused: Option<Instant>,
+}
+
+impl GameClock {
+    // A clock for a loaded game whose earlier moves, made by `movers`, took `move_times`. Each
+    // side starts with the time its recorded moves used, so an adjourned game resumes with its
+    // clocks where they stood.
+    pub fn with_move_times(move_times: Vec<Option<Duration>>, movers: &[CellState]) -> Self {
+        let mut clock = Self::default();
+        for (player, time) in movers.iter().zip(&move_times) {
+            *clock.slot(*player) += time.unwrap_or_default();
+        }
+        Self { move_times, ..clock }
+    }
+
+    // Stops the running side's clock and starts `player`'s.
+    pub fn switch_to(&mut self, player: CellState, now: Instant) {
+        self.stop(now);
+        self.running = Some((player, now));
+        self.move_started.get_or_insert(now);
+    }
+
+    // Records a move made at `now`, timed from the previous one (or from the start).
+    pub fn record_move(&mut self, now: Instant) {
+        self.move_times.push(self.move_started.map(|started| now.saturating_duration_since(started)));
+        self.move_started = Some(now);
+    }
+
+    // Forgets the last `count` moves, made by `movers` (one entry per remaining move time, in
+    // order), and hands their thinking time back. The move being taken back is timed afresh from
+    // `now`.
+    pub fn take_back(&mut self, movers: &[CellState], count: usize, now: Instant) {
+        let keep = self.move_times.len().saturating_sub(count);
+        let undone = self.move_times.split_off(keep);
+        for (player, time) in movers.iter().skip(keep).zip(undone) {
+            if let Some(time) = time {
+                let slot = self.slot(*player);
+                *slot = slot.saturating_sub(time);
+            }
+        }
+        self.move_started = Some(now);
+    }
+
+    // Stops the clocks for a break. The move being thought about is timed without it once
+    // `resume` is called.
+    pub fn pause(&mut self, now: Instant) {
+        self.stop(now);
+        self.paused.get_or_insert(now);
+    }
+
+    // Ends a break; the caller starts the clock of whoever is to move.
+    pub fn resume(&mut self, now: Instant) {
+        if let (Some(paused), Some(started)) = (self.paused.take(), &mut self.move_started) {
+            *started += now.saturating_duration_since(paused);
+        }
+    }
+
+    pub fn move_times(&self) -> &[Option<Duration>] {
+        &self.move_times
+    }
+
+    pub fn stop(&mut self, now: Instant) {
+        if let Some((player, since)) = self.running.take() {
+            *self.slot(player) += now.saturating_duration_since(since);
+        }
+    }
+
+    pub fn running(&self) -> Option<CellState> {
+        self.running.map(|(player, _)| player)
+    }
+
+    // How long until the running side's time, shown to the second, next changes; None while the
+    // clocks are stopped. Countdowns from whole minutes tick over at the same moments.
+    pub fn next_tick(&self, now: Instant) -> Option<Duration> {
+        let player = self.running()?;
+        Some(Duration::from_secs(1) - Duration::from_nanos(self.used(player, now).subsec_nanos().into()))
+    }
+
+    pub fn used(&self, player: CellState, now: Instant) -> Duration {
+        let stored = match player {
+            CellState::Red => self.red,
+            CellState::Blue => self.blue,
+            CellState::Empty | CellState::Blocked => Duration::ZERO,
+        };
+        match self.running {
+            Some((running, since)) if running == player => stored + now.saturating_duration_since(since),
+            _ => stored,
+        }
+    }
+
+    fn slot(&mut self, player: CellState) -> &mut Duration {
+        match player {
+            CellState::Blue => &mut self.blue,
+            _ => &mut self.red,
+        }
+    }
+}
+
+// "m:ss", or "h:mm:ss" from an hour on.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_accumulates_per_player() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut clock = GameClock::default();
+
+        clock.switch_to(CellState::Red, at(0));
+        assert_eq!(clock.used(CellState::Red, at(5)), Duration::from_secs(5));
+        clock.switch_to(CellState::Blue, at(7));
+        clock.switch_to(CellState::Red, at(10));
+        clock.stop(at(12));
+
+        assert_eq!(clock.used(CellState::Red, at(100)), Duration::from_secs(9));
+        assert_eq!(clock.used(CellState::Blue, at(100)), Duration::from_secs(3));
+        assert_eq!(clock.running(), None);
+        assert_eq!(clock.next_tick(at(100)), None);
+        clock.switch_to(CellState::Blue, at(100));
+        assert_eq!(clock.next_tick(at(100) + Duration::from_millis(300)), Some(Duration::from_millis(700)));
+    }
+
+    #[test]
+    fn test_move_times() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut clock = GameClock::with_move_times(vec![None], &[CellState::Red]);
+
+        clock.switch_to(CellState::Red, at(0));
+        clock.record_move(at(4));
+        // The pie rule decision is timed even though the same side stays on move.
+        clock.switch_to(CellState::Blue, at(4));
+        clock.record_move(at(6));
+        clock.record_move(at(16));
+        assert_eq!(
+            clock.move_times(),
+            [None, Some(Duration::from_secs(4)), Some(Duration::from_secs(2)), Some(Duration::from_secs(10))]
+        );
+    }
+
+    #[test]
+    fn test_loaded_clock_resumes_from_move_times() {
+        let now = Instant::now();
+        let seconds = |secs| Some(Duration::from_secs(secs));
+        let movers = [CellState::Red, CellState::Blue, CellState::Red, CellState::Blue];
+        let clock = GameClock::with_move_times(vec![seconds(3), None, seconds(5), seconds(7)], &movers);
+        assert_eq!(clock.used(CellState::Red, now), Duration::from_secs(8));
+        assert_eq!(clock.used(CellState::Blue, now), Duration::from_secs(7));
+    }
+
+    #[test]
+    fn test_take_back_returns_time() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut clock = GameClock::default();
+
+        clock.switch_to(CellState::Red, at(0));
+        clock.record_move(at(4));
+        clock.switch_to(CellState::Blue, at(4));
+        clock.record_move(at(10));
+        clock.switch_to(CellState::Red, at(10));
+
+        clock.take_back(&[CellState::Red, CellState::Blue], 1, at(12));
+        clock.switch_to(CellState::Blue, at(12));
+        assert_eq!(clock.move_times(), [Some(Duration::from_secs(4))]);
+        assert_eq!(clock.used(CellState::Blue, at(12)), Duration::ZERO);
+        assert_eq!(clock.used(CellState::Red, at(12)), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(5)), "0:05");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(Duration::from_secs(3_725)), "1:02:05");
+    }
+}
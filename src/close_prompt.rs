@@ -0,0 +1,106 @@
+use eframe::egui;
+
+use crate::i18n::{tr, Language};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseChoice {
+    Save,
+    Discard,
+    Cancel,
+}
+
+// Holds the window open when it is asked to close with a game that has changed since it was
+// last saved, and asks what to do with it. The autosave keeps the game either way; this is
+// about the saved game the user knows by name.
+#[derive(Default)]
+pub struct ClosePrompt {
+    open: bool,
+    // Set once the user has answered, so the close that follows goes through.
+    closing: bool,
+}
+
+impl ClosePrompt {
+    // Call each frame before anything else handles the close request.
+    pub fn intercept(&mut self, ctx: &egui::Context, unsaved: bool) {
+        if ctx.input(|i| i.viewport().close_requested()) && unsaved && !self.closing {
+            tracing::debug!("close held for the unsaved game prompt");
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.open = true;
+        }
+    }
+
+    // Closes the window for real.
+    pub fn close(&mut self, ctx: &egui::Context) {
+        self.open = false;
+        self.closing = true;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    // The user's answer, once given. Save and Discard leave the closing to the caller.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) -> Option<CloseChoice> {
+        if !self.open {
+            return None;
+        }
+        let mut choice = None;
+        egui::Window::new(tr(language, "close.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr(language, "close.message"));
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "close.save")).clicked() {
+                        choice = Some(CloseChoice::Save);
+                    }
+                    if ui.button(tr(language, "close.discard")).clicked() {
+                        choice = Some(CloseChoice::Discard);
+                    }
+                    if ui.button(tr(language, "close.cancel")).clicked() {
+                        choice = Some(CloseChoice::Cancel);
+                    }
+                });
+            });
+        if choice == Some(CloseChoice::Cancel) {
+            self.open = false;
+        }
+        choice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+
+    #[test]
+    fn test_close_is_held_until_answered() {
+        let mut prompt = ClosePrompt::default();
+        let ctx = egui::Context::default();
+        let close = egui::RawInput {
+            viewports: std::iter::once((
+                egui::ViewportId::ROOT,
+                egui::ViewportInfo { events: vec![egui::ViewportEvent::Close], ..Default::default() },
+            ))
+            .collect(),
+            ..Default::default()
+        };
+        let output = ctx.run(close.clone(), |ctx| prompt.intercept(ctx, true));
+        let commands = &output.viewport_output[&egui::ViewportId::ROOT].commands;
+        assert!(commands.contains(&egui::ViewportCommand::CancelClose));
+        assert!(prompt.open);
+
+        let mut choices = Vec::new();
+        let mut harness = UiHarness::new(600.0, 400.0);
+        let mut frame = |ui: &mut egui::Ui| choices.extend(prompt.show(ui.ctx(), Language::English));
+        harness.run(&mut frame);
+        harness.run(&mut frame);
+        harness.click("Cancel", &mut frame);
+        assert_eq!(choices, [CloseChoice::Cancel]);
+        assert!(!prompt.open);
+
+        prompt.close(&ctx);
+        let output = ctx.run(close, |ctx| prompt.intercept(ctx, true));
+        let commands = &output.viewport_output[&egui::ViewportId::ROOT].commands;
+        assert_eq!(commands, &[egui::ViewportCommand::Close]);
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use coast_to_coast::saves::{AUTOSAVE_FILE, SAVES_DIR};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+// The settings as last synced, kept next to the saves so they sync like any other file.
+const SETTINGS_FILE: &str = "settings.json";
+// Per synced file, its local and remote modification times after the last sync.
+const STATE_FILE: &str = "sync-state.txt";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+type SyncError = Box<dyn std::error::Error + Send + Sync>;
+
+// Where to sync saved games and settings to; an empty URL leaves syncing off. The password is
+// stored with the other settings, in plain text, but is never synced itself.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+}
+
+// A file on the server, by its path relative to the sync root, e.g. "games/game-1.txt".
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteFile {
+    pub path: String,
+    // Seconds since the epoch.
+    pub modified: u64,
+}
+
+// Storage the data directory can be synced with. Paths are relative to its root and use "/".
+pub trait SyncBackend: Send {
+    // The files directly in `dir` ("" for the root); a directory that doesn't exist is empty.
+    fn list(&self, dir: &str) -> Result<Vec<RemoteFile>, SyncError>;
+    fn get(&self, path: &str) -> Result<Vec<u8>, SyncError>;
+    fn put(&self, path: &str, contents: &[u8]) -> Result<(), SyncError>;
+}
+
+pub struct WebDav {
+    // Ends with "/".
+    root: String,
+    authorization: Option<String>,
+}
+
+impl WebDav {
+    pub fn new(config: &SyncConfig) -> Self {
+        let root = format!("{}/", config.url.trim().trim_end_matches('/'));
+        let authorization = (!config.username.is_empty())
+            .then(|| format!("Basic {}", base64(format!("{}:{}", config.username, config.password).as_bytes())));
+        Self { root, authorization }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let request = ureq::request(method, &format!("{}{}", self.root, path))
+            .timeout(TIMEOUT)
+            .set("User-Agent", concat!("coast-to-coast/", env!("CARGO_PKG_VERSION")));
+        match &self.authorization {
+            Some(authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+}
+
+impl SyncBackend for WebDav {
+    fn list(&self, dir: &str) -> Result<Vec<RemoteFile>, SyncError> {
+        let body = concat!(
+            r#"<?xml version="1.0"?>"#,
+            r#"<propfind xmlns="DAV:"><prop><getlastmodified/><resourcetype/></prop></propfind>"#
+        );
+        let response = match self
+            .request("PROPFIND", dir)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml")
+            .send_string(body)
+        {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(parse_listing(&response.into_string()?, dir))
+    }
+
+    fn get(&self, path: &str) -> Result<Vec<u8>, SyncError> {
+        let mut contents = Vec::new();
+        self.request("GET", path).call()?.into_reader().read_to_end(&mut contents)?;
+        Ok(contents)
+    }
+
+    fn put(&self, path: &str, contents: &[u8]) -> Result<(), SyncError> {
+        match self.request("PUT", path).send_bytes(contents) {
+            // The directory doesn't exist yet.
+            Err(ureq::Error::Status(409, _)) if path.contains('/') => {
+                let (dir, _) = path.rsplit_once('/').unwrap_or_default();
+                self.request("MKCOL", &format!("{}/", dir)).call()?;
+                self.request("PUT", path).send_bytes(contents)?;
+            }
+            result => {
+                result?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The files in a PROPFIND multistatus answer for `dir`; the directory itself and any
+// subdirectories are left out.
+fn parse_listing(xml: &str, dir: &str) -> Vec<RemoteFile> {
+    elements(xml, "response")
+        .into_iter()
+        .filter(|response| !elements(response, "resourcetype").iter().any(|kind| kind.contains("collection")))
+        .filter_map(|response| {
+            let href = elements(response, "href").into_iter().next()?.trim();
+            let name = percent_decode(href.rsplit('/').next()?);
+            let modified = parse_http_date(elements(response, "getlastmodified").into_iter().next()?)?;
+            (!name.is_empty()).then(|| RemoteFile { path: format!("{}{}", dir, name), modified })
+        })
+        .collect()
+}
+
+// The contents of every `name` element in `xml`, whatever namespace prefix it carries.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let tag_end = rest.find(|c: char| c.is_whitespace() || c == '>' || c == '/').unwrap_or(rest.len());
+        let tag = &rest[..tag_end];
+        if tag.rsplit(':').next() != Some(name) {
+            continue;
+        }
+        let Some(open_end) = rest.find('>') else {
+            break;
+        };
+        if rest[..open_end].ends_with('/') {
+            continue;
+        }
+        let content = &rest[open_end + 1..];
+        let Some(close) = content.find(&format!("</{}>", tag)) else {
+            break;
+        };
+        found.push(&content[..close]);
+        rest = &content[close..];
+    }
+    found
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// An RFC 1123 date as WebDAV servers send it, e.g. "Wed, 15 Oct 2026 10:00:00 GMT", in seconds
+// since the epoch.
+fn parse_http_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [_, day, month, year, time, ..] = parts.as_slice() else {
+        return None;
+    };
+    let day: i64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|name| name == month)? as i64 + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (hours, minutes, seconds) = (clock.next()??, clock.next()??, clock.next()??);
+    // Days from the epoch to the date, counting years from March so leap days come last.
+    let (year, month) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400 + hours * 3_600 + minutes * 60 + seconds).ok()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (i, byte)| word | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(word >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    Push(String),
+    Pull(String),
+}
+
+// What to copy which way, given each side's modification times now and after the last sync.
+// A file changed on one side only is copied from it; when both changed, or it was never
+// synced, the newer copy wins.
+fn plan(local: &BTreeMap<String, u64>, remote: &BTreeMap<String, u64>, last: &BTreeMap<String, (u64, u64)>) -> Vec<Step> {
+    let mut paths: Vec<&String> = local.keys().chain(remote.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .filter_map(|path| match (local.get(path), remote.get(path)) {
+            (Some(_), None) => Some(Step::Push(path.clone())),
+            (None, Some(_)) => Some(Step::Pull(path.clone())),
+            (Some(local), Some(remote)) => {
+                let (local_changed, remote_changed) = match last.get(path) {
+                    Some((last_local, last_remote)) => (local != last_local, remote != last_remote),
+                    None => (true, true),
+                };
+                match (local_changed, remote_changed) {
+                    (false, false) => None,
+                    (true, false) => Some(Step::Push(path.clone())),
+                    (false, true) => Some(Step::Pull(path.clone())),
+                    (true, true) if local == remote => None,
+                    (true, true) if local > remote => Some(Step::Push(path.clone())),
+                    (true, true) => Some(Step::Pull(path.clone())),
+                }
+            }
+            (None, None) => None,
+        })
+        .collect()
+}
+
+// The synced files in the data directory with their modification times: saved games other
+// than the autosave, which each device keeps for itself, and the settings.
+fn local_files(data_dir: &Path) -> BTreeMap<String, u64> {
+    let mut paths = vec![SETTINGS_FILE.to_string()];
+    if let Ok(entries) = fs::read_dir(data_dir.join(SAVES_DIR)) {
+        paths.extend(
+            entries
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .filter(|name| name.ends_with(".txt") && name != AUTOSAVE_FILE)
+                .map(|name| format!("{}/{}", SAVES_DIR, name)),
+        );
+    }
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(data_dir.join(&path)).and_then(|metadata| metadata.modified()).ok()?;
+            Some((path, modified.duration_since(UNIX_EPOCH).ok()?.as_secs()))
+        })
+        .collect()
+}
+
+fn remote_files(backend: &dyn SyncBackend) -> Result<BTreeMap<String, u64>, SyncError> {
+    let mut files = BTreeMap::new();
+    for file in backend.list("")?.into_iter().chain(backend.list(&format!("{}/", SAVES_DIR))?) {
+        if file.path == SETTINGS_FILE || file.path.starts_with(&format!("{}/", SAVES_DIR)) {
+            files.insert(file.path, file.modified);
+        }
+    }
+    files.remove(&format!("{}/{}", SAVES_DIR, AUTOSAVE_FILE));
+    Ok(files)
+}
+
+fn load_state(data_dir: &Path) -> BTreeMap<String, (u64, u64)> {
+    let text = fs::read_to_string(data_dir.join(STATE_FILE)).unwrap_or_default();
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let path = fields.next()?.to_string();
+            Some((path, (fields.next()?.parse().ok()?, fields.next()?.parse().ok()?)))
+        })
+        .collect()
+}
+
+fn save_state(data_dir: &Path, local: &BTreeMap<String, u64>, remote: &BTreeMap<String, u64>) -> std::io::Result<()> {
+    let text: String = local
+        .iter()
+        .filter_map(|(path, local)| Some(format!("{}\t{}\t{}\n", path, local, remote.get(path)?)))
+        .collect();
+    fs::write(data_dir.join(STATE_FILE), text)
+}
+
+// Rewrites the settings file when the settings changed since it was last written, so its
+// modification time says when they did. The first one written is dated 1970, so a new
+// device takes the settings already on the server rather than replacing them with its own.
+// The sync configuration stays on this device.
+fn write_settings(data_dir: &Path, settings: &Settings) -> Result<(), SyncError> {
+    let settings = Settings { sync: SyncConfig::default(), ..settings.clone() };
+    let text = ureq::serde_json::to_string_pretty(&settings)?;
+    let path = data_dir.join(SETTINGS_FILE);
+    match fs::read_to_string(&path) {
+        Ok(written) if written == text => {}
+        Ok(_) => fs::write(path, text)?,
+        Err(_) => {
+            fs::write(&path, text)?;
+            fs::File::options().append(true).open(&path)?.set_modified(UNIX_EPOCH)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    // Settings that came from the server, to replace all but the sync configuration.
+    pub settings: Option<Box<Settings>>,
+}
+
+fn sync(backend: &dyn SyncBackend, data_dir: &Path) -> Result<SyncReport, SyncError> {
+    let steps = plan(&local_files(data_dir), &remote_files(backend)?, &load_state(data_dir));
+    let mut report = SyncReport::default();
+    for step in steps {
+        match step {
+            Step::Push(path) => {
+                backend.put(&path, &fs::read(data_dir.join(&path))?)?;
+                report.pushed += 1;
+            }
+            Step::Pull(path) => {
+                let contents = backend.get(&path)?;
+                if path == SETTINGS_FILE {
+                    report.settings = Some(Box::new(ureq::serde_json::from_slice(&contents)?));
+                }
+                let local = data_dir.join(&path);
+                if let Some(dir) = local.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&local, contents)?;
+                report.pulled += 1;
+            }
+        }
+    }
+    // Pushed files have new times on the server, so the state is taken from a fresh listing.
+    save_state(data_dir, &local_files(data_dir), &remote_files(backend)?)?;
+    Ok(report)
+}
+
+// Runs one sync at a time in the background and hands back its outcome.
+#[derive(Default)]
+pub struct CloudSync {
+    pending: Option<Receiver<Result<SyncReport, String>>>,
+}
+
+impl CloudSync {
+    pub fn running(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn start(&mut self, ctx: &egui::Context, data_dir: &Path, settings: &Settings) {
+        if let Err(e) = write_settings(data_dir, settings) {
+            tracing::warn!(error = %e, "failed to write settings for syncing");
+        }
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let data_dir = data_dir.to_path_buf();
+        let backend = WebDav::new(&settings.sync);
+        thread::spawn(move || {
+            let result = sync(&backend, &data_dir).map_err(|e| e.to_string());
+            if let Err(e) = &result {
+                tracing::warn!(error = %e, "sync failed");
+            }
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.pending = Some(receiver);
+    }
+
+    // The outcome of the sync started last, once it has finished.
+    pub fn poll(&mut self) -> Option<Result<SyncReport, String>> {
+        let result = self.pending.as_ref()?.try_recv().ok()?;
+        self.pending = None;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_copies_the_changed_or_newer_side() {
+        let files = |entries: &[(&str, u64)]| -> BTreeMap<String, u64> {
+            entries.iter().map(|(path, time)| (path.to_string(), *time)).collect()
+        };
+        let local = files(&[("a", 10), ("b", 10), ("c", 30), ("d", 50), ("e", 10)]);
+        let remote = files(&[("a", 20), ("b", 20), ("c", 40), ("d", 40), ("f", 10)]);
+        let last = [("a", (10, 20)), ("b", (5, 15)), ("c", (30, 20)), ("d", (20, 20))]
+            .into_iter()
+            .map(|(path, times)| (path.to_string(), times))
+            .collect();
+        // a is unchanged; b changed on both sides and the server's copy is newer; c changed
+        // only on the server; d changed on both and here is newer.
+        assert_eq!(
+            plan(&local, &remote, &last),
+            [
+                Step::Pull("b".to_string()),
+                Step::Pull("c".to_string()),
+                Step::Push("d".to_string()),
+                Step::Push("e".to_string()),
+                Step::Pull("f".to_string()),
+            ]
+        );
+
+        let xml = r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/dav/games/</d:href><d:propstat><d:prop>
+              <d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>
+            <d:response><d:href>/dav/games/game%201.txt</d:href><d:propstat><d:prop>
+              <d:getlastmodified>Thu, 01 Jan 1970 00:01:40 GMT</d:getlastmodified>
+              <d:resourcetype/></d:prop></d:propstat></d:response></d:multistatus>"#;
+        assert_eq!(parse_listing(xml, "games/"), [RemoteFile { path: "games/game 1.txt".to_string(), modified: 100 }]);
+        assert_eq!(parse_http_date("Thu, 15 Oct 2026 10:00:00 GMT"), Some(1_792_058_400));
+        assert_eq!(base64(b"user:pass"), "dXNlcjpwYXNz");
+    }
+}
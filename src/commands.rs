@@ -0,0 +1,144 @@
+//! Text commands for moves and admin actions, typed as a line such as `play c5`, `undo 2`,
+//! `size 13`, `engine level 3` or `save foo.sgf`. A leading `:` is allowed, as typed in the
+//! app's command line, and a move on its own is short for `play`.
+//!
+//! Parsing only checks the form of a command; whether it can be carried out now is up to
+//! whoever runs it.
+//!
+//! ```
+//! use coast_to_coast::commands::{self, Command};
+//!
+//! assert_eq!(commands::parse(":undo 2").unwrap(), Command::Undo(2));
+//! assert_eq!(commands::parse("size 13").unwrap(), Command::Size(13));
+//! assert!(commands::parse("size 0").is_err());
+//! ```
+
+use std::fmt;
+
+use crate::game::MAX_BOARD_SIZE;
+use crate::notation::{Move, ParseMoveError};
+
+/// A command, as parsed from a line.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Play(Move),
+    /// Take back this many moves.
+    Undo(usize),
+    Redo,
+    Resign,
+    NewGame,
+    /// Start a new game on a board of this size.
+    Size(i32),
+    /// Set the engine's playing strength, from 1 up.
+    EngineLevel(u32),
+    /// Save the game to this file; an `.sgf` name is saved as SGF.
+    Save(String),
+    Help,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    Empty,
+    Unknown(String),
+    MissingArgument(&'static str),
+    InvalidArgument(String),
+    InvalidMove(ParseMoveError),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "empty command"),
+            CommandError::Unknown(command) => write!(f, "unknown command '{}'", command),
+            CommandError::MissingArgument(argument) => write!(f, "missing {}", argument),
+            CommandError::InvalidArgument(argument) => write!(f, "invalid argument '{}'", argument),
+            CommandError::InvalidMove(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// One line of every command, for help output.
+pub const USAGE: [&str; 9] = [
+    "play <move>       play a move, e.g. play c5, play swap, play pass",
+    "undo [n]          take back the last move, or the last n",
+    "redo              replay a move taken back",
+    "resign            the player to move resigns",
+    "new               start a new game",
+    "size <n>          start a new game on an n×n board",
+    "engine level <n>  set the engine's strength",
+    "save <file>       save the game; .sgf files as SGF",
+    "help              list the commands",
+];
+
+/// Parses one command line.
+pub fn parse(line: &str) -> Result<Command, CommandError> {
+    let line = line.trim();
+    let line = line.strip_prefix(':').unwrap_or(line).trim();
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or(CommandError::Empty)?.to_ascii_lowercase();
+    let rest: Vec<&str> = words.collect();
+    let argument = |what| rest.first().copied().ok_or(CommandError::MissingArgument(what));
+    let number = |text: &str| text.parse::<u32>().map_err(|_| CommandError::InvalidArgument(text.to_string()));
+    let command = match name.as_str() {
+        "play" | "p" => Command::Play(argument("move")?.parse().map_err(CommandError::InvalidMove)?),
+        "undo" | "u" => match rest.first() {
+            Some(count) => match number(count)? {
+                0 => return Err(CommandError::InvalidArgument(count.to_string())),
+                count => Command::Undo(count as usize),
+            },
+            None => Command::Undo(1),
+        },
+        "redo" => Command::Redo,
+        "resign" => Command::Resign,
+        "new" => Command::NewGame,
+        "size" => {
+            let size = argument("board size")?;
+            match number(size)? as i32 {
+                size @ 1..=MAX_BOARD_SIZE => Command::Size(size),
+                _ => return Err(CommandError::InvalidArgument(size.to_string())),
+            }
+        }
+        "engine" => match rest.as_slice() {
+            ["level", level] => match number(level)? {
+                0 => return Err(CommandError::InvalidArgument(level.to_string())),
+                level => Command::EngineLevel(level),
+            },
+            ["level"] => return Err(CommandError::MissingArgument("engine level")),
+            _ => return Err(CommandError::Unknown(line.to_string())),
+        },
+        "save" if rest.is_empty() => return Err(CommandError::MissingArgument("file name")),
+        // File names may have spaces.
+        "save" => Command::Save(rest.join(" ")),
+        "help" | "?" => Command::Help,
+        _ if rest.is_empty() => match line.parse() {
+            Ok(mv) => Command::Play(mv),
+            Err(_) => return Err(CommandError::Unknown(name)),
+        },
+        _ => return Err(CommandError::Unknown(name)),
+    };
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Hex;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(":play c5"), Ok(Command::Play(Move::Place(Hex { q: 2, r: 4 }))));
+        assert_eq!(parse("c5"), Ok(Command::Play(Move::Place(Hex { q: 2, r: 4 }))));
+        assert_eq!(parse("play swap"), Ok(Command::Play(Move::Swap)));
+        assert_eq!(parse("undo"), Ok(Command::Undo(1)));
+        assert_eq!(parse("Engine level 3"), Ok(Command::EngineLevel(3)));
+        assert_eq!(parse("save my game.sgf"), Ok(Command::Save("my game.sgf".to_string())));
+        assert_eq!(parse(":"), Err(CommandError::Empty));
+        assert_eq!(parse("save"), Err(CommandError::MissingArgument("file name")));
+        assert_eq!(parse("size 99"), Err(CommandError::InvalidArgument("99".to_string())));
+        assert_eq!(parse("undo two"), Err(CommandError::InvalidArgument("two".to_string())));
+        assert_eq!(parse("play 5c"), Err(CommandError::InvalidMove(ParseMoveError::MissingColumn)));
+        assert_eq!(parse("fly away"), Err(CommandError::Unknown("fly".to_string())));
+    }
+}
@@ -0,0 +1,120 @@
+use eframe::egui;
+
+use crate::i18n::{tr, Language};
+
+// Replies kept on screen above the input.
+const SCROLLBACK: usize = 6;
+
+// The in-app command line: `:` opens it at the bottom of the window, Enter runs the line and
+// Escape closes it. Lines are parsed by `coast_to_coast::commands`.
+#[derive(Default)]
+pub struct CommandLine {
+    pub open: bool,
+    input: String,
+    // Lines run and their replies, oldest first.
+    scrollback: Vec<String>,
+    // Lines run, for Up and Down to bring back.
+    history: Vec<String>,
+    recalled: Option<usize>,
+}
+
+impl CommandLine {
+    // Opens on `:` unless a text field is taking the keys.
+    pub fn check_open(&mut self, ctx: &egui::Context) {
+        if self.open || ctx.wants_keyboard_input() {
+            return;
+        }
+        if ctx.input(|i| i.events.iter().any(|event| matches!(event, egui::Event::Text(text) if text == ":"))) {
+            self.open = true;
+            self.input.clear();
+            self.recalled = None;
+        }
+    }
+
+    pub fn reply(&mut self, line: &str, reply: &str) {
+        self.scrollback.push(format!(":{}", line));
+        self.scrollback.extend(reply.lines().map(str::to_string));
+        let excess = self.scrollback.len().saturating_sub(SCROLLBACK);
+        self.scrollback.drain(..excess);
+    }
+
+    // The line entered this frame, if any.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+        let mut entered = None;
+        egui::TopBottomPanel::bottom("command_line").show(ctx, |ui| {
+            for line in &self.scrollback {
+                ui.monospace(line);
+            }
+            ui.horizontal(|ui| {
+                ui.monospace(":");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.input)
+                        .font(egui::TextStyle::Monospace)
+                        .hint_text(tr(language, "console.hint"))
+                        .desired_width(f32::INFINITY),
+                );
+                if !response.has_focus() && !response.lost_focus() {
+                    response.request_focus();
+                }
+                let (enter, escape, up, down) = ui.input(|i| {
+                    let key = |key| i.key_pressed(key);
+                    (key(egui::Key::Enter), key(egui::Key::Escape), key(egui::Key::ArrowUp), key(egui::Key::ArrowDown))
+                });
+                if escape {
+                    self.open = false;
+                } else if enter && response.lost_focus() {
+                    let line = std::mem::take(&mut self.input);
+                    if !line.trim().is_empty() {
+                        self.history.push(line.clone());
+                        entered = Some(line);
+                    }
+                    self.recalled = None;
+                    response.request_focus();
+                } else if up || down {
+                    self.recall(up);
+                }
+            });
+        });
+        entered
+    }
+
+    fn recall(&mut self, older: bool) {
+        let recalled = match (self.recalled, older) {
+            (None, true) => self.history.len().checked_sub(1),
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < self.history.len() => Some(i + 1),
+            _ => None,
+        };
+        self.recalled = recalled;
+        self.input = recalled.map(|i| self.history[i].clone()).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrollback_and_recall() {
+        let mut console = CommandLine::default();
+        for i in 0..4 {
+            console.reply(&format!("undo {}", i), "ok");
+        }
+        assert_eq!(console.scrollback.len(), SCROLLBACK);
+        assert_eq!(console.scrollback[0], ":undo 1");
+
+        console.history = vec!["c3".to_string(), "d4".to_string()];
+        console.recall(true);
+        assert_eq!(console.input, "d4");
+        console.recall(true);
+        console.recall(true);
+        assert_eq!(console.input, "c3");
+        console.recall(false);
+        assert_eq!(console.input, "d4");
+        console.recall(false);
+        assert_eq!(console.input, "");
+    }
+}
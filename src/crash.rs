@@ -0,0 +1,124 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::diagnostics::LogBuffer;
+
+const CRASH_DIR: &str = "crash-reports";
+const PENDING_MARKER: &str = "pending";
+const LOG_TAIL_LINES: usize = 200;
+
+// Snapshot of game state and config, refreshed by the app every frame so the panic hook can
+// write it out without touching the (possibly poisoned) app itself.
+#[derive(Clone, Default)]
+pub struct CrashContext {
+    state: Arc<Mutex<CrashState>>,
+}
+
+#[derive(Default)]
+struct CrashState {
+    game: String,
+    config: String,
+}
+
+impl CrashContext {
+    pub fn update(&self, game: String, config: String) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.game = game;
+        state.config = config;
+    }
+
+    fn snapshot(&self) -> (String, String) {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        (state.game.clone(), state.config.clone())
+    }
+}
+
+pub fn install(data_dir: Option<PathBuf>, log: LogBuffer, context: CrashContext) {
+    let Some(data_dir) = data_dir else {
+        return;
+    };
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let panic = format!("{}\n\n{}", info, backtrace);
+        let (game, config) = context.snapshot();
+        let lines = log.lines();
+        let tail = &lines[lines.len().saturating_sub(LOG_TAIL_LINES)..];
+        if let Err(e) = write_bundle(&data_dir, unix_timestamp(), &panic, &game, &config, tail) {
+            eprintln!("failed to write crash report: {}", e);
+        }
+    }));
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn write_bundle(
+    data_dir: &Path,
+    timestamp: u64,
+    panic: &str,
+    game: &str,
+    config: &str,
+    log_tail: &[String],
+) -> io::Result<PathBuf> {
+    let crash_dir = data_dir.join(CRASH_DIR);
+    let bundle = crash_dir.join(format!("crash-{}", timestamp));
+    fs::create_dir_all(&bundle)?;
+    fs::write(bundle.join("panic.txt"), panic)?;
+    fs::write(bundle.join("game.txt"), game)?;
+    fs::write(bundle.join("config.txt"), config)?;
+    fs::write(bundle.join("log.txt"), log_tail.join("\n"))?;
+    fs::write(crash_dir.join(PENDING_MARKER), bundle.to_string_lossy().as_bytes())?;
+    Ok(bundle)
+}
+
+// Returns the bundle written by a previous crashed session, at most once.
+pub fn take_pending_report(data_dir: &Path) -> Option<PathBuf> {
+    let marker = data_dir.join(CRASH_DIR).join(PENDING_MARKER);
+    let bundle = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(&marker);
+    Some(PathBuf::from(bundle.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hex-crash-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_write_bundle_and_take_pending_report() {
+        let dir = temp_dir("bundle");
+        let tail = vec!["INFO move played".to_string()];
+        let bundle = write_bundle(&dir, 42, "boom", "R . .", "language: English", &tail).unwrap();
+
+        assert!(bundle.ends_with("crash-42"));
+        assert_eq!(fs::read_to_string(bundle.join("panic.txt")).unwrap(), "boom");
+        assert_eq!(fs::read_to_string(bundle.join("game.txt")).unwrap(), "R . .");
+        assert_eq!(fs::read_to_string(bundle.join("log.txt")).unwrap(), "INFO move played");
+
+        assert_eq!(take_pending_report(&dir), Some(bundle));
+        // The marker is consumed so the dialog only appears once.
+        assert_eq!(take_pending_report(&dir), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_no_pending_report() {
+        let dir = temp_dir("none");
+        assert_eq!(take_pending_report(&dir), None);
+    }
+}
@@ -0,0 +1,158 @@
+//! Dark Hex (Phantom Hex), the imperfect-information variant.
+//!
+//! Each player sees only their own stones. Trying to place on a cell the opponent already
+//! holds reveals that stone to the player, who then tries again. [`DarkHex`] is the referee:
+//! it keeps the true game plus one view per player. There is no swap in Dark Hex.
+
+use crate::ai::AiPlayer;
+use crate::board::{Board, CellState, Hex};
+use crate::game::{Game, GameState};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attempt {
+    /// The stone was placed and the turn passed.
+    Placed,
+    /// The opponent already holds the cell; it is now revealed and the same player moves again.
+    Collision,
+    /// The stone was placed and won the game.
+    Won,
+}
+
+#[derive(Clone)]
+pub struct DarkHex {
+    game: Game,
+    red_view: Board,
+    blue_view: Board,
+}
+
+impl DarkHex {
+    pub fn new(size: i32) -> Self {
+        Self { game: Game::with_size(size).with_pie_rule(false), red_view: Board::new(size), blue_view: Board::new(size) }
+    }
+
+    /// The true game. Only the referee (and spectators after the game) should look at it.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn current_player(&self) -> CellState {
+        self.game.current_player
+    }
+
+    pub fn winner(&self) -> Option<CellState> {
+        match self.game.state {
+            GameState::Finished { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    /// What `player` knows: their own stones plus the opponent stones revealed to them.
+    pub fn view(&self, player: CellState) -> &Board {
+        match player {
+            CellState::Blue => &self.blue_view,
+            _ => &self.red_view,
+        }
+    }
+
+    /// `player`'s view as a game with them to move, so any [`AiPlayer`] can choose from it.
+    pub fn view_game(&self, player: CellState) -> Game {
+        let mut game = Game::with_size(self.game.board.size);
+        game.board = self.view(player).clone();
+        game.current_player = player;
+        game.state = self.game.state;
+        game
+    }
+
+    /// Plays `hex` for the player to move. Errors when the game is over, the cell is off the
+    /// board, or the player already knows the cell is taken.
+    pub fn attempt(&mut self, hex: Hex) -> Result<Attempt, &'static str> {
+        let player = self.game.current_player;
+        if self.winner().is_some() {
+            return Err("Game is already finished");
+        }
+        match self.view(player).get_cell(&hex) {
+            None => return Err("Hex is out of bounds"),
+            Some(CellState::Empty) => {}
+            Some(_) => return Err("Cell is already known to be taken"),
+        }
+
+        if let Some(owner) = self.game.board.get_cell(&hex).copied().filter(|owner| *owner != CellState::Empty) {
+            self.view_mut(player).set_cell(hex, owner);
+            return Ok(Attempt::Collision);
+        }
+
+        self.game.handle_click(hex);
+        self.view_mut(player).set_cell(hex, player);
+        Ok(if self.winner().is_some() { Attempt::Won } else { Attempt::Placed })
+    }
+
+    /// Lets `policy` move for the player to move from that player's view, retrying after each
+    /// collision. Returns the cell finally played, or None if the policy gave up.
+    pub fn play_ai(&mut self, policy: &mut dyn AiPlayer) -> Option<Hex> {
+        let player = self.game.current_player;
+        loop {
+            let hex = policy.choose_move(&self.view_game(player))?;
+            match self.attempt(hex) {
+                Ok(Attempt::Collision) => continue,
+                Ok(_) => return Some(hex),
+                Err(_) => return None,
+            }
+        }
+    }
+
+    fn view_mut(&mut self, player: CellState) -> &mut Board {
+        match player {
+            CellState::Blue => &mut self.blue_view,
+            _ => &mut self.red_view,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::RandomPlayer;
+
+    #[test]
+    fn test_collision_reveals_and_keeps_turn() {
+        let mut dark = DarkHex::new(3);
+        assert_eq!(dark.attempt(Hex { q: 1, r: 1 }), Ok(Attempt::Placed));
+        // No pie rule: Blue simply moves.
+        assert_eq!(dark.current_player(), CellState::Blue);
+        assert_eq!(dark.view(CellState::Blue).get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Empty));
+
+        assert_eq!(dark.attempt(Hex { q: 1, r: 1 }), Ok(Attempt::Collision));
+        assert_eq!(dark.current_player(), CellState::Blue);
+        assert_eq!(dark.view(CellState::Blue).get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Red));
+        assert!(dark.attempt(Hex { q: 1, r: 1 }).is_err());
+
+        assert_eq!(dark.attempt(Hex { q: 0, r: 0 }), Ok(Attempt::Placed));
+        assert_eq!(dark.view(CellState::Red).get_cell(&Hex { q: 0, r: 0 }), Some(&CellState::Empty));
+    }
+
+    #[test]
+    fn test_win_detection_uses_true_board() {
+        let mut dark = DarkHex::new(2);
+        assert_eq!(dark.attempt(Hex { q: 0, r: 0 }), Ok(Attempt::Placed));
+        assert_eq!(dark.attempt(Hex { q: 0, r: 1 }), Ok(Attempt::Placed));
+        assert_eq!(dark.attempt(Hex { q: 1, r: 0 }), Ok(Attempt::Won));
+        assert_eq!(dark.winner(), Some(CellState::Red));
+        assert!(dark.attempt(Hex { q: 1, r: 1 }).is_err());
+    }
+
+    #[test]
+    fn test_ai_games_finish() {
+        let mut red = RandomPlayer::seeded(1);
+        let mut blue = RandomPlayer::seeded(2);
+        for size in 1..=5 {
+            let mut dark = DarkHex::new(size);
+            while dark.winner().is_none() {
+                let policy: &mut dyn AiPlayer = match dark.current_player() {
+                    CellState::Red => &mut red,
+                    _ => &mut blue,
+                };
+                assert!(dark.play_ai(policy).is_some());
+            }
+        }
+    }
+}
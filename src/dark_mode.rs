@@ -0,0 +1,159 @@
+use std::time::Instant;
+
+use coast_to_coast::ai::RandomPlayer;
+use coast_to_coast::board::CellState;
+use coast_to_coast::dark::{Attempt, DarkHex};
+use eframe::egui;
+
+use crate::ai_opponent::{thinking_overlay, AiConfig, PendingReply};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::renderer::BoardRenderer;
+
+// A Dark Hex game on screen: the user plays Red against the random AI and sees only what the
+// referee has shown them. The true board is revealed once the game ends.
+//
+// In hot-seat mode two people share the machine instead, each seeing only their own view.
+// Between turns the board is blanked until the next player says they have the screen, so
+// neither sees the other's stones.
+pub struct DarkSession {
+    referee: DarkHex,
+    ai: RandomPlayer,
+    human: CellState,
+    message: Option<String>,
+    reply: Option<PendingReply>,
+    hot_seat: bool,
+    // The board is hidden while the machine changes hands.
+    handing_over: bool,
+}
+
+impl DarkSession {
+    pub fn new(size: i32) -> Self {
+        Self {
+            referee: DarkHex::new(size),
+            ai: RandomPlayer::new(),
+            human: CellState::Red,
+            message: None,
+            reply: None,
+            hot_seat: false,
+            handing_over: false,
+        }
+    }
+
+    // Starts over, against the computer or hot seat. A hot-seat game opens on the hand-over
+    // screen so Red's first view is private too.
+    fn restart(&mut self, hot_seat: bool) {
+        *self = Self { hot_seat, handing_over: hot_seat, ..Self::new(self.referee.game().board.size) };
+    }
+
+    // Returns true once the user asks to leave.
+    pub fn show(&mut self, ui: &mut egui::Ui, renderer: &mut BoardRenderer, ai: &AiConfig, language: Language) -> bool {
+        let mut leave = false;
+        ui.horizontal(|ui| {
+            ui.heading(tr(language, "dark.title"));
+            leave = ui.button(tr(language, "dark.leave")).clicked();
+        });
+        ui.horizontal(|ui| {
+            let mut hot_seat = self.hot_seat;
+            ui.radio_value(&mut hot_seat, false, tr(language, "dark.vs_ai"));
+            ui.radio_value(&mut hot_seat, true, tr(language, "dark.hot_seat"));
+            if hot_seat != self.hot_seat {
+                self.restart(hot_seat);
+            }
+        });
+        ui.label(tr(language, if self.hot_seat { "dark.hot_seat_hint" } else { "dark.hint" }));
+
+        match self.referee.winner() {
+            Some(winner) => {
+                ui.strong(tr_args(language, "game.winner", &[("winner", player_name(language, winner))]));
+                renderer.render_board(ui, self.referee.game(), language);
+            }
+            None if self.handing_over => {
+                let player = player_name(language, self.referee.current_player());
+                ui.vertical_centered(|ui| {
+                    ui.add_space(ui.available_height() / 3.0);
+                    ui.heading(tr_args(language, "dark.hand_over", &[("player", player)]));
+                    ui.label(tr_args(language, "dark.hand_over_hint", &[("player", player)]));
+                    if ui.button(tr_args(language, "dark.show_board", &[("player", player)])).clicked() {
+                        self.handing_over = false;
+                    }
+                });
+            }
+            None => {
+                let now = Instant::now();
+                if let Some(reply) = self.reply {
+                    match reply.remaining(now) {
+                        Some(remaining) => ui.ctx().request_repaint_after(remaining),
+                        None => {
+                            self.reply = None;
+                            self.referee.play_ai(&mut self.ai);
+                            self.message = Some(tr(language, "dark.ai_moved").to_string());
+                        }
+                    }
+                }
+                let mover = if self.hot_seat { self.referee.current_player() } else { self.human };
+                if self.hot_seat {
+                    ui.horizontal(|ui| {
+                        ui.strong(tr_args(language, "dark.to_move", &[("player", player_name(language, mover))]));
+                        if ui.button(tr(language, "dark.hide_board")).clicked() {
+                            self.handing_over = true;
+                        }
+                    });
+                }
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+                let view = self.referee.view_game(mover);
+                let board_rect = ui.available_rect_before_wrap();
+                let clicked = renderer.render_board(ui, &view, language);
+                if self.reply.is_some() {
+                    thinking_overlay(ui.ctx(), board_rect, language);
+                } else if let Some(hex) = clicked {
+                    self.message = match self.referee.attempt(hex) {
+                        Ok(Attempt::Collision) => {
+                            Some(tr_args(language, "dark.collision", &[("cell", &hex.notation())]))
+                        }
+                        Ok(Attempt::Placed) if self.hot_seat => {
+                            self.handing_over = true;
+                            None
+                        }
+                        Ok(Attempt::Placed) => {
+                            self.reply = Some(PendingReply::new(ai, now));
+                            ui.ctx().request_repaint();
+                            None
+                        }
+                        Ok(Attempt::Won) | Err(_) => None,
+                    };
+                }
+            }
+        }
+        leave
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+
+    #[test]
+    fn test_hot_seat_hides_the_board_between_turns() {
+        let mut session = DarkSession::new(3);
+        let mut harness = UiHarness::new(600.0, 500.0);
+        let mut renderer = BoardRenderer::new(&harness.ctx);
+        let ai = AiConfig::default();
+        let mut show = |ui: &mut egui::Ui| {
+            session.show(ui, &mut renderer, &ai, Language::English);
+        };
+        harness.run(&mut show);
+        harness.click("Two players, one screen", &mut show);
+        assert!(harness.find("a1, empty").is_none());
+        harness.click("Show Red's board", &mut show);
+        harness.click("b2, empty", &mut show);
+        assert!(harness.find("b2, Red").is_none() && harness.find("b2, empty").is_none());
+        harness.click("Show Blue's board", &mut show);
+        // Blue's view has no sign of Red's stone until Blue runs into it.
+        harness.click("b2, empty", &mut show);
+        assert!(harness.find("b2, Red").is_some());
+        assert_eq!(session.referee.current_player(), CellState::Blue);
+    }
+}
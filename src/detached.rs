@@ -0,0 +1,47 @@
+use eframe::egui;
+
+// Shows `add_contents` in an OS window of its own, for a second monitor. Backends that only have
+// the one window get an egui window inside it instead. Returns whether the user closed it.
+pub fn show(
+    ctx: &egui::Context,
+    id: &str,
+    title: &str,
+    size: [f32; 2],
+    mut add_contents: impl FnMut(&mut egui::Ui),
+) -> bool {
+    let builder = egui::ViewportBuilder::default().with_title(title).with_inner_size(size);
+    ctx.show_viewport_immediate(egui::ViewportId::from_hash_of(id), builder, |ctx, class| {
+        if class == egui::ViewportClass::Embedded {
+            let mut open = true;
+            egui::Window::new(title)
+                .id(egui::Id::new(id))
+                .open(&mut open)
+                .default_size(size)
+                .show(ctx, |ui| add_contents(ui));
+            return !open;
+        }
+        egui::CentralPanel::default().show(ctx, |ui| add_contents(ui));
+        ctx.input(|i| i.viewport().close_requested())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_viewports_the_contents_go_in_a_window() {
+        let ctx = egui::Context::default();
+        let mut shown = 0;
+        let mut closed = true;
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            closed = show(ctx, "detached_test", "Detached", [200.0, 100.0], |ui| {
+                ui.label("contents");
+                shown += 1;
+            });
+        });
+        assert_eq!(shown, 1);
+        assert!(!closed);
+        assert!(ctx.memory(|m| m.layer_ids().any(|layer| layer.id == egui::Id::new("detached_test"))));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use eframe::egui;
+use tracing::field::{Field, Visit};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::frame_stats::FrameStats;
+
+const LOG_FILE_PREFIX: &str = "hex.log";
+const MAX_CONSOLE_LINES: usize = 500;
+
+// Recent log lines kept in memory for the debug console and diagnostics reports.
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    pub fn push(&self, line: String) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == MAX_CONSOLE_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().cloned().collect()
+    }
+}
+
+struct ConsoleLayer {
+    buffer: LogBuffer,
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.buffer.push(format!(
+            "{:>5} {}: {}{}",
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        ));
+    }
+}
+
+// Installs the global subscriber: a daily rolling file in `log_dir` (when available) plus the
+// in-memory console buffer. The returned guard must live as long as the app to flush the file.
+pub fn init(log_dir: Option<&Path>) -> (LogBuffer, Option<WorkerGuard>) {
+    let buffer = LogBuffer::default();
+    let console = ConsoleLayer { buffer: buffer.clone() };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    let (file, guard) = match log_dir {
+        Some(dir) => {
+            let appender = tracing_appender::rolling::daily(dir.join("logs"), LOG_FILE_PREFIX);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(filter)
+        .with(console)
+        .with(file)
+        .try_init();
+    (buffer, guard)
+}
+
+pub struct DebugConsole {
+    pub open: bool,
+    buffer: LogBuffer,
+    pub frame_stats: FrameStats,
+}
+
+impl DebugConsole {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { open: false, buffer, frame_stats: FrameStats::default() }
+    }
+
+    // `context` is app state (settings, position) prepended to the log when copying diagnostics.
+    pub fn show(&mut self, ctx: &egui::Context, context: &str) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.open = !self.open;
+        }
+
+        let lines = self.buffer.lines();
+        let frame_stats = &mut self.frame_stats;
+        egui::Window::new("Debug Console")
+            .open(&mut self.open)
+            .default_size([520.0, 320.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Copy diagnostics").clicked() {
+                        ui.ctx().copy_text(diagnostics_report(context, &lines));
+                    }
+                    ui.checkbox(&mut frame_stats.overlay, "Frame stats overlay")
+                        .on_hover_text("FPS, frame times, shapes per frame and what asked for repaints");
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for line in &lines {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+    }
+}
+
+pub fn diagnostics_report(context: &str, lines: &[String]) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "os: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(report, "{}", context);
+    let _ = writeln!(report, "--- recent log ---");
+    for line in lines {
+        let _ = writeln!(report, "{}", line);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer_keeps_most_recent_lines() {
+        let buffer = LogBuffer::default();
+        for i in 0..MAX_CONSOLE_LINES + 10 {
+            buffer.push(format!("line {}", i));
+        }
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), MAX_CONSOLE_LINES);
+        assert_eq!(lines[0], "line 10");
+        assert_eq!(lines.last().unwrap(), &format!("line {}", MAX_CONSOLE_LINES + 9));
+    }
+
+    #[test]
+    fn test_console_layer_formats_events() {
+        let buffer = LogBuffer::default();
+        let subscriber = tracing_subscriber::registry().with(ConsoleLayer { buffer: buffer.clone() });
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(cell = "c5", "move played");
+        });
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("move played cell=c5"), "{}", lines[0]);
+    }
+
+    #[test]
+    fn test_diagnostics_report_contains_context_and_log() {
+        let report = diagnostics_report("turn: 3", &["INFO a: b".to_string()]);
+        assert!(report.contains("turn: 3"));
+        assert!(report.contains("INFO a: b"));
+    }
+}
@@ -0,0 +1,226 @@
+use std::io::{self, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use coast_to_coast::board::CellState;
+use coast_to_coast::record::GameResult;
+use serde_json::{json, Value};
+
+use crate::events::GameEvent;
+
+// Discord application id, supplied at build time since it belongs to whoever ships the build:
+//   HEX_DISCORD_CLIENT_ID=1234567890 cargo build --features discord
+const CLIENT_ID: Option<&str> = option_env!("HEX_DISCORD_CLIENT_ID");
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+
+enum Command {
+    Event(GameEvent),
+    Enable(bool),
+}
+
+// Publishes the game on screen as Discord Rich Presence. All IPC happens on a worker thread,
+// which connects lazily and retries on the next update if Discord isn't running.
+#[derive(Clone)]
+pub struct Presence {
+    sender: Sender<Command>,
+    enabled: bool,
+}
+
+impl Presence {
+    pub fn start() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || run(receiver));
+        Self { sender, enabled: false }
+    }
+
+    pub fn handle(&self, event: &GameEvent) {
+        let _ = self.sender.send(Command::Event(event.clone()));
+    }
+
+    // Cheap to call every frame; only changes reach the worker.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.enabled {
+            self.enabled = enabled;
+            let _ = self.sender.send(Command::Enable(enabled));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Activity {
+    size: i32,
+    moves: usize,
+    opponent: Option<String>,
+    result: Option<GameResult>,
+    started: u64,
+}
+
+impl Activity {
+    fn apply(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::GameStarted { size, moves, opponent } => {
+                *self = Activity {
+                    size: *size,
+                    moves: *moves,
+                    opponent: opponent.clone(),
+                    started: now(),
+                    ..Default::default()
+                };
+            }
+            GameEvent::MovePlayed { move_number, .. } => self.moves = *move_number,
+            GameEvent::GameFinished { winner } => self.result = Some(GameResult::Win(*winner)),
+            GameEvent::ResultAgreed { result } => self.result = Some(*result),
+            GameEvent::TakenBack { moves } => self.moves = *moves,
+        }
+    }
+
+    // ("Playing Hex 11x11", "Move 23 vs MCTS (hard)")
+    fn text(&self) -> (String, String) {
+        let details = format!("Playing Hex {}x{}", self.size, self.size);
+        let mut state = match self.result {
+            Some(GameResult::Win(CellState::Red)) => "Red won".to_string(),
+            Some(GameResult::Win(CellState::Blue)) => "Blue won".to_string(),
+            Some(GameResult::Resignation(CellState::Red)) => "Red won by resignation".to_string(),
+            Some(GameResult::Resignation(CellState::Blue)) => "Blue won by resignation".to_string(),
+            Some(GameResult::Draw) => "Draw agreed".to_string(),
+            Some(GameResult::Adjourned) => "Adjourned".to_string(),
+            _ if self.moves == 0 => "Opening".to_string(),
+            _ => format!("Move {}", self.moves),
+        };
+        if let Some(opponent) = &self.opponent {
+            state.push_str(&format!(" vs {}", opponent));
+        }
+        (details, state)
+    }
+}
+
+fn run(receiver: Receiver<Command>) {
+    let Some(client_id) = CLIENT_ID else {
+        tracing::warn!("Discord presence unavailable: built without HEX_DISCORD_CLIENT_ID");
+        return;
+    };
+    let mut activity = Activity { size: coast_to_coast::game::DEFAULT_BOARD_SIZE, started: now(), ..Default::default() };
+    let mut enabled = false;
+    let mut connection: Option<Box<dyn Stream>> = None;
+
+    for command in receiver {
+        match command {
+            Command::Event(event) => activity.apply(&event),
+            Command::Enable(on) => enabled = on,
+        }
+        if connection.is_none() && enabled {
+            connection = connect(client_id)
+                .map_err(|e| tracing::debug!(error = %e, "Discord is not reachable"))
+                .ok();
+        }
+        let Some(stream) = connection.as_mut() else {
+            continue;
+        };
+        let payload = if enabled {
+            let (details, state) = activity.text();
+            json!({ "details": details, "state": state, "timestamps": { "start": activity.started } })
+        } else {
+            Value::Null
+        };
+        let message = json!({
+            "cmd": "SET_ACTIVITY",
+            "args": { "pid": std::process::id(), "activity": payload },
+            "nonce": now().to_string(),
+        });
+        if let Err(e) = send(stream.as_mut(), OP_FRAME, &message) {
+            tracing::debug!(error = %e, "lost the Discord connection");
+            connection = None;
+        }
+    }
+}
+
+trait Stream: Read + Write + Send {}
+impl<T: Read + Write + Send> Stream for T {}
+
+fn connect(client_id: &str) -> io::Result<Box<dyn Stream>> {
+    let mut stream = open_socket()?;
+    send(stream.as_mut(), OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+    Ok(stream)
+}
+
+#[cfg(unix)]
+fn open_socket() -> io::Result<Box<dyn Stream>> {
+    let dirs = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .chain(["/tmp".to_string()]);
+    for dir in dirs {
+        for i in 0..10 {
+            let path = std::path::Path::new(&dir).join(format!("discord-ipc-{}", i));
+            if let Ok(stream) = std::os::unix::net::UnixStream::connect(path) {
+                return Ok(Box::new(stream));
+            }
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "no Discord IPC socket"))
+}
+
+#[cfg(windows)]
+fn open_socket() -> io::Result<Box<dyn Stream>> {
+    for i in 0..10 {
+        let pipe = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(format!(r"\\.\pipe\discord-ipc-{}", i));
+        if let Ok(pipe) = pipe {
+            return Ok(Box::new(pipe));
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "no Discord IPC pipe"))
+}
+
+// Frames are a little-endian opcode and length followed by JSON. Discord answers every frame,
+// so read the reply to keep the pipe from filling up.
+fn send(stream: &mut dyn Stream, opcode: u32, message: &Value) -> io::Result<()> {
+    let payload = message.to_string();
+    let mut frame = Vec::with_capacity(8 + payload.len());
+    frame.extend_from_slice(&opcode.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(payload.as_bytes());
+    stream.write_all(&frame)?;
+
+    let mut header = [0; 8];
+    stream.read_exact(&mut header)?;
+    let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let mut reply = vec![0; length as usize];
+    stream.read_exact(&mut reply)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::notation::Move;
+
+    #[test]
+    fn test_activity_text() {
+        let mut activity = Activity::default();
+        let opponent = Some("MCTS (hard)".to_string());
+        activity.apply(&GameEvent::GameStarted { size: 11, moves: 0, opponent });
+        assert_eq!(activity.text(), ("Playing Hex 11x11".to_string(), "Opening vs MCTS (hard)".to_string()));
+
+        activity.apply(&GameEvent::MovePlayed { player: CellState::Red, mv: Move::Swap, move_number: 23 });
+        assert_eq!(activity.text().1, "Move 23 vs MCTS (hard)");
+
+        activity.apply(&GameEvent::GameFinished { winner: CellState::Blue });
+        assert_eq!(activity.text().1, "Blue won vs MCTS (hard)");
+
+        activity.apply(&GameEvent::ResultAgreed { result: GameResult::Draw });
+        assert_eq!(activity.text().1, "Draw agreed vs MCTS (hard)");
+    }
+}
@@ -0,0 +1,185 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use coast_to_coast::board::Hex;
+use coast_to_coast::eval;
+use coast_to_coast::game::Game;
+use coast_to_coast::insights::Mistake;
+use coast_to_coast::record::GameRecord;
+use serde::{Deserialize, Serialize};
+
+// The study deck, in `<data_dir>/drills.toml`: positions where the user blundered, drilled as
+// "find the best move" flashcards. Cards are rescheduled the way SM-2 does it, further apart
+// each time they are answered right and back to the next day when not.
+pub const DRILLS_FILE: &str = "drills.toml";
+// An answer this close to the best move's win chance, by the evaluation, is as good as it.
+const ANSWER_TOLERANCE: f32 = 0.05;
+const START_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+const MAX_EASE: f32 = 3.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    // The moves up to the position, as a game record (see `GameRecord::to_text`).
+    pub position: String,
+    // The move played in the game and the evaluation's best, in notation.
+    pub played: String,
+    pub best: String,
+    // In days since the Unix epoch.
+    pub due: u64,
+    // Days from one right answer to the next review; 0 until the card is first answered right.
+    pub interval: u32,
+    pub ease: f32,
+    pub reviews: u32,
+    pub correct: u32,
+}
+
+impl Card {
+    fn new(mistake: &Mistake, today: u64) -> Self {
+        Self {
+            position: mistake.position.to_text(),
+            played: mistake.played.notation(),
+            best: mistake.best.notation(),
+            due: today,
+            interval: 0,
+            ease: START_EASE,
+            reviews: 0,
+            correct: 0,
+        }
+    }
+
+    // None if the position no longer replays.
+    pub fn game(&self) -> Option<Game> {
+        GameRecord::parse(&self.position).ok()?.replay().ok()
+    }
+
+    // Reschedules the card after an answer: a right one comes back after 1 day, then 6, then
+    // the interval times the ease, which grows with right answers and shrinks with wrong ones.
+    pub fn review(&mut self, right: bool, today: u64) {
+        self.reviews += 1;
+        if right {
+            self.correct += 1;
+            self.interval = match self.interval {
+                0 => 1,
+                1 => 6,
+                days => (days as f32 * self.ease).round() as u32,
+            };
+            self.ease = (self.ease + 0.1).min(MAX_EASE);
+        } else {
+            self.interval = 0;
+            self.ease = (self.ease - 0.2).max(MIN_EASE);
+        }
+        self.due = today + u64::from(self.interval.max(1));
+    }
+}
+
+// Whether `answer` finds the best move in `game`, or one the evaluation rates as good.
+pub fn is_best(game: &Game, answer: Hex) -> bool {
+    let ranked = eval::rank_moves(game);
+    let answered = ranked.iter().find(|(hex, _)| *hex == answer).map(|(_, score)| *score);
+    match (ranked.first(), answered) {
+        (Some((_, best)), Some(answered)) => best - answered <= ANSWER_TOLERANCE,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deck {
+    // The saved games already searched for mistakes, by file name.
+    #[serde(default)]
+    pub collected: Vec<String>,
+    #[serde(default)]
+    pub cards: Vec<Card>,
+}
+
+impl Deck {
+    // A missing or unreadable deck starts over empty.
+    pub fn load(data_dir: &Path) -> Self {
+        let Ok(text) = fs::read_to_string(data_dir.join(DRILLS_FILE)) else {
+            return Self::default();
+        };
+        toml::from_str(&text).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "unreadable study deck, starting over");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, data_dir: &Path) -> io::Result<()> {
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+        fs::create_dir_all(data_dir)?;
+        fs::write(data_dir.join(DRILLS_FILE), text)
+    }
+
+    // Adds the mistakes not already in the deck, due today. Returns how many were new.
+    pub fn add(&mut self, mistakes: &[Mistake], today: u64) -> usize {
+        let before = self.cards.len();
+        for mistake in mistakes {
+            let card = Card::new(mistake, today);
+            if !self.cards.iter().any(|known| known.position == card.position && known.played == card.played) {
+                self.cards.push(card);
+            }
+        }
+        self.cards.len() - before
+    }
+
+    // The card to drill next: the longest overdue.
+    pub fn next_due(&self, today: u64) -> Option<usize> {
+        let due = self.cards.iter().enumerate().filter(|(_, card)| card.due <= today);
+        due.min_by_key(|(_, card)| card.due).map(|(index, _)| index)
+    }
+
+    pub fn due(&self, today: u64) -> usize {
+        self.cards.iter().filter(|card| card.due <= today).count()
+    }
+}
+
+// Days since the Unix epoch, in UTC.
+pub fn today() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::record::GameMetadata;
+
+    #[test]
+    fn test_cards_are_added_once_and_spaced_out() {
+        let dir = std::env::temp_dir().join(format!("hex-drills-test-{}", std::process::id()));
+        let mut game = Game::with_size(5).with_pie_rule(false);
+        for mv in ["c3", "a1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let position = GameRecord::from_game(&game, &GameMetadata::default());
+        let mistake = Mistake { position, played: Hex { q: 2, r: 1 }, best: Hex { q: 2, r: 3 }, loss: 0.3 };
+        let mut deck = Deck::default();
+        assert_eq!(deck.add(&[mistake.clone(), mistake.clone()], 100), 1);
+        assert_eq!(deck.add(&[mistake], 101), 0);
+        assert_eq!((deck.due(100), deck.next_due(100)), (1, Some(0)));
+        assert_eq!(deck.cards[0].game().unwrap().history, game.history);
+
+        let card = &mut deck.cards[0];
+        let intervals: Vec<u32> = [true, true, true, false, true]
+            .into_iter()
+            .map(|right| {
+                card.review(right, 100);
+                card.interval
+            })
+            .collect();
+        assert_eq!(intervals, [1, 6, 16, 0, 1]);
+        assert_eq!((card.due, card.reviews, card.correct), (101, 5, 4));
+        assert_eq!(deck.next_due(100), None);
+
+        deck.save(&dir).unwrap();
+        assert_eq!(Deck::load(&dir), deck);
+        fs::write(dir.join(DRILLS_FILE), "cards = 3").unwrap();
+        assert_eq!(Deck::load(&dir), Deck::default());
+        let _ = fs::remove_dir_all(&dir);
+
+        let ranked = eval::rank_moves(&game);
+        assert!(is_best(&game, ranked[0].0));
+        assert!(!is_best(&game, ranked[ranked.len() - 1].0));
+    }
+}
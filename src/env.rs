@@ -0,0 +1,208 @@
+//! Gymnasium-style environment over the rules engine, for training RL agents.
+//!
+//! Everything is expressed from the point of view of the player to move ("canonical" form):
+//! when Blue is to move the board is transposed, so the mover always connects the left and
+//! right edges. Actions `0..size*size` place a stone at canonical `row * size + col`;
+//! [`HexEnv::swap_action`] and [`HexEnv::no_swap_action`] answer the pie rule.
+
+use crate::board::{CellState, Hex};
+use crate::game::{Game, GameState};
+
+/// Planes of `size * size` values each: own stones, opponent stones, empty cells, and a
+/// plane of ones while the pie-rule decision is pending.
+pub const OBSERVATION_PLANES: usize = 4;
+
+pub type Observation = Vec<f32>;
+
+pub struct HexEnv {
+    size: i32,
+    game: Game,
+}
+
+impl HexEnv {
+    pub fn new(size: i32) -> Self {
+        Self { size, game: Game::with_size(size) }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// `[planes, rows, cols]` of the flat observation vector.
+    pub fn observation_shape(&self) -> [usize; 3] {
+        let size = self.size as usize;
+        [OBSERVATION_PLANES, size, size]
+    }
+
+    pub fn n_actions(&self) -> usize {
+        (self.size * self.size) as usize + 2
+    }
+
+    pub fn swap_action(&self) -> usize {
+        (self.size * self.size) as usize
+    }
+
+    pub fn no_swap_action(&self) -> usize {
+        self.swap_action() + 1
+    }
+
+    pub fn reset(&mut self) -> Observation {
+        self.game = Game::with_size(self.size);
+        self.observation()
+    }
+
+    /// Applies `action` for the player to move and returns `(observation, reward, done)`.
+    /// The reward is for the player who acted: 1.0 for a winning move, -1.0 for an illegal
+    /// action (which also ends the episode), 0.0 otherwise.
+    pub fn step(&mut self, action: usize) -> (Observation, f32, bool) {
+        if !self.is_legal(action) {
+            return (self.observation(), -1.0, true);
+        }
+
+        if action == self.swap_action() || action == self.no_swap_action() {
+            self.game.handle_pie_rule_decision(action == self.swap_action());
+        } else {
+            let hex = self.action_to_hex(action);
+            self.game.handle_click(hex);
+        }
+
+        match self.game.state {
+            GameState::Finished { .. } => (self.observation(), 1.0, true),
+            _ => (self.observation(), 0.0, false),
+        }
+    }
+
+    pub fn is_legal(&self, action: usize) -> bool {
+        match self.game.state {
+            GameState::Finished { .. } | GameState::Drawn | GameState::Resigned { .. } | GameState::Adjourned { .. } => {
+                false
+            }
+            GameState::WaitingForPieRuleChoice => action == self.swap_action() || action == self.no_swap_action(),
+            GameState::InProgress => {
+                action < self.swap_action() && self.game.is_legal_move(&self.action_to_hex(action))
+            }
+        }
+    }
+
+    pub fn action_mask(&self) -> Vec<bool> {
+        (0..self.n_actions()).map(|action| self.is_legal(action)).collect()
+    }
+
+    /// Maps a canonical cell action to the board cell it places on.
+    pub fn action_to_hex(&self, action: usize) -> Hex {
+        let row = action as i32 / self.size;
+        let col = action as i32 % self.size;
+        self.canonical_to_hex(row, col)
+    }
+
+    pub fn hex_to_action(&self, hex: Hex) -> usize {
+        let (row, col) = match self.game.current_player {
+            CellState::Blue => (hex.q, hex.r),
+            _ => (hex.r, hex.q),
+        };
+        (row * self.size + col) as usize
+    }
+
+    fn canonical_to_hex(&self, row: i32, col: i32) -> Hex {
+        match self.game.current_player {
+            CellState::Blue => Hex { q: row, r: col },
+            _ => Hex { q: col, r: row },
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        let size = self.size as usize;
+        let plane = size * size;
+        let mut observation = vec![0.0; OBSERVATION_PLANES * plane];
+        let me = self.game.current_player;
+        let pending = self.game.state == GameState::WaitingForPieRuleChoice;
+
+        for row in 0..size {
+            for col in 0..size {
+                let hex = self.canonical_to_hex(row as i32, col as i32);
+                let index = row * size + col;
+                match self.game.board.get_cell(&hex) {
+                    Some(CellState::Empty) => observation[2 * plane + index] = 1.0,
+                    // Blocked cells are in no plane, like cells off the board.
+                    Some(CellState::Blocked) | None => {}
+                    Some(state) if *state == me => observation[index] = 1.0,
+                    Some(_) => observation[plane + index] = 1.0,
+                }
+                if pending {
+                    observation[3 * plane + index] = 1.0;
+                }
+            }
+        }
+        observation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_observation() {
+        let mut env = HexEnv::new(3);
+        let observation = env.reset();
+        assert_eq!(env.observation_shape(), [4, 3, 3]);
+        assert_eq!(observation.len(), 36);
+        // Only the empty plane is set.
+        assert!(observation[..9].iter().all(|v| *v == 0.0));
+        assert!(observation[18..27].iter().all(|v| *v == 1.0));
+        assert_eq!(env.action_mask().iter().filter(|legal| **legal).count(), 9);
+    }
+
+    #[test]
+    fn test_first_move_then_pie_rule_actions() {
+        let mut env = HexEnv::new(3);
+        env.reset();
+        let (observation, reward, done) = env.step(1); // Red plays row 0, col 1 = q 1, r 0
+        assert_eq!((reward, done), (0.0, false));
+        assert_eq!(env.game().board.get_cell(&Hex { q: 1, r: 0 }), Some(&CellState::Red));
+
+        // Blue to move, so the Red stone is an opponent stone at transposed row 1, col 0.
+        assert_eq!(observation[9 + 3], 1.0);
+        assert!(observation[27..].iter().all(|v| *v == 1.0));
+
+        assert!(!env.is_legal(0));
+        assert!(env.is_legal(env.swap_action()));
+        let (_, reward, done) = env.step(env.no_swap_action());
+        assert_eq!((reward, done), (0.0, false));
+        assert_eq!(env.game().state, GameState::InProgress);
+    }
+
+    #[test]
+    fn test_illegal_action_ends_episode() {
+        let mut env = HexEnv::new(3);
+        env.reset();
+        env.step(0);
+        let (_, reward, done) = env.step(0);
+        assert_eq!((reward, done), (-1.0, true));
+    }
+
+    #[test]
+    fn test_canonical_actions_round_trip() {
+        let mut env = HexEnv::new(4);
+        env.reset();
+        env.step(env.hex_to_action(Hex { q: 3, r: 3 }));
+        env.step(env.no_swap_action());
+        // Blue to move: actions are transposed.
+        let hex = Hex { q: 1, r: 2 };
+        assert_eq!(env.hex_to_action(hex), 4 + 2);
+        assert_eq!(env.action_to_hex(env.hex_to_action(hex)), hex);
+    }
+
+    #[test]
+    fn test_winning_move_rewards_mover() {
+        let mut env = HexEnv::new(2);
+        env.reset();
+        // Red a1, Blue keeps colors and plays a2, then Red b1 joins Red's two edges.
+        env.step(env.hex_to_action(Hex { q: 0, r: 0 }));
+        env.step(env.no_swap_action());
+        env.step(env.hex_to_action(Hex { q: 0, r: 1 }));
+        let (_, reward, done) = env.step(env.hex_to_action(Hex { q: 1, r: 0 }));
+        assert_eq!((reward, done), (1.0, true));
+        assert_eq!(env.game().state, GameState::Finished { winner: CellState::Red });
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::board::{Board, CellState, Hex};
+use crate::game::{Game, GameState};
+use crate::notation::Move;
+
+// Fewest empty cells `player` still has to fill to join their two edges, counting their own
+// stones as free and treating the opponent's as walls. None when the opponent has cut them off.
+// For shapes with more than two goal sides (Y) only the first two are measured.
+pub fn stones_to_connect(board: &Board, player: CellState) -> Option<u32> {
+    let size = board.size;
+    let shape = board.shape();
+    let (from, to) = match shape.goal_sides(player)[..] {
+        [from, to, ..] => (from, to),
+        _ => return None,
+    };
+    let cost = |hex: &Hex| match board.get_cell(hex) {
+        Some(state) if *state == player => Some(0),
+        Some(CellState::Empty) => Some(1),
+        _ => None,
+    };
+
+    // 0-1 BFS: own stones cost nothing, empty cells one stone.
+    let mut distance = vec![u32::MAX; (size * size) as usize];
+    let index = |hex: Hex| (hex.r * size + hex.q) as usize;
+    let mut queue = VecDeque::new();
+    for (hex, _) in board.cells.iter().filter(|(hex, _)| shape.on_side(**hex, from)) {
+        if let Some(c) = cost(hex) {
+            if c < distance[index(*hex)] {
+                distance[index(*hex)] = c;
+                if c == 0 {
+                    queue.push_front(*hex);
+                } else {
+                    queue.push_back(*hex);
+                }
+            }
+        }
+    }
+    while let Some(hex) = queue.pop_front() {
+        let d = distance[index(hex)];
+        if shape.on_side(hex, to) {
+            return Some(d);
+        }
+        for neighbor in hex.get_neighbors() {
+            let Some(c) = cost(&neighbor) else {
+                continue;
+            };
+            if d + c < distance[index(neighbor)] {
+                distance[index(neighbor)] = d + c;
+                if c == 0 {
+                    queue.push_front(neighbor);
+                } else {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    None
+}
+
+// `player`'s two-distance between their edges: a cell next to an edge is 1 from it, any other
+// cell one more than its second-closest neighbor, so a single route the opponent can cut
+// doesn't count. Their own stones join the empty cells around them and the opponent's are
+// walls. Some(0) once connected, None when no two routes are left. Like `stones_to_connect`,
+// only the first two goal sides are measured.
+pub fn two_distance(board: &Board, player: CellState) -> Option<u32> {
+    let (from, to) = match board.shape().goal_sides(player)[..] {
+        [from, to, ..] => (from, to),
+        _ => return None,
+    };
+    let graph = TwoDistanceGraph::new(board, player, [from, to]);
+    if graph.joined {
+        return Some(0);
+    }
+    let distance = graph.distances_from(0);
+    // The far edge is as far as its second-closest cell; no stone is placed on the edge itself.
+    let mut ends: Vec<u32> = graph.on_side[1].iter().filter_map(|hex| distance.get(hex).copied()).collect();
+    ends.sort_unstable();
+    ends.get(1).copied()
+}
+
+// How much playing each empty cell would shorten `player`'s two-distance, for every empty cell.
+// Being cut off counts as needing every cell on the board.
+pub fn two_distance_gains(board: &Board, player: CellState) -> Vec<(Hex, u32)> {
+    let unreachable = board.size as u32 * board.size as u32;
+    let before = two_distance(board, player).unwrap_or(unreachable);
+    board
+        .empty_cells()
+        .into_iter()
+        .map(|hex| {
+            let mut next = board.clone();
+            next.set_cell(hex, player);
+            (hex, before.saturating_sub(two_distance(&next, player).unwrap_or(unreachable)))
+        })
+        .collect()
+}
+
+// The empty cells as `player` sees them for the two-distance: each of their chains makes the
+// empty cells around it neighbors, and carries the edges it touches to them.
+struct TwoDistanceGraph {
+    neighbors: HashMap<Hex, HashSet<Hex>>,
+    // The empty cells next to each of the two edges.
+    on_side: [HashSet<Hex>; 2],
+    // One of `player`'s chains already touches both edges.
+    joined: bool,
+}
+
+impl TwoDistanceGraph {
+    fn new(board: &Board, player: CellState, sides: [usize; 2]) -> Self {
+        let shape = board.shape();
+        let is = |hex: &Hex, state: CellState| board.get_cell(hex) == Some(&state);
+        let mut neighbors: HashMap<Hex, HashSet<Hex>> = HashMap::new();
+        let mut on_side: [HashSet<Hex>; 2] = Default::default();
+        for hex in board.empty_cells() {
+            let adjacent = hex.get_neighbors().into_iter().filter(|n| is(n, CellState::Empty)).collect();
+            neighbors.insert(hex, adjacent);
+            for (i, side) in sides.iter().enumerate() {
+                if shape.on_side(hex, *side) {
+                    on_side[i].insert(hex);
+                }
+            }
+        }
+
+        let mut joined = false;
+        let mut visited = HashSet::new();
+        for (start, _) in board.cells.iter().filter(|(hex, _)| is(hex, player)) {
+            if !visited.insert(*start) {
+                continue;
+            }
+            let (mut touched, mut liberties) = ([false; 2], HashSet::new());
+            let mut queue = VecDeque::from([*start]);
+            while let Some(hex) = queue.pop_front() {
+                for (i, side) in sides.iter().enumerate() {
+                    touched[i] |= shape.on_side(hex, *side);
+                }
+                for neighbor in hex.get_neighbors() {
+                    if is(&neighbor, CellState::Empty) {
+                        liberties.insert(neighbor);
+                    } else if is(&neighbor, player) && visited.insert(neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            joined |= touched == [true; 2];
+            for hex in &liberties {
+                let adjacent = neighbors.entry(*hex).or_default();
+                adjacent.extend(liberties.iter().filter(|other| *other != hex));
+                for i in 0..2 {
+                    if touched[i] {
+                        on_side[i].insert(*hex);
+                    }
+                }
+            }
+        }
+        Self { neighbors, on_side, joined }
+    }
+
+    // Each empty cell's two-distance from edge `side`, for those with one.
+    fn distances_from(&self, side: usize) -> HashMap<Hex, u32> {
+        let mut distance: HashMap<Hex, u32> = self.on_side[side].iter().map(|hex| (*hex, 1)).collect();
+        let mut queue: VecDeque<Hex> = distance.keys().copied().collect();
+        // Neighbors already reached; the second one settles a cell's distance. Cells come off the
+        // queue closest first, so that is its second-closest neighbor.
+        let mut reached: HashMap<Hex, u32> = HashMap::new();
+        while let Some(hex) = queue.pop_front() {
+            let d = distance[&hex];
+            for neighbor in &self.neighbors[&hex] {
+                if distance.contains_key(neighbor) {
+                    continue;
+                }
+                let count = reached.entry(*neighbor).or_default();
+                *count += 1;
+                if *count == 2 {
+                    distance.insert(*neighbor, d + 1);
+                    queue.push_back(*neighbor);
+                }
+            }
+        }
+        distance
+    }
+}
+
+// A rough 0.0 (Blue winning) to 1.0 (Red winning) estimate from the connection distances,
+// cheap enough to recompute every frame. Not a substitute for search.
+pub fn evaluate(game: &Game) -> f32 {
+    match game.state {
+        GameState::Finished { winner: CellState::Red } => return 1.0,
+        GameState::Finished { .. } => return 0.0,
+        _ => {}
+    }
+    evaluate_board(&game.board, game.current_player)
+}
+
+// `evaluate` for a bare board with `to_move` to play, for searches that don't keep a `Game`.
+pub fn evaluate_board(board: &Board, to_move: CellState) -> f32 {
+    let unreachable = board.size as u32 * board.size as u32;
+    let red = stones_to_connect(board, CellState::Red).unwrap_or(unreachable) as f32;
+    let blue = stones_to_connect(board, CellState::Blue).unwrap_or(unreachable) as f32;
+    // Being on move is worth about half a stone.
+    let tempo = match to_move {
+        CellState::Red => 0.5,
+        _ => -0.5,
+    };
+    1.0 / (1.0 + (-(blue - red + tempo) * 0.6).exp())
+}
+
+// Legal moves for the player to move, best first by `evaluate` after the move. Ties go to the
+// cell nearer the center, which is usually the stronger shape.
+pub fn rank_moves(game: &Game) -> Vec<(Hex, f32)> {
+    let player = game.current_player;
+    let center = (game.board.size - 1) as f32 / 2.0;
+    let mut ranked: Vec<(Hex, f32, f32)> = game
+        .legal_moves()
+        .filter_map(|hex| {
+            let mut next = game.clone();
+            next.play_move(Move::Place(hex)).ok()?;
+            let red = evaluate(&next);
+            let score = if player == CellState::Red { red } else { 1.0 - red };
+            let (dq, dr) = (hex.q as f32 - center, hex.r as f32 - center);
+            // Axial distance from the center.
+            let off_center = (dq.abs() + dr.abs() + (dq + dr).abs()) / 2.0;
+            Some((hex, score, off_center))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.2.total_cmp(&b.2)));
+    ranked.into_iter().map(|(hex, score, _)| (hex, score)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stones_to_connect_empty_board() {
+        let board = Board::new(5);
+        assert_eq!(stones_to_connect(&board, CellState::Red), Some(5));
+        assert_eq!(stones_to_connect(&board, CellState::Blue), Some(5));
+    }
+
+    #[test]
+    fn test_stones_to_connect_counts_own_stones_and_walls() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 0, r: 1 }, CellState::Red);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        assert_eq!(stones_to_connect(&board, CellState::Red), Some(1));
+        // Red's stones leave Blue only the c column.
+        assert_eq!(stones_to_connect(&board, CellState::Blue), Some(3));
+
+        board.set_cell(Hex { q: 2, r: 1 }, CellState::Red);
+        assert_eq!(stones_to_connect(&board, CellState::Red), Some(0));
+        assert_eq!(stones_to_connect(&board, CellState::Blue), None);
+    }
+
+    #[test]
+    fn test_two_distance_needs_two_routes() {
+        let mut board = Board::new(3);
+        assert_eq!(two_distance(&board, CellState::Red), Some(4));
+        board.set_cell(Hex { q: 0, r: 1 }, CellState::Red);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        // b2 reaches the right edge through c1 or c2.
+        assert_eq!(two_distance(&board, CellState::Red), Some(1));
+        board.set_cell(Hex { q: 2, r: 0 }, CellState::Blue);
+        // c2, or b3 and then c3.
+        assert_eq!(two_distance(&board, CellState::Red), Some(2));
+        board.set_cell(Hex { q: 2, r: 2 }, CellState::Blue);
+        // Only c2 is left; one cut ends it.
+        assert_eq!(two_distance(&board, CellState::Red), None);
+        board.set_cell(Hex { q: 2, r: 1 }, CellState::Red);
+        assert_eq!(two_distance(&board, CellState::Red), Some(0));
+
+        let gains: HashMap<Hex, u32> = two_distance_gains(&Board::new(3), CellState::Red).into_iter().collect();
+        assert_eq!(gains.len(), 9);
+        // The centre halves it, more than any other cell.
+        assert_eq!(gains[&Hex { q: 1, r: 1 }], 2);
+        assert!(gains.values().all(|gain| *gain <= 2));
+    }
+
+    #[test]
+    fn test_rank_moves_prefers_winning_and_central_moves() {
+        let game = Game::with_size(5);
+        let ranked = rank_moves(&game);
+        assert_eq!(ranked.len(), 25);
+        assert_eq!(ranked[0].0, Hex { q: 2, r: 2 });
+
+        // Red a1, b1 with c1 to complete on a 3x3 board.
+        let mut game = Game::with_size(3);
+        for mv in ["a1", "a3", "b1", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(rank_moves(&game)[0], (Hex { q: 2, r: 0 }, 1.0));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mut game = Game::with_size(5);
+        let start = evaluate(&game);
+        assert!(start > 0.5 && start < 0.7, "Red on move is slightly ahead: {}", start);
+
+        game.handle_click(Hex { q: 2, r: 2 });
+        game.handle_pie_rule_decision(false);
+        game.handle_click(Hex { q: 0, r: 4 });
+        assert!(evaluate(&game) > 0.5);
+
+        let mut game = Game::with_size(2);
+        for mv in ["a1", "a2", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(evaluate(&game), 1.0);
+    }
+}
@@ -0,0 +1,59 @@
+use coast_to_coast::board::CellState;
+use coast_to_coast::notation::Move;
+use coast_to_coast::record::{GameRecord, GameResult};
+
+// What happened in the game on screen, published by the app as it happens so integrations
+// (logging, Discord presence, ...) don't have to poll the game state. Each event comes with
+// the game's record as it stands after it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    // `moves` is non-zero when a saved or imported game is resumed.
+    GameStarted { size: i32, moves: usize, opponent: Option<String> },
+    MovePlayed { player: CellState, mv: Move, move_number: usize },
+    GameFinished { winner: CellState },
+    // The players agreed a draw or adjourned the game.
+    ResultAgreed { result: GameResult },
+    // An accepted takeback left `moves` moves in the game.
+    TakenBack { moves: usize },
+}
+
+pub type Subscriber = Box<dyn FnMut(&GameEvent, &GameRecord)>;
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Subscriber>,
+}
+
+impl EventBus {
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&GameEvent, &GameRecord) + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    pub fn publish(&mut self, event: GameEvent, record: &GameRecord) {
+        for subscriber in &mut self.subscribers {
+            subscriber(&event, record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::game::Game;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_publish_reaches_every_subscriber() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::default();
+        for name in ["a", "b"] {
+            let seen = seen.clone();
+            bus.subscribe(move |event, _| seen.borrow_mut().push((name, event.clone())));
+        }
+
+        let event = GameEvent::GameFinished { winner: CellState::Blue };
+        bus.publish(event.clone(), &GameRecord::from_game(&Game::with_size(3), &Default::default()));
+        assert_eq!(*seen.borrow(), vec![("a", event.clone()), ("b", event)]);
+    }
+}
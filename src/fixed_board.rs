@@ -0,0 +1,289 @@
+//! Fast playouts for the common 11x11 and 13x13 Hex boards.
+//!
+//! A [`FixedBoard`] keeps each row of a size `N` rhombus as bitmasks of Red, Blue and empty
+//! cells, with every cell's neighbours worked out at compile time. Who has won comes from
+//! flooding the rows from a player's first edge, a whole row at a time, instead of walking a
+//! chain cell by cell through a map. [`playout`] plays the built-in policies on it, drawing
+//! from the random generator exactly as they do on a [`Board`], so a seeded search picks the
+//! same moves either way, only sooner.
+//!
+//! ```
+//! use coast_to_coast::board::{Board, CellState, Hex};
+//! use coast_to_coast::fixed_board::FixedBoard;
+//!
+//! let mut board = Board::new(11);
+//! for q in 0..11 {
+//!     board.set_cell(Hex { q, r: 5 }, CellState::Red);
+//! }
+//! let fixed = FixedBoard::<11>::from_board(&board);
+//! assert_eq!(fixed.winner(), Some(CellState::Red));
+//! assert_eq!(fixed.winner(), board.winner());
+//! ```
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::board::{Board, CellState, Hex};
+
+/// The sizes [`playout`] is built for; the search uses it on these.
+pub const FIXED_SIZES: [i32; 2] = [11, 13];
+
+// Neighbours in the order `Hex::get_neighbors` gives them, which the policies rely on.
+const OFFSETS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// The built-in playout policies, as [`playout`] plays them; see [`crate::mcts`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedPolicy {
+    Uniform,
+    Pattern,
+    Bridge,
+}
+
+/// An `N` by `N` Hex board, a row of bits per player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedBoard<const N: usize> {
+    red: [u16; N],
+    blue: [u16; N],
+    empty: [u16; N],
+}
+
+impl<const N: usize> FixedBoard<N> {
+    // Rows are u16 bitmasks.
+    const FITS: () = assert!(N >= 1 && N <= 16, "rows are 16 bits");
+
+    /// Each cell's neighbours by row and column, None off the board.
+    const NEIGHBORS: [[[Option<Hex>; 6]; N]; N] = neighbor_table();
+
+    const LAST_COLUMN: u16 = 1 << (N - 1);
+
+    /// The cells of `board`, which must be the plain `N` by `N` rhombus (see
+    /// [`Board::is_rhombus`]). Blocked cells stay out of every row.
+    pub fn from_board(board: &Board) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS;
+        debug_assert!(board.size as usize == N && board.is_rhombus(), "not a {N}x{N} Hex board");
+        let mut fixed = FixedBoard { red: [0; N], blue: [0; N], empty: [0; N] };
+        for r in 0..N {
+            for q in 0..N {
+                let hex = Hex { q: q as i32, r: r as i32 };
+                if let Some(state) = board.get_cell(&hex) {
+                    fixed.set_cell(hex, *state);
+                }
+            }
+        }
+        fixed
+    }
+
+    /// The state of `hex`; None off the board.
+    pub fn get_cell(&self, hex: Hex) -> Option<CellState> {
+        let (q, r) = (usize::try_from(hex.q).ok()?, usize::try_from(hex.r).ok()?);
+        if q >= N || r >= N {
+            return None;
+        }
+        let bit = 1 << q;
+        Some(if self.red[r] & bit != 0 {
+            CellState::Red
+        } else if self.blue[r] & bit != 0 {
+            CellState::Blue
+        } else if self.empty[r] & bit != 0 {
+            CellState::Empty
+        } else {
+            CellState::Blocked
+        })
+    }
+
+    /// Sets `hex`, which must be on the board.
+    pub fn set_cell(&mut self, hex: Hex, state: CellState) {
+        let (q, r) = (hex.q as usize, hex.r as usize);
+        let bit = 1 << q;
+        for row in [&mut self.red[r], &mut self.blue[r], &mut self.empty[r]] {
+            *row &= !bit;
+        }
+        match state {
+            CellState::Red => self.red[r] |= bit,
+            CellState::Blue => self.blue[r] |= bit,
+            CellState::Empty => self.empty[r] |= bit,
+            CellState::Blocked => {}
+        }
+    }
+
+    /// The empty cells row by row, as [`Board::empty_cells`] lists them.
+    pub fn empty_cells(&self) -> Vec<Hex> {
+        let mut empty = Vec::with_capacity(N * N);
+        for (r, row) in self.empty.iter().enumerate() {
+            empty.extend((0..N).filter(|q| row & (1 << q) != 0).map(|q| Hex { q: q as i32, r: r as i32 }));
+        }
+        empty
+    }
+
+    /// As [`Board::winner`]: Red joining the `q` sides, Blue the `r` sides, Red first.
+    pub fn winner(&self) -> Option<CellState> {
+        let red = flood(&self.red, self.red.map(|row| row & 1));
+        if red.iter().any(|row| row & Self::LAST_COLUMN != 0) {
+            return Some(CellState::Red);
+        }
+        let mut start = [0; N];
+        start[0] = self.blue[0];
+        (flood(&self.blue, start)[N - 1] != 0).then_some(CellState::Blue)
+    }
+
+    // The cell that saves `player`'s bridge after an intrusion at `intrusion`, as
+    // `mcts::bridge_reply` finds it.
+    fn bridge_reply(&self, player: CellState, intrusion: Hex) -> Option<Hex> {
+        let ring = &Self::NEIGHBORS[intrusion.r as usize][intrusion.q as usize];
+        (0..6).find_map(|i| {
+            let (a, carrier, b) = (ring[i], ring[(i + 1) % 6], ring[(i + 2) % 6]);
+            let owned = |hex: Option<Hex>| hex.and_then(|hex| self.get_cell(hex)) == Some(player);
+            let carrier = carrier.filter(|hex| self.get_cell(*hex) == Some(CellState::Empty))?;
+            (owned(a) && owned(b)).then_some(carrier)
+        })
+    }
+
+    // Where in `empty` `policy` plays for `player`, drawing from `rng` as the policy does.
+    fn choose(&self, policy: FixedPolicy, player: CellState, last: Option<Hex>, empty: &[Hex], rng: &mut StdRng) -> usize {
+        let reply = match policy {
+            FixedPolicy::Uniform => None,
+            FixedPolicy::Pattern if rng.gen_bool(0.5) => {
+                let mut local = [Hex { q: 0, r: 0 }; 6];
+                let mut count = 0;
+                for hex in last.into_iter().flat_map(|hex| Self::NEIGHBORS[hex.r as usize][hex.q as usize]).flatten() {
+                    if self.get_cell(hex) == Some(CellState::Empty) {
+                        local[count] = hex;
+                        count += 1;
+                    }
+                }
+                local[..count].choose(rng).copied()
+            }
+            FixedPolicy::Pattern => None,
+            FixedPolicy::Bridge => last.and_then(|last| self.bridge_reply(player, last)),
+        };
+        match reply {
+            Some(hex) => empty.iter().position(|cell| *cell == hex).expect("replies are empty cells"),
+            None => rng.gen_range(0..empty.len()),
+        }
+    }
+}
+
+// Grows `reach` through `stones` until it stops changing. In a row, a cell touches its left
+// and right; it touches the cell above it and the one above and right, and the cell below it
+// and the one below and left.
+fn flood<const N: usize>(stones: &[u16; N], mut reach: [u16; N]) -> [u16; N] {
+    loop {
+        let mut changed = false;
+        for r in 0..N {
+            let mut row = reach[r];
+            if r > 0 {
+                row |= reach[r - 1] | reach[r - 1] >> 1;
+            }
+            if r + 1 < N {
+                row |= reach[r + 1] | reach[r + 1] << 1;
+            }
+            row &= stones[r];
+            loop {
+                let wider = (row | row << 1 | row >> 1) & stones[r];
+                if wider == row {
+                    break;
+                }
+                row = wider;
+            }
+            if row != reach[r] {
+                reach[r] = row;
+                changed = true;
+            }
+        }
+        if !changed {
+            return reach;
+        }
+    }
+}
+
+const fn neighbor_table<const N: usize>() -> [[[Option<Hex>; 6]; N]; N] {
+    let mut table = [[[None; 6]; N]; N];
+    let mut r = 0;
+    while r < N {
+        let mut q = 0;
+        while q < N {
+            let mut i = 0;
+            while i < 6 {
+                let (nq, nr) = (q as i32 + OFFSETS[i].0, r as i32 + OFFSETS[i].1);
+                if nq >= 0 && nr >= 0 && (nq as usize) < N && (nr as usize) < N {
+                    table[r][q][i] = Some(Hex { q: nq, r: nr });
+                }
+                i += 1;
+            }
+            q += 1;
+        }
+        r += 1;
+    }
+    table
+}
+
+/// Fills `board` with `policy`, `player` first after `last`, and returns who connects their
+/// sides. Plays the same moves as the policy would on `board` itself with the same `rng`;
+/// `board` must be the plain `N` by `N` rhombus and is left as it is.
+pub fn playout<const N: usize>(
+    board: &Board,
+    policy: FixedPolicy,
+    mut player: CellState,
+    mut last: Option<Hex>,
+    rng: &mut StdRng,
+) -> Option<CellState> {
+    let mut fixed = FixedBoard::<N>::from_board(board);
+    let mut empty = fixed.empty_cells();
+    while !empty.is_empty() {
+        let index = fixed.choose(policy, player, last, &empty, rng);
+        let hex = empty.swap_remove(index);
+        fixed.set_cell(hex, player);
+        last = Some(hex);
+        player = match player {
+            CellState::Red => CellState::Blue,
+            _ => CellState::Red,
+        };
+    }
+    fixed.winner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_neighbors_match_the_board() {
+        for (r, row) in FixedBoard::<11>::NEIGHBORS.iter().enumerate() {
+            for (q, neighbors) in row.iter().enumerate() {
+                let hex = Hex { q: q as i32, r: r as i32 };
+                let expected: Vec<Option<Hex>> = hex
+                    .get_neighbors()
+                    .into_iter()
+                    .map(|neighbor| FixedBoard::<11>::from_board(&Board::new(11)).get_cell(neighbor).map(|_| neighbor))
+                    .collect();
+                assert_eq!(neighbors.to_vec(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cells_and_winner_match_the_board() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for _ in 0..200 {
+            // Random stones and a hole or two, up to a full board.
+            let mut board = Board::new(13);
+            let filled = rng.gen_range(0..=169);
+            for hex in board.empty_cells().choose_multiple(&mut rng, filled) {
+                let state = match rng.gen_range(0..20) {
+                    0 => CellState::Blocked,
+                    n if n % 2 == 0 => CellState::Red,
+                    _ => CellState::Blue,
+                };
+                board.set_cell(*hex, state);
+            }
+            let fixed = FixedBoard::<13>::from_board(&board);
+            assert_eq!(fixed.empty_cells(), board.empty_cells());
+            assert_eq!(fixed.winner(), board.winner(), "\n{board}");
+            assert!(board.cells.iter().all(|(hex, state)| fixed.get_cell(*hex) == Some(*state)));
+        }
+        assert_eq!(FixedBoard::<13>::from_board(&Board::new(13)).get_cell(Hex { q: 13, r: 0 }), None);
+    }
+}
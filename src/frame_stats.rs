@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+// Frames the statistics are taken over, a few seconds' worth at full speed.
+const WINDOW: usize = 240;
+const CAUSES_SHOWN: usize = 5;
+
+struct Sample {
+    at: Instant,
+    // Seconds spent on the frame before this one, as eframe measures it.
+    cpu: Option<f32>,
+    shapes: usize,
+    causes: Vec<String>,
+}
+
+// Frame-time and repaint statistics for the debug overlay, to see what large boards cost. Nothing
+// is measured while the overlay is off.
+#[derive(Default)]
+pub struct FrameStats {
+    pub overlay: bool,
+    samples: VecDeque<Sample>,
+}
+
+impl FrameStats {
+    // Call once the frame's UI is laid out, before the overlay itself is drawn.
+    pub fn record(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        if !self.overlay {
+            self.samples.clear();
+            return;
+        }
+        let layers: Vec<egui::LayerId> = ctx.memory(|memory| memory.layer_ids().collect());
+        let shapes = ctx.graphics(|graphics| {
+            layers.iter().map(|layer| graphics.get(*layer).map_or(0, |list| list.next_idx().0)).sum()
+        });
+        let causes = ctx.repaint_causes().iter().map(ToString::to_string).collect();
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample { at: Instant::now(), cpu: frame.info().cpu_usage, shapes, causes });
+    }
+
+    pub fn show(&self, ctx: &egui::Context) {
+        if !self.overlay {
+            return;
+        }
+        let Some(last) = self.samples.back() else {
+            return;
+        };
+        let mut times: Vec<f32> = self.samples.iter().filter_map(|sample| sample.cpu).collect();
+        times.sort_by(f32::total_cmp);
+        let millis = |p| percentile(&times, p).map_or("-".to_string(), |time| format!("{:.1}", time * 1000.0));
+        let mut lines = vec![
+            format!("fps   {:.0}", fps(self.samples.iter().map(|sample| sample.at), last.at)),
+            format!("ms    p50 {}  p95 {}  p99 {}", millis(0.5), millis(0.95), millis(0.99)),
+            format!("shapes {}", last.shapes),
+            "repaints".to_string(),
+        ];
+        lines.extend(top_causes(&self.samples).into_iter().map(|(cause, count)| format!("{:>4} {}", count, cause)));
+
+        egui::Area::new(egui::Id::new("frame_stats_overlay"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_TOP, [-8.0, 32.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for line in &lines {
+                        ui.monospace(line);
+                    }
+                });
+            });
+    }
+}
+
+// Frames drawn in the second up to `now`. egui only draws when something changes, so an idle
+// window shows few.
+fn fps(times: impl Iterator<Item = Instant>, now: Instant) -> f32 {
+    times.filter(|at| now.duration_since(*at) < Duration::from_secs(1)).count() as f32
+}
+
+// The `p` percentile (0 to 1) of sorted `samples`, by nearest rank.
+fn percentile(samples: &[f32], p: f32) -> Option<f32> {
+    let rank = (p * samples.len() as f32).ceil() as usize;
+    samples.get(rank.clamp(1, samples.len().max(1)) - 1).copied()
+}
+
+// The most frequent repaint causes over the window, most frequent first.
+fn top_causes(samples: &VecDeque<Sample>) -> Vec<(&str, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for cause in samples.iter().flat_map(|sample| &sample.causes) {
+        *counts.entry(cause.as_str()).or_default() += 1;
+    }
+    let mut causes: Vec<(&str, usize)> = counts.into_iter().collect();
+    causes.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    causes.truncate(CAUSES_SHOWN);
+    causes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_fps() {
+        let times: Vec<f32> = (1..=100).map(|ms| ms as f32).collect();
+        assert_eq!(percentile(&times, 0.5), Some(50.0));
+        assert_eq!(percentile(&times, 0.99), Some(99.0));
+        assert_eq!(percentile(&times, 0.0), Some(1.0));
+        assert_eq!(percentile(&[], 0.5), None);
+
+        let now = Instant::now() + Duration::from_secs(5);
+        let frames = [now - Duration::from_secs(2), now - Duration::from_millis(500), now];
+        assert_eq!(fps(frames.into_iter(), now), 2.0);
+    }
+}
@@ -1,7 +1,13 @@
-use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+
 use crate::board::{Board, CellState, Hex};
+use crate::notation::Move;
 
 pub const DEFAULT_BOARD_SIZE: i32 = 11;
+// The largest board records load and the settings offer; past this the cells get too small to
+// click on most screens, and a hostile record could make replaying it exhaust memory.
+pub const MAX_BOARD_SIZE: i32 = 64;
 pub const HEX_DRAW_SIZE: f32 = 20.0;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -9,124 +15,501 @@ pub enum GameState {
     InProgress,
     Finished { winner: CellState },
     WaitingForPieRuleChoice, // Added for pie rule
+    Drawn, // Agreed by the players in match play; the board itself never draws
+    Resigned { winner: CellState }, // The other player resigned; the board may not be won yet
+    Adjourned { pie_rule_pending: bool }, // Stopped to be finished in a later session, see `resume`
+}
+
+// How many stones a turn places: `first` on the opening turn and `then` on every later one.
+// Standard Hex is 1 and 1; "12*" variants place one stone and then two per turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TurnStructure {
+    pub first: u32,
+    pub then: u32,
+    // Games recorded before format 3 (see `record`): a swap recolours Red's stone where it
+    // stands and leaves Blue to place, rather than being Blue's turn.
+    pub legacy_swap: bool,
 }
 
+impl TurnStructure {
+    pub const STANDARD: Self = Self { first: 1, then: 1, legacy_swap: false };
+    pub const ONE_THEN_TWO: Self = Self { first: 1, then: 2, legacy_swap: false };
+
+    // Stones to place on turn `turn`, counting the opening turn as 0.
+    pub fn stones_in_turn(&self, turn: u32) -> u32 {
+        if turn == 0 { self.first.max(1) } else { self.then.max(1) }
+    }
+
+    // Who made each move in `history`, Red first. The turn passes once a turn's stones are all
+    // placed, or its player passes or swaps: a swap is Blue's whole turn, as taking over Red's
+    // stone is a move, so Red places next.
+    pub fn movers(&self, history: &[Move]) -> Vec<CellState> {
+        self.walk(history).0
+    }
+
+    // Who is to move after `history`, however the game stands.
+    pub fn to_move(&self, history: &[Move]) -> CellState {
+        self.walk(history).1
+    }
+
+    fn walk(&self, history: &[Move]) -> (Vec<CellState>, CellState) {
+        let (mut player, mut turn, mut stones) = (CellState::Red, 0, 0);
+        let movers = history
+            .iter()
+            .map(|mv| {
+                let mover = player;
+                let ends_turn = match mv {
+                    Move::Place(_) => {
+                        stones += 1;
+                        stones >= self.stones_in_turn(turn)
+                    }
+                    Move::Pass => true,
+                    Move::Swap => !self.legacy_swap,
+                };
+                if ends_turn {
+                    player = match player {
+                        CellState::Red => CellState::Blue,
+                        _ => CellState::Red,
+                    };
+                    turn += 1;
+                    stones = 0;
+                }
+                mover
+            })
+            .collect();
+        (movers, player)
+    }
+}
+
+impl Default for TurnStructure {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+// What a player, or the players together, can do to a game; see `Game::apply`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Place(Hex),
+    // The answer to the pie rule: true takes over the first stone.
+    SwapDecision(bool),
+    Pass,
+    // By this player, whoever is to move.
+    Resign(CellState),
+    AgreeDraw,
+    Adjourn,
+    Resume,
+    // Undoes this many moves, e.g. once a takeback request is accepted.
+    TakeBack(usize),
+}
+
+// A recorded move is the action that made it; a swap is the pie rule taken.
+impl From<Move> for Action {
+    fn from(mv: Move) -> Self {
+        match mv {
+            Move::Place(hex) => Action::Place(hex),
+            Move::Swap => Action::SwapDecision(true),
+            Move::Pass => Action::Pass,
+        }
+    }
+}
+
+// What an action did, in order. A placement that wins or opens the pie rule is followed by
+// `Won` or `PieRuleOffered`; one that declines the swap is preceded by `SwapDeclined`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Placed { player: CellState, hex: Hex },
+    PieRuleOffered,
+    Swapped { player: CellState },
+    SwapDeclined { player: CellState },
+    Passed { player: CellState },
+    Won { winner: CellState },
+    Resigned { player: CellState },
+    DrawAgreed,
+    Adjourned,
+    Resumed,
+    TakenBack { count: usize },
+}
+
+// Why an action was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    Over,
+    Adjourned,
+    Illegal,
+    SwapNotOffered,
+    PassingNotAllowed,
+    PieRulePending,
+    NotAPlayer,
+    NotInProgress,
+    NotAdjourned,
+    NotEnoughMoves,
+}
+
+impl MoveError {
+    pub fn message(self) -> &'static str {
+        match self {
+            MoveError::Over => "Game is already over",
+            MoveError::Adjourned => "Game is adjourned",
+            MoveError::Illegal => "Illegal move",
+            MoveError::SwapNotOffered => "Swap is only allowed right after the first move",
+            MoveError::PassingNotAllowed => "Passing is not allowed in this game",
+            MoveError::PieRulePending => "Cannot pass now",
+            MoveError::NotAPlayer => "Only a player can resign",
+            MoveError::NotInProgress => "Only a game in progress can be adjourned",
+            MoveError::NotAdjourned => "Game is not adjourned",
+            MoveError::NotEnoughMoves => "Not enough moves to take back",
+        }
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+fn opponent(player: CellState) -> Option<CellState> {
+    match player {
+        CellState::Red => Some(CellState::Blue),
+        CellState::Blue => Some(CellState::Red),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
 pub struct Game {
     pub board: Board,
     pub current_player: CellState,
     pub state: GameState,
     pub turn_count: u32, // Added to track turns for pie rule
     pub first_player_move: Option<Hex>, // Added for pie rule
+    pub history: Vec<Move>, // Placements and swaps in play order; declining the swap is implicit
+    pub pie_rule: bool, // When false the first move simply passes the turn
+    pub turn_structure: TurnStructure,
+    pub turn: u32, // Index of the current turn; the opening turn is 0
+    pub turn_stones: u32, // Stones placed so far in the current turn
+    pub allow_pass: bool, // Standard Hex has no passing; some variants and engine protocols need it
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
     pub fn new() -> Self {
+        Self::with_size(DEFAULT_BOARD_SIZE)
+    }
+
+    pub fn with_size(size: i32) -> Self {
+        Self::with_board(Board::new(size))
+    }
+
+    // A game on any board shape; the shape decides which sides each player must join. A board
+    // that already has a winning chain, e.g. one set up by hand, starts out finished.
+    pub fn with_board(board: Board) -> Self {
+        let winner = board.winner();
+        if let Some(winner) = winner {
+            tracing::info!(?winner, "loaded position is already won");
+        }
         Self {
-            board: Board::new(DEFAULT_BOARD_SIZE),
-            current_player: CellState::Red,
-            state: GameState::InProgress,
+            board,
+            current_player: winner.unwrap_or(CellState::Red),
+            state: winner.map_or(GameState::InProgress, |winner| GameState::Finished { winner }),
             turn_count: 0, // Initialize turn count
             first_player_move: None, // Initialize first player move
+            history: Vec::new(),
+            pie_rule: true,
+            turn_structure: TurnStructure::STANDARD,
+            turn: 0,
+            turn_stones: 0,
+            allow_pass: false,
         }
     }
 
-    pub fn handle_click(&mut self, hex: Hex) {
-        if self.state != GameState::InProgress {
-            return;
-        }
+    pub fn with_pie_rule(mut self, pie_rule: bool) -> Self {
+        self.pie_rule = pie_rule;
+        self
+    }
 
-        if let Some(cell) = self.board.cells.get(&hex) {
-            if *cell == CellState::Empty {
-                self.board.set_cell(hex, self.current_player);
-                self.turn_count += 1; // Increment turn count
+    pub fn with_turn_structure(mut self, turn_structure: TurnStructure) -> Self {
+        self.turn_structure = turn_structure;
+        self
+    }
 
-                if self.turn_count == 1 { // After the very first move
-                    self.first_player_move = Some(hex);
-                    // Switch current player to the other color, as they will be the one deciding on the pie rule
-                    self.current_player = match self.current_player {
-                        CellState::Red => CellState::Blue,
-                        CellState::Blue => CellState::Red,
-                        _ => self.current_player,
-                    };
-                    self.state = GameState::WaitingForPieRuleChoice;
-                    return; // Wait for pie rule decision
-                }
+    pub fn with_pass(mut self, allow_pass: bool) -> Self {
+        self.allow_pass = allow_pass;
+        self
+    }
 
-                if self.check_win_condition() {
-                    self.state = GameState::Finished { winner: self.current_player };
-                } else {
-                    self.current_player = match self.current_player {
-                        CellState::Red => CellState::Blue,
-                        CellState::Blue => CellState::Red,
-                        _ => self.current_player,
-                    };
+    // Everything that changes a game goes through here; the methods below are shorthands for
+    // it. On error the game is left as it was.
+    pub fn apply(&mut self, action: Action) -> Result<Vec<GameEvent>, MoveError> {
+        let mut events = Vec::new();
+        match (self.state, action) {
+            (_, Action::TakeBack(count)) => {
+                self.replay_without(count)?;
+                events.push(GameEvent::TakenBack { count });
+            }
+            (GameState::Adjourned { pie_rule_pending }, Action::Resume) => {
+                tracing::info!(moves = self.history.len(), "game resumed");
+                self.state = if pie_rule_pending { GameState::WaitingForPieRuleChoice } else { GameState::InProgress };
+                events.push(GameEvent::Resumed);
+            }
+            (_, Action::Resume) => return Err(MoveError::NotAdjourned),
+            (GameState::InProgress | GameState::WaitingForPieRuleChoice, Action::Adjourn) => {
+                tracing::info!(moves = self.history.len(), "game adjourned");
+                let pie_rule_pending = self.state == GameState::WaitingForPieRuleChoice;
+                self.state = GameState::Adjourned { pie_rule_pending };
+                events.push(GameEvent::Adjourned);
+            }
+            (_, Action::Adjourn) => return Err(MoveError::NotInProgress),
+            (GameState::Finished { .. } | GameState::Drawn | GameState::Resigned { .. }, _) => {
+                return Err(MoveError::Over)
+            }
+            (_, Action::Resign(player)) => {
+                let winner = opponent(player).ok_or(MoveError::NotAPlayer)?;
+                tracing::info!(?player, moves = self.history.len(), "resigned");
+                self.state = GameState::Resigned { winner };
+                events.push(GameEvent::Resigned { player });
+            }
+            (_, Action::AgreeDraw) => {
+                tracing::info!(moves = self.history.len(), "draw agreed");
+                self.state = GameState::Drawn;
+                events.push(GameEvent::DrawAgreed);
+            }
+            (GameState::Adjourned { .. }, _) => return Err(MoveError::Adjourned),
+            (GameState::InProgress, Action::SwapDecision(_)) => return Err(MoveError::SwapNotOffered),
+            (GameState::WaitingForPieRuleChoice, Action::SwapDecision(swap)) => self.decide_pie_rule(swap, &mut events),
+            // A placement or pass while the pie rule is pending declines the swap, as move lists
+            // record it.
+            (GameState::InProgress | GameState::WaitingForPieRuleChoice, Action::Place(hex)) => {
+                if !self.board.is_valid_move(&hex) {
+                    return Err(MoveError::Illegal);
+                }
+                if self.state == GameState::WaitingForPieRuleChoice {
+                    self.decide_pie_rule(false, &mut events);
+                }
+                self.place(hex, &mut events);
+            }
+            (GameState::InProgress | GameState::WaitingForPieRuleChoice, Action::Pass) => {
+                if !self.allow_pass {
+                    return Err(MoveError::PassingNotAllowed);
+                }
+                if self.state == GameState::WaitingForPieRuleChoice {
+                    self.decide_pie_rule(false, &mut events);
                 }
+                tracing::info!(player = ?self.current_player, "pass");
+                events.push(GameEvent::Passed { player: self.current_player });
+                self.history.push(Move::Pass);
+                self.end_turn();
             }
         }
+        // A finished game leaves the winner as the player to move.
+        debug_assert!(
+            !matches!(self.state, GameState::InProgress | GameState::WaitingForPieRuleChoice)
+                || self.current_player == self.turn_structure.to_move(&self.history),
+            "turn order broken after {:?}",
+            action
+        );
+        Ok(events)
     }
 
-    pub fn handle_pie_rule_decision(&mut self, apply_pie_rule: bool) {
-        if self.state != GameState::WaitingForPieRuleChoice {
-            return;
+    // Ends the current turn without placing (the rest of it, in multi-stone turns). Unlike a
+    // recorded pass, this does not answer a pending pie rule.
+    pub fn pass(&mut self) -> Result<(), &'static str> {
+        if self.state == GameState::WaitingForPieRuleChoice {
+            return Err(MoveError::PieRulePending.message());
         }
+        self.apply_quietly(Action::Pass)
+    }
 
-        if apply_pie_rule {
-            if let Some(first_move_hex) = self.first_player_move {
-                let second_player_color = self.current_player; // The player who chose the pie rule
+    // Ends an unfinished game as a draw both players agreed to.
+    pub fn agree_draw(&mut self) -> Result<(), &'static str> {
+        self.apply_quietly(Action::AgreeDraw)
+    }
 
-                // Swap the colors
-                self.board.set_cell(first_move_hex, second_player_color);
-                // current_player remains the same, as they now play with the swapped color.
-            }
+    // Ends an unfinished game with `player` resigning, whoever is to move.
+    pub fn resign(&mut self, player: CellState) -> Result<(), &'static str> {
+        self.apply_quietly(Action::Resign(player))
+    }
+
+    // Stops play until `resume`; nothing can be played while adjourned.
+    pub fn adjourn(&mut self) -> Result<(), &'static str> {
+        self.apply_quietly(Action::Adjourn)
+    }
+
+    pub fn resume(&mut self) {
+        let _ = self.apply(Action::Resume);
+    }
+
+    fn apply_quietly(&mut self, action: Action) -> Result<(), &'static str> {
+        self.apply(action).map(drop).map_err(MoveError::message)
+    }
+
+    fn end_turn(&mut self) {
+        self.current_player = opponent(self.current_player).unwrap_or(self.current_player);
+        self.turn += 1;
+        self.turn_stones = 0;
+    }
+
+    // Cells the player to move may place on. While the pie-rule choice (swap / no swap, see
+    // `handle_pie_rule_decision`) is pending, placing declines the swap, as `play_move` takes it;
+    // nothing is playable once the game is over or adjourned.
+    pub fn legal_moves(&self) -> impl Iterator<Item = Hex> {
+        let cells = if self.accepts_placements() {
+            self.board.empty_cells()
         } else {
-            // No pie rule. current_player is already set to the second player after the first move,
-            // so they just continue playing as that color.
+            Vec::new()
+        };
+        cells.into_iter()
+    }
+
+    // Whether `player` placing at `hex` would win on the spot, without playing it. Either player
+    // can be asked, so a search can spot the opponent's threats as well as its own wins.
+    pub fn would_win(&self, hex: Hex, player: CellState) -> bool {
+        self.state == GameState::InProgress
+            && matches!(player, CellState::Red | CellState::Blue)
+            && self.board.would_connect(hex, player, &self.board.shape().goal_sides(player))
+    }
+
+    pub fn is_legal_move(&self, hex: &Hex) -> bool {
+        self.accepts_placements() && self.board.is_valid_move(hex)
+    }
+
+    fn accepts_placements(&self) -> bool {
+        matches!(self.state, GameState::InProgress | GameState::WaitingForPieRuleChoice)
+    }
+
+    // Places for the player to move; does nothing unless that is a legal move. Clicks don't
+    // answer the pie rule.
+    pub fn handle_click(&mut self, hex: Hex) {
+        if self.state == GameState::InProgress {
+            let _ = self.apply(Action::Place(hex));
         }
-        self.state = GameState::InProgress; // Resume game
     }
 
-    fn check_win_condition(&self) -> bool {
-        let size = self.board.size;
-        let mut visited = HashSet::new();
-        let mut queue = VecDeque::new();
-
-        let (start_condition, end_condition): (Box<dyn Fn(Hex) -> bool>, Box<dyn Fn(Hex) -> bool>) = match self.current_player {
-            CellState::Red => (
-                Box::new(move |h: Hex| h.q == 0),
-                Box::new(move |h: Hex| h.q == size - 1),
-            ),
-            CellState::Blue => (
-                Box::new(move |h: Hex| h.r == 0),
-                Box::new(move |h: Hex| h.r == size - 1),
-            ),
-            _ => return false,
-        };
+    fn place(&mut self, hex: Hex, events: &mut Vec<GameEvent>) {
+        let player = self.current_player;
+        self.board.set_cell(hex, player);
+        self.turn_count += 1;
+        self.history.push(Move::Place(hex));
+        tracing::info!(?player, cell = %hex.notation(), turn = self.turn_count, "move played");
+        events.push(GameEvent::Placed { player, hex });
 
-        for (hex, state) in &self.board.cells {
-            if *state == self.current_player && start_condition(*hex) {
-                queue.push_back(*hex);
-                visited.insert(*hex);
+        // Checked before the pie rule: on a 1x1 board the first stone already wins.
+        if self.check_win_condition() {
+            tracing::info!(winner = ?player, turns = self.turn_count, "game finished");
+            self.state = GameState::Finished { winner: player };
+            events.push(GameEvent::Won { winner: player });
+        } else if self.turn == 0 && self.pie_rule && self.turn_structure.stones_in_turn(0) == 1 {
+            // After a one-stone opening turn the other player decides on the pie rule.
+            self.first_player_move = Some(hex);
+            self.end_turn();
+            self.state = GameState::WaitingForPieRuleChoice;
+            events.push(GameEvent::PieRuleOffered);
+        } else {
+            self.turn_stones += 1;
+            if self.turn_stones >= self.turn_structure.stones_in_turn(self.turn) {
+                self.end_turn();
             }
+            // Otherwise the same player places again.
         }
+    }
 
-        while let Some(hex) = queue.pop_front() {
-            if end_condition(hex) {
-                return true;
-            }
+    pub fn handle_pie_rule_decision(&mut self, apply_pie_rule: bool) {
+        let _ = self.apply(Action::SwapDecision(apply_pie_rule));
+    }
 
-            for neighbor in hex.get_neighbors() {
-                if !visited.contains(&neighbor) {
-                    if let Some(state) = self.board.cells.get(&neighbor) {
-                        if *state == self.current_player {
-                            visited.insert(neighbor);
-                            queue.push_back(neighbor);
-                        }
-                    }
+    // Blue decides. Each color stays with the player who started with it: a swap replaces Red's
+    // opening stone with a Blue one (see `swapped_cell`) and is Blue's turn, so Red moves next;
+    // declining leaves Blue to place.
+    fn decide_pie_rule(&mut self, swap: bool, events: &mut Vec<GameEvent>) {
+        let player = self.current_player;
+        tracing::info!(applied = swap, ?player, "pie rule decided");
+        match self.first_player_move.filter(|_| swap) {
+            Some(first_move) => {
+                let legacy = self.turn_structure.legacy_swap;
+                let cell = if legacy { first_move } else { self.swapped_cell(first_move) };
+                self.board.set_cell(first_move, CellState::Empty);
+                self.board.set_cell(cell, player);
+                self.history.push(Move::Swap);
+                events.push(GameEvent::Swapped { player });
+                if !legacy {
+                    self.end_turn();
                 }
             }
+            None => events.push(GameEvent::SwapDeclined { player }),
+        }
+        self.state = GameState::InProgress;
+    }
+
+    // Where the swap puts Blue's stone: Red's opening stone mirrored in the short diagonal, as
+    // SGF's swap-pieces has it. A Blue stone at (q, r) is worth what a Red one at (r, q) is, so
+    // Blue ends up with Red's position and Red is to move, as Blue was. Shapes where both players
+    // join the same sides keep the stone on its cell, as does a mirror off the board or on a hole.
+    pub fn swapped_cell(&self, hex: Hex) -> Hex {
+        let mirrored = Hex { q: hex.r, r: hex.q };
+        let shape = self.board.shape();
+        let same_goals = shape.goal_sides(CellState::Red) == shape.goal_sides(CellState::Blue);
+        if same_goals || (mirrored != hex && self.board.get_cell(&mirrored) != Some(&CellState::Empty)) {
+            hex
+        } else {
+            mirrored
+        }
+    }
+
+    // Plays a move given in notation form. A placement or pass while the pie-rule choice is
+    // pending declines the swap, matching how move lists record it.
+    pub fn play_move(&mut self, mv: Move) -> Result<(), &'static str> {
+        self.apply_quietly(mv.into())
+    }
+
+    // Undoes the last `count` moves (a swap or pass counts as one) by replaying the rest on the
+    // same board and rules. Taking back the move after the first one reopens the pie rule choice.
+    pub fn take_back(&mut self, count: usize) -> Result<(), &'static str> {
+        self.apply_quietly(Action::TakeBack(count))
+    }
+
+    fn replay_without(&mut self, count: usize) -> Result<(), MoveError> {
+        if count == 0 || count > self.history.len() {
+            return Err(MoveError::NotEnoughMoves);
+        }
+        let mut game = self.without_moves();
+        for mv in &self.history[..self.history.len() - count] {
+            game.apply((*mv).into())?;
+        }
+        tracing::info!(count, moves = game.history.len(), "moves taken back");
+        *self = game;
+        Ok(())
+    }
+
+    // The same board, blocked cells and rules before anyone moved.
+    pub fn without_moves(&self) -> Game {
+        let mut board = self.board.clone();
+        for cell in Arc::make_mut(&mut board.cells).values_mut() {
+            if matches!(cell, CellState::Red | CellState::Blue) {
+                *cell = CellState::Empty;
+            }
         }
+        Game::with_board(board)
+            .with_pie_rule(self.pie_rule)
+            .with_turn_structure(self.turn_structure)
+            .with_pass(self.allow_pass)
+    }
+
+    // Who made each move of a standard game; see `TurnStructure::movers` for variants.
+    pub fn movers(history: &[Move]) -> Vec<CellState> {
+        TurnStructure::STANDARD.movers(history)
+    }
 
-        false
+    fn check_win_condition(&self) -> bool {
+        let sides = self.board.shape().goal_sides(self.current_player);
+        self.board.connects(self.current_player, &sides)
     }
 }
 
@@ -134,6 +517,19 @@ impl Game {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_movers_match_play() {
+        for moves in [&["b2", "swap", "a1", "a2"][..], &["b2", "a1", "a2", "c1"][..]] {
+            let mut game = Game::with_size(3);
+            let mut expected = Vec::new();
+            for mv in moves {
+                expected.push(game.current_player);
+                game.play_move(mv.parse().unwrap()).unwrap();
+            }
+            assert_eq!(Game::movers(&game.history), expected, "{:?}", moves);
+        }
+    }
+
     #[test]
     fn test_red_wins() {
         let mut game = Game::new();
@@ -162,6 +558,23 @@ mod tests {
         assert!(game.check_win_condition());
     }
 
+    #[test]
+    fn test_loaded_position_already_won() {
+        // Blue's column is complete though Red would be to move next.
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 0, r: 0 }, CellState::Red);
+        assert_eq!(board.winner(), None);
+        assert_eq!(Game::with_board(board.clone()).state, GameState::InProgress);
+        for r in 0..3 {
+            board.set_cell(Hex { q: 1, r }, CellState::Blue);
+        }
+        assert_eq!(board.winner(), Some(CellState::Blue));
+
+        let mut game = Game::with_board(board);
+        assert_eq!(game.state, GameState::Finished { winner: CellState::Blue });
+        assert_eq!(game.apply(Action::Place(Hex { q: 2, r: 2 })), Err(MoveError::Over));
+    }
+
     #[test]
     fn test_no_win() {
         let mut game = Game::new();
@@ -251,8 +664,8 @@ mod tests {
         assert_eq!(game.board.get_cell(&first_move_hex), Some(&CellState::Blue));
         // Game state should be InProgress
         assert_eq!(game.state, GameState::InProgress);
-        // Current player should still be Blue (who chose pie rule, now playing as original Red)
-        assert_eq!(game.current_player, CellState::Blue);
+        // The swap was Blue's turn, so Red moves next
+        assert_eq!(game.current_player, CellState::Red);
     }
 
     #[test]
@@ -281,15 +694,15 @@ mod tests {
         let mut game = Game::new();
         let first_move_hex = Hex { q: 0, r: 0 };
         game.handle_click(first_move_hex); // Red plays 1st move
-        game.handle_pie_rule_decision(true); // Blue applies pie rule, Red's piece is now Blue's, Blue plays as Red.
+        game.handle_pie_rule_decision(true); // Blue applies pie rule, Red's piece is now Blue's
 
-        // Blue's turn (as Red color)
-        assert_eq!(game.current_player, CellState::Blue);
+        // Red's turn, one stone behind
+        assert_eq!(game.current_player, CellState::Red);
         let second_move_hex = Hex { q: 1, r: 0 };
         game.handle_click(second_move_hex);
 
-        assert_eq!(game.board.get_cell(&second_move_hex), Some(&CellState::Blue));
-        assert_eq!(game.current_player, CellState::Red); // Red's turn (as Blue color)
+        assert_eq!(game.board.get_cell(&second_move_hex), Some(&CellState::Red));
+        assert_eq!(game.current_player, CellState::Blue);
         assert_eq!(game.turn_count, 2);
     }
 
@@ -309,4 +722,390 @@ mod tests {
         assert_eq!(game.current_player, CellState::Red); // Red's turn (as Red color)
         assert_eq!(game.turn_count, 2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_legal_moves_in_progress() {
+        let mut game = Game::with_size(2);
+        assert_eq!(game.legal_moves().count(), 4);
+
+        game.handle_click(Hex { q: 0, r: 0 });
+        game.handle_pie_rule_decision(false);
+        let moves: Vec<Hex> = game.legal_moves().collect();
+        assert_eq!(moves, vec![Hex { q: 1, r: 0 }, Hex { q: 0, r: 1 }, Hex { q: 1, r: 1 }]);
+        assert!(game.is_legal_move(&Hex { q: 1, r: 1 }));
+        assert!(!game.is_legal_move(&Hex { q: 0, r: 0 }));
+        assert!(!game.is_legal_move(&Hex { q: 5, r: 5 }));
+    }
+
+    #[test]
+    fn test_placing_while_waiting_for_pie_rule_declines_it() {
+        let mut game = Game::with_size(3);
+        game.handle_click(Hex { q: 1, r: 1 });
+        assert_eq!(game.state, GameState::WaitingForPieRuleChoice);
+        assert_eq!(game.legal_moves().count(), 8);
+        assert!(!game.is_legal_move(&Hex { q: 1, r: 1 }));
+        for hex in game.board.cells.keys() {
+            assert_eq!(game.is_legal_move(hex), game.clone().play_move(Move::Place(*hex)).is_ok(), "{hex:?}");
+        }
+        let hex = Hex { q: 0, r: 0 };
+        assert!(game.is_legal_move(&hex));
+        game.play_move(Move::Place(hex)).unwrap();
+        assert_eq!(game.state, GameState::InProgress);
+        assert_eq!(game.board.get_cell(&hex), Some(&CellState::Blue));
+        assert_eq!(game.history, vec![Move::Place(Hex { q: 1, r: 1 }), Move::Place(hex)]);
+    }
+
+    #[test]
+    fn test_no_legal_moves_when_finished() {
+        let mut game = Game::with_size(3);
+        game.state = GameState::Finished { winner: CellState::Red };
+        assert_eq!(game.legal_moves().count(), 0);
+    }
+
+    #[test]
+    fn test_history_records_moves_and_swap() {
+        let mut game = Game::with_size(3);
+        game.handle_click(Hex { q: 1, r: 1 });
+        game.handle_pie_rule_decision(true);
+        game.handle_click(Hex { q: 0, r: 0 });
+        assert_eq!(
+            game.history,
+            vec![Move::Place(Hex { q: 1, r: 1 }), Move::Swap, Move::Place(Hex { q: 0, r: 0 })]
+        );
+    }
+
+    #[test]
+    fn test_win_detection_on_large_boards() {
+        for size in [26, 30] {
+            // Red runs along the first row while Blue answers on the second.
+            let mut game = Game::with_size(size);
+            for q in 0..size {
+                let red = Hex { q, r: 0 }.notation();
+                game.play_move(Move::parse_for_size(&red, size).unwrap()).unwrap();
+                if q + 1 < size {
+                    assert!(!matches!(game.state, GameState::Finished { .. }), "{} on size {}", red, size);
+                    let blue = Hex { q, r: 1 }.notation();
+                    game.play_move(Move::parse_for_size(&blue, size).unwrap()).unwrap();
+                }
+            }
+            assert_eq!(game.state, GameState::Finished { winner: CellState::Red });
+            assert_eq!(game.history.len() as i32, 2 * size - 1);
+        }
+        assert_eq!(Hex { q: 29, r: 0 }.notation(), "ad1");
+    }
+
+    #[test]
+    fn test_size_one_is_an_instant_win() {
+        let mut game = Game::with_size(1);
+        assert_eq!(game.legal_moves().collect::<Vec<_>>(), vec![Hex { q: 0, r: 0 }]);
+        game.handle_click(Hex { q: 0, r: 0 });
+        assert_eq!(game.state, GameState::Finished { winner: CellState::Red });
+        assert_eq!(game.legal_moves().count(), 0);
+        assert!(game.play_move(Move::Swap).is_err());
+    }
+
+    #[test]
+    fn test_without_pie_rule() {
+        let mut game = Game::with_size(2).with_pie_rule(false);
+        game.handle_click(Hex { q: 0, r: 0 });
+        assert_eq!(game.state, GameState::InProgress);
+        assert_eq!(game.current_player, CellState::Blue);
+        assert!(game.play_move(Move::Swap).is_err());
+        game.handle_click(Hex { q: 0, r: 1 });
+        game.handle_click(Hex { q: 1, r: 0 });
+        assert_eq!(game.state, GameState::Finished { winner: CellState::Red });
+        assert_eq!(Game::movers(&game.history), vec![CellState::Red, CellState::Blue, CellState::Red]);
+    }
+
+    #[test]
+    fn test_small_boards_always_finish() {
+        for size in 1..=3 {
+            for pie_rule in [true, false] {
+                let mut game = Game::with_size(size).with_pie_rule(pie_rule);
+                while !matches!(game.state, GameState::Finished { .. }) {
+                    if game.state == GameState::WaitingForPieRuleChoice {
+                        game.handle_pie_rule_decision(false);
+                    }
+                    let hex = game.legal_moves().min_by_key(|hex| (hex.r, hex.q)).unwrap();
+                    game.handle_click(hex);
+                }
+                assert!(game.history.len() as i32 <= size * size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_of_y_on_a_triangle() {
+        let mut game = Game::with_board(Board::with_shape(crate::shape::Triangle { side: 3 })).with_pie_rule(false);
+        assert_eq!(game.legal_moves().count(), 6);
+        // Red's a1-b1 touches sides q = 0 and r = 0 but not the far side yet.
+        for mv in ["a1", "a2", "b1", "a3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(game.state, GameState::InProgress);
+        game.play_move("c1".parse().unwrap()).unwrap();
+        assert_eq!(game.state, GameState::Finished { winner: CellState::Red });
+    }
+
+    #[test]
+    fn test_blocked_cells_are_not_playable() {
+        let mut game = Game::with_size(3);
+        game.board.set_cell(Hex { q: 1, r: 1 }, CellState::Blocked);
+        assert_eq!(game.legal_moves().count(), 8);
+        assert!(!game.is_legal_move(&Hex { q: 1, r: 1 }));
+        assert!(game.play_move(Move::Place(Hex { q: 1, r: 1 })).is_err());
+        game.handle_click(Hex { q: 1, r: 1 });
+        assert_eq!(game.history, vec![]);
+
+        // With a hole the board can fill up without a winner, so play until nothing is legal.
+        let mut ai = crate::ai::RandomPlayer::seeded(3);
+        loop {
+            if game.state == GameState::WaitingForPieRuleChoice {
+                game.handle_pie_rule_decision(false);
+            }
+            let Some(hex) = crate::ai::AiPlayer::choose_move(&mut ai, &game) else {
+                break;
+            };
+            assert_ne!(hex, Hex { q: 1, r: 1 });
+            game.handle_click(hex);
+        }
+        assert_eq!(game.board.get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Blocked));
+    }
+
+    #[test]
+    fn test_two_stones_per_turn() {
+        let structure = TurnStructure::ONE_THEN_TWO;
+        assert_eq!((0..3).map(|turn| structure.stones_in_turn(turn)).collect::<Vec<_>>(), [1, 2, 2]);
+
+        let mut game = Game::with_size(4).with_turn_structure(structure);
+        game.handle_click(Hex { q: 0, r: 0 });
+        game.handle_pie_rule_decision(false);
+        game.handle_click(Hex { q: 0, r: 1 });
+        assert_eq!(game.current_player, CellState::Blue);
+        game.handle_click(Hex { q: 1, r: 1 });
+        assert_eq!(game.current_player, CellState::Red);
+        for hex in [Hex { q: 1, r: 0 }, Hex { q: 2, r: 0 }] {
+            game.handle_click(hex);
+        }
+        assert_eq!(game.current_player, CellState::Blue);
+        assert_eq!(
+            structure.movers(&game.history),
+            vec![CellState::Red, CellState::Blue, CellState::Blue, CellState::Red, CellState::Red]
+        );
+
+        // A swap is the whole of Blue's turn; Red then places two.
+        let mut game = Game::with_size(4).with_turn_structure(structure);
+        for mv in ["a1", "swap", "b2", "c2", "a3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(
+            structure.movers(&game.history),
+            vec![CellState::Red, CellState::Blue, CellState::Red, CellState::Red, CellState::Blue]
+        );
+        assert_eq!(game.current_player, CellState::Blue);
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut game = Game::with_size(3);
+        game.play_move(Move::Place(Hex { q: 0, r: 0 })).unwrap();
+        game.handle_pie_rule_decision(false);
+        assert!(game.play_move(Move::Pass).is_err());
+
+        let mut game = Game::with_size(3).with_pass(true).with_turn_structure(TurnStructure::ONE_THEN_TWO);
+        assert!(game.pass().is_ok());
+        assert_eq!(game.current_player, CellState::Blue);
+        game.handle_click(Hex { q: 1, r: 1 });
+        // Passing mid-turn gives up the second stone.
+        game.play_move(Move::Pass).unwrap();
+        assert_eq!(game.current_player, CellState::Red);
+        game.handle_click(Hex { q: 0, r: 0 });
+        assert_eq!(game.current_player, CellState::Red);
+        assert_eq!(
+            game.turn_structure.movers(&game.history),
+            vec![CellState::Red, CellState::Blue, CellState::Blue, CellState::Red]
+        );
+        assert_eq!(game.history, vec![Move::Pass, Move::Place(Hex { q: 1, r: 1 }), Move::Pass, Move::Place(Hex { q: 0, r: 0 })]);
+
+        // A recorded pass while the pie rule is pending declines the swap, like a placement, so
+        // declining and then passing replays.
+        let mut game = Game::with_size(3).with_pass(true);
+        game.handle_click(Hex { q: 1, r: 1 });
+        assert!(game.pass().is_err());
+        game.play_move(Move::Pass).unwrap();
+        assert_eq!((game.state, game.current_player), (GameState::InProgress, CellState::Red));
+        assert_eq!(game.history, vec![Move::Place(Hex { q: 1, r: 1 }), Move::Pass]);
+    }
+
+    #[test]
+    fn test_play_move() {
+        let mut game = Game::with_size(3);
+        assert!(game.play_move(Move::Swap).is_err());
+        assert!(game.play_move(Move::Place(Hex { q: 0, r: 0 })).is_ok());
+        assert!(game.play_move(Move::Place(Hex { q: 0, r: 0 })).is_err());
+        // Still waiting: an illegal placement must not decide the pie rule.
+        assert_eq!(game.state, GameState::WaitingForPieRuleChoice);
+
+        assert!(game.play_move(Move::Place(Hex { q: 1, r: 1 })).is_ok());
+        assert_eq!(game.board.get_cell(&Hex { q: 0, r: 0 }), Some(&CellState::Red));
+        assert_eq!(game.board.get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Blue));
+        assert_eq!(game.current_player, CellState::Red);
+        assert!(game.play_move(Move::Swap).is_err());
+    }
+
+    #[test]
+    fn test_take_back() {
+        let mut game = Game::with_size(3);
+        for mv in ["b2", "swap", "a1", "c1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        game.take_back(1).unwrap();
+        assert_eq!(game.board.get_cell(&Hex { q: 2, r: 0 }), Some(&CellState::Empty));
+        assert_eq!(game.current_player, CellState::Blue);
+
+        // Back past the swap: Blue gets the pie rule choice again.
+        game.take_back(2).unwrap();
+        assert_eq!(game.state, GameState::WaitingForPieRuleChoice);
+        assert_eq!(game.board.get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Red));
+        assert_eq!(game.history.len(), 1);
+
+        assert!(game.take_back(2).is_err());
+        game.take_back(1).unwrap();
+        assert_eq!(game.current_player, CellState::Red);
+        assert_eq!(game.state, GameState::InProgress);
+    }
+
+    #[test]
+    fn test_every_action_in_every_state() {
+        use MoveError::*;
+        let b2 = Hex { q: 1, r: 1 };
+        let fresh = Game::with_size(3).with_pass(true);
+        let mut waiting = fresh.clone();
+        waiting.apply(Action::Place(b2)).unwrap();
+        let after = |game: &Game, action| {
+            let mut game = game.clone();
+            game.apply(action).unwrap();
+            game
+        };
+        let mut won = Game::with_size(1);
+        won.apply(Action::Place(Hex { q: 0, r: 0 })).unwrap();
+        let states = [
+            fresh.clone(),
+            waiting.clone(),
+            after(&waiting, Action::Adjourn),
+            won,
+            after(&waiting, Action::Resign(CellState::Blue)),
+            after(&waiting, Action::AgreeDraw),
+        ];
+        let actions = [
+            Action::Place(Hex { q: 2, r: 2 }),
+            Action::SwapDecision(true),
+            Action::SwapDecision(false),
+            Action::Pass,
+            Action::Resign(CellState::Red),
+            Action::Resign(CellState::Empty),
+            Action::AgreeDraw,
+            Action::Adjourn,
+            Action::Resume,
+            Action::TakeBack(1),
+        ];
+        const OK: Result<(), MoveError> = Ok(());
+        let (no_swap, adjourned, over) = (Err(SwapNotOffered), Err(Adjourned), Err(Over));
+        let over = [over, over, over, over, over, over, over, Err(NotInProgress), Err(NotAdjourned), OK];
+        let expected = [
+            [OK, no_swap, no_swap, OK, OK, Err(NotAPlayer), OK, OK, Err(NotAdjourned), Err(NotEnoughMoves)],
+            [OK, OK, OK, OK, OK, Err(NotAPlayer), OK, OK, Err(NotAdjourned), OK],
+            [adjourned, adjourned, adjourned, adjourned, OK, Err(NotAPlayer), OK, Err(NotInProgress), OK, OK],
+            over,
+            over,
+            over,
+        ];
+        for (state, (game, expected)) in states.iter().zip(expected).enumerate() {
+            for (action, expected) in actions.iter().zip(expected) {
+                let mut played = game.clone();
+                let result = played.apply(*action).map(drop);
+                assert_eq!(result, expected, "{:?} in state {}", action, state);
+                if result.is_err() {
+                    // A refused action changes nothing.
+                    assert_eq!((played.state, played.history), (game.state, game.history.clone()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_reports_events() {
+        let (b2, a1) = (Hex { q: 1, r: 1 }, Hex { q: 0, r: 0 });
+        let mut game = Game::with_size(3);
+        assert_eq!(
+            game.apply(Action::Place(b2)),
+            Ok(vec![GameEvent::Placed { player: CellState::Red, hex: b2 }, GameEvent::PieRuleOffered])
+        );
+        assert_eq!(game.apply(Action::SwapDecision(true)), Ok(vec![GameEvent::Swapped { player: CellState::Blue }]));
+        assert_eq!(game.apply(Action::TakeBack(1)), Ok(vec![GameEvent::TakenBack { count: 1 }]));
+        // A placement while the pie rule is pending declines it first.
+        assert_eq!(
+            game.apply(Action::Place(a1)),
+            Ok(vec![
+                GameEvent::SwapDeclined { player: CellState::Blue },
+                GameEvent::Placed { player: CellState::Blue, hex: a1 }
+            ])
+        );
+        assert_eq!(game.apply(Move::Swap.into()), Err(MoveError::SwapNotOffered));
+
+        let mut game = Game::with_size(1);
+        let events = game.apply(Action::Place(a1)).unwrap();
+        let placed = GameEvent::Placed { player: CellState::Red, hex: a1 };
+        assert_eq!(events, [placed, GameEvent::Won { winner: CellState::Red }]);
+    }
+
+    #[test]
+    fn test_turn_order_after_either_pie_decision() {
+        // Every way to play moves 2 to 4 on a 3x3 board after b1, with and without the swap.
+        let (b1, a2) = (Hex { q: 1, r: 0 }, Hex { q: 0, r: 1 });
+        for swap in [true, false] {
+            let mut opened = Game::with_size(3);
+            opened.apply(Action::Place(b1)).unwrap();
+            opened.apply(Action::SwapDecision(swap)).unwrap();
+            // The swap mirrors the stone to a2 in Blue, so Blue holds what Red held on b1.
+            let (stone, owner) = if swap { (a2, CellState::Blue) } else { (b1, CellState::Red) };
+            assert_eq!(opened.board.get_cell(&stone), Some(&owner));
+            assert_eq!(opened.board.empty_cells().len(), 8);
+            let cells = opened.board.empty_cells();
+            for (i, &first) in cells.iter().enumerate() {
+                for (j, &second) in cells.iter().enumerate().filter(|&(j, _)| j != i) {
+                    for &third in cells.iter().enumerate().filter(|&(k, _)| k != i && k != j).map(|(_, hex)| hex) {
+                        let mut game = opened.clone();
+                        for hex in [first, second, third] {
+                            if game.state != GameState::InProgress {
+                                break;
+                            }
+                            let mover = game.current_player;
+                            game.apply(Action::Place(hex)).unwrap();
+                            // Each player keeps their color: the stone is the mover's, and after a
+                            // swap Red moves first.
+                            assert_eq!(game.board.get_cell(&hex), Some(&mover));
+                            let movers = game.turn_structure.movers(&game.history);
+                            assert_eq!(movers.last(), Some(&mover));
+                            let placed = game.history.iter().filter(|mv| matches!(mv, Move::Place(_))).count();
+                            let expected = if (placed % 2 == 0) == swap { CellState::Red } else { CellState::Blue };
+                            assert_eq!(mover, expected, "{:?}", game.history);
+                            if game.state == GameState::InProgress {
+                                assert_eq!(game.current_player, game.turn_structure.to_move(&game.history));
+                            }
+                            // Neither side is ever more than a stone ahead.
+                            let count = |color| game.board.cells.values().filter(|&&cell| cell == color).count();
+                            assert!(count(CellState::Red).abs_diff(count(CellState::Blue)) <= 1);
+                        }
+                        let mut replayed = Game::with_size(3);
+                        for mv in &game.history {
+                            replayed.play_move(*mv).unwrap();
+                        }
+                        assert_eq!((replayed.current_player, replayed.state), (game.current_player, game.state));
+                        assert_eq!(replayed.board.to_string(), game.board.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
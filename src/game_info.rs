@@ -0,0 +1,164 @@
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::record::{self, GameMetadata, GameResult};
+use eframe::egui;
+
+use crate::i18n::{player_name, tr, tr_args, Language};
+
+#[derive(Default)]
+pub struct GameInfoDialog {
+    pub open: bool,
+}
+
+impl GameInfoDialog {
+    pub fn show(&mut self, ctx: &egui::Context, language: Language, metadata: &mut GameMetadata) {
+        egui::Window::new(tr(language, "info.title"))
+            .open(&mut self.open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("game_info").num_columns(2).show(ui, |ui| {
+                    for (key, value) in [
+                        ("info.red", &mut metadata.red),
+                        ("info.blue", &mut metadata.blue),
+                        ("info.event", &mut metadata.event),
+                        ("info.date", &mut metadata.date),
+                        ("info.round", &mut metadata.round),
+                        ("info.time_control", &mut metadata.time_control),
+                    ] {
+                        ui.label(tr(language, key));
+                        ui.text_edit_singleline(value);
+                        ui.end_row();
+                    }
+
+                    ui.label(tr(language, "info.tags"));
+                    tags_edit(ui, egui::Id::new("game_info_tags"), &mut metadata.tags, ui.available_width());
+                    ui.end_row();
+
+                    ui.label(tr(language, "info.result"));
+                    egui::ComboBox::from_id_salt("game_info_result")
+                        .selected_text(result_text(language, metadata.result))
+                        .show_ui(ui, |ui| {
+                            for result in [
+                                None,
+                                Some(GameResult::Win(CellState::Red)),
+                                Some(GameResult::Win(CellState::Blue)),
+                                Some(GameResult::Resignation(CellState::Red)),
+                                Some(GameResult::Resignation(CellState::Blue)),
+                                Some(GameResult::Draw),
+                                Some(GameResult::Adjourned),
+                            ] {
+                                ui.selectable_value(&mut metadata.result, result, result_text(language, result));
+                            }
+                        });
+                    ui.end_row();
+                });
+            });
+    }
+}
+
+// A comma-separated text field for a game's tags. The text is kept as typed while the field has
+// focus and parsed when it loses it; returns whether the tags changed then.
+pub fn tags_edit(ui: &mut egui::Ui, id: egui::Id, tags: &mut Vec<String>, width: f32) -> bool {
+    let mut text = ui.data_mut(|data| data.get_temp::<String>(id)).unwrap_or_else(|| tags.join(", "));
+    let response = ui.add(egui::TextEdit::singleline(&mut text).id(id).desired_width(width));
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(id, text));
+        return false;
+    }
+    ui.data_mut(|data| data.remove::<String>(id));
+    if !response.lost_focus() {
+        return false;
+    }
+    let parsed = record::parse_tags(&text);
+    let changed = parsed != *tags;
+    *tags = parsed;
+    changed
+}
+
+pub fn result_text(language: Language, result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::Win(winner)) => player_name(language, winner),
+        Some(GameResult::Resignation(CellState::Red)) => tr(language, "info.result_red_resignation"),
+        Some(GameResult::Resignation(_)) => tr(language, "info.result_blue_resignation"),
+        Some(GameResult::Draw) => tr(language, "info.result_draw"),
+        Some(GameResult::Adjourned) => tr(language, "info.result_adjourned"),
+        None => tr(language, "info.result_unfinished"),
+    }
+}
+
+// One line summarising the metadata, e.g. "Alice vs Bob · Club night · Round 3 · 2026.10.15",
+// or None while nothing beyond the date has been filled in.
+pub fn header_text(language: Language, metadata: &GameMetadata) -> Option<String> {
+    if metadata.red.is_empty() && metadata.blue.is_empty() && metadata.event.is_empty() && metadata.round.is_empty() {
+        return None;
+    }
+    let name = |name: &str, player| {
+        if name.is_empty() {
+            player_name(language, player).to_string()
+        } else {
+            name.to_string()
+        }
+    };
+    let mut parts = vec![tr_args(
+        language,
+        "header.players",
+        &[
+            ("red", &name(&metadata.red, CellState::Red)),
+            ("blue", &name(&metadata.blue, CellState::Blue)),
+        ],
+    )];
+    if !metadata.event.is_empty() {
+        parts.push(metadata.event.clone());
+    }
+    if !metadata.round.is_empty() {
+        parts.push(tr_args(language, "header.round", &[("round", &metadata.round)]));
+    }
+    if !metadata.date.is_empty() {
+        parts.push(metadata.date.clone());
+    }
+    Some(parts.join(" · "))
+}
+
+// The window title: the game and its state, e.g. "Hex 11x11 — Red to move", so the taskbar
+// tells games apart.
+pub fn window_title(language: Language, game: &Game) -> String {
+    let status = match game.state {
+        GameState::InProgress | GameState::WaitingForPieRuleChoice => {
+            tr_args(language, "announce.to_move", &[("player", player_name(language, game.current_player))])
+        }
+        GameState::Finished { winner } => tr_args(language, "title.winner", &[("winner", player_name(language, winner))]),
+        GameState::Resigned { winner } => {
+            tr_args(language, "title.resigned", &[("winner", player_name(language, winner))])
+        }
+        GameState::Drawn => tr(language, "title.drawn").to_string(),
+        GameState::Adjourned { .. } => tr(language, "title.adjourned").to_string(),
+    };
+    tr_args(language, "title.game", &[("size", &game.board.size.to_string()), ("status", &status)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_text() {
+        let mut metadata = GameMetadata { date: "2026.10.15".to_string(), ..Default::default() };
+        assert_eq!(header_text(Language::English, &metadata), None);
+
+        metadata.red = "Alice".to_string();
+        metadata.round = "3".to_string();
+        assert_eq!(
+            header_text(Language::English, &metadata).as_deref(),
+            Some("Alice vs Blue · Round 3 · 2026.10.15")
+        );
+    }
+
+    #[test]
+    fn test_window_title() {
+        let mut game = Game::with_size(11);
+        assert_eq!(window_title(Language::English, &game), "Hex 11x11 — Red to move");
+        game.agree_draw().unwrap();
+        assert_eq!(window_title(Language::Japanese, &game), "ヘックス 11×11 — 引き分け");
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use coast_to_coast::saves;
+use eframe::egui::{self, text::LayoutJob, RichText};
+
+use crate::i18n::{tr, Language};
+
+// Notes are written this long after the last keystroke, and when the game changes or the app
+// closes.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+// The side panel with the notes kept beside the saved game on the board: training takeaways,
+// an opponent's habits. Markdown, typed in one view and read rendered in the other.
+#[derive(Default)]
+pub struct GameNotes {
+    pub open: bool,
+    // The save the notes belong to; unsaved games have none to keep them with.
+    path: Option<PathBuf>,
+    text: String,
+    editing: bool,
+    // When the notes were last typed in, until they are written.
+    unsaved: Option<Instant>,
+}
+
+impl GameNotes {
+    // Follows the game on the board to its save, first writing what was typed for the last one.
+    pub fn follow(&mut self, save_path: Option<&Path>) {
+        if self.path.as_deref() == save_path {
+            return;
+        }
+        self.flush();
+        self.path = save_path.map(Path::to_path_buf);
+        self.text = self.path.as_deref().map(saves::read_notes).unwrap_or_default();
+        self.editing = self.text.is_empty();
+    }
+
+    pub fn flush(&mut self) {
+        if let (Some(path), Some(_)) = (&self.path, self.unsaved.take()) {
+            match saves::write_notes(path, &self.text) {
+                Ok(()) => tracing::debug!(path = %path.display(), "notes saved"),
+                Err(e) => tracing::warn!(path = %path.display(), error = %e, "failed to save notes"),
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, language: Language) {
+        ui.horizontal(|ui| {
+            ui.heading(tr(language, "notes.title"));
+            ui.selectable_value(&mut self.editing, false, tr(language, "notes.read"));
+            ui.selectable_value(&mut self.editing, true, tr(language, "notes.edit"));
+        });
+        if self.path.is_none() {
+            ui.label(tr(language, "notes.unsaved"));
+            return;
+        }
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            if self.editing {
+                let editor = egui::TextEdit::multiline(&mut self.text)
+                    .hint_text(tr(language, "notes.hint"))
+                    .desired_rows(16)
+                    .desired_width(f32::INFINITY);
+                if ui.add(editor).changed() {
+                    self.unsaved = Some(Instant::now());
+                }
+            } else if self.text.trim().is_empty() {
+                ui.weak(tr(language, "notes.empty"));
+            } else {
+                show_markdown(ui, &self.text);
+            }
+        });
+        match self.unsaved {
+            Some(typed) if typed.elapsed() >= SAVE_DELAY => self.flush(),
+            Some(typed) => ui.ctx().request_repaint_after(SAVE_DELAY.saturating_sub(typed.elapsed())),
+            None => {}
+        }
+    }
+}
+
+// The Markdown notes are written in: headings, lists, quotes, code blocks and paragraphs, with
+// bold, italic and code inline. Anything else shows as typed.
+#[derive(Debug, PartialEq)]
+enum Block<'a> {
+    Heading(usize, &'a str),
+    Bullet(&'a str),
+    Numbered(&'a str, &'a str),
+    Quote(&'a str),
+    Code(Vec<&'a str>),
+    Paragraph(Vec<&'a str>),
+    Gap,
+}
+
+#[derive(Debug, PartialEq)]
+enum Span<'a> {
+    Plain(&'a str),
+    Strong(&'a str),
+    Italic(&'a str),
+    Code(&'a str),
+}
+
+// Longest first, so "**" is not read as two "*".
+const MARKS: [&str; 4] = ["`", "**", "*", "_"];
+
+fn blocks(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        let block = if line.starts_with("```") {
+            Block::Code(lines.by_ref().take_while(|line| !line.trim_start().starts_with("```")).collect())
+        } else if line.is_empty() {
+            if matches!(blocks.last(), None | Some(Block::Gap)) {
+                continue;
+            }
+            Block::Gap
+        } else if let Some((hashes, heading)) = line.split_once(' ').filter(|(hashes, _)| is_heading(hashes)) {
+            Block::Heading(hashes.len(), heading.trim())
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            Block::Bullet(item.trim())
+        } else if let Some(quote) = line.strip_prefix('>') {
+            Block::Quote(quote.trim())
+        } else if let Some((number, item)) = line.split_once(". ").filter(|(number, _)| is_number(number)) {
+            Block::Numbered(number, item.trim())
+        } else {
+            if let Some(Block::Paragraph(lines)) = blocks.last_mut() {
+                lines.push(line);
+                continue;
+            }
+            Block::Paragraph(vec![line])
+        };
+        blocks.push(block);
+    }
+    blocks
+}
+
+fn is_heading(hashes: &str) -> bool {
+    (1..=6).contains(&hashes.len()) && hashes.chars().all(|c| c == '#')
+}
+
+fn is_number(number: &str) -> bool {
+    !number.is_empty() && number.len() <= 3 && number.chars().all(|c| c.is_ascii_digit())
+}
+
+// A line's inline formatting. Marks left open are plain text.
+fn spans(text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let (mut plain, mut at) = (0, 0);
+    while at < text.len() {
+        let rest = &text[at..];
+        let marked = MARKS.iter().find_map(|mark| {
+            let inner = rest.strip_prefix(mark)?;
+            let end = inner.find(mark).filter(|end| *end > 0)?;
+            Some((*mark, &inner[..end]))
+        });
+        let Some((mark, inner)) = marked else {
+            at += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+        if plain < at {
+            spans.push(Span::Plain(&text[plain..at]));
+        }
+        spans.push(match mark {
+            "`" => Span::Code(inner),
+            "**" => Span::Strong(inner),
+            _ => Span::Italic(inner),
+        });
+        at += 2 * mark.len() + inner.len();
+        plain = at;
+    }
+    if plain < text.len() {
+        spans.push(Span::Plain(&text[plain..]));
+    }
+    spans
+}
+
+fn show_markdown(ui: &mut egui::Ui, text: &str) {
+    for block in blocks(text) {
+        match block {
+            Block::Heading(1, heading) => {
+                ui.label(RichText::new(heading).heading());
+            }
+            Block::Heading(2, heading) => {
+                ui.label(RichText::new(heading).size(17.0).strong());
+            }
+            Block::Heading(_, heading) => {
+                ui.label(RichText::new(heading).strong());
+            }
+            Block::Bullet(item) => item_row(ui, "•", item),
+            Block::Numbered(number, item) => item_row(ui, &format!("{}.", number), item),
+            Block::Quote(quote) => {
+                egui::Frame::NONE.inner_margin(egui::Margin { left: 12, ..Default::default() }).show(ui, |ui| {
+                    ui.label(RichText::new(quote).italics().weak());
+                });
+            }
+            Block::Code(lines) => {
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.monospace(lines.join("\n"));
+                });
+            }
+            Block::Paragraph(lines) => {
+                ui.label(inline(ui, &lines.join(" ")));
+            }
+            Block::Gap => ui.add_space(6.0),
+        }
+    }
+}
+
+fn item_row(ui: &mut egui::Ui, marker: &str, item: &str) {
+    ui.horizontal_top(|ui| {
+        ui.label(marker);
+        ui.add(egui::Label::new(inline(ui, item)).wrap());
+    });
+}
+
+fn inline(ui: &egui::Ui, text: &str) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    for span in spans(text) {
+        let text = match span {
+            Span::Plain(text) => RichText::new(text),
+            Span::Strong(text) => RichText::new(text).strong(),
+            Span::Italic(text) => RichText::new(text).italics(),
+            Span::Code(text) => RichText::new(text).code(),
+        };
+        text.append_to(&mut job, ui.style(), egui::FontSelection::Default, egui::Align::Center);
+    }
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_blocks_and_spans() {
+        let notes = "# Takeaways\n\n\nBlocked too late,\nagain.\n- use **bridges**\n2. read `a1`\n\
+                     > patience\n```\nc3 d4\n```";
+        assert_eq!(
+            blocks(notes),
+            vec![
+                Block::Heading(1, "Takeaways"),
+                Block::Gap,
+                Block::Paragraph(vec!["Blocked too late,", "again."]),
+                Block::Bullet("use **bridges**"),
+                Block::Numbered("2", "read `a1`"),
+                Block::Quote("patience"),
+                Block::Code(vec!["c3 d4"]),
+            ]
+        );
+        assert_eq!(
+            spans("use **bridges**, *not* `ladders` ** here"),
+            vec![
+                Span::Plain("use "),
+                Span::Strong("bridges"),
+                Span::Plain(", "),
+                Span::Italic("not"),
+                Span::Plain(" "),
+                Span::Code("ladders"),
+                Span::Plain(" ** here"),
+            ]
+        );
+        assert_eq!(spans("#hashtag ×2"), vec![Span::Plain("#hashtag ×2")]);
+    }
+}
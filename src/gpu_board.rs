@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use eframe::egui;
+use eframe::egui_wgpu::{self, wgpu};
+use wgpu::util::DeviceExt;
+
+// Draws a board's cells on the GPU: one instance per cell, all of them in a single draw call, for
+// very large boards. Used when the app runs on wgpu; the renderer's meshes are the fallback.
+
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct Uniforms {
+    screen_size: [f32; 2],
+    radius: f32,
+    outline: f32,
+}
+
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct Instance {
+    center: [f32; 2],
+    // Premultiplied, as egui's colors are; transparent for none.
+    fill: [f32; 4],
+    outline: [f32; 4],
+}
+
+impl Instance {
+    pub fn new(center: egui::Pos2, fill: Option<egui::Color32>, outline: Option<egui::Color32>) -> Self {
+        let rgba = |color: Option<egui::Color32>| color.unwrap_or(egui::Color32::TRANSPARENT).to_normalized_gamma_f32();
+        Self { center: center.into(), fill: rgba(fill), outline: rgba(outline) }
+    }
+}
+
+const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4, 2 => Float32x4];
+// Six triangles per cell.
+const VERTICES: u32 = 18;
+
+// The pipeline, shared by every board, and each board's buffers, kept among egui's callback
+// resources.
+struct Resources {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    boards: HashMap<u64, Buffers>,
+}
+
+struct Buffers {
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    instances: wgpu::Buffer,
+    // The instances last written, so they go to the GPU only when the board changes.
+    written: Arc<Vec<Instance>>,
+}
+
+// A board renderer's handle on the GPU.
+pub struct GpuCells {
+    id: u64,
+}
+
+impl GpuCells {
+    // Sets up the pipeline if the app runs on wgpu.
+    pub fn install(cc: &eframe::CreationContext<'_>) -> Option<Self> {
+        let state = cc.wgpu_render_state.as_ref()?;
+        let mut renderer = state.renderer.write();
+        if !renderer.callback_resources.contains::<Resources>() {
+            renderer.callback_resources.insert(Resources::new(&state.device, state.target_format));
+        }
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        tracing::info!("drawing the board on the GPU");
+        Some(Self { id: NEXT_ID.fetch_add(1, Ordering::Relaxed) })
+    }
+
+    // A shape drawing `instances`, cells of `radius` with outlines `outline` wide, within `rect`.
+    pub fn shape(&self, rect: egui::Rect, instances: Vec<Instance>, radius: f32, outline: f32) -> egui::Shape {
+        if instances.is_empty() {
+            return egui::Shape::Noop;
+        }
+        let callback = CellsCallback { id: self.id, instances: Arc::new(instances), radius, outline };
+        egui::Shape::Callback(egui_wgpu::Callback::new_paint_callback(rect, callback))
+    }
+}
+
+impl Resources {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::include_wgsl!("gpu_board.wgsl"));
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("board_cells"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("board_cells"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("board_cells"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<Instance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &ATTRIBUTES,
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            // Matching egui's own pass: no depth buffer and no multisampling.
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        });
+        Self { pipeline, bind_group_layout, boards: HashMap::new() }
+    }
+}
+
+struct CellsCallback {
+    id: u64,
+    instances: Arc<Vec<Instance>>,
+    radius: f32,
+    outline: f32,
+}
+
+impl egui_wgpu::CallbackTrait for CellsCallback {
+    fn prepare(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        screen: &egui_wgpu::ScreenDescriptor,
+        _encoder: &mut wgpu::CommandEncoder,
+        resources: &mut egui_wgpu::CallbackResources,
+    ) -> Vec<wgpu::CommandBuffer> {
+        let Some(resources) = resources.get_mut::<Resources>() else {
+            return Vec::new();
+        };
+        let [width, height] = screen.size_in_pixels;
+        let uniforms = Uniforms {
+            screen_size: [width as f32 / screen.pixels_per_point, height as f32 / screen.pixels_per_point],
+            radius: self.radius,
+            outline: self.outline,
+        };
+        let instances = bytemuck::cast_slice(&self.instances);
+        let fits = |buffers: &Buffers| buffers.instances.size() >= instances.len() as u64;
+        match resources.boards.get_mut(&self.id).filter(|buffers| fits(buffers)) {
+            Some(buffers) => {
+                queue.write_buffer(&buffers.uniforms, 0, bytemuck::bytes_of(&uniforms));
+                if !Arc::ptr_eq(&buffers.written, &self.instances) {
+                    queue.write_buffer(&buffers.instances, 0, instances);
+                    buffers.written = self.instances.clone();
+                }
+            }
+            // A new board, or one that has outgrown its buffer.
+            None => {
+                let uniforms = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("board_cells_uniforms"),
+                    contents: bytemuck::bytes_of(&uniforms),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("board_cells"),
+                    layout: &resources.bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() }],
+                });
+                let instances = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("board_cells_instances"),
+                    contents: instances,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                });
+                let buffers = Buffers { uniforms, bind_group, instances, written: self.instances.clone() };
+                resources.boards.insert(self.id, buffers);
+            }
+        }
+        Vec::new()
+    }
+
+    fn paint(
+        &self,
+        info: egui::PaintCallbackInfo,
+        pass: &mut wgpu::RenderPass<'static>,
+        resources: &egui_wgpu::CallbackResources,
+    ) {
+        let Some((resources, buffers)) =
+            resources.get::<Resources>().and_then(|resources| Some((resources, resources.boards.get(&self.id)?)))
+        else {
+            return;
+        };
+        // Positions are in screen points; egui has already clipped the pass to the board.
+        let [width, height] = info.screen_size_px;
+        pass.set_viewport(0.0, 0.0, width as f32, height as f32, 0.0, 1.0);
+        pass.set_pipeline(&resources.pipeline);
+        pass.set_bind_group(0, &buffers.bind_group, &[]);
+        pass.set_vertex_buffer(0, buffers.instances.slice(..));
+        pass.draw(0..VERTICES, 0..self.instances.len() as u32);
+    }
+}
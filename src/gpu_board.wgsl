@@ -0,0 +1,55 @@
+// One instance per cell: six triangles fanned from its center, the outline and edge smoothing
+// worked out per pixel from how far it is from the center.
+
+struct Uniforms {
+    screen_size: vec2<f32>,
+    radius: f32,
+    outline: f32,
+};
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    // 0 at the center, 1 along the cell's edges.
+    @location(0) edge: f32,
+    @location(1) fill: vec4<f32>,
+    @location(2) outline: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) index: u32,
+    @location(0) center: vec2<f32>,
+    @location(1) fill: vec4<f32>,
+    @location(2) outline: vec4<f32>,
+) -> VertexOut {
+    let corner = index % 3u;
+    var pos = center;
+    var edge = 0.0;
+    if corner != 0u {
+        // Pointy-top, as the renderer's `hexagon` draws them.
+        let angle = 1.0471976 * f32(index / 3u + corner - 1u) + 0.5235988;
+        pos = center + vec2<f32>(cos(angle), sin(angle)) * uniforms.radius;
+        edge = 1.0;
+    }
+    var out: VertexOut;
+    // Points, from the top left, to clip space.
+    let clip = vec2<f32>(pos.x / uniforms.screen_size.x * 2.0 - 1.0, 1.0 - pos.y / uniforms.screen_size.y * 2.0);
+    out.position = vec4<f32>(clip, 0.0, 1.0);
+    out.edge = edge;
+    out.fill = fill;
+    out.outline = outline;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOut) -> @location(0) vec4<f32> {
+    // Distance to the nearest edge, in points.
+    let distance = (1.0 - in.edge) * uniforms.radius * 0.8660254;
+    let smoothing = max(fwidth(distance), 0.0001);
+    let inside = smoothstep(uniforms.outline - smoothing, uniforms.outline + smoothing, distance);
+    let coverage = clamp(distance / smoothing, 0.0, 1.0);
+    // Colors are premultiplied, as egui's are.
+    return mix(in.outline, in.fill, inside) * coverage;
+}
@@ -0,0 +1,199 @@
+//! A game's history kept as moves, with a keyframe position every so often.
+//!
+//! Any earlier position is rebuilt by replaying from the nearest keyframe before it, so jumping
+//! around a long game costs at most one keyframe interval of moves. Keyframes are the only part
+//! that grows with the board; once they outgrow the memory budget every other one is dropped
+//! and the interval doubles, so very long analysis sessions stay within it.
+//!
+//! ```
+//! use coast_to_coast::game::Game;
+//! use coast_to_coast::history::History;
+//!
+//! let mut history = History::new(Game::with_size(5));
+//! for mv in ["c3", "b2", "d2"] {
+//!     history.push(mv.parse().unwrap()).unwrap();
+//! }
+//! let earlier = history.position_at(1).unwrap();
+//! assert_eq!(earlier.history.len(), 1);
+//! ```
+
+use std::mem;
+
+use crate::board::{CellState, Hex};
+use crate::game::Game;
+use crate::notation::Move;
+
+// Moves between keyframes until the memory budget forces them further apart.
+const KEYFRAME_INTERVAL: usize = 32;
+
+// Enough for thousands of keyframes of a standard board.
+const DEFAULT_MEMORY_LIMIT: usize = 16 << 20;
+
+pub struct History {
+    moves: Vec<Move>,
+    // Positions after the number of moves they are paired with, in order, starting with the
+    // empty board. Their own move lists are left empty; `moves` has them.
+    keyframes: Vec<(usize, Game)>,
+    current: Game,
+    interval: usize,
+    memory_limit: usize,
+}
+
+impl History {
+    /// An empty history starting from `start`, which should have no moves played.
+    pub fn new(start: Game) -> Self {
+        let keyframe = keyframe_of(&start);
+        Self {
+            moves: Vec::new(),
+            keyframes: vec![(0, keyframe)],
+            current: start,
+            interval: KEYFRAME_INTERVAL,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+        }
+    }
+
+    /// The history of `game` so far, replayed from its empty board.
+    pub fn from_game(game: &Game) -> Result<Self, &'static str> {
+        let mut history = Self::new(game.without_moves());
+        for mv in &game.history {
+            history.push(*mv)?;
+        }
+        Ok(history)
+    }
+
+    /// Caps what the keyframes may take, roughly, in bytes. There is always at least the first.
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self.enforce_memory_limit();
+        self
+    }
+
+    /// Plays `mv` on the latest position.
+    pub fn push(&mut self, mv: Move) -> Result<(), &'static str> {
+        self.current.play_move(mv)?;
+        self.moves.push(mv);
+        if self.moves.len().is_multiple_of(self.interval) {
+            self.keyframes.push((self.moves.len(), keyframe_of(&self.current)));
+            self.enforce_memory_limit();
+        }
+        Ok(())
+    }
+
+    /// Forgets every move after the first `len`, e.g. after taking moves back.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.moves.len() {
+            return;
+        }
+        self.current = self.position_at(len).expect("len is within the history");
+        self.moves.truncate(len);
+        self.keyframes.retain(|(index, _)| *index <= len);
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The latest position.
+    pub fn current(&self) -> &Game {
+        &self.current
+    }
+
+    /// The game after its first `move_index` moves, or None past the end.
+    pub fn position_at(&self, move_index: usize) -> Option<Game> {
+        if move_index > self.moves.len() {
+            return None;
+        }
+        if move_index == self.moves.len() {
+            return Some(self.current.clone());
+        }
+        let nearest = self.keyframes.partition_point(|(index, _)| *index <= move_index) - 1;
+        let (start, keyframe) = &self.keyframes[nearest];
+        let mut game = keyframe.clone();
+        for mv in &self.moves[*start..move_index] {
+            game.play_move(*mv).expect("recorded moves replay");
+        }
+        game.history = self.moves[..move_index].to_vec();
+        Some(game)
+    }
+
+    /// Roughly what the keyframes take, in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.keyframes.iter().map(|(_, game)| keyframe_bytes(game)).sum()
+    }
+
+    fn enforce_memory_limit(&mut self) {
+        while self.keyframes.len() > 1 && self.memory_bytes() > self.memory_limit {
+            self.interval *= 2;
+            let interval = self.interval;
+            self.keyframes.retain(|(index, _)| index.is_multiple_of(interval));
+            tracing::debug!(interval, keyframes = self.keyframes.len(), "history keyframes thinned");
+        }
+    }
+}
+
+// A copy of `game` without its move list, which the history keeps once for all keyframes.
+fn keyframe_of(game: &Game) -> Game {
+    let mut keyframe = game.clone();
+    mem::take(&mut keyframe.history);
+    keyframe
+}
+
+fn keyframe_bytes(game: &Game) -> usize {
+    mem::size_of::<Game>() + game.board.cells.capacity() * (mem::size_of::<(Hex, CellState)>() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_game() -> (Game, Vec<Game>) {
+        // Random-looking but legal: walk the empty cells with a stride.
+        let mut game = Game::with_size(11);
+        let mut positions = vec![game.clone()];
+        for i in 0..100 {
+            let empty = game.board.empty_cells();
+            game.play_move(Move::Place(empty[i * 7 % empty.len()])).unwrap();
+            positions.push(game.clone());
+        }
+        (game, positions)
+    }
+
+    #[test]
+    fn test_positions_match_play() {
+        let (game, positions) = long_game();
+        let history = History::from_game(&game).unwrap();
+        assert_eq!(history.len(), 100);
+        assert_eq!(history.keyframes.len(), 1 + 100 / KEYFRAME_INTERVAL);
+        for (index, expected) in positions.iter().enumerate() {
+            let position = history.position_at(index).unwrap();
+            assert_eq!(position.board.cells, expected.board.cells, "move {}", index);
+            assert_eq!(position.history, expected.history);
+            assert_eq!(position.current_player, expected.current_player);
+        }
+        assert!(history.position_at(101).is_none());
+    }
+
+    #[test]
+    fn test_memory_limit_thins_keyframes() {
+        let (game, positions) = long_game();
+        let one = keyframe_bytes(&positions[0]);
+        let history = History::from_game(&game).unwrap().with_memory_limit(2 * one);
+        assert!(history.memory_bytes() <= 2 * one);
+        assert_eq!(history.keyframes.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0, 64]);
+        assert_eq!(history.position_at(77).unwrap().board.cells, positions[77].board.cells);
+
+        let mut truncated = History::from_game(&game).unwrap();
+        truncated.truncate(40);
+        assert_eq!(truncated.current().board.cells, positions[40].board.cells);
+        truncated.push(positions[41].history[40]).unwrap();
+        assert_eq!(truncated.current().board.cells, positions[41].board.cells);
+    }
+}
@@ -0,0 +1,876 @@
+use coast_to_coast::board::CellState;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    // Shown in the language picker in its own language, so it stays readable whatever is selected.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    fn messages(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => EN,
+            Language::Japanese => JA,
+        }
+    }
+}
+
+const EN: &[(&str, &str)] = &[
+    ("app.title", "Hex Game"),
+    ("menu.file", "File"),
+    ("menu.edit", "Edit"),
+    ("menu.view", "View"),
+    ("menu.game", "Game"),
+    ("menu.help", "Help"),
+    ("menu.sync", "Sync now"),
+    ("menu.board_bridge", "Connect board"),
+    ("console.hint", "play c5, undo 2, size 13, save game.sgf, help"),
+    ("console.done", "OK"),
+    ("console.error", "{error}; type help for the commands"),
+    ("console.illegal", "{move} cannot be played now"),
+    ("console.unavailable", "Not available now"),
+    ("console.no_engine", "This game has no engine opponent"),
+    ("bridge.connected", "Reading moves from the board at {device}"),
+    ("bridge.disconnected", "Board disconnected"),
+    ("bridge.error", "Could not open the board at {device}: {error}"),
+    ("bridge.closed", "The board was disconnected"),
+    ("bridge.failed", "Lost the connection to the board: {error}"),
+    ("bridge.unreadable", "Unreadable line from the board: {line}"),
+    ("bridge.rejected", "The board's move {move} cannot be played now"),
+    ("script.refused", "A script does not allow that move"),
+    ("menu.quit", "Quit"),
+    ("menu.undo", "Undo move"),
+    ("menu.redo", "Redo move"),
+    ("menu.copy_position", "Copy position"),
+    ("menu.paste_game", "Paste game"),
+    ("menu.game_pasted", "Pasted {moves} moves."),
+    ("menu.position_copied", "Position copied to the clipboard"),
+    ("menu.last_move", "Highlight the last move"),
+    ("menu.rotate_board", "Rotate board 180°"),
+    ("menu.dark_theme", "Dark theme"),
+    ("stats.title", "My statistics"),
+    ("stats.computing", "Analysing saved games…"),
+    ("stats.summary", "{games} games, {moves} of your moves, {loss}% win chance lost per move on average"),
+    ("stats.local", "Worked out from your saved games on this computer; nothing is sent anywhere."),
+    ("stats.blunder_rate", "Blunder rate by month"),
+    ("stats.average_loss", "Average evaluation loss per move by month"),
+    ("stats.openings", "Score by opening"),
+    ("stats.opening_score", "{opening}: {score}% in {games} games"),
+    ("stats.no_data", "Not enough games yet."),
+    ("menu.timeline", "Timeline"),
+    ("menu.ownership", "Ownership map"),
+    ("menu.two_distance", "Two-distance gains"),
+    ("menu.templates", "Edge templates"),
+    ("templates.match", "{name}: {cell} to {player}'s edge"),
+    ("timeline.title", "Timeline"),
+    ("timeline.started", "Game started"),
+    ("timeline.resumed", "Game continued after {moves} moves"),
+    ("timeline.taken_back", "Taken back to move {moves}"),
+    ("timeline.resigned", "{player} resigns"),
+    ("timeline.viewing", "Showing the board after move {moves}"),
+    ("timeline.back", "Back to the game"),
+    ("moves.branch", "Continue from here"),
+    ("moves.branch_hint", "Takes back the later moves; Redo replays them"),
+    ("menu.fullscreen", "Fullscreen"),
+    ("menu.resign", "Resign"),
+    ("menu.rules", "Rules"),
+    ("menu.about", "About"),
+    ("help.version", "Version {version}"),
+    ("keymap.title", "Keyboard shortcuts"),
+    ("keymap.press", "Press a shortcut (Esc cancels)"),
+    ("keymap.taken", "That shortcut is already used by \"{action}\"."),
+    ("keymap.conflict", "\"{first}\" and \"{second}\" share a shortcut."),
+    ("keymap.reset", "Restore the defaults"),
+    (
+        "help.rules",
+        "Red and Blue take turns placing a stone of their color on any empty cell. Red wins by joining the \
+         left and right edges with a chain of red stones, Blue by joining the top and bottom edges. \
+         Stones never move or get captured, and the board cannot fill up without one player connecting, \
+         so there are no draws.\n\nWith the pie rule, after Red's first stone Blue may swap: take that \
+         stone as their own instead of playing, mirrored across the short diagonal so it faces Blue's edges \
+         as it faced Red's. This keeps Red from opening with a strong move.",
+    ),
+    ("help.about", "Hex, the connection game, with AI opponents and analysis tools."),
+    ("title.game", "Hex {size}x{size} — {status}"),
+    ("title.winner", "{winner} won"),
+    ("title.resigned", "{winner} won by resignation"),
+    ("title.drawn", "Drawn"),
+    ("title.adjourned", "Adjourned"),
+    ("player.red", "Red"),
+    ("player.blue", "Blue"),
+    ("player.unknown", "Unknown"),
+    ("game.winner", "Winner is: {winner}"),
+    ("game.resigned", "{loser} resigned. Winner is: {winner}"),
+    ("pie_rule.prompt", "Would you like to apply the pie rule?"),
+    ("pie_rule.apply", "Apply Pie Rule"),
+    ("pie_rule.continue", "Continue Normal Play"),
+    ("announce.move", "{player} plays {cell}"),
+    ("announce.swap", "{player} swaps"),
+    ("announce.to_move", "{player} to move"),
+    ("cell.label", "{cell}, {state}"),
+    ("cell.empty", "empty"),
+    ("cell.occupied", "occupied"),
+    ("crash.title", "The game crashed last time"),
+    ("crash.message", "A crash report with the game state and recent log was saved to:"),
+    ("crash.copy_path", "Copy path"),
+    ("crash.dismiss", "Dismiss"),
+    ("export.button", "Export move list"),
+    ("export.saved", "Move list saved to {path} and copied to the clipboard"),
+    ("export.copied", "Move list copied to the clipboard"),
+    ("export.image_button", "Export board image (PNG)"),
+    ("export.gif_button", "Export game animation (GIF)"),
+    ("export.image_saved", "Image saved to {path}"),
+    ("export.image_error", "Could not save the image: {error}"),
+    ("import.button", "Import move list"),
+    ("import.title", "Import move list"),
+    ("import.hint", "Paste a move list (e.g. \"1. c5 2. swap 3. d4\") with optional [Tag \"value\"] header:"),
+    ("import.load", "Load"),
+    ("import.error", "Could not load: {error}"),
+    ("info.button", "Game info"),
+    ("info.title", "Game info"),
+    ("info.red", "Red player"),
+    ("info.blue", "Blue player"),
+    ("info.event", "Event"),
+    ("info.date", "Date"),
+    ("info.round", "Round"),
+    ("info.result", "Result"),
+    ("info.result_unfinished", "Unfinished"),
+    ("info.result_draw", "Draw"),
+    ("info.result_red_resignation", "Red, by resignation"),
+    ("info.result_blue_resignation", "Blue, by resignation"),
+    ("info.result_adjourned", "Adjourned"),
+    ("info.time_control", "Time control"),
+    ("info.tags", "Tags"),
+    ("header.players", "{red} vs {blue}"),
+    ("header.round", "Round {round}"),
+    ("save.button", "Save game"),
+    ("save.saved", "Game saved to {path}"),
+    ("save.error", "Could not save the game: {error}"),
+    ("api.serving", "Serving the API at {address}"),
+    ("api.error", "Could not serve the API at {address}: {error}"),
+    ("saves.button", "Saved games"),
+    ("notes.title", "Notes"),
+    ("notes.read", "Read"),
+    ("notes.edit", "Edit"),
+    ("notes.hint", "Takeaways, the opponent's habits… Markdown: # headings, - lists, **bold**, *italic*"),
+    ("notes.empty", "No notes for this game yet."),
+    ("notes.unsaved", "Save the game to keep notes with it."),
+    ("bookmarks.title", "Bookmarks"),
+    ("bookmarks.name", "Name"),
+    ("bookmarks.note", "Note"),
+    ("bookmarks.add", "Bookmark this position"),
+    ("bookmarks.empty", "No bookmarks yet."),
+    ("bookmarks.moves", "{moves} moves"),
+    ("bookmarks.open_hint", "Show this position on the board"),
+    ("bookmarks.error", "Could not save the bookmark: {error}"),
+    ("bookmarks.no_data_dir", "Bookmarks need a data folder, and none could be found."),
+    ("browser.bookmarks", "Bookmarks ({count})"),
+    ("browser.title", "Recent games"),
+    ("browser.continue", "Continue last game"),
+    ("close.title", "Unsaved game"),
+    ("close.message", "The game has changed since it was last saved. Save it before closing?"),
+    ("close.save", "Save"),
+    ("close.discard", "Discard"),
+    ("close.cancel", "Cancel"),
+    ("browser.new_game", "New game"),
+    ("new_game.title", "New game"),
+    ("new_game.name", "Name"),
+    ("new_game.player", "Played by"),
+    ("new_game.minutes", "Minutes"),
+    ("new_game.minutes_hint", "Thinking time for the whole game; a player who runs out loses. 0 for no limit."),
+    ("new_game.human", "Human"),
+    ("new_game.computer", "Computer: {difficulty}"),
+    ("new_game.swap_colors", "Swap colors"),
+    ("new_game.start", "Start"),
+    ("new_game.flagged", "{player} ran out of time."),
+    ("browser.back", "Back to the board"),
+    ("browser.detach", "Separate window"),
+    ("browser.detach_hint", "Move the list to a window of its own, e.g. on a second monitor"),
+    ("browser.attach", "Main window"),
+    ("browser.attach_hint", "Show the list in place of the board again"),
+    ("browser.sort", "Sort by"),
+    ("browser.sort_newest", "Newest first"),
+    ("browser.sort_oldest", "Oldest first"),
+    ("browser.sort_result", "Result"),
+    ("browser.empty", "No saved games yet."),
+    ("browser.autosave", "auto-saved"),
+    ("browser.load", "Load"),
+    ("browser.delete", "Delete"),
+    ("browser.delete_confirm", "Delete for good?"),
+    ("browser.keep", "Keep"),
+    ("browser.tags", "Tags:"),
+    ("browser.tag", "Tag"),
+    ("browser.all_tags", "All tags"),
+    ("sgf.button", "Export SGF"),
+    ("bundle.export_button", "Export all data"),
+    ("bundle.import_button", "Import data…"),
+    ("bundle.exported", "All data exported to {path}"),
+    ("bundle.export_error", "Could not export the data: {error}"),
+    ("bundle.import_title", "Import data"),
+    ("bundle.import_hint", "Drop an exported .zip file here, or enter its path:"),
+    ("bundle.import", "Import"),
+    ("bundle.imported", "Imported the settings and {count} files"),
+    ("bundle.import_error", "Could not import the data: {error}"),
+    ("sgf.saved", "SGF saved to {path} and copied to the clipboard"),
+    ("sgf.copied", "SGF copied to the clipboard"),
+    ("settings.title", "Settings"),
+    ("settings.language", "Language"),
+    ("settings.player_name", "Your name (for statistics)"),
+    ("settings.check_updates", "Check for updates on startup"),
+    ("settings.update_url", "Release URL"),
+    ("settings.webhook_url", "Webhook URL"),
+    ("settings.webhook_url_hint", "Finished games are posted here as JSON, e.g. for a club leaderboard or a bot"),
+    ("settings.sync", "Cloud sync (WebDAV)"),
+    ("settings.sync_url", "Server URL"),
+    ("settings.sync_username", "User name"),
+    ("settings.sync_password", "Password"),
+    ("settings.sync_hint", "Saved games and settings are synced with File → Sync now."),
+    ("settings.bridge", "Physical board"),
+    ("settings.bridge_device", "Serial device"),
+    ("settings.bridge_transpose", "Sensor x counts rows"),
+    ("settings.bridge_mirror_x", "Mirror sensor x"),
+    ("settings.bridge_mirror_y", "Mirror sensor y"),
+    (
+        "settings.bridge_hint",
+        "The board sends a line per move: sensor coordinates like \"3,5\" or a move like \"c4\". \
+         Connect with Game → Connect board.",
+    ),
+    ("sync.running", "Syncing…"),
+    ("sync.done", "Synced: {pushed} sent, {pulled} received"),
+    ("sync.error", "Sync failed: {error}"),
+    ("settings.discord_presence", "Show the current game on Discord"),
+    ("settings.voice_command", "Speech recognizer"),
+    ("settings.voice_command_hint", "A command that prints a line for each phrase it hears, e.g. a streaming transcriber"),
+    ("voice.title", "Spoken command"),
+    ("voice.heard", "Heard \"{text}\""),
+    ("voice.play", "Play {move}"),
+    ("voice.confirm", "Confirm (\"yes\")"),
+    ("voice.cancel", "Cancel (\"no\")"),
+    ("voice.rejected", "{move} cannot be played now"),
+    ("voice.unavailable", "\"{action}\" is not available now"),
+    ("settings.eval_bar", "Show an evaluation bar in presentation mode"),
+    ("settings.fullscreen", "Fullscreen (F11)"),
+    ("settings.borderless", "Borderless window"),
+    ("settings.left_handed", "Left-handed layout (mirrored board)"),
+    ("settings.board_zoom", "Board zoom"),
+    ("settings.reduced_motion", "Reduced motion"),
+    (
+        "settings.reduced_motion_hint",
+        "No animations, spinners or gradients, and clocks and live windows update once a second. \
+         Easier on motion-sensitive eyes and low-power machines.",
+    ),
+    ("settings.stones", "Stones:"),
+    ("settings.stones_flat", "Flat"),
+    ("settings.stones_shaded", "3D shaded"),
+    ("settings.stones_textured", "Textured"),
+    ("settings.theme", "Theme pack"),
+    ("settings.theme_none", "None"),
+    ("settings.theme_reload", "Reload"),
+    (
+        "settings.theme_hint",
+        "Reads the theme packs in themes/ in the data directory again: a folder each, with a theme.toml \
+         manifest and any images it names",
+    ),
+    ("settings.blind_mode", "Blind mode (hide stones to train board memory)"),
+    ("settings.blind_delay", "Hide stones after"),
+    ("settings.blind_hidden", "Hide completely"),
+    ("settings.blind_neutral", "Show as grey markers"),
+    ("blind.reveal", "Reveal stones"),
+    ("settings.teaching", "Teaching: only the engine's top {count} moves for"),
+    ("settings.ladder_warnings", "Ladder warnings"),
+    ("ladder.off", "Off"),
+    ("ladder.strict", "Strict"),
+    ("ladder.lenient", "Lenient"),
+    ("ladder.warning", "This starts a ladder with no escape"),
+    ("teaching.restricted", "Choose one of the highlighted moves."),
+    ("moves.time_graph", "Time per move"),
+    ("settings.ai_delay", "Minimum AI response time"),
+    ("settings.idle_pause", "Pause when away for"),
+    ("settings.idle_pause_hint", "Stop the clocks and dim the board once the window is unfocused this long; 0 for never"),
+    ("idle.paused", "Paused"),
+    ("idle.resuming", "Resuming in {seconds}…"),
+    ("settings.playout", "Hard AI playouts"),
+    ("playout.uniform", "Uniform random"),
+    ("playout.pattern", "Local replies"),
+    ("playout.bridge", "Keep bridges"),
+    ("settings.reuse_tree", "Hard AI keeps its search between moves"),
+    ("settings.tree_memory", "Search tree memory"),
+    ("settings.table_memory", "Search table memory"),
+    ("settings.table_replacement", "Search table replacement"),
+    ("table.always", "Always replace"),
+    ("table.depth_preferred", "Keep deeper results"),
+    ("ai.thinking", "Thinking…"),
+    ("settings.board_size", "Board size for new games"),
+    ("settings.pie_rule", "Offer the pie rule (swap) in new games"),
+    ("cell.blocked", "blocked"),
+    ("settings.stones_per_turn", "Stones per turn after the first"),
+    ("settings.allow_pass", "Allow passing in new games"),
+    ("presets.title", "Match preset"),
+    ("presets.none", "None"),
+    ("presets.name_hint", "Name for a preset of the board size, pie rule, time control and opponent"),
+    ("presets.save", "Save as preset"),
+    ("presets.error", "Could not save the presets: {error}"),
+    ("game.pass", "Pass"),
+    ("announce.pass", "{player} passes"),
+    ("takeback.button", "Take back"),
+    ("takeback.title", "Takeback request"),
+    ("takeback.message", "{player} asks to take back their last move. {opponent}, do you accept?"),
+    ("takeback.accept", "Accept"),
+    ("takeback.decline", "Decline"),
+    ("takeback.declined", "Takeback declined"),
+    ("announce.takeback", "Move taken back"),
+    ("match.draw", "Agree draw"),
+    ("match.adjourn", "Adjourn"),
+    ("match.resume", "Resume"),
+    ("game.drawn", "Drawn by agreement"),
+    ("game.adjourned", "Game adjourned. It was saved and can be resumed later."),
+    ("moves.title", "Moves"),
+    ("dark.button", "Dark Hex"),
+    ("dark.title", "Dark Hex"),
+    ("dark.hint", "You play Red and see only your own stones. Playing on a hidden enemy stone reveals it."),
+    ("dark.collision", "{cell} is already taken by the opponent. It is now revealed; play again."),
+    ("dark.ai_moved", "Blue has moved somewhere you cannot see."),
+    ("dark.leave", "Leave"),
+    ("dark.vs_ai", "Against the computer"),
+    ("dark.hot_seat", "Two players, one screen"),
+    ("dark.hot_seat_hint", "Take turns at this screen. Each sees only their own stones; the board hides between turns."),
+    ("dark.to_move", "{player} to move"),
+    ("dark.hide_board", "Hide board"),
+    ("dark.hand_over", "Hand over to {player}"),
+    ("dark.hand_over_hint", "Only {player} should look at the screen now."),
+    ("dark.show_board", "Show {player}'s board"),
+    ("simul.button", "Simul vs AIs"),
+    ("simul.title", "Simul"),
+    ("simul.hint", "You play Red on every board. Choose your opponents, then start."),
+    ("simul.board", "Board {number}"),
+    ("simul.board_title", "Board {number} ({difficulty})"),
+    ("ai.easy", "Easy"),
+    ("ai.medium", "Medium"),
+    ("ai.hard", "Hard"),
+    ("ai.classic", "Classic (alpha-beta)"),
+    ("simul.add", "Add board"),
+    ("simul.remove", "Remove"),
+    ("simul.start", "Start"),
+    ("simul.your_move", "Your move"),
+    ("simul.score", "You won {won} of {total} games."),
+    ("simul.leave", "Leave"),
+    ("watch.button", "Watch AI vs AI"),
+    ("watch.title", "AI vs AI"),
+    ("watch.pause", "Pause"),
+    ("watch.resume", "Resume"),
+    ("watch.paused", "Paused"),
+    ("watch.restart", "Restart"),
+    ("watch.delay", "Move delay"),
+    ("watch.take_over", "Play this side myself"),
+    ("watch.leave", "Leave"),
+    ("watch.telemetry", "Engine telemetry"),
+    ("watch.copy_csv", "Copy as CSV"),
+    ("watch.no_telemetry", "No engine moves yet."),
+    ("study.button", "Study my mistakes"),
+    ("study.title", "Study"),
+    ("study.summary", "{due} due of {total} cards"),
+    ("study.collecting", "Looking through saved games for mistakes…"),
+    ("study.added", "{count} new cards from your games"),
+    ("study.empty", "No mistakes to study yet. Positions where you lost ground in saved games become cards here."),
+    ("study.done", "Nothing due today. Come back tomorrow."),
+    ("study.prompt", "{player} to move: find the best move."),
+    ("study.right", "Right: {answer}. In the game {played} was played."),
+    ("study.wrong", "Not quite: the best move was {best}, not {answer}. In the game {played} was played."),
+    ("study.next", "Next"),
+    ("study.card_stats", "Seen {reviews} times, right {correct}"),
+    ("telemetry.move", "Move"),
+    ("telemetry.nodes", "Nodes"),
+    ("telemetry.nps", "Nodes/s"),
+    ("telemetry.depth", "Depth"),
+    ("telemetry.ponder", "Reused"),
+    ("telemetry.table", "TT hits"),
+    ("presentation.button", "Presentation mode"),
+    ("update.title", "Update available"),
+    ("update.message", "Version {version} is available."),
+    ("update.open", "Open the release page"),
+    ("update.dismiss", "Not now"),
+    ("update.never", "Never ask again"),
+];
+
+const JA: &[(&str, &str)] = &[
+    ("app.title", "ヘックス"),
+    ("menu.file", "ファイル"),
+    ("menu.edit", "編集"),
+    ("menu.view", "表示"),
+    ("menu.game", "対局"),
+    ("menu.help", "ヘルプ"),
+    ("menu.sync", "今すぐ同期"),
+    ("menu.board_bridge", "盤を接続"),
+    ("console.hint", "play c5, undo 2, size 13, save game.sgf, help"),
+    ("console.done", "OK"),
+    ("console.error", "{error}（help でコマンド一覧）"),
+    ("console.illegal", "{move} には今は打てません"),
+    ("console.unavailable", "今は使えません"),
+    ("console.no_engine", "この対局にはエンジンの相手がいません"),
+    ("bridge.connected", "{device} の盤から着手を読み取っています"),
+    ("bridge.disconnected", "盤の接続を解除しました"),
+    ("bridge.error", "{device} の盤を開けませんでした: {error}"),
+    ("bridge.closed", "盤の接続が切れました"),
+    ("bridge.failed", "盤との接続が失われました: {error}"),
+    ("bridge.unreadable", "盤から読み取れない行を受信しました: {line}"),
+    ("bridge.rejected", "盤の着手 {move} は今は打てません"),
+    ("script.refused", "スクリプトによりその手は打てません"),
+    ("menu.quit", "終了"),
+    ("menu.undo", "一手戻す"),
+    ("menu.redo", "一手進める"),
+    ("menu.copy_position", "局面をコピー"),
+    ("menu.paste_game", "対局を貼り付け"),
+    ("menu.game_pasted", "{moves}手を貼り付けました。"),
+    ("menu.position_copied", "局面をクリップボードにコピーしました"),
+    ("menu.last_move", "最終手を強調表示"),
+    ("menu.rotate_board", "盤を180°回転"),
+    ("menu.dark_theme", "ダークテーマ"),
+    ("stats.title", "自分の統計"),
+    ("stats.computing", "保存した対局を分析中…"),
+    ("stats.summary", "{games} 局、自分の手 {moves} 手、1手あたり平均 {loss}% の勝率を失っています"),
+    ("stats.local", "このコンピューターに保存した対局から計算しています。どこにも送信されません。"),
+    ("stats.blunder_rate", "月ごとの悪手率"),
+    ("stats.average_loss", "月ごとの1手あたり平均評価損失"),
+    ("stats.openings", "初手ごとの成績"),
+    ("stats.opening_score", "{opening}: {games} 局で {score}%"),
+    ("stats.no_data", "まだ対局が足りません。"),
+    ("menu.timeline", "タイムライン"),
+    ("menu.ownership", "支配マップ"),
+    ("menu.two_distance", "2距離の改善量"),
+    ("menu.templates", "辺テンプレート"),
+    ("templates.match", "{name}: {cell}から{player}の辺へ"),
+    ("timeline.title", "タイムライン"),
+    ("timeline.started", "対局開始"),
+    ("timeline.resumed", "{moves} 手目から対局再開"),
+    ("timeline.taken_back", "{moves} 手目まで戻しました"),
+    ("timeline.resigned", "{player}が投了"),
+    ("timeline.viewing", "{moves} 手目の局面を表示中"),
+    ("timeline.back", "対局に戻る"),
+    ("moves.branch", "ここから打ち直す"),
+    ("moves.branch_hint", "以降の手を戻します（やり直しで再生できます）"),
+    ("menu.fullscreen", "全画面表示"),
+    ("menu.resign", "投了"),
+    ("menu.rules", "ルール"),
+    ("menu.about", "このアプリについて"),
+    ("help.version", "バージョン {version}"),
+    ("keymap.title", "キーボードショートカット"),
+    ("keymap.press", "ショートカットを押してください（Esc で取り消し）"),
+    ("keymap.taken", "そのショートカットは「{action}」で使われています。"),
+    ("keymap.conflict", "「{first}」と「{second}」のショートカットが重複しています。"),
+    ("keymap.reset", "初期設定に戻す"),
+    (
+        "help.rules",
+        "赤と青が交互に、空いているマスに自分の色の石を置きます。赤は左右の辺を、青は上下の辺を自分の石の\
+         つながりで結べば勝ちです。石は動かず取られることもなく、盤が埋まるまでにどちらかが必ずつながるので\
+         引き分けはありません。\n\nパイ・ルールでは、赤の最初の一手のあと青はスワップを選べます。その石を短い対角線で\
+         折り返した位置の自分の石に置き換えて手番を終えるため、赤は強すぎる初手を打てません。",
+    ),
+    ("help.about", "つながりのゲーム、ヘックス。AI 対戦と検討のための機能付き。"),
+    ("title.game", "ヘックス {size}×{size} — {status}"),
+    ("title.winner", "{winner}の勝ち"),
+    ("title.resigned", "{winner}の中押し勝ち"),
+    ("title.drawn", "引き分け"),
+    ("title.adjourned", "指し掛け"),
+    ("player.red", "赤"),
+    ("player.blue", "青"),
+    ("player.unknown", "不明"),
+    ("game.winner", "勝者: {winner}"),
+    ("game.resigned", "{loser}が投了しました。勝者: {winner}"),
+    ("pie_rule.prompt", "パイ・ルールを適用しますか？"),
+    ("pie_rule.apply", "パイ・ルールを適用"),
+    ("pie_rule.continue", "そのまま続行"),
+    ("announce.move", "{player}が{cell}に着手"),
+    ("announce.swap", "{player}がスワップ"),
+    ("announce.to_move", "{player}の手番"),
+    ("cell.label", "{cell}、{state}"),
+    ("cell.empty", "空き"),
+    ("cell.occupied", "石あり"),
+    ("crash.title", "前回ゲームが異常終了しました"),
+    ("crash.message", "ゲームの状態と直近のログを含むクラッシュレポートを保存しました:"),
+    ("crash.copy_path", "パスをコピー"),
+    ("crash.dismiss", "閉じる"),
+    ("export.button", "棋譜を書き出す"),
+    ("export.saved", "棋譜を {path} に保存し、クリップボードにコピーしました"),
+    ("export.copied", "棋譜をクリップボードにコピーしました"),
+    ("export.image_button", "盤面を画像で書き出す (PNG)"),
+    ("export.gif_button", "対局をアニメーションで書き出す (GIF)"),
+    ("export.image_saved", "画像を {path} に保存しました"),
+    ("export.image_error", "画像を保存できませんでした: {error}"),
+    ("import.button", "棋譜を読み込む"),
+    ("import.title", "棋譜を読み込む"),
+    ("import.hint", "棋譜（例: \"1. c5 2. swap 3. d4\"）を貼り付けてください。[Tag \"value\"] 形式のヘッダーも使えます:"),
+    ("import.load", "読み込む"),
+    ("import.error", "読み込めませんでした: {error}"),
+    ("info.button", "対局情報"),
+    ("info.title", "対局情報"),
+    ("info.red", "赤の対局者"),
+    ("info.blue", "青の対局者"),
+    ("info.event", "大会"),
+    ("info.date", "日付"),
+    ("info.round", "ラウンド"),
+    ("info.result", "結果"),
+    ("info.result_unfinished", "未決着"),
+    ("info.result_draw", "引き分け"),
+    ("info.result_red_resignation", "赤の中押し勝ち"),
+    ("info.result_blue_resignation", "青の中押し勝ち"),
+    ("info.result_adjourned", "指し掛け"),
+    ("info.time_control", "持ち時間"),
+    ("info.tags", "タグ"),
+    ("header.players", "{red} 対 {blue}"),
+    ("header.round", "第{round}ラウンド"),
+    ("save.button", "対局を保存"),
+    ("save.saved", "対局を {path} に保存しました"),
+    ("save.error", "対局を保存できませんでした: {error}"),
+    ("api.serving", "API を {address} で公開しています"),
+    ("api.error", "API を {address} で公開できませんでした: {error}"),
+    ("saves.button", "保存した対局"),
+    ("notes.title", "メモ"),
+    ("notes.read", "表示"),
+    ("notes.edit", "編集"),
+    ("notes.hint", "学んだこと、相手の癖など。Markdown: # 見出し、- 箇条書き、**太字**、*斜体*"),
+    ("notes.empty", "この対局のメモはまだありません。"),
+    ("notes.unsaved", "メモを残すには対局を保存してください。"),
+    ("bookmarks.title", "ブックマーク"),
+    ("bookmarks.name", "名前"),
+    ("bookmarks.note", "メモ"),
+    ("bookmarks.add", "この局面をブックマーク"),
+    ("bookmarks.empty", "ブックマークはまだありません。"),
+    ("bookmarks.moves", "{moves}手"),
+    ("bookmarks.open_hint", "この局面を盤に表示します"),
+    ("bookmarks.error", "ブックマークを保存できませんでした: {error}"),
+    ("bookmarks.no_data_dir", "ブックマークにはデータフォルダーが必要ですが、見つかりませんでした。"),
+    ("browser.bookmarks", "ブックマーク（{count}）"),
+    ("browser.title", "最近の対局"),
+    ("browser.continue", "前回の対局を続ける"),
+    ("close.title", "未保存の対局"),
+    ("close.message", "最後に保存してから対局が変わっています。閉じる前に保存しますか？"),
+    ("close.save", "保存"),
+    ("close.discard", "破棄"),
+    ("close.cancel", "キャンセル"),
+    ("browser.new_game", "新しい対局"),
+    ("new_game.title", "新しい対局"),
+    ("new_game.name", "名前"),
+    ("new_game.player", "対局者"),
+    ("new_game.minutes", "持ち時間（分）"),
+    ("new_game.minutes_hint", "対局全体の持ち時間です。使い切ると負けになります。0 で無制限。"),
+    ("new_game.human", "人間"),
+    ("new_game.computer", "コンピューター: {difficulty}"),
+    ("new_game.swap_colors", "色を入れ替える"),
+    ("new_game.start", "開始"),
+    ("new_game.flagged", "{player}の持ち時間が切れました。"),
+    ("browser.back", "盤面に戻る"),
+    ("browser.detach", "別ウィンドウで表示"),
+    ("browser.detach_hint", "一覧を別のウィンドウに移します（2台目のモニター用など）"),
+    ("browser.attach", "メインウィンドウで表示"),
+    ("browser.attach_hint", "一覧を再び盤面の代わりに表示します"),
+    ("browser.sort", "並べ替え"),
+    ("browser.sort_newest", "新しい順"),
+    ("browser.sort_oldest", "古い順"),
+    ("browser.sort_result", "結果"),
+    ("browser.empty", "保存した対局はまだありません。"),
+    ("browser.autosave", "自動保存"),
+    ("browser.load", "開く"),
+    ("browser.delete", "削除"),
+    ("browser.delete_confirm", "完全に削除しますか？"),
+    ("browser.keep", "残す"),
+    ("browser.tags", "タグ:"),
+    ("browser.tag", "タグ"),
+    ("browser.all_tags", "すべてのタグ"),
+    ("sgf.button", "SGF を書き出す"),
+    ("bundle.export_button", "すべてのデータを書き出す"),
+    ("bundle.import_button", "データを読み込む…"),
+    ("bundle.exported", "すべてのデータを {path} に書き出しました"),
+    ("bundle.export_error", "データを書き出せませんでした: {error}"),
+    ("bundle.import_title", "データの読み込み"),
+    ("bundle.import_hint", "書き出した .zip ファイルをここにドロップするか、パスを入力してください:"),
+    ("bundle.import", "読み込む"),
+    ("bundle.imported", "設定と {count} 件のファイルを読み込みました"),
+    ("bundle.import_error", "データを読み込めませんでした: {error}"),
+    ("sgf.saved", "SGF を {path} に保存し、クリップボードにコピーしました"),
+    ("sgf.copied", "SGF をクリップボードにコピーしました"),
+    ("settings.title", "設定"),
+    ("settings.language", "言語"),
+    ("settings.player_name", "あなたの名前 (統計用)"),
+    ("settings.check_updates", "起動時に更新を確認する"),
+    ("settings.update_url", "リリース情報の URL"),
+    ("settings.webhook_url", "Webhook URL"),
+    ("settings.webhook_url_hint", "終局した対局を JSON で送信します（クラブのランキングやボット向け）"),
+    ("settings.sync", "クラウド同期 (WebDAV)"),
+    ("settings.sync_url", "サーバーURL"),
+    ("settings.sync_username", "ユーザー名"),
+    ("settings.sync_password", "パスワード"),
+    ("settings.sync_hint", "保存した対局と設定は「ファイル → 今すぐ同期」で同期されます。"),
+    ("settings.bridge", "実物の盤"),
+    ("settings.bridge_device", "シリアルデバイス"),
+    ("settings.bridge_transpose", "センサーの x が行を数える"),
+    ("settings.bridge_mirror_x", "センサーの x を反転"),
+    ("settings.bridge_mirror_y", "センサーの y を反転"),
+    (
+        "settings.bridge_hint",
+        "盤は 1 手ごとに 1 行を送ります: \"3,5\" のようなセンサー座標か \"c4\" のような着手です。\
+         「対局 → 盤を接続」で接続します。",
+    ),
+    ("sync.running", "同期中…"),
+    ("sync.done", "同期しました: 送信 {pushed} 件、受信 {pulled} 件"),
+    ("sync.error", "同期できませんでした: {error}"),
+    ("settings.discord_presence", "対局中のゲームを Discord に表示する"),
+    ("settings.voice_command", "音声認識"),
+    ("settings.voice_command_hint", "聞き取ったフレーズごとに 1 行を出力するコマンド（ストリーミング文字起こしなど）"),
+    ("voice.title", "音声コマンド"),
+    ("voice.heard", "「{text}」と聞き取りました"),
+    ("voice.play", "{move} に打つ"),
+    ("voice.confirm", "確定（\"yes\"）"),
+    ("voice.cancel", "取り消し（\"no\"）"),
+    ("voice.rejected", "{move} には今は打てません"),
+    ("voice.unavailable", "「{action}」は今は使えません"),
+    ("settings.eval_bar", "プレゼンテーションモードで形勢バーを表示する"),
+    ("settings.fullscreen", "全画面表示（F11）"),
+    ("settings.borderless", "枠なしウィンドウ"),
+    ("settings.left_handed", "左利き用レイアウト（盤を左右反転）"),
+    ("settings.board_zoom", "盤の拡大率"),
+    ("settings.reduced_motion", "動きを減らす"),
+    (
+        "settings.reduced_motion_hint",
+        "アニメーション・スピナー・グラデーションをなくし、時計やライブ表示の更新を1秒ごとにします。\
+         動きに敏感な方や低性能のマシン向けです。",
+    ),
+    ("settings.stones", "石:"),
+    ("settings.stones_flat", "フラット"),
+    ("settings.stones_shaded", "立体"),
+    ("settings.stones_textured", "テクスチャ"),
+    ("settings.theme", "テーマパック"),
+    ("settings.theme_none", "なし"),
+    ("settings.theme_reload", "再読み込み"),
+    (
+        "settings.theme_hint",
+        "データディレクトリの themes/ 内のテーマパックを読み直します。パックごとにフォルダを作り、\
+         theme.toml と画像を入れます",
+    ),
+    ("settings.blind_mode", "目隠しモード（石を隠して盤面の記憶を鍛える）"),
+    ("settings.blind_delay", "石を隠すまでの時間"),
+    ("settings.blind_hidden", "完全に隠す"),
+    ("settings.blind_neutral", "灰色の印で表示する"),
+    ("blind.reveal", "石を表示する"),
+    ("settings.teaching", "指導モード: エンジンの上位{count}手に限定する手番"),
+    ("settings.ladder_warnings", "シチョウの警告"),
+    ("ladder.off", "オフ"),
+    ("ladder.strict", "厳しめ"),
+    ("ladder.lenient", "ゆるめ"),
+    ("ladder.warning", "逃げ道のないシチョウになります"),
+    ("teaching.restricted", "ハイライトされた手から選んでください。"),
+    ("moves.time_graph", "一手ごとの消費時間"),
+    ("settings.ai_delay", "AIの最小応答時間"),
+    ("settings.idle_pause", "離席時に一時停止するまで"),
+    ("settings.idle_pause_hint", "ウィンドウのフォーカスがこの時間外れると、時計を止めて盤面を暗くします。0で無効"),
+    ("idle.paused", "一時停止中"),
+    ("idle.resuming", "{seconds}秒後に再開…"),
+    ("settings.playout", "強いAIのプレイアウト"),
+    ("playout.uniform", "一様ランダム"),
+    ("playout.pattern", "近くに応じる"),
+    ("playout.bridge", "ブリッジを守る"),
+    ("settings.reuse_tree", "強いAIが手の間で探索を引き継ぐ"),
+    ("settings.tree_memory", "探索木のメモリ"),
+    ("settings.table_memory", "探索テーブルのメモリ"),
+    ("settings.table_replacement", "探索テーブルの置き換え"),
+    ("table.always", "常に置き換える"),
+    ("table.depth_preferred", "深い結果を残す"),
+    ("ai.thinking", "思考中…"),
+    ("settings.board_size", "新しい対局の盤サイズ"),
+    ("settings.pie_rule", "新しい対局でパイルール（スワップ）を使う"),
+    ("cell.blocked", "使用不可"),
+    ("settings.stones_per_turn", "2手目以降の1手番の石数"),
+    ("settings.allow_pass", "新しい対局でパスを認める"),
+    ("presets.title", "対局プリセット"),
+    ("presets.none", "なし"),
+    ("presets.name_hint", "盤の大きさ・スワップ・持ち時間・相手をまとめたプリセットの名前"),
+    ("presets.save", "プリセットとして保存"),
+    ("presets.error", "プリセットを保存できませんでした: {error}"),
+    ("game.pass", "パス"),
+    ("announce.pass", "{player}がパスしました"),
+    ("takeback.button", "待った"),
+    ("takeback.title", "待ったの申し込み"),
+    ("takeback.message", "{player}が直前の手の取り消しを求めています。{opponent}は承諾しますか？"),
+    ("takeback.accept", "承諾"),
+    ("takeback.decline", "断る"),
+    ("takeback.declined", "待ったは断られました"),
+    ("announce.takeback", "一手戻しました"),
+    ("match.draw", "合意の引き分け"),
+    ("match.adjourn", "指し掛け"),
+    ("match.resume", "再開"),
+    ("game.drawn", "合意により引き分け"),
+    ("game.adjourned", "指し掛けです。保存済みなので後で再開できます。"),
+    ("moves.title", "棋譜"),
+    ("dark.button", "ダークヘックス"),
+    ("dark.title", "ダークヘックス"),
+    ("dark.hint", "あなたは赤で、自分の石しか見えません。隠れた相手の石に打つとその石が表示されます。"),
+    ("dark.collision", "{cell} には相手の石があります。表示されたので、もう一度打ってください。"),
+    ("dark.ai_moved", "青が見えない場所に打ちました。"),
+    ("dark.leave", "終了"),
+    ("dark.vs_ai", "コンピューターと対局"),
+    ("dark.hot_seat", "一つの画面で二人対局"),
+    ("dark.hot_seat_hint", "この画面で交互に打ちます。それぞれ自分の石しか見えず、交代の間は盤が隠れます。"),
+    ("dark.to_move", "{player}の手番"),
+    ("dark.hide_board", "盤を隠す"),
+    ("dark.hand_over", "{player}に交代してください"),
+    ("dark.hand_over_hint", "ここからは{player}だけが画面を見てください。"),
+    ("dark.show_board", "{player}の盤を表示"),
+    ("simul.button", "多面指し（AI 対戦）"),
+    ("simul.title", "多面指し"),
+    ("simul.hint", "すべての盤であなたは赤です。対戦相手を選んで開始してください。"),
+    ("simul.board", "{number} 面目"),
+    ("simul.board_title", "{number} 面目（{difficulty}）"),
+    ("ai.easy", "やさしい"),
+    ("ai.medium", "ふつう"),
+    ("ai.hard", "つよい"),
+    ("ai.classic", "クラシック（αβ探索）"),
+    ("simul.add", "盤を追加"),
+    ("simul.remove", "削除"),
+    ("simul.start", "開始"),
+    ("simul.your_move", "あなたの番"),
+    ("simul.score", "{total} 局中 {won} 勝しました。"),
+    ("simul.leave", "終了"),
+    ("watch.button", "AI 同士の対局を観戦"),
+    ("watch.title", "AI 対 AI"),
+    ("watch.pause", "一時停止"),
+    ("watch.resume", "再開"),
+    ("watch.paused", "一時停止中"),
+    ("watch.restart", "最初から"),
+    ("watch.delay", "着手の間隔"),
+    ("watch.take_over", "この手番を自分で打つ"),
+    ("watch.leave", "終了"),
+    ("watch.telemetry", "エンジンの探索情報"),
+    ("watch.copy_csv", "CSV としてコピー"),
+    ("watch.no_telemetry", "エンジンはまだ着手していません。"),
+    ("study.button", "自分の悪手を復習"),
+    ("study.title", "復習"),
+    ("study.summary", "{total} 枚中 {due} 枚が復習日"),
+    ("study.collecting", "保存した対局から悪手を探しています…"),
+    ("study.added", "対局から {count} 枚のカードを追加しました"),
+    ("study.empty", "復習する悪手はまだありません。保存した対局で形勢を損ねた局面がここでカードになります。"),
+    ("study.done", "今日の復習は終わりです。また明日どうぞ。"),
+    ("study.prompt", "{player}の手番: 最善手を見つけてください。"),
+    ("study.right", "正解: {answer}。対局では {played} と打ちました。"),
+    ("study.wrong", "惜しい: 最善手は {answer} ではなく {best} でした。対局では {played} と打ちました。"),
+    ("study.next", "次へ"),
+    ("study.card_stats", "出題 {reviews} 回、正解 {correct} 回"),
+    ("telemetry.move", "手"),
+    ("telemetry.nodes", "ノード数"),
+    ("telemetry.nps", "ノード/秒"),
+    ("telemetry.depth", "深さ"),
+    ("telemetry.ponder", "再利用"),
+    ("telemetry.table", "TT ヒット"),
+    ("presentation.button", "プレゼンテーションモード"),
+    ("update.title", "更新があります"),
+    ("update.message", "バージョン {version} が公開されています。"),
+    ("update.open", "リリースページを開く"),
+    ("update.dismiss", "後で"),
+    ("update.never", "今後は確認しない"),
+];
+
+// Falls back to English, then to the key itself, so a missing translation never hides text.
+pub fn tr(language: Language, key: &'static str) -> &'static str {
+    lookup(language.messages(), key)
+        .or_else(|| lookup(EN, key))
+        .unwrap_or(key)
+}
+
+pub fn tr_args(language: Language, key: &'static str, args: &[(&str, &str)]) -> String {
+    let mut text = tr(language, key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+pub fn player_name(language: Language, player: CellState) -> &'static str {
+    match player {
+        CellState::Red => tr(language, "player.red"),
+        CellState::Blue => tr(language, "player.blue"),
+        CellState::Empty | CellState::Blocked => tr(language, "player.unknown"),
+    }
+}
+
+// "Blue resigned. Winner is: Red"
+pub fn resigned_text(language: Language, winner: CellState) -> String {
+    let loser = match winner {
+        CellState::Red => CellState::Blue,
+        _ => CellState::Red,
+    };
+    tr_args(
+        language,
+        "game.resigned",
+        &[("loser", player_name(language, loser)), ("winner", player_name(language, winner))],
+    )
+}
+
+fn lookup(messages: &[(&'static str, &'static str)], key: &str) -> Option<&'static str> {
+    messages.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+// egui's bundled fonts have no CJK glyphs, so borrow one from the system when available.
+const CJK_FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/fonts-japanese-gothic.ttf",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+];
+
+pub fn install_cjk_font(ctx: &egui::Context) {
+    let Some(font) = CJK_FONT_PATHS.iter().find_map(|path| std::fs::read(path).ok()) else {
+        return;
+    };
+
+    let mut fonts = egui::FontDefinitions::default();
+    fonts
+        .font_data
+        .insert("cjk".to_owned(), egui::FontData::from_owned(font).into());
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts.families.entry(family).or_default().push("cjk".to_owned());
+    }
+    ctx.set_fonts(fonts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_english_key_is_translated() {
+        for (key, _) in EN {
+            assert!(lookup(JA, key).is_some(), "missing Japanese translation for {}", key);
+        }
+        assert_eq!(EN.len(), JA.len());
+    }
+
+    #[test]
+    fn test_tr_falls_back_to_key() {
+        assert_eq!(tr(Language::Japanese, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_tr_args() {
+        let text = tr_args(Language::English, "game.winner", &[("winner", "Red")]);
+        assert_eq!(text, "Winner is: Red");
+
+        let text = tr_args(Language::Japanese, "game.winner", &[("winner", "赤")]);
+        assert_eq!(text, "勝者: 赤");
+    }
+}
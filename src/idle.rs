@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::i18n::{tr, tr_args, Language};
+
+// How long the board stays dimmed after the window is back in focus, so the player to move
+// can find their place before their clock runs again.
+const COUNTDOWN: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleChange {
+    Paused,
+    Resumed,
+}
+
+// Pauses a game while nobody is at the window: once it has been out of focus for the
+// configured time the clocks stop, and they start again after a countdown when it is back.
+#[derive(Debug, Default)]
+pub struct IdlePause {
+    unfocused_since: Option<Instant>,
+    paused: bool,
+    resume_at: Option<Instant>,
+}
+
+impl IdlePause {
+    // Call each frame. `after` is how long the window may be out of focus before the game is
+    // paused; None where games aren't paused, which also ends a pause at once.
+    pub fn update(&mut self, focused: bool, now: Instant, after: Option<Duration>) -> Option<IdleChange> {
+        let Some(after) = after else {
+            self.unfocused_since = None;
+            return self.end();
+        };
+        if !focused {
+            self.resume_at = None;
+            let since = *self.unfocused_since.get_or_insert(now);
+            if !self.paused && now.saturating_duration_since(since) >= after {
+                tracing::debug!("game paused while the window is out of focus");
+                self.paused = true;
+                return Some(IdleChange::Paused);
+            }
+            return None;
+        }
+        self.unfocused_since = None;
+        if !self.paused {
+            return None;
+        }
+        match *self.resume_at.get_or_insert(now + COUNTDOWN) {
+            resume_at if now >= resume_at => self.end(),
+            _ => None,
+        }
+    }
+
+    fn end(&mut self) -> Option<IdleChange> {
+        self.resume_at = None;
+        std::mem::take(&mut self.paused).then_some(IdleChange::Resumed)
+    }
+
+    // True through the countdown as well.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // When `update` next has something to do, for the repaint.
+    pub fn next_change(&self, now: Instant, after: Option<Duration>) -> Option<Duration> {
+        if let Some(resume_at) = self.resume_at {
+            return Some(resume_at.saturating_duration_since(now).min(Duration::from_millis(250)));
+        }
+        let since = self.unfocused_since.filter(|_| !self.paused)?;
+        Some((since + after?).saturating_duration_since(now))
+    }
+
+    // Dims `rect` and says why while the game is paused.
+    pub fn overlay(&self, ctx: &egui::Context, rect: egui::Rect, now: Instant, language: Language) {
+        if !self.paused {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("idle_dim")));
+        painter.rect_filled(rect, 0.0, egui::Color32::from_black_alpha(170));
+        let text = match self.resume_at {
+            Some(resume_at) => {
+                let seconds = resume_at.saturating_duration_since(now).as_secs_f32().ceil().to_string();
+                tr_args(language, "idle.resuming", &[("seconds", &seconds)])
+            }
+            None => tr(language, "idle.paused").to_string(),
+        };
+        egui::Area::new(egui::Id::new("idle_pause"))
+            .order(egui::Order::Tooltip)
+            .fixed_pos(rect.center())
+            .pivot(egui::Align2::CENTER_CENTER)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| ui.heading(text));
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::GameClock;
+    use coast_to_coast::board::CellState;
+
+    #[test]
+    fn test_pause_after_unfocused_and_resume_after_countdown() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let after = Some(Duration::from_secs(30));
+        let mut idle = IdlePause::default();
+        let mut clock = GameClock::default();
+        clock.switch_to(CellState::Red, at(0));
+
+        assert_eq!(idle.update(false, at(10), after), None);
+        assert_eq!(idle.next_change(at(10), after), Some(Duration::from_secs(30)));
+        assert_eq!(idle.update(false, at(40), after), Some(IdleChange::Paused));
+        clock.pause(at(40));
+        assert!(idle.is_paused());
+
+        // Back at the window: the game stays paused through the countdown.
+        assert_eq!(idle.update(true, at(100), after), None);
+        assert_eq!(idle.update(true, at(102), after), None);
+        assert_eq!(idle.update(true, at(103), after), Some(IdleChange::Resumed));
+        clock.resume(at(103));
+        clock.switch_to(CellState::Red, at(103));
+        assert!(!idle.is_paused());
+
+        clock.record_move(at(105));
+        assert_eq!(clock.used(CellState::Red, at(105)), Duration::from_secs(42));
+        assert_eq!(clock.move_times(), [Some(Duration::from_secs(42))]);
+
+        // Turning the pause off ends it.
+        idle.update(false, at(200), after);
+        assert_eq!(idle.update(false, at(300), after), Some(IdleChange::Paused));
+        assert_eq!(idle.update(false, at(301), None), Some(IdleChange::Resumed));
+    }
+}
@@ -0,0 +1,297 @@
+//! Inferior cell analysis: empty cells a search can leave out because another move is known
+//! to be at least as good.
+//!
+//! - A *dead* cell lies on no minimal winning chain of either player, so who takes it never
+//!   matters. It is found from its six neighbours: every two of them that a chain could pass
+//!   between through the cell are already joined around it.
+//! - Two adjacent empty cells are *captured* by a player when, whichever one the opponent
+//!   takes, the player's reply in the other leaves the opponent's stone dead. The player can
+//!   count both as theirs.
+//! - A cell is *vulnerable* when the player to move taking one of its neighbours, the killer,
+//!   would leave it dead. The killer is then at least as good a move.
+//!
+//! Dead cells are filled in as holes and captured pairs with their captor's stones until
+//! nothing more is found, and only then are vulnerable cells looked for. In games joining two
+//! sides, such as Hex, a board edge counts as a stone of the player whose goal it is. The
+//! patterns assume a convex board, as every [`crate::shape`] is.
+//!
+//! ```
+//! use coast_to_coast::board::{Board, CellState, Hex};
+//! use coast_to_coast::inferior;
+//!
+//! let mut board = Board::new(7);
+//! // Four Red stones in a row around d4 leave it dead.
+//! for hex in [Hex { q: 4, r: 3 }, Hex { q: 3, r: 4 }, Hex { q: 2, r: 4 }, Hex { q: 2, r: 3 }] {
+//!     board.set_cell(hex, CellState::Red);
+//! }
+//! let d4 = Hex { q: 3, r: 3 };
+//! assert!(inferior::analyse(&board, CellState::Blue).dead.contains(&d4));
+//! assert!(!inferior::candidate_moves(&board, CellState::Blue).contains(&d4));
+//! ```
+
+use std::collections::HashSet;
+
+use crate::board::{Board, CellState, Hex};
+
+/// What [`analyse`] found in one position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Inferior {
+    /// Dead cells, row by row in the order found, including those that only die once
+    /// captured cells are filled in.
+    pub dead: Vec<Hex>,
+    /// Captured cells and who captured them.
+    pub captured: Vec<(Hex, CellState)>,
+    /// Vulnerable cells and their killers. A killer is never itself left out.
+    pub vulnerable: Vec<(Hex, Hex)>,
+    /// Whether filling in dead and captured cells already completes somebody's chain. Nothing
+    /// is left out then, as the search still has to find the moves that make it real.
+    pub decided: bool,
+}
+
+impl Inferior {
+    /// Whether `hex` was found inferior.
+    pub fn contains(&self, hex: Hex) -> bool {
+        self.dead.contains(&hex)
+            || self.captured.iter().any(|(cell, _)| *cell == hex)
+            || self.vulnerable.iter().any(|(cell, _)| *cell == hex)
+    }
+}
+
+// How a neighbour of a cell can take part in one player's chain through that cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Link {
+    // The player's stone, or an edge that counts as one.
+    Stone,
+    // Empty, or an edge the patterns can't tell the owner of.
+    Open,
+    // The opponent's stone, a hole, or an edge that is nobody's goal.
+    Wall,
+}
+
+/// Finds the inferior cells for `player`, the player to move.
+pub fn analyse(board: &Board, player: CellState) -> Inferior {
+    let mut found = Inferior::default();
+    let mut work = board.clone();
+    loop {
+        let mut changed = false;
+        for hex in work.empty_cells() {
+            if is_dead(&work, hex) {
+                work.set_cell(hex, CellState::Blocked);
+                found.dead.push(hex);
+                changed = true;
+            }
+        }
+        for captor in [CellState::Red, CellState::Blue] {
+            for hex in work.empty_cells() {
+                for other in hex.get_neighbors() {
+                    if (other.r, other.q) > (hex.r, hex.q) && is_captured(&mut work, hex, other, captor) {
+                        work.set_cell(hex, captor);
+                        work.set_cell(other, captor);
+                        found.captured.extend([(hex, captor), (other, captor)]);
+                        changed = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    found.decided = work.winner().is_some();
+    if found.decided {
+        return found;
+    }
+
+    // A cell already kept as a killer is not left out in turn, nor is a victim kept as a killer.
+    let (mut victims, mut killers) = (HashSet::new(), HashSet::new());
+    for hex in work.empty_cells() {
+        if killers.contains(&hex) {
+            continue;
+        }
+        let killer = hex.get_neighbors().into_iter().find(|killer| {
+            !victims.contains(killer) && with_stone(&mut work, *killer, player, |board| is_dead(board, hex))
+        });
+        if let Some(killer) = killer {
+            victims.insert(hex);
+            killers.insert(killer);
+            found.vulnerable.push((hex, killer));
+        }
+    }
+    found
+}
+
+/// The empty cells worth searching for `player`, row by row: all of them less the inferior
+/// ones. Every empty cell when the analysis leaves nothing or the position is decided.
+pub fn candidate_moves(board: &Board, player: CellState) -> Vec<Hex> {
+    let empty = board.empty_cells();
+    let inferior = analyse(board, player);
+    if inferior.decided {
+        return empty;
+    }
+    let candidates: Vec<Hex> = empty.iter().copied().filter(|hex| !inferior.contains(*hex)).collect();
+    if candidates.is_empty() {
+        return empty;
+    }
+    tracing::trace!(pruned = empty.len() - candidates.len(), "inferior cells left out");
+    candidates
+}
+
+// Whether neither player could ever need `hex`.
+fn is_dead(board: &Board, hex: Hex) -> bool {
+    !matters_to(board, hex, CellState::Red) && !matters_to(board, hex, CellState::Blue)
+}
+
+// Whether one of `player`'s chains through `hex` joins two neighbours that aren't joined
+// around it. Neighbours come round the cell in order, so each touches the next.
+fn matters_to(board: &Board, hex: Hex, player: CellState) -> bool {
+    let links: Vec<Link> =
+        hex.get_neighbors().into_iter().map(|neighbor| link(board, hex, neighbor, player)).collect();
+    let joined_around = |from: usize, to: usize| {
+        let forward = (from + 1..to).all(|i| links[i] == Link::Stone);
+        let backward = (to + 1..from + 6).all(|i| links[i % 6] == Link::Stone);
+        forward || backward
+    };
+    let usable: Vec<usize> = (0..6).filter(|i| links[*i] != Link::Wall).collect();
+    usable
+        .iter()
+        .enumerate()
+        .any(|(n, from)| usable[n + 1..].iter().any(|to| !joined_around(*from, *to)))
+}
+
+fn link(board: &Board, hex: Hex, neighbor: Hex, player: CellState) -> Link {
+    match board.get_cell(&neighbor) {
+        Some(state) if *state == player => Link::Stone,
+        Some(CellState::Empty) => Link::Open,
+        Some(_) => Link::Wall,
+        None => {
+            // Off the board: beyond the one side `hex` lies on, or past a corner.
+            let shape = board.shape();
+            let sides: Vec<usize> = (0..shape.side_count()).filter(|side| shape.on_side(hex, *side)).collect();
+            let goals = shape.goal_sides(player);
+            match sides[..] {
+                [side] if !goals.contains(&side) => Link::Wall,
+                // Joining two groups on the same edge only helps when the edge is one of two.
+                [_] if goals.len() == 2 => Link::Stone,
+                _ => Link::Open,
+            }
+        }
+    }
+}
+
+// Whether `captor` can answer the opponent in either of the empty cells `a` and `b` with the
+// other one, leaving the opponent's stone dead.
+fn is_captured(board: &mut Board, a: Hex, b: Hex, captor: CellState) -> bool {
+    let empty = |board: &Board, hex| board.get_cell(&hex) == Some(&CellState::Empty);
+    empty(board, a)
+        && empty(board, b)
+        && with_stone(board, b, captor, |board| is_dead(board, a))
+        && with_stone(board, a, captor, |board| is_dead(board, b))
+}
+
+// Answers `question` with a `player` stone on the empty `hex`, then takes the stone back.
+fn with_stone(board: &mut Board, hex: Hex, player: CellState, question: impl FnOnce(&Board) -> bool) -> bool {
+    if board.get_cell(&hex) != Some(&CellState::Empty) {
+        return false;
+    }
+    board.set_cell(hex, player);
+    let answer = question(board);
+    board.set_cell(hex, CellState::Empty);
+    answer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board_with(size: i32, red: &[&str], blue: &[&str]) -> Board {
+        let mut board = Board::new(size);
+        for (cells, player) in [(red, CellState::Red), (blue, CellState::Blue)] {
+            for cell in cells {
+                let crate::notation::Move::Place(hex) = cell.parse().unwrap() else {
+                    panic!("{cell} is not a cell");
+                };
+                board.set_cell(hex, player);
+            }
+        }
+        board
+    }
+
+    #[test]
+    fn test_dead_cells() {
+        let c3 = Hex { q: 2, r: 2 };
+        // Four stones of one colour in a row around c3.
+        let board = board_with(5, &["d3", "c4", "b4", "b3"], &[]);
+        assert!(is_dead(&board, c3));
+        // Three of one colour facing two of the other, one neighbour empty between them.
+        let board = board_with(5, &["d3", "c4", "b4"], &["c2", "d2"]);
+        assert!(is_dead(&board, c3));
+        // Three in a row is not enough, nor are stones of one colour on opposite sides.
+        assert!(!is_dead(&board_with(5, &["d3", "c4", "b4"], &[]), c3));
+        assert!(!is_dead(&board_with(5, &["d3", "b3", "c2", "c4"], &[]), c3));
+
+        // On Red's edge, the edge behind a1..a5 counts as two more Red stones.
+        let a3 = Hex { q: 0, r: 2 };
+        assert!(is_dead(&board_with(5, &["a2", "a4"], &[]), a3));
+        assert!(!is_dead(&board_with(5, &["b2", "b3"], &[]), a3));
+        // A corner touches two players' edges, so is left alone.
+        assert!(!is_dead(&board_with(5, &["b1", "a2"], &[]), Hex { q: 0, r: 0 }));
+    }
+
+    #[test]
+    fn test_captured_pair() {
+        // Red's b4, c4 and d4 below c3 and d3, d2 above: whichever Blue takes, Red's reply in
+        // the other surrounds it with four Red stones in a row.
+        let board = board_with(5, &["b4", "c4", "d4", "d2"], &[]);
+        let found = analyse(&board, CellState::Blue);
+        assert!(found.captured.contains(&(Hex { q: 2, r: 2 }, CellState::Red)));
+        assert!(found.captured.contains(&(Hex { q: 3, r: 2 }, CellState::Red)));
+        assert!(found.captured.iter().all(|(_, captor)| *captor == CellState::Red));
+        assert!(!found.decided);
+        assert!(!candidate_moves(&board, CellState::Blue).contains(&Hex { q: 2, r: 2 }));
+    }
+
+    #[test]
+    fn test_vulnerable_cells_keep_their_killer() {
+        // A Red stone on d3 would make four in a row around c3 with c4, b4 and b3.
+        let board = board_with(5, &["c4", "b4", "b3"], &[]);
+        let found = analyse(&board, CellState::Red);
+        let (victim, killer) = found.vulnerable.iter().find(|(victim, _)| *victim == Hex { q: 2, r: 2 }).unwrap();
+        assert!(!found.contains(*killer));
+        assert!(board.get_cell(killer) == Some(&CellState::Empty) && victim.get_neighbors().contains(killer));
+        assert!(!candidate_moves(&board, CellState::Red).contains(victim));
+    }
+
+    #[test]
+    fn test_killers_are_never_left_out() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..3000 {
+            let mut board = Board::new(rng.gen_range(4..=7));
+            for hex in board.empty_cells() {
+                match rng.gen_range(0..3) {
+                    0 => board.set_cell(hex, CellState::Red),
+                    1 => board.set_cell(hex, CellState::Blue),
+                    _ => {}
+                }
+            }
+            let player = if rng.gen_bool(0.5) { CellState::Red } else { CellState::Blue };
+            let found = analyse(&board, player);
+            for (victim, killer) in &found.vulnerable {
+                assert!(!found.contains(*killer), "{victim:?} killed by {killer:?}, itself left out\n{board}");
+                assert_eq!(board.get_cell(killer), Some(&CellState::Empty));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nothing_left_out_once_decided() {
+        // b2-c2-d2 reaches Red's right edge, and a2 and a3 in front of b2 and b3 are captured.
+        let board = board_with(4, &["b2", "b3", "c2", "d2"], &[]);
+        let found = analyse(&board, CellState::Blue);
+        assert!(found.captured.contains(&(Hex { q: 0, r: 1 }, CellState::Red)));
+        assert!(found.decided);
+        assert_eq!(candidate_moves(&board, CellState::Blue), board.empty_cells());
+        assert_eq!(candidate_moves(&Board::new(1), CellState::Red), vec![Hex { q: 0, r: 0 }]);
+    }
+}
@@ -0,0 +1,280 @@
+//! Insights into the user's own play, computed from their saved games on this machine: how
+//! often they blunder, how much each move gives away by the static evaluation, and how they
+//! score with each opening. Nothing leaves the computer.
+//!
+//! A game's own side is the one carrying the user's name, or else the only side no engine
+//! searched for. Hot-seat games without the name have no own side; all their moves count as
+//! the user's, but they have no score.
+//!
+//! ```
+//! use coast_to_coast::game::Game;
+//! use coast_to_coast::insights;
+//! use coast_to_coast::record::{GameMetadata, GameRecord};
+//!
+//! let mut game = Game::with_size(5).with_pie_rule(false);
+//! for mv in ["c3", "a1", "b3", "b1", "d3", "c1", "a3", "d1", "e3"] {
+//!     game.play_move(mv.parse().unwrap()).unwrap();
+//! }
+//! let metadata = GameMetadata { red: "Alice".to_string(), date: "2026.10.15".to_string(), ..Default::default() };
+//! let insight = insights::analyse(&GameRecord::from_game(&game, &metadata), "Alice").unwrap();
+//! // Red won, and wasted no moves going straight across the middle.
+//! assert_eq!(insight.score, Some(1.0));
+//! assert_eq!(insight.blunders, 0);
+//! ```
+
+use crate::board::{CellState, Hex};
+use crate::eval;
+use crate::game::Game;
+use crate::notation::Move;
+use crate::record::{GameMetadata, GameRecord, GameResult};
+
+// A move that gives away this much of the evaluation's win chance counts as a blunder.
+const BLUNDER_LOSS: f32 = 0.2;
+
+/// What one saved game says about the user's play.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameInsight {
+    /// The year and month played, e.g. "2026.10", if the game has a date.
+    pub month: Option<String>,
+    /// The first stone placed.
+    pub opening: Option<Hex>,
+    /// The color the user played, when it is known.
+    pub own: Option<CellState>,
+    /// 1 for a win, 0.5 for a draw and 0 for a loss, for finished games with an own side.
+    pub score: Option<f32>,
+    /// The user's stones, each checked against the best move by the evaluation.
+    pub moves: usize,
+    pub blunders: usize,
+    /// The win chance given away over all of `moves`, by the evaluation.
+    pub loss: f32,
+}
+
+/// Replays `record` and rates the user's moves in it. None if it no longer replays.
+pub fn analyse(record: &GameRecord, own_name: &str) -> Option<GameInsight> {
+    let own = own_side(record, own_name);
+    let mut insight = GameInsight {
+        month: month(&record.metadata.date),
+        opening: record.moves.iter().find_map(|mv| match mv {
+            Move::Place(hex) => Some(*hex),
+            _ => None,
+        }),
+        own,
+        score: None,
+        moves: 0,
+        blunders: 0,
+        loss: 0.0,
+    };
+    let game = rate_own_moves(record, own, |rating| {
+        insight.moves += 1;
+        insight.loss += rating.loss;
+        if rating.loss >= BLUNDER_LOSS {
+            insight.blunders += 1;
+        }
+    })?;
+    // Agreed results are only in the metadata.
+    insight.score = own.and_then(|own| match record.metadata.result.or(GameResult::of(game.state))? {
+        GameResult::Win(winner) | GameResult::Resignation(winner) => Some(if winner == own { 1.0 } else { 0.0 }),
+        GameResult::Draw => Some(0.5),
+        GameResult::Adjourned => None,
+    });
+    Some(insight)
+}
+
+/// A blunder of the user's, to study: the position before it, the stone placed and the one the
+/// evaluation rates best.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mistake {
+    pub position: GameRecord,
+    pub played: Hex,
+    pub best: Hex,
+    /// The win chance given away, by the evaluation.
+    pub loss: f32,
+}
+
+/// The user's blunders in `record`, in the order played. Empty if it no longer replays.
+pub fn mistakes(record: &GameRecord, own_name: &str) -> Vec<Mistake> {
+    let mut mistakes = Vec::new();
+    rate_own_moves(record, own_side(record, own_name), |rating| {
+        if rating.loss >= BLUNDER_LOSS {
+            mistakes.push(Mistake {
+                position: GameRecord::from_game(rating.before, &GameMetadata::default()),
+                played: rating.played,
+                best: rating.best,
+                loss: rating.loss,
+            });
+        }
+    });
+    mistakes
+}
+
+// The side the user played in `record`; see the module comment.
+fn own_side(record: &GameRecord, own_name: &str) -> Option<CellState> {
+    let movers = record.turns.movers(&record.moves);
+    let searched = |player| {
+        movers
+            .iter()
+            .zip(&record.telemetry)
+            .any(|(mover, telemetry)| *mover == player && telemetry.is_some())
+    };
+    let named = |name: &str| !own_name.trim().is_empty() && name.trim().eq_ignore_ascii_case(own_name.trim());
+    match (named(&record.metadata.red), named(&record.metadata.blue)) {
+        (true, false) => Some(CellState::Red),
+        (false, true) => Some(CellState::Blue),
+        _ => match (searched(CellState::Red), searched(CellState::Blue)) {
+            (false, true) => Some(CellState::Red),
+            (true, false) => Some(CellState::Blue),
+            _ => None,
+        },
+    }
+}
+
+struct Rating<'a> {
+    before: &'a Game,
+    played: Hex,
+    best: Hex,
+    loss: f32,
+}
+
+// Replays `record`, rating each stone `own` placed against the evaluation's best move; with no
+// own side, every move no engine searched. Returns the final position, or None if the moves no
+// longer replay.
+fn rate_own_moves(record: &GameRecord, own: Option<CellState>, mut rated: impl FnMut(Rating)) -> Option<Game> {
+    let movers = record.turns.movers(&record.moves);
+    let start = GameRecord { metadata: GameMetadata::default(), moves: Vec::new(), ..record.clone() };
+    let mut game: Game = start.replay().ok()?;
+    for (i, mv) in record.moves.iter().enumerate() {
+        let own_move = own.map_or(record.telemetry.get(i).is_none_or(Option::is_none), |own| movers[i] == own);
+        if let (Move::Place(hex), true) = (mv, own_move) {
+            let ranked = eval::rank_moves(&game);
+            let played = ranked.iter().find(|(candidate, _)| candidate == hex).map(|(_, score)| *score);
+            if let (Some((best, best_score)), Some(played)) = (ranked.first(), played) {
+                rated(Rating { before: &game, played: *hex, best: *best, loss: (best_score - played).max(0.0) });
+            }
+        }
+        game.play_move(*mv).ok()?;
+    }
+    Some(game)
+}
+
+// "2026.10" from a "2026.10.15" date; PGN's "??" placeholders don't count.
+fn month(date: &str) -> Option<String> {
+    let mut parts = date.split(['.', '-', '/']);
+    let (year, month) = (parts.next()?, parts.next()?);
+    let valid = |part: &str, digits| part.len() == digits && part.bytes().all(|byte| byte.is_ascii_digit());
+    (valid(year, 4) && valid(month, 2)).then(|| format!("{}.{}", year, month))
+}
+
+/// The user's moves and blunders over some games.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Totals {
+    pub games: usize,
+    pub moves: usize,
+    pub blunders: usize,
+    pub loss: f32,
+}
+
+impl Totals {
+    fn add(&mut self, insight: &GameInsight) {
+        self.games += 1;
+        self.moves += insight.moves;
+        self.blunders += insight.blunders;
+        self.loss += insight.loss;
+    }
+
+    /// Blunders per move, from 0 to 1.
+    pub fn blunder_rate(&self) -> f32 {
+        self.blunders as f32 / self.moves.max(1) as f32
+    }
+
+    /// The win chance given away per move, by the evaluation.
+    pub fn average_loss(&self) -> f32 {
+        self.loss / self.moves.max(1) as f32
+    }
+}
+
+/// Totals for each month with dated games, oldest first.
+pub fn by_month(insights: &[GameInsight]) -> Vec<(String, Totals)> {
+    let mut months: Vec<(String, Totals)> = Vec::new();
+    for insight in insights {
+        let Some(month) = &insight.month else {
+            continue;
+        };
+        match months.iter_mut().find(|(known, _)| known == month) {
+            Some((_, totals)) => totals.add(insight),
+            None => {
+                let mut totals = Totals::default();
+                totals.add(insight);
+                months.push((month.clone(), totals));
+            }
+        }
+    }
+    months.sort_by(|a, b| a.0.cmp(&b.0));
+    months
+}
+
+/// How the user scored after each opening stone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpeningScore {
+    pub opening: Hex,
+    pub games: usize,
+    /// The average score, from 0 (every game lost) to 1.
+    pub score: f32,
+}
+
+/// Openings of the scored games, most played first.
+pub fn by_opening(insights: &[GameInsight]) -> Vec<OpeningScore> {
+    let mut openings: Vec<OpeningScore> = Vec::new();
+    for insight in insights {
+        let (Some(opening), Some(score)) = (insight.opening, insight.score) else {
+            continue;
+        };
+        match openings.iter_mut().find(|known| known.opening == opening) {
+            Some(known) => {
+                known.score = (known.score * known.games as f32 + score) / (known.games + 1) as f32;
+                known.games += 1;
+            }
+            None => openings.push(OpeningScore { opening, games: 1, score }),
+        }
+    }
+    openings.sort_by(|a, b| b.games.cmp(&a.games).then((a.opening.r, a.opening.q).cmp(&(b.opening.r, b.opening.q))));
+    openings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insights_count_own_moves() {
+        // Red's c2 heads for Blue's edges rather than across to its own, which the evaluation
+        // counts as a blunder.
+        let mut game = Game::with_size(5).with_pie_rule(false);
+        for mv in ["c3", "a1", "c2", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let metadata = GameMetadata { red: "bob".to_string(), date: "2026.09.01".to_string(), ..Default::default() };
+        let record = GameRecord::from_game(&game, &metadata);
+        let red = analyse(&record, "Bob").unwrap();
+        assert_eq!((red.own, red.moves, red.blunders, red.score), (Some(CellState::Red), 2, 1, None));
+        assert_eq!(red.opening, Some(Hex { q: 2, r: 2 }));
+        // Without the name, both sides are the user's.
+        let both = analyse(&record, "").unwrap();
+        assert_eq!((both.own, both.moves), (None, 4));
+        let mistakes = mistakes(&record, "Bob");
+        assert_eq!(mistakes.len(), 1);
+        assert_eq!(mistakes[0].played, Hex { q: 2, r: 1 });
+        assert_ne!(mistakes[0].best, mistakes[0].played);
+        assert_eq!(mistakes[0].position.moves, record.moves[..2]);
+
+        let won = GameInsight { month: Some("2026.10".to_string()), score: Some(1.0), ..red.clone() };
+        let months = by_month(&[won.clone(), red.clone(), won.clone()]);
+        assert_eq!(months.iter().map(|(month, totals)| (month.as_str(), totals.games)).collect::<Vec<_>>(), [
+            ("2026.09", 1),
+            ("2026.10", 2)
+        ]);
+        assert_eq!(months[0].1.blunder_rate(), 0.5);
+        let lost = GameInsight { score: Some(0.0), ..won.clone() };
+        assert_eq!(by_opening(&[won, lost, red]), [OpeningScore { opening: Hex { q: 2, r: 2 }, games: 2, score: 0.5 }]);
+        assert_eq!(month("????.??.??"), None);
+    }
+}
@@ -0,0 +1,188 @@
+//! The game in progress kept as an append-only journal next to the autosave.
+//!
+//! A journal starts with a full game record, the base, followed by a `%journal` line and one
+//! line per action since: `move <move> <ms>` (`-` for an unknown time), `takeback <moves left>`
+//! and `result <tag>`. Every line is synced to disk as it is written, so a crash or power loss
+//! loses at most the action being written; a torn last line is ignored on recovery. Compaction
+//! folds the journal into the autosave and removes it.
+//!
+//! ```
+//! use coast_to_coast::journal::{self, Entry};
+//! use coast_to_coast::record::GameRecord;
+//!
+//! let base = GameRecord::parse("[Size \"5\"]\n\n1. c3\n").unwrap();
+//! let text = format!("{}%journal\nmove b2 1500\nmove d2 -\ntakeback 2\nmove a1", base.to_text());
+//! let record = journal::recover(&text).unwrap();
+//! // The torn "move a1" line was never completed.
+//! assert_eq!(record.moves, GameRecord::parse("c3 b2").unwrap().moves);
+//! assert_eq!(Entry::parse("takeback 2"), Some(Entry::TakeBack(2)));
+//! ```
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::notation::Move;
+use crate::record::{GameRecord, GameResult, RecordError};
+use crate::saves::{self, SAVES_DIR};
+
+// Kept in the saves directory; not a `.txt`, so the games list and sync never pick it up.
+pub const JOURNAL_FILE: &str = "autosave.journal";
+const SEPARATOR: &str = "%journal";
+
+// One action after the base record.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Entry {
+    // A move and the time taken over it.
+    Move(Move, Option<Duration>),
+    // A takeback that left this many moves.
+    TakeBack(usize),
+    Result(GameResult),
+}
+
+impl Entry {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let entry = match (words.next()?, words.next()?) {
+            ("move", mv) => {
+                let time = match words.next()? {
+                    "-" => None,
+                    millis => Some(Duration::from_millis(millis.parse().ok()?)),
+                };
+                Entry::Move(mv.parse().ok()?, time)
+            }
+            ("takeback", moves) => Entry::TakeBack(moves.parse().ok()?),
+            ("result", tag) => Entry::Result(GameResult::from_tag(tag)?),
+            _ => return None,
+        };
+        words.next().is_none().then_some(entry)
+    }
+
+    fn line(&self) -> String {
+        match self {
+            Entry::Move(mv, time) => {
+                let time = time.map_or("-".to_string(), |time| time.as_millis().to_string());
+                format!("move {} {}", mv, time)
+            }
+            Entry::TakeBack(moves) => format!("takeback {}", moves),
+            Entry::Result(result) => format!("result {}", result.tag()),
+        }
+    }
+
+    fn apply(&self, record: &mut GameRecord) {
+        match self {
+            Entry::Move(mv, time) => {
+                record.moves.push(*mv);
+                record.move_times.push(*time);
+                record.telemetry.push(None);
+            }
+            Entry::TakeBack(moves) => {
+                record.moves.truncate(*moves);
+                record.move_times.truncate(*moves);
+                record.telemetry.truncate(*moves);
+                record.metadata.result = None;
+            }
+            Entry::Result(result) => record.metadata.result = Some(*result),
+        }
+    }
+}
+
+// The open journal of the game in progress.
+pub struct Journal {
+    file: File,
+}
+
+impl Journal {
+    // Starts a new journal with `record` as its base, replacing any earlier one.
+    pub fn start(data_dir: &Path, record: &GameRecord) -> io::Result<Self> {
+        let dir = data_dir.join(SAVES_DIR);
+        fs::create_dir_all(&dir)?;
+        let mut file = File::create(dir.join(JOURNAL_FILE))?;
+        writeln!(file, "{}{}", record.to_text(), SEPARATOR)?;
+        file.sync_all()?;
+        // Reopened for appending so every write lands at the end.
+        let file = OpenOptions::new().append(true).open(dir.join(JOURNAL_FILE))?;
+        Ok(Self { file })
+    }
+
+    // Returns once the entry is on disk.
+    pub fn append(&mut self, entry: &Entry) -> io::Result<()> {
+        self.file.write_all(format!("{}\n", entry.line()).as_bytes())?;
+        self.file.sync_data()
+    }
+}
+
+fn path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SAVES_DIR).join(JOURNAL_FILE)
+}
+
+// The game a journal describes: its base with every complete entry applied. Entries that
+// don't read are skipped rather than losing the rest.
+pub fn recover(text: &str) -> Result<GameRecord, RecordError> {
+    let (base, entries) = text.split_once(&format!("\n{}\n", SEPARATOR)).unwrap_or((text, ""));
+    let mut record = GameRecord::parse(base)?;
+    // Only lines ended by a newline were written in full.
+    let complete = &entries[..entries.rfind('\n').map_or(0, |end| end + 1)];
+    for line in complete.lines().filter(|line| !line.trim().is_empty()) {
+        match Entry::parse(line) {
+            Some(entry) => entry.apply(&mut record),
+            None => tracing::warn!(line, "skipping unreadable journal entry"),
+        }
+    }
+    Ok(record)
+}
+
+// Folds a journal left behind, e.g. by a crash, into the autosave and removes it. Returns
+// whether there was one. A journal that can't be read is kept for another look.
+pub fn compact(data_dir: &Path) -> io::Result<bool> {
+    let path = path(data_dir);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let record = recover(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    if !record.moves.is_empty() {
+        saves::autosave(data_dir, &record)?;
+    }
+    fs::remove_file(&path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::record::GameMetadata;
+
+    #[test]
+    fn test_journal_survives_and_compacts() {
+        let dir = std::env::temp_dir().join(format!("hex-journal-test-{}", std::process::id()));
+        let mut game = Game::with_size(5);
+        game.play_move("c3".parse().unwrap()).unwrap();
+        let record = GameRecord::from_game(&game, &GameMetadata::default());
+
+        let mut journal = Journal::start(&dir, &record).unwrap();
+        journal.append(&Entry::Move("b2".parse().unwrap(), Some(Duration::from_millis(2500)))).unwrap();
+        journal.append(&Entry::Move("d4".parse().unwrap(), None)).unwrap();
+        journal.append(&Entry::TakeBack(2)).unwrap();
+        journal.append(&Entry::Result(GameResult::Resignation(crate::board::CellState::Red))).unwrap();
+        // The power went out halfway through the next line.
+        journal.file.write_all(b"move e").unwrap();
+        drop(journal);
+
+        assert!(compact(&dir).unwrap());
+        assert!(!path(&dir).exists());
+        let saved = saves::list(&dir);
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].record.moves, GameRecord::parse("c3 b2").unwrap().moves);
+        assert_eq!(saved[0].record.metadata.result_tag(), "Red+Resign");
+        assert!(!compact(&dir).unwrap());
+        let time = Some(Duration::from_millis(2500));
+        assert_eq!(Entry::parse("move b2 2500"), Some(Entry::Move("b2".parse().unwrap(), time)));
+        assert_eq!(Entry::parse("move b2"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
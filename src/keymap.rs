@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use eframe::egui::{self, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, tr_args, Language};
+use crate::menu_bar::MenuAction;
+
+// The user's keyboard shortcuts: the defaults with whatever they rebound. Only the changes are
+// saved, so new defaults reach users who never touched those actions.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    rebound: BTreeMap<MenuAction, KeyboardShortcut>,
+}
+
+impl Keymap {
+    pub fn shortcut(&self, action: MenuAction) -> KeyboardShortcut {
+        self.rebound.get(&action).copied().unwrap_or_else(|| action.default_shortcut())
+    }
+
+    // Binds `shortcut` to `action`, unless another action already has it; that one is returned.
+    pub fn rebind(&mut self, action: MenuAction, shortcut: KeyboardShortcut) -> Result<(), MenuAction> {
+        if let Some(other) = MenuAction::all().find(|other| *other != action && self.shortcut(*other) == shortcut) {
+            return Err(other);
+        }
+        if shortcut == action.default_shortcut() {
+            self.rebound.remove(&action);
+        } else {
+            self.rebound.insert(action, shortcut);
+        }
+        Ok(())
+    }
+
+    // Pairs of actions sharing a shortcut, e.g. after a newer version's defaults took one the
+    // user had already rebound.
+    pub fn conflicts(&self) -> Vec<(MenuAction, MenuAction)> {
+        let actions: Vec<MenuAction> = MenuAction::all().collect();
+        let mut conflicts = Vec::new();
+        for (i, a) in actions.iter().enumerate() {
+            for b in &actions[..i] {
+                if self.shortcut(*a) == self.shortcut(*b) {
+                    conflicts.push((*b, *a));
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn reset(&mut self) {
+        self.rebound.clear();
+    }
+
+    // The shortcuts editor: a row per action; click a shortcut, then press the new one.
+    pub fn show_editor(&mut self, ui: &mut egui::Ui, language: Language) {
+        let recording = recording(ui.ctx());
+        if let Some(action) = recording {
+            if let Some(shortcut) = pressed_shortcut(ui.ctx()) {
+                let message = match shortcut {
+                    None => None,
+                    Some(shortcut) => self.rebind(action, shortcut).err().map(|other| {
+                        tr_args(language, "keymap.taken", &[("action", tr(language, other.label()))])
+                    }),
+                };
+                stop_recording(ui.ctx());
+                ui.data_mut(|data| data.insert_temp(message_id(), message));
+            }
+        }
+
+        let message: Option<String> = ui.data(|data| data.get_temp(message_id())).flatten();
+        if let Some(message) = message {
+            ui.colored_label(ui.visuals().error_fg_color, message);
+        }
+        for (a, b) in self.conflicts() {
+            let names = [("first", tr(language, a.label())), ("second", tr(language, b.label()))];
+            ui.colored_label(ui.visuals().warn_fg_color, tr_args(language, "keymap.conflict", &names));
+        }
+        egui::Grid::new("keymap").num_columns(2).striped(true).show(ui, |ui| {
+            for action in MenuAction::all() {
+                ui.label(tr(language, action.label()));
+                let text = if recording == Some(action) {
+                    tr(language, "keymap.press").to_string()
+                } else {
+                    ui.ctx().format_shortcut(&self.shortcut(action))
+                };
+                if ui.selectable_label(recording == Some(action), text).clicked() {
+                    ui.data_mut(|data| {
+                        data.insert_temp(recording_id(), action);
+                        data.insert_temp::<Option<String>>(message_id(), None);
+                    });
+                }
+                ui.end_row();
+            }
+        });
+        if ui.button(tr(language, "keymap.reset")).clicked() {
+            self.reset();
+        }
+    }
+}
+
+// The action the editor is waiting for a new shortcut for. Shortcuts do nothing meanwhile.
+pub fn recording(ctx: &egui::Context) -> Option<MenuAction> {
+    ctx.data(|data| data.get_temp(recording_id()))
+}
+
+// Stops waiting for a shortcut, e.g. when the editor is closed.
+pub fn stop_recording(ctx: &egui::Context) {
+    ctx.data_mut(|data| data.remove::<MenuAction>(recording_id()));
+}
+
+fn recording_id() -> egui::Id {
+    egui::Id::new("keymap_recording")
+}
+
+fn message_id() -> egui::Id {
+    egui::Id::new("keymap_message")
+}
+
+// The shortcut pressed this frame: Some(None) for Escape, which cancels, and None for nothing
+// yet. Modifier keys alone are not shortcuts; egui reports no key for them.
+fn pressed_shortcut(ctx: &egui::Context) -> Option<Option<KeyboardShortcut>> {
+    ctx.input(|i| {
+        i.events.iter().find_map(|event| match event {
+            egui::Event::Key { key: egui::Key::Escape, pressed: true, .. } => Some(None),
+            egui::Event::Key { key, pressed: true, modifiers, .. } => {
+                let modifiers =
+                    Modifiers { alt: modifiers.alt, shift: modifiers.shift, command: modifiers.command, ..Default::default() };
+                Some(Some(KeyboardShortcut::new(modifiers, *key)))
+            }
+            _ => None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebinding_refuses_conflicts() {
+        let mut keymap = Keymap::default();
+        assert!(keymap.conflicts().is_empty());
+        let undo = MenuAction::Undo.default_shortcut();
+        assert_eq!(keymap.rebind(MenuAction::Resign, undo), Err(MenuAction::Undo));
+
+        let backspace = KeyboardShortcut::new(Modifiers::NONE, egui::Key::Backspace);
+        keymap.rebind(MenuAction::Undo, backspace).unwrap();
+        keymap.rebind(MenuAction::Resign, undo).unwrap();
+        assert_eq!(keymap.shortcut(MenuAction::Resign), undo);
+        // Undo gets its shortcut back once Resign lets go of it, and the defaults leave nothing saved.
+        keymap.rebind(MenuAction::Undo, undo).unwrap_err();
+        keymap.rebind(MenuAction::Resign, MenuAction::Resign.default_shortcut()).unwrap();
+        keymap.rebind(MenuAction::Undo, undo).unwrap();
+        assert_eq!(keymap, Keymap::default());
+
+        keymap.rebound.insert(MenuAction::Pass, undo);
+        assert_eq!(keymap.conflicts(), vec![(MenuAction::Undo, MenuAction::Pass)]);
+    }
+}
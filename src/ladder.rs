@@ -0,0 +1,167 @@
+//! Second-row ladders: what happens when a stone next to its own edge can only reach it one way.
+//!
+//! A stone on the second row joins the edge through the two edge cells in front of it. Once
+//! the opponent holds one of them, the stone has to run: the opponent blocks the other, the
+//! stone's owner steps along the second row, and so on until they reach a stone of theirs
+//! that ends it (an escape) or the ladder runs into the opponent or off the board, in which
+//! case every stone it cost was wasted. Only the Hex rhombus's four sides are read this way.
+//!
+//! ```
+//! use coast_to_coast::board::{Board, CellState, Hex};
+//! use coast_to_coast::ladder;
+//!
+//! // Blue's a2 already sits in front of b2, so a Red stone there starts a ladder down the
+//! // b column with nothing to run to.
+//! let mut board = Board::new(5);
+//! board.set_cell(Hex { q: 0, r: 1 }, CellState::Blue);
+//! let ladder = ladder::ladder_after(&board, CellState::Red, Hex { q: 1, r: 1 }).unwrap();
+//! assert!(ladder.escape.is_none());
+//! ```
+
+use crate::board::{Board, CellState, Hex};
+
+/// A ladder the player would start, run out as far as it goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ladder {
+    /// The edge the player is trying to reach.
+    pub side: usize,
+    /// The second-row cells the player would fill after the first, in order.
+    pub path: Vec<Hex>,
+    /// A stone of the player's on the first or second row ahead, which wins them the ladder.
+    pub escape: Option<Hex>,
+    /// A stone of the player's on the third row next to the path. It may give them an escape
+    /// with a double threat, but doesn't by itself.
+    pub support: Option<Hex>,
+}
+
+/// The ladder `player` starts by playing `hex`, if it is on the second row from one of their
+/// edges and the opponent already holds one of the two edge cells in front of it.
+pub fn ladder_after(board: &Board, player: CellState, hex: Hex) -> Option<Ladder> {
+    if board.get_cell(&hex) != Some(&CellState::Empty) {
+        return None;
+    }
+    let shape = board.shape();
+    shape.goal_sides(player).into_iter().filter(|side| *side < 4).find_map(|side| {
+        let rows = Rows { side, size: board.size };
+        let (depth, along) = rows.position(hex);
+        if depth != 1 {
+            return None;
+        }
+        let step = rows.step();
+        let [first, second] = [along, along + step].map(|a| rows.hex(0, a));
+        if !shape.on_side(first, side) || !shape.on_side(second, side) {
+            return None;
+        }
+        // The ladder runs towards the edge cell the opponent hasn't taken yet.
+        let (threatened, direction) = match (board.get_cell(&first), board.get_cell(&second)) {
+            (Some(CellState::Empty), Some(state)) if blocks(*state, player) => (along, -step),
+            (Some(state), Some(CellState::Empty)) if blocks(*state, player) => (along + step, step),
+            _ => return None,
+        };
+        Some(run(board, player, rows, along, threatened, direction))
+    })
+}
+
+// Follows a ladder from its first stone at `along` on the second row. Each round the opponent
+// blocks the threatened edge cell and the player steps one further along the second row.
+fn run(board: &Board, player: CellState, rows: Rows, mut along: i32, mut threatened: i32, direction: i32) -> Ladder {
+    let mut ladder = Ladder { side: rows.side, path: Vec::new(), escape: None, support: None };
+    loop {
+        if ladder.support.is_none() {
+            let current = rows.hex(1, along);
+            ladder.support = current.get_neighbors().into_iter().find(|neighbor| {
+                rows.position(*neighbor).0 == 2 && board.get_cell(neighbor) == Some(&player)
+            });
+        }
+        let edge = rows.hex(0, threatened);
+        match board.get_cell(&edge) {
+            Some(state) if *state == player => {
+                ladder.escape = Some(edge);
+                return ladder;
+            }
+            Some(CellState::Empty) => {}
+            _ => return ladder,
+        }
+        along += direction;
+        threatened += direction;
+        let next = rows.hex(1, along);
+        match board.get_cell(&next) {
+            // Reaching a second-row stone for free leaves the player a move to take the edge.
+            Some(state) if *state == player => {
+                ladder.escape = Some(next);
+                return ladder;
+            }
+            Some(CellState::Empty) => ladder.path.push(next),
+            _ => return ladder,
+        }
+    }
+}
+
+fn blocks(state: CellState, player: CellState) -> bool {
+    state != CellState::Empty && state != player
+}
+
+/// Cells counted from one side of the rhombus: `depth` rows in from it and `along` it. On
+/// sides 0 and 2 a cell touches `along` and `along + 1` on the row nearer the edge; on sides 1
+/// and 3 the rows lean the other way.
+#[derive(Clone, Copy, Debug)]
+pub struct Rows {
+    pub side: usize,
+    pub size: i32,
+}
+
+impl Rows {
+    pub fn hex(self, depth: i32, along: i32) -> Hex {
+        match self.side {
+            0 => Hex { q: depth, r: along },
+            1 => Hex { q: self.size - 1 - depth, r: along },
+            2 => Hex { q: along, r: depth },
+            _ => Hex { q: along, r: self.size - 1 - depth },
+        }
+    }
+
+    pub fn position(self, hex: Hex) -> (i32, i32) {
+        match self.side {
+            0 => (hex.q, hex.r),
+            1 => (self.size - 1 - hex.q, hex.r),
+            2 => (hex.r, hex.q),
+            _ => (self.size - 1 - hex.r, hex.q),
+        }
+    }
+
+    /// A second-row cell at `along` touches the edge cells at `along` and `along + step`.
+    pub fn step(self) -> i32 {
+        match self.side {
+            0 | 2 => 1,
+            _ => -1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladders_run_to_an_escape_or_the_end() {
+        // Red's e1 sits in front of d2 on Blue's top edge, so the ladder runs left to a2.
+        let mut board = Board::new(5);
+        board.set_cell(Hex { q: 4, r: 0 }, CellState::Red);
+        let ladder = ladder_after(&board, CellState::Blue, Hex { q: 3, r: 1 }).unwrap();
+        assert_eq!(ladder.side, 2);
+        assert_eq!(ladder.path, vec![Hex { q: 2, r: 1 }, Hex { q: 1, r: 1 }, Hex { q: 0, r: 1 }]);
+        assert_eq!((ladder.escape, ladder.support), (None, None));
+
+        // A Blue stone on b2 is reached for free, and one on c3 supports the ladder on its way.
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Blue);
+        board.set_cell(Hex { q: 2, r: 2 }, CellState::Blue);
+        let ladder = ladder_after(&board, CellState::Blue, Hex { q: 3, r: 1 }).unwrap();
+        assert_eq!(ladder.path, vec![Hex { q: 2, r: 1 }]);
+        assert_eq!(ladder.escape, Some(Hex { q: 1, r: 1 }));
+        assert_eq!(ladder.support, Some(Hex { q: 2, r: 2 }));
+
+        // Both edge cells free is a bridge to the edge, not a ladder; Red has no top edge.
+        assert_eq!(ladder_after(&board, CellState::Blue, Hex { q: 3, r: 3 }), None);
+        assert_eq!(ladder_after(&board, CellState::Red, Hex { q: 3, r: 1 }), None);
+    }
+}
@@ -0,0 +1,64 @@
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::game::Game;
+use coast_to_coast::ladder;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, Language};
+
+// When hovering a cell warns that it starts a ladder the player would lose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LadderWarnings {
+    #[default]
+    Off,
+    // Any ladder without a stone on the first or second row to escape to.
+    Strict,
+    // Also stay quiet when a third-row stone along the way might give an escape.
+    Lenient,
+}
+
+impl LadderWarnings {
+    pub const ALL: [LadderWarnings; 3] = [LadderWarnings::Off, LadderWarnings::Strict, LadderWarnings::Lenient];
+
+    pub fn name(self, language: Language) -> &'static str {
+        match self {
+            LadderWarnings::Off => tr(language, "ladder.off"),
+            LadderWarnings::Strict => tr(language, "ladder.strict"),
+            LadderWarnings::Lenient => tr(language, "ladder.lenient"),
+        }
+    }
+
+    // The cells where the player to move would start a losing ladder.
+    pub fn losing_ladders(self, game: &Game) -> Vec<Hex> {
+        if self == LadderWarnings::Off || !matches!(game.current_player, CellState::Red | CellState::Blue) {
+            return Vec::new();
+        }
+        game.legal_moves()
+            .filter(|hex| {
+                ladder::ladder_after(&game.board, game.current_player, *hex).is_some_and(|ladder| {
+                    ladder.escape.is_none() && (self == LadderWarnings::Strict || ladder.support.is_none())
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lenient_warnings_trust_third_row_stones() {
+        // Blue's a2 cuts b1 and b2 off from one of their edge cells each. Red's c3 is on the third
+        // row beside the b2 ladder's path, but nothing helps b1.
+        let mut game = Game::with_size(5).with_pie_rule(false);
+        for mv in ["c3", "a2"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let (b1, b2) = (Hex { q: 1, r: 0 }, Hex { q: 1, r: 1 });
+        assert!(LadderWarnings::Off.losing_ladders(&game).is_empty());
+        let mut strict = LadderWarnings::Strict.losing_ladders(&game);
+        strict.sort_by_key(|hex| hex.r);
+        assert_eq!(strict, vec![b1, b2]);
+        assert_eq!(LadderWarnings::Lenient.losing_ladders(&game), vec![b1]);
+    }
+}
@@ -0,0 +1,33 @@
+pub mod ai;
+pub mod alphabeta;
+pub mod board;
+pub mod bookmarks;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod commands;
+pub mod dark;
+pub mod env;
+pub mod eval;
+pub mod fixed_board;
+pub mod game;
+pub mod history;
+pub mod inferior;
+pub mod insights;
+pub mod journal;
+pub mod ladder;
+pub mod mcts;
+pub mod notation;
+pub mod ownership;
+pub mod presets;
+pub mod prover;
+#[cfg(feature = "pyhex")]
+pub mod python;
+pub mod record;
+pub mod saves;
+pub mod sgf;
+pub mod shape;
+pub mod simulation;
+pub mod tactics;
+pub mod templates;
+pub mod tournament;
+pub mod transposition;
@@ -1,75 +1,1664 @@
+use coast_to_coast::history::History;
+use coast_to_coast::notation::Move;
+use coast_to_coast::record::{self, GameMetadata, GameRecord};
+use coast_to_coast::{board, eval, game, ownership, saves, sgf, tactics, templates};
 use eframe::{self, egui};
-use egui_extras;
 
 const DEFAULT_WINDOW_WIDTH: f32 = 800.0;
 const DEFAULT_WINDOW_HEIGHT: f32 = 600.0;
+// Below this the toolbar and move list no longer leave the board room to be played on.
+const MIN_WINDOW_WIDTH: f32 = 360.0;
+const MIN_WINDOW_HEIGHT: f32 = 360.0;
+// Narrower than this, the move list folds into a menu to give the board the width.
+const COMPACT_WINDOW_WIDTH: f32 = 640.0;
+const APP_NAME: &str = "Hex Game";
+// Groups the windows in the taskbar and matches the desktop entry on Wayland.
+const APP_ID: &str = "coast-to-coast";
+// Enough for a steady ownership overlay on 11x11 without stalling the frame it is computed in.
+const OWNERSHIP_PLAYOUTS: u32 = 400;
 
-mod board;
-mod game;
+mod ai_opponent;
+mod api_server;
+mod autosave;
+mod clock;
+mod close_prompt;
+mod blind;
+mod board_bridge;
+mod bookmark_panel;
+mod bundle;
+mod cloud_sync;
+mod console;
+mod crash;
+mod dark_mode;
+mod detached;
+mod diagnostics;
+#[cfg(feature = "discord")]
+mod discord;
+mod drills;
+mod events;
+mod frame_stats;
+mod game_info;
+mod game_notes;
+#[cfg(feature = "gpu")]
+mod gpu_board;
+mod i18n;
+mod idle;
+mod keymap;
+mod ladders;
+mod match_presets;
+mod menu_bar;
+mod motion;
+mod move_list;
+mod new_game;
+mod offscreen;
+mod presentation;
 mod renderer;
+mod saved_games;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod search_inspector;
+mod settings;
+mod simul;
+mod stats;
+mod stones;
+mod study;
+mod table_panel;
+mod takeback;
+mod timeline;
+mod teaching;
+mod themes;
+mod thumbnail;
+#[cfg(test)]
+mod ui_test;
+mod updater;
+#[cfg(feature = "voice")]
+mod voice;
+mod watch;
+mod webhook;
+
+use i18n::{player_name, tr, tr_args};
 
 fn main() -> Result<(), eframe::Error> {
+    let data_dir = eframe::storage_dir(APP_NAME);
+    let (log_buffer, _log_guard) = diagnostics::init(data_dir.as_deref());
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "starting");
+    // `--tactics` scores the engines on the built-in tactics positions instead of opening the window.
+    if let Some(opponents) = tactics::opponents_from_args(std::env::args()) {
+        let opponents = opponents.unwrap_or_else(|name| {
+            eprintln!("unknown engine {name:?} for --tactics");
+            std::process::exit(2);
+        });
+        let positions = tactics::builtin();
+        for opponent in opponents {
+            println!("{}\n{}", opponent.key(), tactics::run(&mut *opponent.player(), &positions));
+        }
+        return Ok(());
+    }
+    let crash_context = crash::CrashContext::default();
+    let pending_crash_report = data_dir.as_deref().and_then(crash::take_pending_report);
+    crash::install(data_dir.clone(), log_buffer.clone(), crash_context.clone());
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_app_id(APP_ID)
+        .with_inner_size([DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT])
+        .with_min_inner_size([MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT]);
+    match eframe::icon_data::from_png_bytes(include_bytes!("../assets/icon.png")) {
+        Ok(icon) => viewport = viewport.with_icon(icon),
+        Err(e) => tracing::warn!(error = %e, "app icon unreadable"),
+    }
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT]),
+        viewport,
+        // The board's cells are drawn through wgpu paint callbacks.
+        #[cfg(feature = "gpu")]
+        renderer: eframe::Renderer::Wgpu,
         ..Default::default()
     };
+    let api_address = api_server::address_from_args(std::env::args());
     eframe::run_native(
-        "Hex Game",
+        APP_NAME,
         options,
-        Box::new(|cc| Ok(Box::new(MyApp::new(cc)))),
+        Box::new(|cc| {
+            let mut app = MyApp::new(cc, data_dir, log_buffer, crash_context);
+            app.pending_crash_report = pending_crash_report;
+            #[cfg(feature = "gpu")]
+            {
+                app.board_renderer.gpu = gpu_board::GpuCells::install(cc);
+            }
+            app.open_startup_browser();
+            if let Some(address) = api_address {
+                app.start_api(&cc.egui_ctx, &address);
+            }
+            Ok(Box::new(app))
+        }),
     )
 }
 
 struct MyApp {
     game: game::Game,
     board_renderer: renderer::BoardRenderer,
+    settings: settings::Settings,
+    show_settings: bool,
+    last_action: Option<String>,
+    debug_console: diagnostics::DebugConsole,
+    crash_context: crash::CrashContext,
+    pending_crash_report: Option<std::path::PathBuf>,
+    data_dir: Option<std::path::PathBuf>,
+    import_dialog: move_list::ImportDialog,
+    import_data: bundle::ImportDataDialog,
+    notice: Option<String>,
+    metadata: GameMetadata,
+    // The file the current game was loaded from or last saved to.
+    save_path: Option<std::path::PathBuf>,
+    // The moves and game info as last saved or loaded, to tell whether closing would lose any.
+    saved: (Vec<Move>, GameMetadata),
+    // The annotations the game was loaded with, per move, kept for when it is saved again.
+    annotations: Vec<Option<String>>,
+    close_prompt: close_prompt::ClosePrompt,
+    game_info: game_info::GameInfoDialog,
+    games_browser: saved_games::GamesBrowser,
+    bookmarks: bookmark_panel::BookmarkPanel,
+    notes: game_notes::GameNotes,
+    updater: updater::UpdateChecker,
+    cloud_sync: cloud_sync::CloudSync,
+    webhook: webhook::Webhook,
+    journal: autosave::AutosaveJournal,
+    themes: themes::ThemeLibrary,
+    match_presets: match_presets::MatchPresets,
+    // The stone style, theme pack and theme library generation last applied.
+    theme_applied: (stones::StoneStyle, String, u64),
+    // Serving the HTTP API, when started with --serve-api.
+    api: Option<api_server::ApiServer>,
+    board_bridge: board_bridge::BoardBridge,
+    console: console::CommandLine,
+    #[cfg(feature = "voice")]
+    voice: voice::VoiceInput,
+    events: events::EventBus,
+    clock: clock::GameClock,
+    presentation: bool,
+    // The fullscreen and borderless state last sent to the window, if any.
+    window_mode: Option<(bool, bool)>,
+    // The title last sent to the window.
+    window_title: String,
+    // Paste game asked the window for the clipboard, which comes with the next frame's input.
+    pasting_game: bool,
+    // Moves taken back, the next to replay last, until another move is made.
+    redo: Vec<Move>,
+    timeline: timeline::Timeline,
+    show_timeline: bool,
+    stats: stats::StatsWindow,
+    new_game_dialog: new_game::NewGameDialog,
+    // Who plays the game on the board, and the engines in its computer seats.
+    seats: new_game::Seats,
+    seat_engines: new_game::SeatEngines,
+    // Pauses the game on the board while the window is out of focus.
+    idle: idle::IdlePause,
+    show_ownership: bool,
+    // The position the ownership overlay was estimated for.
+    ownership_position: Option<(u64, board::CellState)>,
+    show_two_distance: bool,
+    // The position the two-distance gains were computed for.
+    two_distance_position: Option<(u64, board::CellState)>,
+    // The position and strictness the ladder warnings were found for.
+    ladder_position: Option<(u64, board::CellState, ladders::LadderWarnings)>,
+    // The built-in edge templates and the user's own.
+    templates: Vec<templates::Template>,
+    show_templates: bool,
+    // The position the edge templates were matched against.
+    templates_position: Option<u64>,
+    // An earlier position picked on the timeline, shown instead of the game until dismissed.
+    viewing: Option<game::Game>,
+    help: menu_bar::HelpWindows,
+    blind: blind::BlindTracker,
+    reveal_stones: bool,
+    teaching: teaching::TeachingCandidates,
+    takeback: takeback::TakebackDialog,
+    dark: Option<dark_mode::DarkSession>,
+    simul: Option<simul::SimulSession>,
+    watch: Option<watch::WatchSession>,
+    study: Option<study::StudySession>,
+    engine_shared: ai_opponent::EngineShared,
+    table_panel: table_panel::TablePanel,
+    search_inspector: search_inspector::SearchInspector,
+    #[cfg(feature = "discord")]
+    presence: discord::Presence,
+    #[cfg(feature = "scripting")]
+    scripts: scripting::Scripts,
 }
 
-
-
 impl MyApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        data_dir: Option<std::path::PathBuf>,
+        log_buffer: diagnostics::LogBuffer,
+        crash_context: crash::CrashContext,
+    ) -> Self {
         egui_extras::install_image_loaders(&cc.egui_ctx);
+        i18n::install_cjk_font(&cc.egui_ctx);
+        let settings: settings::Settings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        let updater = if settings.check_for_updates {
+            updater::UpdateChecker::start(&cc.egui_ctx, &settings.update_url)
+        } else {
+            updater::UpdateChecker::default()
+        };
+        let mut events = events::EventBus::default();
+        events.subscribe(|event, _| tracing::debug!(?event, "game event"));
+        #[cfg(feature = "discord")]
+        let presence = {
+            let presence = discord::Presence::start();
+            let subscriber = presence.clone();
+            events.subscribe(move |event, _| subscriber.handle(event));
+            presence
+        };
+        #[cfg(feature = "scripting")]
+        let scripts = {
+            let scripts = data_dir.as_deref().map(scripting::Scripts::load).unwrap_or_default();
+            let subscriber = scripts.clone();
+            events.subscribe(move |event, record| subscriber.handle(event, record));
+            scripts
+        };
+        let webhook = webhook::Webhook::start();
+        let subscriber = webhook.clone();
+        events.subscribe(move |event, record| subscriber.handle(event, record));
+        let timeline = timeline::Timeline::default();
+        let subscriber = timeline.clone();
+        events.subscribe(move |event, _| subscriber.handle(event));
+        let journal = autosave::AutosaveJournal::open(data_dir.as_deref());
+        let subscriber = journal.clone();
+        events.subscribe(move |event, record| subscriber.handle(event, record));
+        let themes = themes::ThemeLibrary::load(data_dir.as_deref());
+        let match_presets = match_presets::MatchPresets::load(data_dir.as_deref());
+        let mut edge_templates = templates::builtin();
+        edge_templates.extend(data_dir.as_deref().map(templates::load_user).unwrap_or_default());
+        let game = settings.new_game();
+        let (search_inspector, snapshots) = search_inspector::SearchInspector::new();
+        let engine_shared = ai_opponent::EngineShared::new(&settings.ai, snapshots);
+        let mut clock = clock::GameClock::default();
+        clock.switch_to(game.current_player, std::time::Instant::now());
+        let metadata = GameMetadata { date: record::today(), ..Default::default() };
+        events.publish(
+            events::GameEvent::GameStarted { size: game.board.size, moves: 0, opponent: None },
+            &GameRecord::from_game(&game, &metadata),
+        );
         Self {
-            game: game::Game::new(),
+            game,
             board_renderer: renderer::BoardRenderer::new(&cc.egui_ctx),
+            settings,
+            show_settings: false,
+            last_action: None,
+            debug_console: diagnostics::DebugConsole::new(log_buffer),
+            crash_context,
+            pending_crash_report: None,
+            data_dir,
+            import_dialog: move_list::ImportDialog::default(),
+            import_data: bundle::ImportDataDialog::default(),
+            notice: None,
+            metadata,
+            save_path: None,
+            saved: (Vec::new(), GameMetadata::default()),
+            annotations: Vec::new(),
+            close_prompt: close_prompt::ClosePrompt::default(),
+            game_info: game_info::GameInfoDialog::default(),
+            games_browser: saved_games::GamesBrowser::default(),
+            bookmarks: bookmark_panel::BookmarkPanel::default(),
+            notes: game_notes::GameNotes::default(),
+            updater,
+            cloud_sync: cloud_sync::CloudSync::default(),
+            webhook,
+            journal,
+            themes,
+            match_presets,
+            theme_applied: Default::default(),
+            api: None,
+            board_bridge: board_bridge::BoardBridge::default(),
+            console: console::CommandLine::default(),
+            #[cfg(feature = "voice")]
+            voice: voice::VoiceInput::default(),
+            events,
+            clock,
+            presentation: false,
+            window_mode: None,
+            window_title: String::new(),
+            pasting_game: false,
+            redo: Vec::new(),
+            timeline,
+            show_timeline: false,
+            stats: stats::StatsWindow::default(),
+            new_game_dialog: new_game::NewGameDialog::default(),
+            seats: new_game::Seats::default(),
+            seat_engines: new_game::SeatEngines::default(),
+            idle: idle::IdlePause::default(),
+            show_ownership: false,
+            ownership_position: None,
+            show_two_distance: false,
+            two_distance_position: None,
+            ladder_position: None,
+            templates: edge_templates,
+            show_templates: false,
+            templates_position: None,
+            viewing: None,
+            help: menu_bar::HelpWindows::default(),
+            blind: blind::BlindTracker::default(),
+            reveal_stones: false,
+            teaching: teaching::TeachingCandidates::default(),
+            takeback: takeback::TakebackDialog::default(),
+            dark: None,
+            simul: None,
+            watch: None,
+            study: None,
+            engine_shared,
+            table_panel: table_panel::TablePanel::default(),
+            search_inspector,
+            #[cfg(feature = "discord")]
+            presence,
+            #[cfg(feature = "scripting")]
+            scripts,
+        }
+    }
+
+    // Opens the recent games screen at startup when there is anything to continue or load.
+    fn open_startup_browser(&mut self) {
+        self.games_browser.open(self.data_dir.as_deref());
+        self.games_browser.open = self.games_browser.has_games();
+    }
+
+    fn export_move_list(&mut self, ctx: &egui::Context, sgf: bool) {
+        let language = self.settings.language;
+        let (text, extension, saved_key, copied_key) = if sgf {
+            (sgf::to_sgf(&self.record()), "sgf", "sgf.saved", "sgf.copied")
+        } else {
+            (self.record().to_text(), "txt", "export.saved", "export.copied")
+        };
+        ctx.copy_text(text.clone());
+        self.notice = Some(match self.data_dir.as_deref().map(|dir| move_list::save_export(dir, &text, extension)) {
+            Some(Ok(path)) => tr_args(language, saved_key, &[("path", &path.display().to_string())]),
+            Some(Err(e)) => {
+                tracing::warn!(error = %e, "failed to save export");
+                tr(language, copied_key).to_string()
+            }
+            None => tr(language, copied_key).to_string(),
+        });
+    }
+
+    // The board as a PNG, or the game as an animated GIF, in the exports folder.
+    fn export_image(&mut self, animated: bool) {
+        let language = self.settings.language;
+        let Some(data_dir) = self.data_dir.as_deref() else {
+            return;
+        };
+        let saved = if animated {
+            offscreen::gif(&self.record(), offscreen::GIF_WIDTH).and_then(|gif| move_list::save_export(data_dir, gif, "gif"))
+        } else {
+            // With the ownership tints and teaching suggestions on screen, if any.
+            let scene = offscreen::Scene::of_game(&self.game)
+                .with_ownership(&self.board_renderer.ownership)
+                .with_candidates(&self.board_renderer.candidates);
+            offscreen::png(&scene, offscreen::PNG_WIDTH).and_then(|png| move_list::save_export(data_dir, png, "png"))
+        };
+        self.notice = Some(match saved {
+            Ok(path) => {
+                tracing::info!(path = %path.display(), "image exported");
+                tr_args(language, "export.image_saved", &[("path", &path.display().to_string())])
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to export image");
+                tr_args(language, "export.image_error", &[("error", &e.to_string())])
+            }
+        });
+    }
+
+    fn has_unsaved_changes(&self) -> bool {
+        let (moves, metadata) = &self.saved;
+        self.game.history != *moves || self.metadata != *metadata
+    }
+
+    fn record(&self) -> GameRecord {
+        GameRecord::from_game(&self.game, &self.metadata)
+            .with_move_times(self.clock.move_times())
+            .with_annotations(&self.annotations)
+    }
+
+    // Saves over the file the game came from, or to a new file the first time.
+    fn save_game(&mut self) {
+        let language = self.settings.language;
+        let Some(data_dir) = self.data_dir.as_deref() else {
+            self.notice = Some(tr_args(language, "save.error", &[("error", "no data directory")]));
+            return;
+        };
+        let record = self.record();
+        let saved = match &self.save_path {
+            Some(path) => saves::update(path, &record).map(|_| path.clone()),
+            None => saves::save(data_dir, &record),
+        };
+        self.notice = Some(match saved {
+            Ok(path) => {
+                tracing::info!(path = %path.display(), "game saved");
+                let message = tr_args(language, "save.saved", &[("path", &path.display().to_string())]);
+                self.save_path = Some(path);
+                self.saved = (self.game.history.clone(), self.metadata.clone());
+                message
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to save game");
+                tr_args(language, "save.error", &[("error", &e.to_string())])
+            }
+        });
+    }
+
+    fn export_data(&mut self) {
+        let language = self.settings.language;
+        let Some(data_dir) = self.data_dir.as_deref() else {
+            return;
+        };
+        self.notice = Some(match bundle::export(data_dir, &self.settings) {
+            Ok(path) => {
+                tracing::info!(path = %path.display(), "data exported");
+                tr_args(language, "bundle.exported", &[("path", &path.display().to_string())])
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to export data");
+                tr_args(language, "bundle.export_error", &[("error", &e.to_string())])
+            }
+        });
+    }
+
+    // Takes on imported settings, keeping where this device syncs to, and picks up the
+    // imported games and templates.
+    fn finish_import(&mut self, language: i18n::Language, data_dir: &std::path::Path, report: bundle::ImportReport) {
+        if let Some(mut settings) = report.settings {
+            settings.sync = self.settings.sync.clone();
+            self.settings = *settings;
+        }
+        self.templates = templates::builtin();
+        self.templates.extend(templates::load_user(data_dir));
+        self.templates_position = None;
+        if self.games_browser.open {
+            self.games_browser.open(Some(data_dir));
+        }
+        self.notice = Some(tr_args(language, "bundle.imported", &[("count", &report.files.to_string())]));
+    }
+
+    // Reports a finished sync and takes on settings that came from the server, keeping where
+    // this device syncs to.
+    fn finish_sync(&mut self, language: i18n::Language) {
+        let Some(result) = self.cloud_sync.poll() else {
+            return;
+        };
+        self.notice = Some(match result {
+            Ok(report) => {
+                tracing::info!(pushed = report.pushed, pulled = report.pulled, "synced");
+                if let Some(mut settings) = report.settings {
+                    settings.sync = self.settings.sync.clone();
+                    self.settings = *settings;
+                }
+                if self.games_browser.open {
+                    self.games_browser.open(self.data_dir.as_deref());
+                }
+                tr_args(
+                    language,
+                    "sync.done",
+                    &[("pushed", &report.pushed.to_string()), ("pulled", &report.pulled.to_string())],
+                )
+            }
+            Err(e) => tr_args(language, "sync.error", &[("error", &e)]),
+        });
+    }
+
+    // Loaded games are played on by people at the board, untimed.
+    fn load_game(&mut self, game: game::Game, record: GameRecord, save_path: Option<std::path::PathBuf>) {
+        self.start_game(game, record, save_path, new_game::Seats::default());
+    }
+
+    fn start_game(
+        &mut self,
+        game: game::Game,
+        record: GameRecord,
+        save_path: Option<std::path::PathBuf>,
+        seats: new_game::Seats,
+    ) {
+        self.seat_engines.cancel();
+        self.seat_engines = new_game::SeatEngines::new(&seats, &self.settings.ai, &self.engine_shared);
+        let opponent = seats.opponent(self.settings.language);
+        self.seats = seats;
+        self.game = game;
+        self.metadata = record.metadata;
+        self.annotations = record.annotations;
+        self.save_path = save_path;
+        self.saved = (self.game.history.clone(), self.metadata.clone());
+        self.last_action = None;
+        self.notice = None;
+        self.redo.clear();
+        self.viewing = None;
+        let movers = self.game.turn_structure.movers(&self.game.history);
+        self.clock = clock::GameClock::with_move_times(record.move_times, &movers);
+        self.sync_clock();
+        self.blind.reset();
+        self.publish(events::GameEvent::GameStarted {
+            size: self.game.board.size,
+            moves: self.game.history.len(),
+            opponent,
+        });
+    }
+
+    // Replays the move list Paste game fetched from the clipboard as a new game, under the rules
+    // and board size in the settings.
+    fn paste_game(&mut self, ctx: &egui::Context, language: i18n::Language) {
+        let text = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+        match record::replay_move_list(self.settings.new_game(), text.as_deref().unwrap_or_default()) {
+            Ok(game) => {
+                let moves = game.history.len().to_string();
+                tracing::info!(moves = game.history.len(), "move list pasted");
+                let metadata = GameMetadata { date: record::today(), ..Default::default() };
+                let record = GameRecord::from_game(&game, &metadata);
+                self.load_game(game, record, None);
+                self.notice = Some(tr_args(language, "menu.game_pasted", &[("moves", &moves)]));
+            }
+            Err(e) => self.notice = Some(tr_args(language, "import.error", &[("error", &e.to_string())])),
+        }
+    }
+
+    // Starts an empty game at the board size and with the seats chosen in the settings.
+    fn new_game(&mut self) {
+        let game = self.settings.new_game();
+        let seats = self.settings.seats.clone();
+        let metadata = GameMetadata {
+            red: seats.red.name.trim().to_string(),
+            blue: seats.blue.name.trim().to_string(),
+            date: record::today(),
+            time_control: self.settings.time_control.trim().to_string(),
+            ..Default::default()
+        };
+        let record = GameRecord::from_game(&game, &metadata);
+        self.start_game(game, record, None, seats);
+    }
+
+    // Pauses a game in play once the window has been out of focus for the configured time. There
+    // is no rated or network play for this to be unfair to.
+    fn update_idle(&mut self, ctx: &egui::Context) {
+        let in_play = self.menu_enabled(menu_bar::MenuAction::NewGame)
+            && self.viewing.is_none()
+            && matches!(self.game.state, game::GameState::InProgress | game::GameState::WaitingForPieRuleChoice);
+        let after = (in_play && self.settings.idle_pause_secs > 0.0)
+            .then(|| std::time::Duration::from_secs_f32(self.settings.idle_pause_secs));
+        let focused = ctx.input(|i| i.viewport().focused.unwrap_or(true));
+        let now = std::time::Instant::now();
+        match self.idle.update(focused, now, after) {
+            Some(idle::IdleChange::Paused) => self.clock.pause(now),
+            Some(idle::IdleChange::Resumed) => {
+                self.clock.resume(now);
+                self.sync_clock();
+            }
+            None => {}
+        }
+        if let Some(next) = self.idle.next_change(now, after) {
+            ctx.request_repaint_after(next);
+        }
+    }
+
+    // Lets the engine in a computer seat move once its decision is in, and flags a player whose
+    // thinking time has run out.
+    fn update_seats(&mut self, ctx: &egui::Context) {
+        if self.idle.is_paused() {
+            return;
+        }
+        if !self.menu_enabled(menu_bar::MenuAction::NewGame) || self.viewing.is_some() {
+            self.seat_engines.cancel();
+            return;
+        }
+        let now = std::time::Instant::now();
+        let player = self.game.current_player;
+        if let Some(allowance) = self.seats.allowance(player).filter(|_| self.clock.running() == Some(player)) {
+            match allowance.checked_sub(self.clock.used(player, now)) {
+                Some(left) if !left.is_zero() => {
+                    ctx.request_repaint_after(left.min(self.clock.next_tick(now).unwrap_or(left)))
+                }
+                _ => {
+                    self.resign();
+                    let language = self.settings.language;
+                    self.notice = Some(tr_args(language, "new_game.flagged", &[("player", player_name(language, player))]));
+                    return;
+                }
+            }
+        }
+        match self.seat_engines.poll(ctx, &self.game, &self.settings.ai, now) {
+            Some(ai_opponent::Decision::PieRule(swap)) => self.decide_pie_rule(swap),
+            Some(ai_opponent::Decision::Move(Some(hex))) => self.play_move(Move::Place(hex)),
+            Some(ai_opponent::Decision::Move(None)) => tracing::warn!(?player, "engine in a seat found no move"),
+            None => {}
         }
     }
+
+    // Runs the clock of whoever is to move, and stops it once the game is over.
+    fn sync_clock(&mut self) {
+        let now = std::time::Instant::now();
+        match self.game.state {
+            game::GameState::Finished { .. }
+            | game::GameState::Drawn
+            | game::GameState::Resigned { .. }
+            | game::GameState::Adjourned { .. } => self.clock.stop(now),
+            _ if self.idle.is_paused() => self.clock.pause(now),
+            _ if self.clock.running() != Some(self.game.current_player) => {
+                self.clock.switch_to(self.game.current_player, now)
+            }
+            _ => {}
+        }
+    }
+
+    fn set_presentation(&mut self, ctx: &egui::Context, on: bool) {
+        self.presentation = on;
+        self.board_renderer.fill = on;
+        self.sync_window_mode(ctx);
+    }
+
+    // Applies the configured window mode, and presentation mode's fullscreen, when they change.
+    fn sync_window_mode(&mut self, ctx: &egui::Context) {
+        let mode = (self.settings.fullscreen || self.presentation, self.settings.borderless);
+        if self.window_mode == Some(mode) {
+            return;
+        }
+        let (fullscreen, borderless) = mode;
+        tracing::debug!(fullscreen, borderless, "window mode changed");
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(!borderless));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(fullscreen));
+        // The new window size arrives with the next frame; lay the board out again for it.
+        ctx.request_repaint();
+        self.window_mode = Some(mode);
+    }
+
+    // Works out which stones blind mode masks this frame and schedules the next change.
+    fn update_blind_mode(&mut self, ctx: &egui::Context) {
+        self.board_renderer.hidden.clear();
+        self.board_renderer.blind_style = self.settings.blind_style;
+        let other_board = self.dark.is_some() || self.simul.is_some() || self.watch.is_some() || self.study.is_some();
+        if !self.settings.blind_mode || self.reveal_stones || other_board {
+            return;
+        }
+        let delay = std::time::Duration::from_secs_f32(self.settings.blind_delay_secs.max(0.0));
+        let (hidden, next_change) = self.blind.hidden(&self.game.board, delay, std::time::Instant::now());
+        self.board_renderer.hidden = hidden;
+        if let Some(next_change) = next_change {
+            ctx.request_repaint_after(next_change);
+        }
+    }
+
+    // Rings the engine's candidates when the player to move is under the teaching constraint.
+    fn update_teaching(&mut self) {
+        self.board_renderer.candidates.clear();
+        let other_board = self.dark.is_some() || self.simul.is_some() || self.watch.is_some() || self.study.is_some();
+        if other_board || self.game.state != game::GameState::InProgress {
+            return;
+        }
+        if self.settings.teaches(self.game.current_player) {
+            self.board_renderer.candidates = self.teaching.for_game(&self.game).to_vec();
+        }
+    }
+
+    // Tints the empty cells by who tends to win through them, estimated once per position.
+    fn update_ownership(&mut self) {
+        let shown = self.show_ownership
+            && self.menu_enabled(menu_bar::MenuAction::Ownership)
+            && self.viewing.is_none()
+            && self.game.state == game::GameState::InProgress;
+        if !shown {
+            self.ownership_position = None;
+            self.board_renderer.ownership.clear();
+            return;
+        }
+        let position = (self.game.board.position_hash(), self.game.current_player);
+        if self.ownership_position != Some(position) {
+            self.ownership_position = Some(position);
+            let map = ownership::estimate(&self.game, OWNERSHIP_PLAYOUTS, &mut rand::thread_rng());
+            self.board_renderer.ownership = map.cells().collect();
+        }
+    }
+
+    // Writes on each empty cell how much it would shorten the player to move's two-distance.
+    fn update_two_distance(&mut self) {
+        let shown = self.show_two_distance
+            && self.menu_enabled(menu_bar::MenuAction::TwoDistance)
+            && self.viewing.is_none()
+            && self.game.state == game::GameState::InProgress;
+        if !shown {
+            self.two_distance_position = None;
+            self.board_renderer.distance_gains.clear();
+            return;
+        }
+        let position = (self.game.board.position_hash(), self.game.current_player);
+        if self.two_distance_position != Some(position) {
+            self.two_distance_position = Some(position);
+            self.board_renderer.distance_gains = eval::two_distance_gains(&self.game.board, self.game.current_player);
+        }
+    }
+
+    // Finds the moves that would start a losing ladder, once per position.
+    fn update_ladder_warnings(&mut self) {
+        let warnings = self.settings.ladder_warnings;
+        let shown = warnings != ladders::LadderWarnings::Off
+            && self.dark.is_none()
+            && self.simul.is_none()
+            && self.watch.is_none()
+            && self.study.is_none()
+            && !self.settings.blind_mode
+            && self.viewing.is_none()
+            && self.game.state == game::GameState::InProgress;
+        if !shown {
+            self.ladder_position = None;
+            self.board_renderer.ladder_warnings.clear();
+            return;
+        }
+        let position = (self.game.board.position_hash(), self.game.current_player, warnings);
+        if self.ladder_position != Some(position) {
+            self.ladder_position = Some(position);
+            self.board_renderer.ladder_warnings = warnings.losing_ladders(&self.game);
+        }
+    }
+
+    // Outlines the edge templates on the board, matched once per position.
+    fn update_templates(&mut self) {
+        let shown = self.show_templates && self.menu_enabled(menu_bar::MenuAction::Templates) && self.viewing.is_none();
+        if !shown {
+            self.templates_position = None;
+            self.board_renderer.templates.clear();
+            return;
+        }
+        let position = self.game.board.position_hash();
+        if self.templates_position != Some(position) {
+            self.templates_position = Some(position);
+            self.board_renderer.templates = templates::find(&self.game.board, &self.templates);
+        }
+    }
+
+    fn publish(&mut self, event: events::GameEvent) {
+        let record = self.record();
+        self.events.publish(event, &record);
+    }
+
+    fn publish_move(&mut self, player: board::CellState, mv: Move) {
+        self.redo.clear();
+        self.viewing = None;
+        self.clock.record_move(std::time::Instant::now());
+        self.sync_clock();
+        let move_number = self.game.history.len();
+        self.publish(events::GameEvent::MovePlayed { player, mv, move_number });
+        if let game::GameState::Finished { winner } = self.game.state {
+            self.publish(events::GameEvent::GameFinished { winner });
+        }
+    }
+
+    fn show_crash_report_dialog(&mut self, ctx: &egui::Context) {
+        let Some(bundle) = &self.pending_crash_report else {
+            return;
+        };
+        let language = self.settings.language;
+        let path = bundle.display().to_string();
+        let mut dismissed = false;
+        egui::Window::new(tr(language, "crash.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr(language, "crash.message"));
+                ui.monospace(&path);
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "crash.copy_path")).clicked() {
+                        ui.ctx().copy_text(path.clone());
+                    }
+                    if ui.button(tr(language, "crash.dismiss")).clicked() {
+                        dismissed = true;
+                    }
+                });
+            });
+        if dismissed {
+            self.pending_crash_report = None;
+        }
+    }
+
+    fn diagnostics_context(&self) -> String {
+        format!(
+            "settings: {:?}\nstate: {:?}, turn: {}, to move: {:?}, board size: {}",
+            self.settings, self.game.state, self.game.turn_count, self.game.current_player, self.game.board.size
+        )
+    }
+
+    fn play(&mut self, hex: board::Hex) {
+        let player = self.game.current_player;
+        #[cfg(feature = "scripting")]
+        if self.game.board.get_cell(&hex) == Some(&board::CellState::Empty) && !self.scripts.allow_move(hex, player) {
+            self.notice = Some(tr(self.settings.language, "script.refused").to_string());
+            return;
+        }
+        self.game.handle_click(hex);
+        if self.game.board.get_cell(&hex) == Some(&player) {
+            self.blind.placed(hex, std::time::Instant::now());
+            let language = self.settings.language;
+            self.last_action = Some(tr_args(
+                language,
+                "announce.move",
+                &[("player", player_name(language, player)), ("cell", &hex.notation())],
+            ));
+            self.publish_move(player, Move::Place(hex));
+        }
+    }
+
+    fn pass(&mut self) {
+        let player = self.game.current_player;
+        if self.game.pass().is_ok() {
+            let language = self.settings.language;
+            self.last_action = Some(tr_args(language, "announce.pass", &[("player", player_name(language, player))]));
+            self.publish_move(player, Move::Pass);
+        }
+    }
+
+    // Rolls the game and its clock back together, so a declined or failed takeback changes neither.
+    fn take_back(&mut self, moves: usize) {
+        let mut game = self.game.clone();
+        if let Err(e) = game.take_back(moves) {
+            tracing::warn!(error = e, moves, "takeback failed");
+            return;
+        }
+        let movers = self.game.turn_structure.movers(&self.game.history);
+        self.clock.take_back(&movers, moves, std::time::Instant::now());
+        self.redo.extend(self.game.history[game.history.len()..].iter().rev());
+        // What was said about a move taken back does not carry over to the one played instead.
+        self.annotations.truncate(game.history.len());
+        self.game = game;
+        self.viewing = None;
+        self.sync_clock();
+        self.blind.reset();
+        let language = self.settings.language;
+        self.last_action = Some(tr(language, "announce.takeback").to_string());
+        self.publish(events::GameEvent::TakenBack { moves: self.game.history.len() });
+    }
+
+    // Hands the renderer stones in the chosen style and theme pack. A pack that sets a light or
+    // dark interface switches to it when chosen or reloaded; the user can still toggle it after.
+    fn apply_theme(&mut self, ctx: &egui::Context) {
+        let (style, theme, generation) = &self.theme_applied;
+        let pack_changed = (self.settings.theme.as_str(), self.themes.generation) != (theme.as_str(), *generation);
+        if !pack_changed && self.settings.stone_style == *style {
+            return;
+        }
+        let pack = self.themes.get(&self.settings.theme);
+        if pack_changed {
+            if let Some(dark) = pack.and_then(|pack| pack.manifest.dark) {
+                ctx.set_theme(if dark { egui::Theme::Dark } else { egui::Theme::Light });
+            }
+            self.board_renderer.backdrop = pack.map(themes::ThemePack::backdrop).unwrap_or_default();
+        }
+        self.board_renderer.stones = stones::painter(self.settings.stone_style, pack);
+        self.theme_applied = (self.settings.stone_style, self.settings.theme.clone(), self.themes.generation);
+    }
+
+    // Shows the board as it was after the game's first `moves` moves; the latest is the game itself.
+    fn view_moment(&mut self, moves: usize) {
+        self.viewing = match History::from_game(&self.game) {
+            Ok(history) if moves < history.len() => history.position_at(moves),
+            _ => None,
+        };
+    }
+
+    // Replays the last move taken back.
+    fn redo(&mut self) {
+        let mut redo = std::mem::take(&mut self.redo);
+        if let Some(mv) = redo.pop() {
+            self.play_move(mv);
+            self.redo = redo;
+        }
+    }
+
+    // Plays a move as if it were made on the board, declining a pending swap for anything else.
+    fn play_move(&mut self, mv: Move) {
+        match mv {
+            // A move that can't be played leaves a pending pie rule open instead of declining it.
+            Move::Place(hex) if !self.game.is_legal_move(&hex) => {}
+            Move::Pass if !self.game.allow_pass => {}
+            Move::Place(hex) => {
+                if self.game.state == game::GameState::WaitingForPieRuleChoice {
+                    self.decide_pie_rule(false);
+                }
+                self.play(hex);
+            }
+            Move::Swap => self.decide_pie_rule(true),
+            Move::Pass => {
+                if self.game.state == game::GameState::WaitingForPieRuleChoice {
+                    self.decide_pie_rule(false);
+                }
+                self.pass();
+            }
+        }
+    }
+
+    // Plays the moves read from a physical board, checked the way clicks are.
+    fn play_from_board(&mut self, language: i18n::Language) {
+        for input in self.board_bridge.poll() {
+            let mv = match input {
+                board_bridge::BridgeInput::Sensor(x, y) => {
+                    Move::Place(self.settings.bridge.map(x, y, self.game.board.size))
+                }
+                board_bridge::BridgeInput::Move(mv) => mv,
+                board_bridge::BridgeInput::Unreadable(line) => {
+                    self.notice = Some(tr_args(language, "bridge.unreadable", &[("line", &line)]));
+                    continue;
+                }
+                board_bridge::BridgeInput::Closed(error) => {
+                    self.notice = Some(match error {
+                        Some(error) => tr_args(language, "bridge.failed", &[("error", &error)]),
+                        None => tr(language, "bridge.closed").to_string(),
+                    });
+                    continue;
+                }
+            };
+            if !self.play_if_legal(mv) {
+                self.notice = Some(tr_args(language, "bridge.rejected", &[("move", &mv.to_string())]));
+            }
+        }
+    }
+
+    // Plays a move from outside the board while a game is on screen, leaving `play_move` to
+    // decide whether it is legal, as the API does. False if it wasn't played.
+    fn play_if_legal(&mut self, mv: Move) -> bool {
+        let moves = self.game.history.len();
+        if self.menu_enabled(menu_bar::MenuAction::NewGame) {
+            self.play_move(mv);
+        }
+        self.game.history.len() > moves
+    }
+
+    #[cfg(feature = "voice")]
+    fn listen(&mut self, ctx: &egui::Context, language: i18n::Language) {
+        self.voice.set_command(ctx, &self.settings.voice_command);
+        match self.voice.show(ctx, language, self.game.board.size) {
+            Some(voice::VoiceCommand::Move(mv)) if !self.play_if_legal(mv) => {
+                self.notice = Some(tr_args(language, "voice.rejected", &[("move", &mv.to_string())]));
+            }
+            Some(voice::VoiceCommand::Action(action)) if self.menu_enabled(action) => self.perform(ctx, action),
+            Some(voice::VoiceCommand::Action(action)) => {
+                self.notice = Some(tr_args(language, "voice.unavailable", &[("action", tr(language, action.label()))]));
+            }
+            Some(voice::VoiceCommand::Move(_)) | None => {}
+        }
+    }
+
+    // Runs a line from the command line or the API and says how it went.
+    fn run_command(&mut self, ctx: &egui::Context, line: &str) -> String {
+        use coast_to_coast::commands::Command;
+        use menu_bar::MenuAction;
+        let language = self.settings.language;
+        let command = match coast_to_coast::commands::parse(line) {
+            Ok(command) => command,
+            Err(e) => return tr_args(language, "console.error", &[("error", &e.to_string())]),
+        };
+        let unavailable = tr(language, "console.unavailable").to_string();
+        match command {
+            Command::Play(mv) if self.play_if_legal(mv) => tr(language, "console.done").to_string(),
+            Command::Play(mv) => tr_args(language, "console.illegal", &[("move", &mv.to_string())]),
+            Command::Undo(moves) if self.menu_enabled(MenuAction::Undo) && moves <= self.game.history.len() => {
+                self.take_back(moves);
+                tr(language, "console.done").to_string()
+            }
+            Command::Redo => self.run_action(ctx, MenuAction::Redo),
+            Command::Resign => self.run_action(ctx, MenuAction::Resign),
+            Command::NewGame if self.menu_enabled(MenuAction::NewGame) => {
+                self.new_game();
+                tr(language, "console.done").to_string()
+            }
+            Command::Size(size) if self.menu_enabled(MenuAction::NewGame) => {
+                self.settings.board_size = size;
+                self.new_game();
+                tr(language, "console.done").to_string()
+            }
+            Command::EngineLevel(_) => tr(language, "console.no_engine").to_string(),
+            Command::Save(name) => {
+                let mut path = std::path::PathBuf::from(&name);
+                if path.is_relative() {
+                    match self.data_dir.as_deref() {
+                        Some(data_dir) => path = data_dir.join(move_list::EXPORT_DIR).join(path),
+                        None => return tr_args(language, "save.error", &[("error", "no data directory")]),
+                    }
+                }
+                let sgf = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("sgf"));
+                let text = if sgf { sgf::to_sgf(&self.record()) } else { self.record().to_text() };
+                let written = path.parent().map_or(Ok(()), std::fs::create_dir_all);
+                match written.and_then(|()| std::fs::write(&path, text)) {
+                    Ok(()) => tr_args(language, "save.saved", &[("path", &path.display().to_string())]),
+                    Err(e) => tr_args(language, "save.error", &[("error", &e.to_string())]),
+                }
+            }
+            Command::Help => coast_to_coast::commands::USAGE.join("\n"),
+            Command::Undo(_) | Command::NewGame | Command::Size(_) => unavailable,
+        }
+    }
+
+    fn run_action(&mut self, ctx: &egui::Context, action: menu_bar::MenuAction) -> String {
+        let language = self.settings.language;
+        if !self.menu_enabled(action) {
+            return tr(language, "console.unavailable").to_string();
+        }
+        self.perform(ctx, action);
+        tr(language, "console.done").to_string()
+    }
+
+    fn start_api(&mut self, ctx: &egui::Context, address: &str) {
+        let language = self.settings.language;
+        match api_server::ApiServer::start(ctx, address) {
+            Ok(api) => {
+                let address = format!("http://{}", api.address);
+                self.notice = Some(tr_args(language, "api.serving", &[("address", &address)]));
+                self.api = Some(api);
+            }
+            Err(e) => {
+                tracing::error!(address, error = %e, "could not serve the API");
+                self.notice = Some(tr_args(language, "api.error", &[("address", address), ("error", &e.to_string())]));
+            }
+        }
+    }
+
+    // Answers API clients. Moves and new games need the game on screen, as they would in the UI.
+    fn answer_api(&mut self, ctx: &egui::Context) {
+        let Some(calls) = self.api.as_ref().map(api_server::ApiServer::poll) else {
+            return;
+        };
+        for call in calls {
+            let in_game = self.menu_enabled(menu_bar::MenuAction::NewGame);
+            let response = match &call.request {
+                api_server::ApiRequest::Position => api_server::Response::json(api_server::position(&self.game)),
+                api_server::ApiRequest::Sgf => api_server::Response::sgf(coast_to_coast::sgf::to_sgf(&self.record())),
+                api_server::ApiRequest::Overlay => api_server::Response::svg(offscreen::Scene::of_game(&self.game).svg()),
+                // Commands check for themselves what they can do now.
+                api_server::ApiRequest::Command(line) => {
+                    let reply = self.run_command(ctx, line);
+                    api_server::Response::json(ureq::serde_json::json!({ "reply": reply }))
+                }
+                _ if !in_game => api_server::Response::error(409, "the game is not on screen"),
+                api_server::ApiRequest::NewGame => {
+                    self.new_game();
+                    api_server::Response::json(api_server::position(&self.game))
+                }
+                api_server::ApiRequest::Move(text) => match Move::parse_for_size(text, self.game.board.size) {
+                    Err(e) => api_server::Response::error(400, &e.to_string()),
+                    Ok(mv) => {
+                        let moves = self.game.history.len();
+                        self.play_move(mv);
+                        if self.game.history.len() == moves {
+                            api_server::Response::error(409, "the move is not legal now")
+                        } else {
+                            api_server::Response::json(api_server::position(&self.game))
+                        }
+                    }
+                },
+            };
+            call.reply(response);
+        }
+    }
+
+    // The player to move resigns; in a game at one screen that is whoever gives up.
+    fn resign(&mut self) {
+        let player = self.game.current_player;
+        if self.game.resign(player).is_ok() {
+            self.sync_clock();
+            if let Some(result) = record::GameResult::of(self.game.state) {
+                self.publish(events::GameEvent::ResultAgreed { result });
+            }
+        }
+    }
+
+    fn agree_draw(&mut self) {
+        if self.game.agree_draw().is_ok() {
+            self.sync_clock();
+            self.publish(events::GameEvent::ResultAgreed { result: record::GameResult::Draw });
+        }
+    }
+
+    // Stops the game and its clocks and saves it, move times included, to be resumed later.
+    fn adjourn(&mut self) {
+        if self.game.adjourn().is_ok() {
+            self.sync_clock();
+            self.save_game();
+            self.publish(events::GameEvent::ResultAgreed { result: record::GameResult::Adjourned });
+        }
+    }
+
+    fn resume(&mut self) {
+        self.game.resume();
+        self.metadata.result = None;
+        self.sync_clock();
+        self.publish(events::GameEvent::GameStarted {
+            size: self.game.board.size,
+            moves: self.game.history.len(),
+            opponent: None,
+        });
+    }
+
+    fn decide_pie_rule(&mut self, apply_pie_rule: bool) {
+        let player = self.game.current_player;
+        let moves = self.game.history.len();
+        self.game.handle_pie_rule_decision(apply_pie_rule);
+        if self.game.history.len() > moves {
+            let language = self.settings.language;
+            self.last_action = Some(tr_args(language, "announce.swap", &[("player", player_name(language, player))]));
+            self.publish_move(player, Move::Swap);
+        }
+    }
+
+    // Follows up a choice made in the saved games browser, wherever it is shown.
+    fn browser_action(&mut self, action: Option<saved_games::BrowserAction>, language: i18n::Language) {
+        match action {
+            Some(saved_games::BrowserAction::Load(saved)) => match saved.record.replay() {
+                Ok(game) => {
+                    let saved = *saved;
+                    tracing::info!(path = %saved.path.display(), "saved game loaded");
+                    // Saving a continued autosave starts a new file rather than overwriting it.
+                    let save_path = (!saved.is_autosave()).then_some(saved.path);
+                    self.load_game(game, saved.record, save_path);
+                }
+                Err(e) => self.notice = Some(tr_args(language, "import.error", &[("error", &e.to_string())])),
+            },
+            Some(saved_games::BrowserAction::Bookmark(bookmark)) => self.open_bookmark(*bookmark, language),
+            Some(saved_games::BrowserAction::NewGame) => self.new_game_dialog.open = true,
+            None => {}
+        }
+    }
+
+    // Shows a bookmarked position: as a look back when it comes earlier in this game, otherwise
+    // as a game of its own.
+    fn open_bookmark(&mut self, bookmark: coast_to_coast::bookmarks::Bookmark, language: i18n::Language) {
+        let record = bookmark.record;
+        let earlier = record.size == self.game.board.size
+            && record.blocked == self.game.board.blocked_cells()
+            && self.game.history.starts_with(&record.moves);
+        if earlier {
+            self.view_moment(record.moves.len());
+            return;
+        }
+        match record.replay() {
+            Ok(game) => {
+                tracing::info!(path = %bookmark.path.display(), "bookmark opened");
+                self.load_game(game, record, None);
+            }
+            Err(e) => self.notice = Some(tr_args(language, "import.error", &[("error", &e.to_string())])),
+        }
+    }
+
+    // Whether a menu action applies now. Game actions need the game itself on screen.
+    fn menu_enabled(&self, action: menu_bar::MenuAction) -> bool {
+        use menu_bar::MenuAction::*;
+        let in_game = !self.presentation
+            && self.dark.is_none()
+            && self.simul.is_none()
+            && self.watch.is_none()
+            && self.study.is_none()
+            && !self.games_browser.covers_board();
+        let playing = matches!(self.game.state, game::GameState::InProgress | game::GameState::WaitingForPieRuleChoice);
+        match action {
+            GameInfo | Settings | Quit | HighlightLastMove | RotateBoard | DarkTheme | Presentation | Fullscreen
+            | Rules | About => true,
+            Timeline => in_game,
+            Bookmarks => in_game && self.data_dir.is_some(),
+            Notes => in_game,
+            Stats => self.data_dir.is_some(),
+            Ownership | TwoDistance | Templates => in_game && !self.settings.blind_mode,
+            ExportData | ImportData => self.data_dir.is_some(),
+            // Images would show stones blind mode hides.
+            ExportImage => in_game && self.data_dir.is_some() && !self.settings.blind_mode,
+            ExportGif => in_game && self.data_dir.is_some() && !self.settings.blind_mode && !self.game.history.is_empty(),
+            Sync => self.data_dir.is_some() && !self.settings.sync.url.trim().is_empty() && !self.cloud_sync.running(),
+            NewGame | OpenSaved | Save | Import | Export | ExportSgf | CopyPosition | PasteGame | DarkHex | Simul
+            | Watch => in_game,
+            Study => in_game && self.data_dir.is_some(),
+            BoardBridge => self.board_bridge.connected() || !self.settings.bridge.device.trim().is_empty(),
+            Undo => in_game && !self.game.history.is_empty(),
+            Redo => in_game && playing && !self.redo.is_empty(),
+            RevealStones => in_game && self.settings.blind_mode,
+            Pass => in_game && self.game.state == game::GameState::InProgress && self.game.allow_pass,
+            OfferTakeback => {
+                in_game && !self.takeback.is_pending() && takeback::TakebackDialog::request_for(&self.game).is_some()
+            }
+            AgreeDraw | Adjourn | Resign => in_game && playing,
+            Resume => in_game && matches!(self.game.state, game::GameState::Adjourned { .. }),
+        }
+    }
+
+    fn menu_checked(&self, ctx: &egui::Context, action: menu_bar::MenuAction) -> bool {
+        use menu_bar::MenuAction::*;
+        match action {
+            HighlightLastMove => self.settings.highlight_last_move,
+            RotateBoard => self.settings.rotate_board,
+            Timeline => self.show_timeline,
+            Bookmarks => self.bookmarks.open,
+            Notes => self.notes.open,
+            Stats => self.stats.open,
+            Ownership => self.show_ownership,
+            TwoDistance => self.show_two_distance,
+            Templates => self.show_templates,
+            RevealStones => self.reveal_stones,
+            DarkTheme => ctx.style().visuals.dark_mode,
+            Presentation => self.presentation,
+            Fullscreen => self.settings.fullscreen,
+            BoardBridge => self.board_bridge.connected(),
+            _ => false,
+        }
+    }
+
+    fn perform(&mut self, ctx: &egui::Context, action: menu_bar::MenuAction) {
+        use menu_bar::MenuAction::*;
+        tracing::debug!(?action, "menu action");
+        let language = self.settings.language;
+        match action {
+            NewGame => self.new_game_dialog.open = true,
+            OpenSaved => self.games_browser.open(self.data_dir.as_deref()),
+            Save => self.save_game(),
+            Import => self.import_dialog.open = true,
+            Export => self.export_move_list(ctx, false),
+            ExportSgf => self.export_move_list(ctx, true),
+            ExportImage => self.export_image(false),
+            ExportGif => self.export_image(true),
+            ExportData => self.export_data(),
+            ImportData => self.import_data.open = true,
+            GameInfo => self.game_info.open = !self.game_info.open,
+            Settings => self.show_settings = !self.show_settings,
+            Sync => {
+                if let Some(data_dir) = self.data_dir.as_deref() {
+                    self.cloud_sync.start(ctx, data_dir, &self.settings);
+                    self.notice = Some(tr(language, "sync.running").to_string());
+                }
+            }
+            Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            Undo => self.take_back(1),
+            Redo => self.redo(),
+            CopyPosition => {
+                ctx.copy_text(self.game.board.to_string());
+                self.notice = Some(tr(language, "menu.position_copied").to_string());
+            }
+            PasteGame => {
+                self.pasting_game = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::RequestPaste);
+                ctx.request_repaint();
+            }
+            HighlightLastMove => self.settings.highlight_last_move = !self.settings.highlight_last_move,
+            RotateBoard => self.settings.rotate_board = !self.settings.rotate_board,
+            Timeline => self.show_timeline = !self.show_timeline,
+            Bookmarks => self.bookmarks.toggle(self.data_dir.as_deref()),
+            Notes => self.notes.open = !self.notes.open,
+            Stats => self.stats.toggle(ctx, self.data_dir.as_deref(), &self.settings.player_name),
+            Ownership => self.show_ownership = !self.show_ownership,
+            TwoDistance => self.show_two_distance = !self.show_two_distance,
+            Templates => self.show_templates = !self.show_templates,
+            RevealStones => self.reveal_stones = !self.reveal_stones,
+            DarkTheme => {
+                let dark = ctx.style().visuals.dark_mode;
+                ctx.set_theme(if dark { egui::Theme::Light } else { egui::Theme::Dark });
+            }
+            Presentation => self.set_presentation(ctx, !self.presentation),
+            Fullscreen => self.settings.fullscreen = !self.settings.fullscreen,
+            Pass => self.pass(),
+            OfferTakeback => {
+                if let Some(request) = takeback::TakebackDialog::request_for(&self.game) {
+                    self.takeback.receive(request);
+                }
+            }
+            AgreeDraw => self.agree_draw(),
+            Adjourn => self.adjourn(),
+            Resume => self.resume(),
+            Resign => self.resign(),
+            DarkHex => self.dark = Some(dark_mode::DarkSession::new(self.settings.board_size)),
+            Simul => self.simul = Some(simul::SimulSession::new(self.settings.board_size)),
+            Watch => {
+                let (size, ai) = (self.settings.board_size, &self.settings.ai);
+                let opponent = self.match_presets.opponent(&self.settings);
+                self.watch = Some(watch::WatchSession::new(size, opponent, ai, &self.engine_shared));
+            }
+            Study => {
+                if let Some(data_dir) = &self.data_dir {
+                    self.study = Some(study::StudySession::new(ctx, data_dir, &self.settings.player_name));
+                }
+            }
+            BoardBridge if self.board_bridge.connected() => {
+                self.board_bridge.disconnect();
+                self.notice = Some(tr(language, "bridge.disconnected").to_string());
+            }
+            BoardBridge => {
+                let device = &self.settings.bridge.device;
+                self.notice = Some(match self.board_bridge.connect(ctx, device) {
+                    Ok(()) => tr_args(language, "bridge.connected", &[("device", device)]),
+                    Err(e) => {
+                        tracing::warn!(device, error = %e, "could not open the physical board");
+                        tr_args(language, "bridge.error", &[("device", device), ("error", &e.to_string())])
+                    }
+                });
+            }
+            Rules => self.help.rules = !self.help.rules,
+            About => self.help.about = !self.help.about,
+        }
+    }
+}
+
+// A label that screen readers announce whenever its text changes.
+fn live_label(ui: &mut egui::Ui, text: impl Into<egui::WidgetText>) {
+    let response = ui.label(text);
+    ui.ctx().accesskit_node_builder(response.id, |node| {
+        node.set_live(accesskit::Live::Polite);
+    });
 }
 
 impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.board_renderer.calculate_offsets(&self.game.board);
-
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Hex Game");
-
-            match self.game.state {
-                game::GameState::Finished { winner } => {
-                    let winner_text = match winner {
-                        board::CellState::Red => "Red",
-                        board::CellState::Blue => "Blue",
-                        _ => "Unknown",
-                    };
-                    ui.label(format!("Winner is: {}", winner_text));
-                }
-                game::GameState::InProgress => {
-                    if let Some(clicked_hex) = self.board_renderer.render_board(ui, &self.game) {
-                        self.game.handle_click(clicked_hex);
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let language = self.settings.language;
+        self.close_prompt.intercept(ctx, self.has_unsaved_changes());
+        match self.close_prompt.show(ctx, language) {
+            Some(close_prompt::CloseChoice::Save) => {
+                self.save_game();
+                // A failed save leaves the prompt up, its error in the notice.
+                if !self.has_unsaved_changes() {
+                    self.close_prompt.close(ctx);
+                }
+            }
+            Some(close_prompt::CloseChoice::Discard) => self.close_prompt.close(ctx),
+            Some(close_prompt::CloseChoice::Cancel) | None => {}
+        }
+        if let Some(action) = menu_bar::pressed(ctx, &self.settings.keymap, |action| self.menu_enabled(action)) {
+            self.perform(ctx, action);
+        } else if self.presentation && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.set_presentation(ctx, false);
+        }
+        if std::mem::take(&mut self.pasting_game) {
+            self.paste_game(ctx, language);
+        }
+        self.answer_api(ctx);
+        self.play_from_board(language);
+        #[cfg(feature = "voice")]
+        self.listen(ctx, language);
+        self.sync_window_mode(ctx);
+        let title = game_info::window_title(language, &self.game);
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+        self.board_renderer.highlight_last_move = self.presentation || self.settings.highlight_last_move;
+        self.board_renderer.rotated = self.settings.rotate_board;
+        self.board_renderer.mirrored = self.settings.left_handed;
+        self.board_renderer.zoom = self.settings.board_zoom;
+        motion::set_reduced(ctx, self.settings.reduced_motion);
+        self.apply_theme(ctx);
+        self.update_idle(ctx);
+        self.update_seats(ctx);
+        self.update_blind_mode(ctx);
+        self.update_teaching();
+        self.update_ownership();
+        self.update_two_distance();
+        self.update_ladder_warnings();
+        self.update_templates();
+        self.notes.follow(self.save_path.as_deref());
+
+        if !self.presentation {
+            let clicked = egui::TopBottomPanel::top("menu_bar")
+                .show(ctx, |ui| {
+                    let keymap = &self.settings.keymap;
+                    menu_bar::show(ui, language, keymap, |action| self.menu_enabled(action), |action| self.menu_checked(ctx, action))
+                })
+                .inner;
+            if let Some(action) = clicked {
+                self.perform(ctx, action);
+            }
+            self.console.check_open(ctx);
+            if let Some(line) = self.console.show(ctx, language) {
+                let reply = self.run_command(ctx, &line);
+                self.console.reply(&line, &reply);
+            }
+        }
+
+        if self.presentation {
+            let view = presentation::PresentationView {
+                game: &self.game,
+                metadata: &self.metadata,
+                clock: &self.clock,
+                last_action: self.last_action.as_deref(),
+                eval_bar: self.settings.presentation_eval_bar,
+            };
+            match presentation::show(ctx, &view, &mut self.board_renderer, language) {
+                Some(presentation::PresentationAction::Play(hex)) => self.play(hex),
+                Some(presentation::PresentationAction::PieRule(swap)) => self.decide_pie_rule(swap),
+                None => {}
+            }
+        } else if let Some(dark) = &mut self.dark {
+            let leave = egui::CentralPanel::default()
+                .show(ctx, |ui| dark.show(ui, &mut self.board_renderer, &self.settings.ai, language))
+                .inner;
+            if leave {
+                self.dark = None;
+            }
+        } else if let Some(simul) = &mut self.simul {
+            let leave = egui::CentralPanel::default()
+                .show(ctx, |ui| simul.show(ui, &mut self.board_renderer, &self.settings.ai, &self.engine_shared, language))
+                .inner;
+            if leave {
+                self.simul = None;
+            }
+        } else if let Some(watch) = &mut self.watch {
+            let leave = egui::CentralPanel::default()
+                .show(ctx, |ui| watch.show(ui, &mut self.board_renderer, &self.settings.ai, &self.engine_shared, language))
+                .inner;
+            if leave {
+                self.watch = None;
+            }
+        } else if let Some(study) = &mut self.study {
+            let leave = egui::CentralPanel::default()
+                .show(ctx, |ui| study.show(ui, &mut self.board_renderer, language))
+                .inner;
+            if leave {
+                self.study = None;
+            }
+        } else if self.games_browser.covers_board() {
+            let action = egui::CentralPanel::default()
+                .show(ctx, |ui| self.games_browser.show(ui, language))
+                .inner;
+            self.browser_action(action, language);
+        } else {
+            let compact = ctx.content_rect().width() < COMPACT_WINDOW_WIDTH;
+            let (moves_side, timeline_side) = match self.settings.left_handed {
+                true => (egui::panel::Side::Left, egui::panel::Side::Right),
+                false => (egui::panel::Side::Right, egui::panel::Side::Left),
+            };
+            if !compact {
+                let viewing = self.viewing.as_ref().map(|viewing| viewing.history.len());
+                let clicked = egui::SidePanel::new(moves_side, "move_list")
+                    .show(ctx, |ui| move_list::show_moves(ui, &self.game, self.clock.move_times(), viewing, language))
+                    .inner;
+                if let Some(moves) = clicked {
+                    self.view_moment(moves);
+                }
+            }
+            if self.show_timeline {
+                let clicked =
+                    egui::SidePanel::new(timeline_side, "timeline").show(ctx, |ui| self.timeline.show(ui, language)).inner;
+                if let Some(moves) = clicked {
+                    self.view_moment(moves);
+                }
+            }
+            if self.notes.open {
+                egui::SidePanel::new(moves_side, "notes")
+                    .default_width(280.0)
+                    .show(ctx, |ui| self.notes.show(ui, language));
+            }
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(tr(language, "app.title"));
+                    if compact {
+                        let viewing = self.viewing.as_ref().map(|viewing| viewing.history.len());
+                        let clicked = ui.menu_button(tr(language, "moves.title"), |ui| {
+                            move_list::show_moves(ui, &self.game, self.clock.move_times(), viewing, language)
+                        });
+                        if let Some(moves) = clicked.inner.flatten() {
+                            self.view_moment(moves);
+                        }
                     }
+                });
+                if let Some(notice) = &self.notice {
+                    ui.label(notice);
+                }
+                if let Some(header) = game_info::header_text(language, &self.metadata) {
+                    ui.strong(header);
                 }
-                game::GameState::WaitingForPieRuleChoice => {
-                    ui.label("Would you like to apply the pie rule?");
+
+                if let Some(viewing) = &self.viewing {
+                    let (mut back, mut branch) = (false, false);
+                    let moves = viewing.history.len();
+                    let can_branch = self.menu_enabled(menu_bar::MenuAction::Undo);
                     ui.horizontal(|ui| {
-                        if ui.button("Apply Pie Rule").clicked() {
-                            self.game.handle_pie_rule_decision(true);
+                        live_label(ui, tr_args(language, "timeline.viewing", &[("moves", &moves.to_string())]));
+                        back = ui.button(tr(language, "timeline.back")).clicked();
+                        branch = ui
+                            .add_enabled(can_branch, egui::Button::new(tr(language, "moves.branch")))
+                            .on_hover_text(tr(language, "moves.branch_hint"))
+                            .clicked();
+                    });
+                    // Only a look back; clicks on the board play nothing.
+                    self.board_renderer.render_board(ui, viewing, language);
+                    if back {
+                        self.viewing = None;
+                    } else if branch {
+                        // The moves after it can still be replayed with Redo.
+                        self.take_back(self.game.history.len() - moves);
+                    }
+                } else {
+                    match self.game.state {
+                        game::GameState::Finished { winner } => {
+                            let winner_text = player_name(language, winner);
+                            live_label(ui, tr_args(language, "game.winner", &[("winner", winner_text)]));
                         }
-                        if ui.button("Continue Normal Play").clicked() {
-                            self.game.handle_pie_rule_decision(false);
+                        game::GameState::Resigned { winner } => live_label(ui, i18n::resigned_text(language, winner)),
+                        game::GameState::Drawn => live_label(ui, tr(language, "game.drawn")),
+                        game::GameState::Adjourned { .. } => {
+                            live_label(ui, tr(language, "game.adjourned"));
+                            if ui.button(tr(language, "match.resume")).clicked() {
+                                self.resume();
+                            }
                         }
-                    });
+                        game::GameState::InProgress => {
+                            let to_move = tr_args(
+                                language,
+                                "announce.to_move",
+                                &[("player", player_name(language, self.game.current_player))],
+                            );
+                            match &self.last_action {
+                                Some(last_action) => live_label(ui, format!("{}  {}", last_action, to_move)),
+                                None => live_label(ui, to_move),
+                            }
+                            if let Some(clocks) = self.seats.clocks(&self.clock, std::time::Instant::now(), language) {
+                                ui.label(clocks);
+                            }
+                            let human_to_move = self.seats.is_human(self.game.current_player);
+                            if human_to_move && self.game.allow_pass && ui.button(tr(language, "game.pass")).clicked() {
+                                self.pass();
+                            }
+                            let board_rect = ui.available_rect_before_wrap();
+                            let clicked = self.board_renderer.render_board(ui, &self.game, language);
+                            let now = std::time::Instant::now();
+                            if self.idle.is_paused() {
+                                self.idle.overlay(ui.ctx(), board_rect, now, language);
+                            } else if self.seat_engines.is_thinking() {
+                                ai_opponent::thinking_overlay(ui.ctx(), board_rect, language);
+                            }
+                            if let Some(clicked_hex) = clicked.filter(|_| human_to_move && !self.idle.is_paused()) {
+                                let candidates = &self.board_renderer.candidates;
+                                if candidates.is_empty() || candidates.contains(&clicked_hex) {
+                                    self.play(clicked_hex);
+                                } else {
+                                    self.notice = Some(tr(language, "teaching.restricted").to_string());
+                                }
+                            }
+                        }
+                        game::GameState::WaitingForPieRuleChoice if !self.seats.is_human(self.game.current_player) => {
+                            live_label(ui, tr(language, "pie_rule.prompt"));
+                            ai_opponent::thinking_overlay(ui.ctx(), ui.available_rect_before_wrap(), language);
+                        }
+                        game::GameState::WaitingForPieRuleChoice => {
+                            live_label(ui, tr(language, "pie_rule.prompt"));
+                            ui.horizontal(|ui| {
+                                if ui.button(tr(language, "pie_rule.apply")).clicked() {
+                                    self.decide_pie_rule(true);
+                                }
+                                if ui.button(tr(language, "pie_rule.continue")).clicked() {
+                                    self.decide_pie_rule(false);
+                                }
+                            });
+                        }
+                    }
                 }
+            });
+        }
+
+        let action = self.games_browser.show_detached(ctx, language);
+        self.browser_action(action, language);
+        self.settings.show(ctx, &mut self.show_settings, &mut self.themes, &mut self.match_presets);
+        if self.new_game_dialog.show(ctx, language, &mut self.settings) {
+            self.new_game();
+        }
+        self.help.show(ctx, language);
+        self.game_info.show(ctx, language, &mut self.metadata);
+        match self.takeback.show(ctx, language) {
+            Some((takeback::TakebackMessage::Accept, moves)) => self.take_back(moves),
+            Some((takeback::TakebackMessage::Decline, _)) => {
+                self.notice = Some(tr(language, "takeback.declined").to_string())
             }
-        });
+            _ => {}
+        }
+        if let Some((game, record)) = self.import_dialog.show(ctx, language) {
+            tracing::info!(moves = game.history.len(), "move list imported");
+            self.load_game(game, record, None);
+        }
+        if let Some(data_dir) = self.data_dir.clone() {
+            if let Some(report) = self.import_data.show(ctx, language, &data_dir) {
+                self.finish_import(language, &data_dir, report);
+            }
+        }
+        let context = self.diagnostics_context();
+        self.debug_console.show(ctx, &context);
+        self.engine_shared.sync(&self.settings.ai);
+        self.table_panel.show(ctx, &self.engine_shared.table);
+        self.search_inspector.show(ctx);
+        self.stats.show(ctx, language);
+        // Hidden along with the game, so a bookmark never replaces it out of sight.
+        if self.menu_enabled(menu_bar::MenuAction::Bookmarks) {
+            let position = || GameRecord::from_game(self.viewing.as_ref().unwrap_or(&self.game), &self.metadata);
+            if let Some(bookmark) = self.bookmarks.show(ctx, language, self.data_dir.as_deref(), position) {
+                self.open_bookmark(bookmark, language);
+            }
+        }
+        self.show_crash_report_dialog(ctx);
+        if let Some(updater::UpdateAction::NeverAskAgain) = self.updater.show(ctx, language) {
+            self.settings.check_for_updates = false;
+        }
+        self.finish_sync(language);
+        self.webhook.set_url(&self.settings.webhook_url);
+        #[cfg(feature = "discord")]
+        self.presence.set_enabled(self.settings.discord_presence);
+
+        self.crash_context.update(
+            format!("{}\n{}", context, self.game.board),
+            format!("{:#?}", self.settings),
+        );
+        self.debug_console.frame_stats.record(ctx, frame);
+        self.debug_console.frame_stats.show(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings);
+        self.notes.flush();
+        // eframe calls this periodically and on exit, which is often enough for an autosave.
+        if let (Some(data_dir), false) = (self.data_dir.as_deref(), self.game.history.is_empty()) {
+            let record = self.record();
+            match saves::autosave(data_dir, &record) {
+                // The autosave has everything the journal did.
+                Ok(()) => self.journal.restart(&record),
+                Err(e) => tracing::warn!(error = %e, "autosave failed"),
+            }
+        }
     }
 }
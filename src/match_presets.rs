@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+
+use coast_to_coast::presets::{self, MatchPreset};
+use eframe::egui;
+
+use crate::ai_opponent::Difficulty;
+use crate::i18n::{tr, tr_args, Language};
+use crate::settings::Settings;
+
+// The match presets offered in the settings: the built-in ones, then the user's own from
+// `<data_dir>/presets.txt`, which the current new-game settings can be saved to.
+#[derive(Default)]
+pub struct MatchPresets {
+    data_dir: Option<PathBuf>,
+    builtin: Vec<MatchPreset>,
+    user: Vec<MatchPreset>,
+    name: String,
+    error: Option<String>,
+}
+
+impl MatchPresets {
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        Self {
+            data_dir: data_dir.map(Path::to_path_buf),
+            builtin: presets::builtin(),
+            user: data_dir.map(presets::load_user).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    // A user preset shadows a built-in one of the same name.
+    pub fn get(&self, name: &str) -> Option<&MatchPreset> {
+        self.user.iter().chain(&self.builtin).find(|preset| preset.name == name)
+    }
+
+    // The computer side in Watch mode: the chosen preset's opponent, or the easy engine.
+    pub fn opponent(&self, settings: &Settings) -> Difficulty {
+        self.get(&settings.match_preset).map_or(Difficulty::Easy, |preset| preset.opponent.into())
+    }
+
+    // The preset dropdown for the settings window. Choosing one copies its board size, pie rule
+    // and time control into the settings; the name is kept for its opponent.
+    pub fn show(&mut self, ui: &mut egui::Ui, language: Language, settings: &mut Settings) {
+        ui.horizontal(|ui| {
+            let none = tr(language, "presets.none");
+            let selected = self.get(&settings.match_preset).map_or(none, |preset| preset.name.as_str());
+            let mut chosen = None;
+            egui::ComboBox::from_label(tr(language, "presets.title")).selected_text(selected).show_ui(ui, |ui| {
+                if ui.selectable_label(settings.match_preset.is_empty(), none).clicked() {
+                    settings.match_preset.clear();
+                }
+                for preset in self.user.iter().chain(&self.builtin) {
+                    if ui.selectable_label(settings.match_preset == preset.name, &preset.name).clicked() {
+                        chosen = Some(preset.clone());
+                    }
+                }
+            });
+            if let Some(preset) = chosen {
+                apply(&preset, settings);
+            }
+            let user = self.user.iter().position(|preset| preset.name == settings.match_preset);
+            if let Some(index) = user.filter(|_| ui.button(tr(language, "browser.delete")).clicked()) {
+                self.user.remove(index);
+                settings.match_preset.clear();
+                self.write(language);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr(language, "info.time_control"));
+            ui.text_edit_singleline(&mut settings.time_control);
+        });
+        ui.add_enabled_ui(self.data_dir.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.name).on_hover_text(tr(language, "presets.name_hint"));
+                let name = self.name.trim().to_string();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new(tr(language, "presets.save"))).clicked() {
+                    let chosen = self.get(&settings.match_preset);
+                    let opponent = chosen.map_or(presets::Opponent::Medium, |preset| preset.opponent);
+                    let preset = MatchPreset {
+                        name: name.clone(),
+                        opponent,
+                        size: settings.board_size,
+                        pie_rule: settings.pie_rule,
+                        time_control: settings.time_control.trim().to_string(),
+                    };
+                    self.user.retain(|known| known.name != name);
+                    self.user.push(preset);
+                    settings.match_preset = name;
+                    self.name.clear();
+                    self.write(language);
+                }
+            });
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn write(&mut self, language: Language) {
+        let Some(data_dir) = &self.data_dir else {
+            return;
+        };
+        self.error = presets::save_user(data_dir, &self.user).err().map(|e| {
+            tracing::warn!(error = %e, "failed to save match presets");
+            tr_args(language, "presets.error", &[("error", &e.to_string())])
+        });
+    }
+}
+
+fn apply(preset: &MatchPreset, settings: &mut Settings) {
+    settings.match_preset = preset.name.clone();
+    settings.board_size = preset.size;
+    settings.pie_rule = preset.pie_rule;
+    settings.time_control = preset.time_control.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choosing_a_preset_sets_up_new_games() {
+        let presets = MatchPresets::load(None);
+        let mut settings = Settings::default();
+        assert_eq!(presets.opponent(&settings), Difficulty::Easy);
+
+        apply(presets.get("Blitz 9×9 vs MCTS hard").unwrap(), &mut settings);
+        assert_eq!((settings.board_size, settings.pie_rule, settings.time_control.as_str()), (9, true, "180+2"));
+        assert_eq!(presets.opponent(&settings), Difficulty::Hard);
+        assert_eq!(settings.new_game().board.size, 9);
+    }
+}
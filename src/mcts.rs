@@ -0,0 +1,600 @@
+//! Monte Carlo tree search (UCT) whose random playouts follow a pluggable [`PlayoutPolicy`].
+//!
+//! Each iteration walks down the tree by UCB1, adds one move, then fills the rest of the board
+//! with the playout policy and scores the full board. The move searched most is played. Turns
+//! are assumed to be one stone each. A fresh root leaves out the [`inferior`] cells. On 11x11
+//! and 13x13 Hex boards the built-in policies play out on a [`FixedBoard`](crate::fixed_board::FixedBoard),
+//! which picks the same moves faster.
+//!
+//! With tree reuse on, the player keeps its tree after deciding and, on its next turn, carries
+//! on from the subtree under the moves played since instead of starting over.
+
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::mpsc::SyncSender;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::ai::{self, AiPlayer, SearchTelemetry};
+use crate::board::{Board, CellState, Hex};
+use crate::fixed_board::{self, FixedPolicy, FIXED_SIZES};
+use crate::game::{Game, GameState};
+use crate::inferior;
+
+/// Exploration constant in UCB1; higher searches wider.
+const EXPLORATION: f64 = 1.0;
+
+/// Iterations between snapshots sent to an inspector.
+const SNAPSHOT_INTERVAL: usize = 500;
+/// Snapshots keep this many plies below the root ...
+const SNAPSHOT_DEPTH: usize = 4;
+/// ... and this many of the most visited children at each node.
+const SNAPSHOT_WIDTH: usize = 8;
+
+/// How playouts pick moves. Policies only ever see boards with at least one empty cell.
+pub trait PlayoutPolicy: Send {
+    /// Picks one of `empty` for `player` to play. `last` is the move just before, if any.
+    fn choose(&mut self, board: &Board, player: CellState, last: Option<Hex>, empty: &[Hex], rng: &mut StdRng) -> Hex;
+
+    /// The same policy for [`fixed_board::playout`], which must choose exactly as `choose`
+    /// does; None plays out on the [`Board`] on every size.
+    fn fixed(&self) -> Option<FixedPolicy> {
+        None
+    }
+}
+
+/// Every empty cell equally likely.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformPlayout;
+
+impl PlayoutPolicy for UniformPlayout {
+    fn choose(&mut self, _board: &Board, _player: CellState, _last: Option<Hex>, empty: &[Hex], rng: &mut StdRng) -> Hex {
+        empty[rng.gen_range(0..empty.len())]
+    }
+
+    fn fixed(&self) -> Option<FixedPolicy> {
+        Some(FixedPolicy::Uniform)
+    }
+}
+
+/// Half the time answers next to the previous move, as local fights usually go; otherwise
+/// uniform.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PatternPlayout;
+
+impl PlayoutPolicy for PatternPlayout {
+    fn choose(&mut self, board: &Board, player: CellState, last: Option<Hex>, empty: &[Hex], rng: &mut StdRng) -> Hex {
+        if rng.gen_bool(0.5) {
+            let local: Vec<Hex> = last
+                .into_iter()
+                .flat_map(|hex| hex.get_neighbors())
+                .filter(|hex| board.get_cell(hex) == Some(&CellState::Empty))
+                .collect();
+            if let Some(hex) = local.choose(rng) {
+                return *hex;
+            }
+        }
+        UniformPlayout.choose(board, player, last, empty, rng)
+    }
+
+    fn fixed(&self) -> Option<FixedPolicy> {
+        Some(FixedPolicy::Pattern)
+    }
+}
+
+/// Restores a bridge the previous move intruded into, otherwise plays uniformly. Keeping
+/// bridges makes playouts look much more like real games.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BridgePlayout;
+
+impl PlayoutPolicy for BridgePlayout {
+    fn choose(&mut self, board: &Board, player: CellState, last: Option<Hex>, empty: &[Hex], rng: &mut StdRng) -> Hex {
+        last.and_then(|last| bridge_reply(board, player, last))
+            .unwrap_or_else(|| UniformPlayout.choose(board, player, last, empty, rng))
+    }
+
+    fn fixed(&self) -> Option<FixedPolicy> {
+        Some(FixedPolicy::Bridge)
+    }
+}
+
+/// The cell that saves `player`'s bridge after an intrusion at `intrusion`, if there is one.
+/// Two of `player`'s stones form a bridge when they share two empty neighbors; taking one of
+/// them is answered by the other.
+pub fn bridge_reply(board: &Board, player: CellState, intrusion: Hex) -> Option<Hex> {
+    // Neighbors come in ring order, so around the intrusion any two neighbors one apart are a
+    // bridge's stones and the neighbor between them is its other carrier cell.
+    let ring = intrusion.get_neighbors();
+    (0..ring.len()).find_map(|i| {
+        let (a, carrier, b) = (ring[i], ring[(i + 1) % 6], ring[(i + 2) % 6]);
+        let owned = |hex: &Hex| board.get_cell(hex) == Some(&player);
+        (owned(&a) && owned(&b) && board.get_cell(&carrier) == Some(&CellState::Empty)).then_some(carrier)
+    })
+}
+
+/// The most visited part of a search tree, copied out while the search runs so it can be
+/// inspected elsewhere.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchSnapshot {
+    pub iterations: usize,
+    pub root: SnapshotNode,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotNode {
+    /// None at the root.
+    pub mv: Option<Hex>,
+    /// Who played `mv`.
+    pub player: CellState,
+    pub visits: u32,
+    /// `player`'s win rate through this node.
+    pub value: f64,
+    /// Most visited first.
+    pub children: Vec<SnapshotNode>,
+}
+
+impl SnapshotNode {
+    /// The line the search currently expects: the most visited child, all the way down.
+    pub fn principal_variation(&self) -> Vec<Hex> {
+        let mut line = Vec::new();
+        let mut node = self;
+        while let Some(child) = node.children.first() {
+            line.extend(child.mv);
+            node = child;
+        }
+        line
+    }
+}
+
+struct Node {
+    mv: Option<Hex>,
+    // Who played `mv`; `wins` counts from their side.
+    player: CellState,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Hex>,
+    visits: u32,
+    wins: f64,
+}
+
+impl Node {
+    fn root(mover: CellState, untried: Vec<Hex>) -> Self {
+        Node { mv: None, player: opponent(mover), parent: None, children: Vec::new(), untried, visits: 0, wins: 0.0 }
+    }
+
+    // Roughly what the node holds on the heap and inline; children are counted by their own
+    // nodes.
+    fn bytes(&self) -> usize {
+        mem::size_of::<Node>() + self.untried.capacity() * mem::size_of::<Hex>() + mem::size_of::<usize>()
+    }
+}
+
+// The tree of the last search and the position at its root.
+struct SavedTree {
+    board: Board,
+    nodes: Vec<Node>,
+}
+
+/// A UCT player running `iterations` playouts per decision.
+pub struct MctsPlayer {
+    iterations: usize,
+    policy: Box<dyn PlayoutPolicy>,
+    rng: StdRng,
+    snapshots: Option<SyncSender<SearchSnapshot>>,
+    memory_limit: usize,
+    reuse: bool,
+    saved: Option<SavedTree>,
+    telemetry: Option<SearchTelemetry>,
+}
+
+impl MctsPlayer {
+    pub fn new(iterations: usize, policy: Box<dyn PlayoutPolicy>) -> Self {
+        Self {
+            iterations: iterations.max(1),
+            policy,
+            rng: StdRng::from_entropy(),
+            snapshots: None,
+            memory_limit: usize::MAX,
+            reuse: false,
+            saved: None,
+            telemetry: None,
+        }
+    }
+
+    /// Stops growing the tree past roughly `bytes`; iterations then only add playouts.
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    /// Keeps the tree between decisions and continues from it when the position follows on.
+    pub fn with_tree_reuse(mut self, reuse: bool) -> Self {
+        self.reuse = reuse;
+        self
+    }
+
+    /// Sends a [`SearchSnapshot`] every few hundred iterations and at the end of each search.
+    /// Snapshots the receiver has no room for are dropped rather than slowing the search.
+    pub fn with_snapshots(mut self, sender: SyncSender<SearchSnapshot>) -> Self {
+        self.snapshots = Some(sender);
+        self
+    }
+
+    /// Same seed, same choices.
+    pub fn seeded(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The most searched move for the player to move and its win rate, or None without legal
+    /// moves.
+    pub fn search(&mut self, game: &Game) -> Option<(Hex, f64)> {
+        let started = Instant::now();
+        let mover = game.current_player;
+        let reused = self.saved.take().and_then(|saved| saved.follow(game));
+        let ponder_hit = reused.is_some();
+        let mut nodes = reused.unwrap_or_else(|| {
+            let mut root_moves = inferior::candidate_moves(&game.board, mover);
+            root_moves.shuffle(&mut self.rng);
+            vec![Node::root(mover, root_moves)]
+        });
+        if nodes[0].untried.is_empty() && nodes[0].children.is_empty() {
+            return None;
+        }
+        let mut bytes: usize = nodes.iter().map(Node::bytes).sum();
+        let mut deepest = 0;
+        let fixed = self.policy.fixed().filter(|_| FIXED_SIZES.contains(&game.board.size) && game.board.is_rhombus());
+
+        for iteration in 1..=self.iterations {
+            let mut board = game.board.clone();
+            let mut node = 0;
+            let mut last = None;
+            let mut depth = 0;
+            // Selection
+            while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+                depth += 1;
+                let parent_visits = nodes[node].visits as f64;
+                node = *nodes[node]
+                    .children
+                    .iter()
+                    .max_by(|a, b| ucb(&nodes[**a], parent_visits).total_cmp(&ucb(&nodes[**b], parent_visits)))
+                    .expect("children checked non-empty");
+                let (hex, player) = (nodes[node].mv.expect("only the root has no move"), nodes[node].player);
+                board.set_cell(hex, player);
+                last = Some(hex);
+            }
+            // Expansion, while there is memory for it
+            let room = bytes < self.memory_limit;
+            if let Some(hex) = nodes[node].untried.pop_if(|_| room) {
+                let player = opponent(nodes[node].player);
+                board.set_cell(hex, player);
+                last = Some(hex);
+                let mut untried = board.empty_cells();
+                untried.shuffle(&mut self.rng);
+                nodes.push(Node { mv: Some(hex), player, parent: Some(node), children: Vec::new(), untried, visits: 0, wins: 0.0 });
+                bytes += nodes[nodes.len() - 1].bytes();
+                let child = nodes.len() - 1;
+                nodes[node].children.push(child);
+                node = child;
+                depth += 1;
+            }
+            deepest = deepest.max(depth);
+            // Playout
+            let winner = self.playout(&mut board, opponent(nodes[node].player), last, fixed);
+            // Backpropagation
+            let mut current = Some(node);
+            while let Some(index) = current {
+                let node = &mut nodes[index];
+                node.visits += 1;
+                node.wins += match winner {
+                    Some(winner) if winner == node.player => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+                current = node.parent;
+            }
+            if let Some(sender) = &self.snapshots {
+                if iteration % SNAPSHOT_INTERVAL == 0 || iteration == self.iterations {
+                    let _ = sender.try_send(SearchSnapshot { iterations: iteration, root: snapshot(&nodes, 0, 0) });
+                }
+            }
+        }
+
+        let best = nodes[0]
+            .children
+            .iter()
+            .map(|child| &nodes[*child])
+            .max_by_key(|child| child.visits)
+            .and_then(|child| Some((child.mv?, child.wins / child.visits.max(1) as f64)));
+        self.telemetry = Some(SearchTelemetry {
+            nodes: self.iterations as u64,
+            elapsed: started.elapsed(),
+            depth: deepest,
+            ponder_hit,
+            table_hit_rate: None,
+        });
+        if self.reuse {
+            self.saved = Some(SavedTree { board: game.board.clone(), nodes });
+        }
+        best
+    }
+
+    // Fills the board, `player` first, and returns who connects their sides. `fixed` is the
+    // policy's fast path when the board is one it is built for.
+    fn playout(
+        &mut self,
+        board: &mut Board,
+        mut player: CellState,
+        mut last: Option<Hex>,
+        fixed: Option<FixedPolicy>,
+    ) -> Option<CellState> {
+        match (fixed, board.size) {
+            (Some(policy), 11) => return fixed_board::playout::<11>(board, policy, player, last, &mut self.rng),
+            (Some(policy), 13) => return fixed_board::playout::<13>(board, policy, player, last, &mut self.rng),
+            _ => {}
+        }
+        let mut empty = board.empty_cells();
+        while !empty.is_empty() {
+            let hex = self.policy.choose(board, player, last, &empty, &mut self.rng);
+            if let Some(index) = empty.iter().position(|cell| *cell == hex) {
+                empty.swap_remove(index);
+            }
+            board.set_cell(hex, player);
+            last = Some(hex);
+            player = opponent(player);
+        }
+        board.winner()
+    }
+}
+
+impl SavedTree {
+    // The subtree for `game`'s position, when it is the saved one plus stones the tree has
+    // explored, with the player to move matching.
+    fn follow(mut self, game: &Game) -> Option<Vec<Node>> {
+        if self.board.cells.len() != game.board.cells.len() {
+            return None;
+        }
+        let mut added = Vec::new();
+        for (hex, state) in game.board.cells.iter() {
+            match self.board.get_cell(hex) {
+                Some(saved) if saved == state => {}
+                Some(CellState::Empty) if matches!(state, CellState::Red | CellState::Blue) => added.push((*hex, *state)),
+                _ => return None,
+            }
+        }
+        let mut node = 0;
+        while !added.is_empty() {
+            let next = opponent(self.nodes[node].player);
+            let position = added.iter().position(|(_, state)| *state == next)?;
+            let (hex, _) = added.swap_remove(position);
+            node = *self.nodes[node].children.iter().find(|child| self.nodes[**child].mv == Some(hex))?;
+        }
+        if opponent(self.nodes[node].player) != game.current_player {
+            return None;
+        }
+        Some(reroot(&mut self.nodes, node))
+    }
+}
+
+// Copies the subtree under `root` into a tree of its own.
+fn reroot(nodes: &mut [Node], root: usize) -> Vec<Node> {
+    let mut kept: Vec<Node> = Vec::new();
+    let mut queue = VecDeque::from([(root, None)]);
+    while let Some((index, parent)) = queue.pop_front() {
+        let node = &mut nodes[index];
+        let children = mem::take(&mut node.children);
+        let untried = mem::take(&mut node.untried);
+        let mv = if parent.is_some() { node.mv } else { None };
+        kept.push(Node { mv, player: node.player, parent, children: Vec::new(), untried, visits: node.visits, wins: node.wins });
+        let new_index = kept.len() - 1;
+        if let Some(parent) = parent {
+            kept[parent].children.push(new_index);
+        }
+        queue.extend(children.into_iter().map(|child| (child, Some(new_index))));
+    }
+    kept
+}
+
+fn snapshot(nodes: &[Node], index: usize, depth: usize) -> SnapshotNode {
+    let node = &nodes[index];
+    let mut children: Vec<usize> = if depth < SNAPSHOT_DEPTH { node.children.clone() } else { Vec::new() };
+    children.sort_by_key(|child| std::cmp::Reverse(nodes[*child].visits));
+    SnapshotNode {
+        mv: node.mv,
+        player: node.player,
+        visits: node.visits,
+        value: node.wins / node.visits.max(1) as f64,
+        children: children.into_iter().take(SNAPSHOT_WIDTH).map(|child| snapshot(nodes, child, depth + 1)).collect(),
+    }
+}
+
+fn ucb(node: &Node, parent_visits: f64) -> f64 {
+    let visits = node.visits.max(1) as f64;
+    node.wins / visits + EXPLORATION * (parent_visits.max(1.0).ln() / visits).sqrt()
+}
+
+fn opponent(player: CellState) -> CellState {
+    match player {
+        CellState::Red => CellState::Blue,
+        _ => CellState::Red,
+    }
+}
+
+impl AiPlayer for MctsPlayer {
+    fn choose_move(&mut self, game: &Game) -> Option<Hex> {
+        if game.state != GameState::InProgress {
+            return None;
+        }
+        // Random playouts can miss a one-move win or threat on a big board; there is nothing
+        // to search for then. The kept tree still follows the move if it explored it.
+        if let Some(hex) = ai::urgent_move(game) {
+            self.telemetry = None;
+            return Some(hex);
+        }
+        self.search(game).map(|(hex, _)| hex)
+    }
+
+    /// Swaps when the best reply to the opening stone still loses more often than not.
+    fn decide_pie_rule(&mut self, game: &Game) -> bool {
+        self.search(game).is_some_and(|(_, win_rate)| win_rate < 0.5)
+    }
+
+    fn telemetry(&self) -> Option<SearchTelemetry> {
+        self.telemetry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Move;
+
+    #[test]
+    fn test_bridge_playout_always_answers_an_intrusion() {
+        let center = Hex { q: 3, r: 3 };
+        let ring = center.get_neighbors();
+        // Every bridge orientation: stones at `center` and two steps away, intruded on either carrier.
+        for i in 0..6 {
+            let (first, second) = (ring[i], ring[(i + 1) % 6]);
+            let partner = Hex { q: first.q + second.q - center.q, r: first.r + second.r - center.r };
+            for (intrusion, reply) in [(first, second), (second, first)] {
+                let mut board = Board::new(7);
+                board.set_cell(center, CellState::Red);
+                board.set_cell(partner, CellState::Red);
+                board.set_cell(intrusion, CellState::Blue);
+                let empty = board.empty_cells();
+                for seed in 0..20 {
+                    let mut rng = StdRng::seed_from_u64(seed);
+                    let chosen = BridgePlayout.choose(&board, CellState::Red, Some(intrusion), &empty, &mut rng);
+                    assert_eq!(chosen, reply, "bridge {:?}-{:?} intruded at {:?}", center, partner, intrusion);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_bridge_reply_without_a_bridge() {
+        let mut board = Board::new(5);
+        // Adjacent stones need no saving, and a bridge already cut on both carriers cannot be saved.
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        board.set_cell(Hex { q: 2, r: 1 }, CellState::Red);
+        board.set_cell(Hex { q: 1, r: 0 }, CellState::Blue);
+        assert_eq!(bridge_reply(&board, CellState::Red, Hex { q: 1, r: 0 }), None);
+        board.set_cell(Hex { q: 3, r: 2 }, CellState::Red);
+        board.set_cell(Hex { q: 2, r: 2 }, CellState::Blue);
+        board.set_cell(Hex { q: 3, r: 1 }, CellState::Blue);
+        assert_eq!(bridge_reply(&board, CellState::Red, Hex { q: 3, r: 1 }), None);
+    }
+
+    #[test]
+    fn test_fixed_playouts_play_the_same_moves() {
+        let mut game = Game::with_size(11).with_pie_rule(false);
+        for mv in ["f6", "e7", "g5", "f5", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        for policy in [Box::new(UniformPlayout) as Box<dyn PlayoutPolicy>, Box::new(PatternPlayout), Box::new(BridgePlayout)] {
+            let fixed = policy.fixed();
+            let mut player = MctsPlayer::new(1, policy).seeded(9);
+            for _ in 0..50 {
+                // Both draw from the same generator state, so they match only if every move does.
+                let before = player.rng.clone();
+                let slow = player.playout(&mut game.board.clone(), CellState::Blue, Some(Hex { q: 2, r: 2 }), None);
+                let after = mem::replace(&mut player.rng, before);
+                let fast = player.playout(&mut game.board.clone(), CellState::Blue, Some(Hex { q: 2, r: 2 }), fixed);
+                assert_eq!(fast, slow);
+                assert_eq!(player.rng.gen::<u64>(), after.clone().gen::<u64>());
+                player.rng = after;
+            }
+        }
+        // Other shapes of the same extent keep to the board.
+        let hexagon = Board::with_shape(crate::shape::Hexagon { side: 6 });
+        assert!(!hexagon.is_rhombus() && game.board.is_rhombus());
+    }
+
+    // Times playouts on empty boards both ways; add `--release -- --nocapture` for the rates.
+    #[cfg(feature = "engine-tests")]
+    #[test]
+    fn test_fixed_playouts_are_faster() {
+        for size in FIXED_SIZES {
+            let board = Board::new(size);
+            let fixed = BridgePlayout.fixed();
+            let mut player = MctsPlayer::new(1, Box::new(BridgePlayout)).seeded(1);
+            let mut rate = |fixed| {
+                let started = Instant::now();
+                for _ in 0..2000 {
+                    player.playout(&mut board.clone(), CellState::Red, None, fixed);
+                }
+                2000.0 / started.elapsed().as_secs_f64()
+            };
+            let (slow, fast) = (rate(None), rate(fixed));
+            println!("{size}x{size}: {slow:.0} playouts/s on the board, {fast:.0} on the fixed board ({:.1}x)", fast / slow);
+            assert!(fast > slow);
+        }
+    }
+
+    #[test]
+    fn test_mcts_finds_the_winning_move() {
+        let mut game = Game::with_size(4);
+        for mv in ["a2", "b1", "b2", "c1", "c2", "d3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        // Red to move: d1 or d2 joins a2-b2-c2 to the far side. d1 leaves d2 dead, so d2 isn't
+        // searched. Playing skips the search for a win this close, so ask the search itself.
+        for policy in [Box::new(UniformPlayout) as Box<dyn PlayoutPolicy>, Box::new(PatternPlayout), Box::new(BridgePlayout)] {
+            let mut player = MctsPlayer::new(800, policy).seeded(5);
+            assert_eq!(player.search(&game).map(|(hex, _)| hex), Some(Hex { q: 3, r: 0 }));
+            assert_eq!(player.choose_move(&game), Some(Hex { q: 3, r: 0 }));
+        }
+    }
+
+    #[test]
+    fn test_tree_is_reused_after_the_reply() {
+        let mut player = MctsPlayer::new(2000, Box::new(BridgePlayout)).seeded(3).with_tree_reuse(true);
+        let mut game = Game::with_size(4).with_pie_rule(false);
+        let first = player.choose_move(&game).unwrap();
+        game.play_move(Move::Place(first)).unwrap();
+        let reply = game.legal_moves().next().unwrap();
+        game.play_move(Move::Place(reply)).unwrap();
+
+        let nodes = &player.saved.as_ref().unwrap().nodes;
+        let child = |index: usize, hex| nodes[index].children.iter().copied().find(|child| nodes[*child].mv == Some(hex));
+        let expected_visits = child(0, first).and_then(|after| child(after, reply)).map_or(0, |node| nodes[node].visits);
+        assert!(!player.telemetry().unwrap().ponder_hit);
+        player.choose_move(&game).unwrap();
+        assert!(player.telemetry().unwrap().ponder_hit);
+        assert!(expected_visits > 0);
+        assert_eq!(player.saved.as_ref().unwrap().nodes[0].visits, expected_visits + 2000);
+
+        // A position the tree never led to starts over.
+        let mut elsewhere = Game::with_size(4).with_pie_rule(false);
+        elsewhere.play_move(Move::Place(Hex { q: 0, r: 0 })).unwrap();
+        let saved = player.saved.take().unwrap();
+        assert!(saved.follow(&elsewhere).is_none());
+    }
+
+    #[test]
+    fn test_memory_limit_caps_the_tree() {
+        let mut player = MctsPlayer::new(3000, Box::new(UniformPlayout)).seeded(2).with_memory_limit(4096).with_tree_reuse(true);
+        assert!(player.choose_move(&Game::with_size(5)).is_some());
+        let nodes = &player.saved.as_ref().unwrap().nodes;
+        assert!(nodes.iter().map(Node::bytes).sum::<usize>() < 4096 + 1024);
+        assert_eq!(nodes[0].visits, 3000);
+    }
+
+    #[test]
+    fn test_snapshots_follow_the_search() {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+        let mut player = MctsPlayer::new(1200, Box::new(BridgePlayout)).seeded(1).with_snapshots(sender);
+        let chosen = player.choose_move(&Game::with_size(4)).unwrap();
+        let snapshots: Vec<SearchSnapshot> = receiver.try_iter().collect();
+        assert_eq!(snapshots.iter().map(|snapshot| snapshot.iterations).collect::<Vec<_>>(), vec![500, 1000, 1200]);
+        let last = &snapshots[2].root;
+        assert_eq!(last.visits, 1200);
+        assert_eq!(last.principal_variation().first(), Some(&chosen));
+        assert!(last.children.len() <= SNAPSHOT_WIDTH);
+        assert!(last.children.windows(2).all(|pair| pair[0].visits >= pair[1].visits));
+    }
+}
@@ -0,0 +1,324 @@
+use std::cmp::Reverse;
+
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, tr_args, Language};
+use crate::keymap::{self, Keymap};
+use crate::presentation;
+
+// Everything the menu bar offers. Each has a keyboard shortcut, which works with the menus
+// closed; see `Keymap` for the user's own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MenuAction {
+    NewGame,
+    OpenSaved,
+    Save,
+    Import,
+    Export,
+    ExportSgf,
+    ExportImage,
+    ExportGif,
+    ExportData,
+    ImportData,
+    GameInfo,
+    Settings,
+    Sync,
+    Quit,
+    Undo,
+    Redo,
+    CopyPosition,
+    PasteGame,
+    HighlightLastMove,
+    RotateBoard,
+    Timeline,
+    Bookmarks,
+    Notes,
+    Stats,
+    Ownership,
+    TwoDistance,
+    Templates,
+    RevealStones,
+    DarkTheme,
+    Presentation,
+    Fullscreen,
+    Pass,
+    OfferTakeback,
+    AgreeDraw,
+    Adjourn,
+    Resume,
+    Resign,
+    DarkHex,
+    Simul,
+    Watch,
+    Study,
+    BoardBridge,
+    Rules,
+    About,
+}
+
+use MenuAction::*;
+
+// The menus in order, each with its title and items.
+const MENUS: [(&str, &[MenuAction]); 5] = [
+    (
+        "menu.file",
+        &[
+            NewGame,
+            OpenSaved,
+            Save,
+            Import,
+            Export,
+            ExportSgf,
+            ExportImage,
+            ExportGif,
+            ExportData,
+            ImportData,
+            GameInfo,
+            Settings,
+            Sync,
+            Quit,
+        ],
+    ),
+    ("menu.edit", &[Undo, Redo, CopyPosition, PasteGame]),
+    (
+        "menu.view",
+        &[
+            HighlightLastMove,
+            RotateBoard,
+            Timeline,
+            Bookmarks,
+            Notes,
+            Stats,
+            Ownership,
+            TwoDistance,
+            Templates,
+            RevealStones,
+            DarkTheme,
+            Presentation,
+            Fullscreen,
+        ],
+    ),
+    (
+        "menu.game",
+        &[Pass, OfferTakeback, AgreeDraw, Adjourn, Resume, Resign, DarkHex, Simul, Watch, Study, BoardBridge],
+    ),
+    ("menu.help", &[Rules, About]),
+];
+
+impl MenuAction {
+    // Every action, in menu order.
+    pub fn all() -> impl Iterator<Item = MenuAction> {
+        MENUS.into_iter().flat_map(|(_, items)| items.iter().copied())
+    }
+
+    pub fn default_shortcut(self) -> KeyboardShortcut {
+        let (modifiers, key) = match self {
+            NewGame => (Modifiers::COMMAND, Key::N),
+            OpenSaved => (Modifiers::COMMAND, Key::O),
+            Save => (Modifiers::COMMAND, Key::S),
+            Import => (Modifiers::COMMAND, Key::I),
+            Export => (Modifiers::COMMAND, Key::E),
+            ExportSgf => (Modifiers::COMMAND | Modifiers::SHIFT, Key::E),
+            ExportImage => (Modifiers::COMMAND | Modifiers::ALT, Key::P),
+            ExportGif => (Modifiers::COMMAND | Modifiers::ALT, Key::G),
+            ExportData => (Modifiers::COMMAND | Modifiers::ALT, Key::E),
+            ImportData => (Modifiers::COMMAND | Modifiers::ALT, Key::I),
+            GameInfo => (Modifiers::COMMAND, Key::G),
+            Settings => (Modifiers::COMMAND, Key::Comma),
+            Sync => (Modifiers::COMMAND | Modifiers::SHIFT, Key::S),
+            Quit => (Modifiers::COMMAND, Key::Q),
+            Undo => (Modifiers::COMMAND, Key::Z),
+            Redo => (Modifiers::COMMAND | Modifiers::SHIFT, Key::Z),
+            CopyPosition => (Modifiers::COMMAND | Modifiers::SHIFT, Key::C),
+            // Not V: the window turns any Command-V into a paste.
+            PasteGame => (Modifiers::COMMAND | Modifiers::SHIFT, Key::P),
+            HighlightLastMove => (Modifiers::COMMAND, Key::L),
+            RotateBoard => (Modifiers::COMMAND, Key::R),
+            Timeline => (Modifiers::COMMAND, Key::H),
+            Bookmarks => (Modifiers::COMMAND, Key::K),
+            Notes => (Modifiers::COMMAND | Modifiers::SHIFT, Key::N),
+            Stats => (Modifiers::COMMAND | Modifiers::SHIFT, Key::I),
+            Ownership => (Modifiers::COMMAND | Modifiers::SHIFT, Key::O),
+            TwoDistance => (Modifiers::COMMAND | Modifiers::SHIFT, Key::D),
+            Templates => (Modifiers::COMMAND | Modifiers::SHIFT, Key::M),
+            RevealStones => (Modifiers::COMMAND, Key::B),
+            DarkTheme => (Modifiers::COMMAND | Modifiers::SHIFT, Key::T),
+            Presentation => (Modifiers::NONE, presentation::TOGGLE_KEY),
+            Fullscreen => (Modifiers::NONE, Key::F11),
+            Pass => (Modifiers::COMMAND, Key::P),
+            OfferTakeback => (Modifiers::COMMAND, Key::T),
+            AgreeDraw => (Modifiers::COMMAND, Key::D),
+            Adjourn => (Modifiers::COMMAND, Key::J),
+            Resume => (Modifiers::COMMAND | Modifiers::SHIFT, Key::J),
+            Resign => (Modifiers::COMMAND | Modifiers::SHIFT, Key::R),
+            DarkHex => (Modifiers::COMMAND, Key::Num1),
+            Simul => (Modifiers::COMMAND, Key::Num2),
+            Watch => (Modifiers::COMMAND, Key::Num3),
+            Study => (Modifiers::COMMAND, Key::Num4),
+            BoardBridge => (Modifiers::COMMAND | Modifiers::SHIFT, Key::B),
+            Rules => (Modifiers::NONE, Key::F1),
+            About => (Modifiers::SHIFT, Key::F1),
+        };
+        KeyboardShortcut::new(modifiers, key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NewGame => "browser.new_game",
+            OpenSaved => "saves.button",
+            Save => "save.button",
+            Import => "import.button",
+            Export => "export.button",
+            ExportSgf => "sgf.button",
+            ExportImage => "export.image_button",
+            ExportGif => "export.gif_button",
+            ExportData => "bundle.export_button",
+            ImportData => "bundle.import_button",
+            GameInfo => "info.button",
+            Settings => "settings.title",
+            Sync => "menu.sync",
+            Quit => "menu.quit",
+            Undo => "menu.undo",
+            Redo => "menu.redo",
+            CopyPosition => "menu.copy_position",
+            PasteGame => "menu.paste_game",
+            HighlightLastMove => "menu.last_move",
+            RotateBoard => "menu.rotate_board",
+            Timeline => "menu.timeline",
+            Bookmarks => "bookmarks.title",
+            Notes => "notes.title",
+            Stats => "stats.title",
+            Ownership => "menu.ownership",
+            TwoDistance => "menu.two_distance",
+            Templates => "menu.templates",
+            RevealStones => "blind.reveal",
+            DarkTheme => "menu.dark_theme",
+            Presentation => "presentation.button",
+            Fullscreen => "menu.fullscreen",
+            Pass => "game.pass",
+            OfferTakeback => "takeback.button",
+            AgreeDraw => "match.draw",
+            Adjourn => "match.adjourn",
+            Resume => "match.resume",
+            Resign => "menu.resign",
+            DarkHex => "dark.button",
+            Simul => "simul.button",
+            Watch => "watch.button",
+            Study => "study.button",
+            BoardBridge => "menu.board_bridge",
+            Rules => "menu.rules",
+            About => "menu.about",
+        }
+    }
+
+    // Items shown with a check mark while on.
+    fn is_toggle(self) -> bool {
+        matches!(
+            self,
+            HighlightLastMove
+                | RotateBoard
+                | Timeline
+                | Bookmarks
+                | Notes
+                | Stats
+                | Ownership
+                | TwoDistance
+                | Templates
+                | RevealStones
+                | DarkTheme
+                | Presentation
+                | Fullscreen
+                | BoardBridge
+        )
+    }
+}
+
+// The action whose shortcut was pressed this frame, if it can be used. Text fields keep their
+// own editing shortcuts, and the shortcuts editor takes the keys while it waits for one.
+pub fn pressed(ctx: &egui::Context, keymap: &Keymap, enabled: impl Fn(MenuAction) -> bool) -> Option<MenuAction> {
+    if ctx.wants_keyboard_input() || keymap::recording(ctx).is_some() {
+        return None;
+    }
+    let mut actions: Vec<MenuAction> = MenuAction::all().collect();
+    // Ctrl+Z also matches Ctrl+Shift+Z, so the shortcuts with more modifiers go first.
+    actions.sort_by_key(|action| {
+        let modifiers = keymap.shortcut(*action).modifiers;
+        Reverse(u8::from(modifiers.command) + u8::from(modifiers.shift) + u8::from(modifiers.alt))
+    });
+    actions.into_iter().filter(|action| enabled(*action)).find(|action| {
+        ctx.input_mut(|i| i.consume_shortcut(&keymap.shortcut(*action)))
+    })
+}
+
+// The menu bar along the top of `ui`; returns the item clicked, if any. `checked` is asked
+// only about toggles.
+pub fn show(
+    ui: &mut egui::Ui,
+    language: Language,
+    keymap: &Keymap,
+    enabled: impl Fn(MenuAction) -> bool,
+    checked: impl Fn(MenuAction) -> bool,
+) -> Option<MenuAction> {
+    let mut clicked = None;
+    egui::MenuBar::new().ui(ui, |ui| {
+        for (title, items) in MENUS {
+            ui.menu_button(tr(language, title), |ui| {
+                for &action in items {
+                    let shortcut = ui.ctx().format_shortcut(&keymap.shortcut(action));
+                    let button = egui::Button::new(tr(language, action.label()))
+                        .shortcut_text(shortcut)
+                        .selected(action.is_toggle() && checked(action));
+                    if ui.add_enabled(enabled(action), button).clicked() {
+                        clicked = Some(action);
+                    }
+                }
+            });
+        }
+    });
+    clicked
+}
+
+// The Help menu's windows.
+#[derive(Default)]
+pub struct HelpWindows {
+    pub rules: bool,
+    pub about: bool,
+}
+
+impl HelpWindows {
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) {
+        egui::Window::new(tr(language, "menu.rules"))
+            .open(&mut self.rules)
+            .collapsible(false)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr(language, "help.rules"));
+            });
+        egui::Window::new(tr(language, "menu.about"))
+            .open(&mut self.about)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.heading(tr(language, "app.title"));
+                ui.label(tr_args(language, "help.version", &[("version", env!("CARGO_PKG_VERSION"))]));
+                ui.label(tr(language, "help.about"));
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcuts_are_unique() {
+        let actions: Vec<MenuAction> = MenuAction::all().collect();
+        for (i, a) in actions.iter().enumerate() {
+            assert!(!actions[..i].contains(a), "{:?} is in two menus", a);
+            for b in &actions[..i] {
+                assert_ne!(a.default_shortcut(), b.default_shortcut(), "{:?} and {:?} share a shortcut", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use eframe::egui;
+
+// How often things that tick on screen, such as clocks, repaint in reduced motion.
+const SLOW_REPAINT: Duration = Duration::from_secs(1);
+
+fn id() -> egui::Id {
+    egui::Id::new("reduced_motion")
+}
+
+// Reduced motion, for motion-sensitive users and low-power machines: no egui animations, no
+// gradients, and anything ticking on screen repaints at most once a second. The flag lives in
+// the egui context so every system that animates can check it through `ctx`.
+pub fn set_reduced(ctx: &egui::Context, on: bool) {
+    if reduced(ctx) == on {
+        return;
+    }
+    ctx.data_mut(|data| data.insert_temp(id(), on));
+    let default = egui::Style::default();
+    ctx.all_styles_mut(|style| {
+        style.animation_time = if on { 0.0 } else { default.animation_time };
+        style.scroll_animation = if on { egui::style::ScrollAnimation::none() } else { default.scroll_animation };
+    });
+}
+
+pub fn reduced(ctx: &egui::Context) -> bool {
+    ctx.data(|data| data.get_temp(id())).unwrap_or(false)
+}
+
+// Asks for the next frame of something ticking on screen, e.g. a running clock.
+pub fn repaint_after(ctx: &egui::Context, interval: Duration) {
+    ctx.request_repaint_after(interval_for(reduced(ctx), interval));
+}
+
+// Shows that work is under way: a spinner, or a still ellipsis in reduced motion, as a spinner
+// repaints every frame.
+pub fn busy(ui: &mut egui::Ui) {
+    if reduced(ui.ctx()) {
+        ui.label("…");
+    } else {
+        ui.spinner();
+    }
+}
+
+fn interval_for(reduced: bool, interval: Duration) -> Duration {
+    if reduced {
+        interval.max(SLOW_REPAINT)
+    } else {
+        interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduced_motion_slows_and_stills() {
+        let ctx = egui::Context::default();
+        assert!(!reduced(&ctx));
+        set_reduced(&ctx, true);
+        assert!(reduced(&ctx) && ctx.style().animation_time == 0.0);
+        assert_eq!(interval_for(true, Duration::from_millis(250)), SLOW_REPAINT);
+        assert_eq!(interval_for(true, Duration::from_secs(5)), Duration::from_secs(5));
+        set_reduced(&ctx, false);
+        assert!(!reduced(&ctx) && ctx.style().animation_time > 0.0);
+        assert_eq!(interval_for(false, Duration::from_millis(250)), Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::record::{verify_record, GameRecord};
+use eframe::egui;
+
+use crate::clock::format_duration;
+use crate::i18n::{player_name, tr, tr_args, Language};
+
+pub const EXPORT_DIR: &str = "exports";
+const TIME_GRAPH_HEIGHT: f32 = 80.0;
+
+// Writes an export to `<data_dir>/exports/hex-<timestamp>.<extension>`.
+pub fn save_export(data_dir: &Path, contents: impl AsRef<[u8]>, extension: &str) -> io::Result<PathBuf> {
+    let dir = data_dir.join(EXPORT_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = dir.join(format!("hex-{}.{}", timestamp, extension));
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// The numbered moves of the game on screen with the time each took, newest at the bottom.
+// Once the game is over the times are also drawn as a graph above the list. Clicking a move
+// returns how many moves in it is, to look at the board there; `viewing` is the one shown.
+pub fn show_moves(
+    ui: &mut egui::Ui,
+    game: &Game,
+    move_times: &[Option<Duration>],
+    viewing: Option<usize>,
+    language: Language,
+) -> Option<usize> {
+    ui.heading(tr(language, "moves.title"));
+    let movers = game.turn_structure.movers(&game.history);
+    if matches!(game.state, GameState::Finished { .. }) && move_times.iter().any(Option::is_some) {
+        ui.label(tr(language, "moves.time_graph"));
+        time_graph(ui, &movers, move_times);
+        ui.separator();
+    }
+    let mut clicked = None;
+    egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+        let numbers = move_numbers(&movers);
+        for (i, ((mv, player), number)) in game.history.iter().zip(&movers).zip(numbers).enumerate() {
+            let time = move_times.get(i).copied().flatten().map(format_move_time).unwrap_or_default();
+            let text = format!("{:>4} {:<6} {:<5} {:>7}", number, player_name(language, *player), mv, time);
+            if ui.selectable_label(viewing == Some(i + 1), egui::RichText::new(text).monospace()).clicked() {
+                clicked = Some(i + 1);
+            }
+        }
+    });
+    clicked
+}
+
+// "1." for Red's moves and "1…" for Blue's, the number going up with each of Red's turns.
+fn move_numbers(movers: &[CellState]) -> Vec<String> {
+    let mut number = 0;
+    let mut previous = None;
+    movers
+        .iter()
+        .map(|mover| {
+            if *mover == CellState::Red && previous != Some(CellState::Red) {
+                number += 1;
+            }
+            previous = Some(*mover);
+            match mover {
+                CellState::Red => format!("{}.", number),
+                _ => format!("{}…", number.max(1)),
+            }
+        })
+        .collect()
+}
+
+// One bar per move, in the mover's color, scaled to the slowest move.
+fn time_graph(ui: &mut egui::Ui, movers: &[CellState], move_times: &[Option<Duration>]) {
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(ui.available_width(), TIME_GRAPH_HEIGHT), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let longest = move_times.iter().flatten().max().copied().unwrap_or_default().as_secs_f32().max(0.001);
+    let width = rect.width() / movers.len().max(1) as f32;
+    for (i, player) in movers.iter().enumerate() {
+        let Some(time) = move_times.get(i).copied().flatten() else {
+            continue;
+        };
+        let height = rect.height() * time.as_secs_f32() / longest;
+        let left = rect.left() + width * i as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + width * 0.1, rect.bottom() - height),
+            egui::pos2(left + width * 0.9, rect.bottom()),
+        );
+        let color = match player {
+            CellState::Red => egui::Color32::from_rgb(220, 70, 60),
+            _ => egui::Color32::from_rgb(60, 110, 220),
+        };
+        painter.rect_filled(bar, 0.0, color);
+    }
+    response.on_hover_text(format_move_time(Duration::from_secs_f32(longest)));
+}
+
+// "4.2s" under a minute, "m:ss" from then on.
+fn format_move_time(time: Duration) -> String {
+    if time < Duration::from_secs(60) {
+        format!("{:.1}s", time.as_secs_f64())
+    } else {
+        format_duration(time)
+    }
+}
+
+#[derive(Default)]
+pub struct ImportDialog {
+    pub open: bool,
+    text: String,
+    error: Option<String>,
+}
+
+impl ImportDialog {
+    // Returns the replayed game and its record once the user loads a valid move list.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) -> Option<(Game, GameRecord)> {
+        let mut imported = None;
+        let mut open = self.open;
+        egui::Window::new(tr(language, "import.title"))
+            .open(&mut open)
+            .default_size([360.0, 300.0])
+            .show(ctx, |ui| {
+                ui.label(tr(language, "import.hint"));
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.text)
+                        .code_editor()
+                        .desired_rows(10)
+                        .desired_width(f32::INFINITY),
+                );
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                if ui.button(tr(language, "import.load")).clicked() {
+                    match load(&self.text) {
+                        Ok(loaded) => {
+                            imported = Some(loaded);
+                            self.error = None;
+                            self.text.clear();
+                        }
+                        Err(e) => {
+                            self.error = Some(tr_args(language, "import.error", &[("error", &e.to_string())]));
+                        }
+                    }
+                }
+            });
+        self.open = open && imported.is_none();
+        imported
+    }
+}
+
+// Move lists come from anywhere, so they have to replay to their stated result to load.
+fn load(text: &str) -> Result<(Game, GameRecord), Box<dyn std::error::Error>> {
+    let record = GameRecord::parse(text)?;
+    verify_record(&record)?;
+    Ok((record.replay()?, record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::notation::Move;
+    use coast_to_coast::record::GameMetadata;
+    use coast_to_coast::sgf;
+
+    #[test]
+    fn test_save_export_writes_file() {
+        let dir = std::env::temp_dir().join(format!("hex-export-test-{}", std::process::id()));
+        let mut game = Game::with_size(3);
+        game.play_move("a1".parse::<Move>().unwrap()).unwrap();
+
+        let record = GameRecord::from_game(&game, &GameMetadata::default());
+        let text = record.to_text();
+        let path = save_export(&dir, &text, "txt").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        assert!(text.contains("1. a1"));
+
+        let sgf = sgf::to_sgf(&record);
+        let path = save_export(&dir, &sgf, "sgf").unwrap();
+        assert_eq!(path.extension().unwrap(), "sgf");
+        assert!(sgf.ends_with(";B[a1])\n"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_move_numbers() {
+        use CellState::{Blue, Red};
+        assert_eq!(move_numbers(&[Red, Blue, Red, Blue, Blue]), ["1.", "1…", "2.", "2…", "2…"]);
+        assert_eq!(move_numbers(&[Red, Red, Blue]), ["1.", "1.", "1…"]);
+    }
+
+    #[test]
+    fn test_format_move_time() {
+        assert_eq!(format_move_time(Duration::from_millis(4_250)), "4.2s");
+        assert_eq!(format_move_time(Duration::from_secs(75)), "1:15");
+    }
+}
@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::{Game, GameState, MAX_BOARD_SIZE};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::ai_opponent::{AiConfig, BackgroundEngine, Decision, Difficulty, EngineShared, PendingReply};
+use crate::clock::{format_duration, GameClock};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::settings::Settings;
+
+// Who plays a seat: someone at the board, or one of the engines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Controller {
+    #[default]
+    Human,
+    Computer(Difficulty),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Seat {
+    // Recorded as the player's name; empty for none.
+    pub name: String,
+    pub controller: Controller,
+    // Thinking time for the whole game; 0 for no limit.
+    pub minutes: u32,
+}
+
+// The two seats of a game, by the color they play. Red moves first, as the rules have it; which
+// seat that is is up to the players.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Seats {
+    pub red: Seat,
+    pub blue: Seat,
+}
+
+impl Seats {
+    pub fn seat(&self, player: CellState) -> &Seat {
+        match player {
+            CellState::Blue => &self.blue,
+            _ => &self.red,
+        }
+    }
+
+    pub fn is_human(&self, player: CellState) -> bool {
+        self.seat(player).controller == Controller::Human
+    }
+
+    pub fn allowance(&self, player: CellState) -> Option<Duration> {
+        let minutes = self.seat(player).minutes;
+        (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    // The computer seat's engine, for a game of one person against it.
+    pub fn opponent(&self, language: Language) -> Option<String> {
+        match (self.red.controller, self.blue.controller) {
+            (Controller::Computer(difficulty), Controller::Human)
+            | (Controller::Human, Controller::Computer(difficulty)) => Some(difficulty.name(language).to_string()),
+            _ => None,
+        }
+    }
+
+    // What is left on each timed side's clock, e.g. "Red 4:32 · Blue 5:00"; None if neither
+    // side is timed.
+    pub fn clocks(&self, clock: &GameClock, now: Instant, language: Language) -> Option<String> {
+        let left: Vec<String> = [CellState::Red, CellState::Blue]
+            .into_iter()
+            .filter_map(|player| {
+                let left = self.allowance(player)?.saturating_sub(clock.used(player, now));
+                Some(format!("{} {}", player_name(language, player), format_duration(left)))
+            })
+            .collect();
+        (!left.is_empty()).then(|| left.join(" · "))
+    }
+}
+
+// The engines playing the computer seats of the game on the board.
+#[derive(Default)]
+pub struct SeatEngines {
+    red: Option<BackgroundEngine>,
+    blue: Option<BackgroundEngine>,
+    // The position being thought about, by its move count; a takeback makes the answer stale.
+    asked: Option<usize>,
+}
+
+impl SeatEngines {
+    pub fn new(seats: &Seats, ai: &AiConfig, shared: &EngineShared) -> Self {
+        let engine = |seat: &Seat| match seat.controller {
+            Controller::Human => None,
+            Controller::Computer(difficulty) => Some(BackgroundEngine::new(difficulty, ai, shared)),
+        };
+        Self { red: engine(&seats.red), blue: engine(&seats.blue), asked: None }
+    }
+
+    fn engine(&mut self, player: CellState) -> Option<&mut BackgroundEngine> {
+        match player {
+            CellState::Blue => self.blue.as_mut(),
+            _ => self.red.as_mut(),
+        }
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.red.as_ref().is_some_and(BackgroundEngine::is_thinking)
+            || self.blue.as_ref().is_some_and(BackgroundEngine::is_thinking)
+    }
+
+    // The decision of the engine to move in `game`, once it is ready. Asks it first if need be.
+    pub fn poll(&mut self, ctx: &egui::Context, game: &Game, ai: &AiConfig, now: Instant) -> Option<Decision> {
+        if !matches!(game.state, GameState::InProgress | GameState::WaitingForPieRuleChoice) {
+            self.cancel();
+            return None;
+        }
+        let moves = game.history.len();
+        if self.asked.is_some_and(|asked| asked != moves) {
+            self.cancel();
+        }
+        let engine = self.engine(game.current_player)?;
+        if !engine.is_thinking() {
+            engine.think(ctx, game, PendingReply::new(ai, now));
+            self.asked = Some(moves);
+            return None;
+        }
+        let decision = engine.poll(ctx, now)?;
+        self.asked = None;
+        Some(decision)
+    }
+
+    pub fn cancel(&mut self) {
+        self.red.iter_mut().chain(&mut self.blue).for_each(BackgroundEngine::cancel);
+        self.asked = None;
+    }
+}
+
+// The New game dialog: the board, the rules, and who sits where.
+#[derive(Default)]
+pub struct NewGameDialog {
+    pub open: bool,
+}
+
+impl NewGameDialog {
+    // Returns true when the user starts the game, with the choices written to `settings`.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language, settings: &mut Settings) -> bool {
+        let mut start = false;
+        let mut open = self.open;
+        egui::Window::new(tr(language, "new_game.title")).open(&mut open).resizable(false).show(ctx, |ui| {
+            let size = egui::Slider::new(&mut settings.board_size, 1..=MAX_BOARD_SIZE);
+            ui.add(size.text(tr(language, "settings.board_size")));
+            ui.checkbox(&mut settings.pie_rule, tr(language, "settings.pie_rule"));
+            ui.separator();
+            egui::Grid::new("seats").num_columns(4).spacing([8.0, 6.0]).show(ui, |ui| {
+                ui.label("");
+                ui.strong(tr(language, "new_game.name"));
+                ui.strong(tr(language, "new_game.player"));
+                ui.strong(tr(language, "new_game.minutes")).on_hover_text(tr(language, "new_game.minutes_hint"));
+                ui.end_row();
+                let seats = &mut settings.seats;
+                let rows = [(CellState::Red, &mut seats.red), (CellState::Blue, &mut seats.blue)];
+                for (i, (player, seat)) in rows.into_iter().enumerate() {
+                    ui.label(player_name(language, player));
+                    ui.add(egui::TextEdit::singleline(&mut seat.name).desired_width(120.0));
+                    let controller = controller_name(seat.controller, language);
+                    egui::ComboBox::from_id_salt(("seat", i))
+                        .selected_text(controller)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut seat.controller, Controller::Human, tr(language, "new_game.human"));
+                            for difficulty in Difficulty::ALL {
+                                let option = Controller::Computer(difficulty);
+                                ui.selectable_value(&mut seat.controller, option, controller_name(option, language));
+                            }
+                        });
+                    ui.add(egui::DragValue::new(&mut seat.minutes).range(0..=600));
+                    ui.end_row();
+                }
+            });
+            if ui.button(tr(language, "new_game.swap_colors")).clicked() {
+                let seats = &mut settings.seats;
+                std::mem::swap(&mut seats.red, &mut seats.blue);
+            }
+            ui.separator();
+            start = ui.button(tr(language, "new_game.start")).clicked();
+        });
+        self.open = open && !start;
+        start
+    }
+}
+
+fn controller_name(controller: Controller, language: Language) -> String {
+    match controller {
+        Controller::Human => tr(language, "new_game.human").to_string(),
+        Controller::Computer(difficulty) => {
+            tr_args(language, "new_game.computer", &[("difficulty", difficulty.name(language))])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+
+    #[test]
+    fn test_seats_swap_colors_and_keep_their_clocks() {
+        let mut settings = Settings::default();
+        settings.seats.red = Seat { name: "Ann".to_string(), controller: Controller::Human, minutes: 5 };
+        settings.seats.blue.controller = Controller::Computer(Difficulty::Hard);
+        let mut dialog = NewGameDialog { open: true };
+        let mut started = false;
+        let mut harness = UiHarness::new(800.0, 600.0);
+        let mut frame = |ui: &mut egui::Ui| started |= dialog.show(ui.ctx(), Language::English, &mut settings);
+        // A new window is laid out unseen first.
+        harness.run(&mut frame);
+        harness.run(&mut frame);
+        harness.click("Swap colors", &mut frame);
+        harness.click("Start", &mut frame);
+        assert!(started);
+        assert!(!dialog.open);
+
+        let seats = &settings.seats;
+        assert_eq!((seats.blue.name.as_str(), seats.red.controller), ("Ann", Controller::Computer(Difficulty::Hard)));
+        assert!(!seats.is_human(CellState::Red) && seats.is_human(CellState::Blue));
+        assert_eq!(seats.opponent(Language::English).as_deref(), Some("Hard"));
+        let clock = GameClock::default();
+        assert_eq!(seats.clocks(&clock, Instant::now(), Language::English).as_deref(), Some("Blue 5:00"));
+        assert_eq!(Seats::default().clocks(&clock, Instant::now(), Language::English), None);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::Hex;
+
+// Standard Hex notation: a column letter along Blue's edge (q) followed by a 1-based row
+// number along Red's edge (r), e.g. "c5", plus "swap" for the pie rule and "pass" for
+// variants that allow passing. Boards wider than 26
+// continue the columns as spreadsheets do: z, aa, ab, ..., az, ba.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    Place(Hex),
+    Swap,
+    Pass,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+    Empty,
+    MissingColumn,
+    InvalidColumn(String),
+    MissingRow,
+    InvalidRow(String),
+    TrailingCharacters(String),
+    OffBoard(String),
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMoveError::Empty => write!(f, "empty move"),
+            ParseMoveError::MissingColumn => write!(f, "move must start with a column letter"),
+            ParseMoveError::InvalidColumn(column) => write!(f, "invalid column '{}'", column),
+            ParseMoveError::MissingRow => write!(f, "move is missing a row number"),
+            ParseMoveError::InvalidRow(row) => write!(f, "invalid row number '{}'", row),
+            ParseMoveError::TrailingCharacters(rest) => write!(f, "unexpected '{}' after move", rest),
+            ParseMoveError::OffBoard(mv) => write!(f, "{} is not on the board", mv),
+        }
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Place(hex) => write!(f, "{}", hex.notation()),
+            Move::Swap => write!(f, "swap"),
+            Move::Pass => write!(f, "pass"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseMoveError::Empty);
+        }
+        if s.eq_ignore_ascii_case("swap") {
+            return Ok(Move::Swap);
+        }
+        if s.eq_ignore_ascii_case("pass") {
+            return Ok(Move::Pass);
+        }
+
+        let (letters, rest) = s.split_at(s.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(s.len()));
+        if letters.is_empty() {
+            return Err(ParseMoveError::MissingColumn);
+        }
+        let column = parse_column(letters).ok_or_else(|| ParseMoveError::InvalidColumn(letters.to_string()))?;
+
+        let (digits, trailing) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()));
+        if !trailing.is_empty() {
+            return Err(ParseMoveError::TrailingCharacters(trailing.to_string()));
+        }
+        if digits.is_empty() {
+            return Err(ParseMoveError::MissingRow);
+        }
+
+        let row: i32 = digits.parse().map_err(|_| ParseMoveError::InvalidRow(digits.to_string()))?;
+        if row < 1 {
+            return Err(ParseMoveError::InvalidRow(digits.to_string()));
+        }
+        Ok(Move::Place(Hex { q: column, r: row - 1 }))
+    }
+}
+
+impl Move {
+    // Parses and checks that a placement lies on a `size`×`size` board.
+    pub fn parse_for_size(s: &str, size: i32) -> Result<Move, ParseMoveError> {
+        let mv: Move = s.parse()?;
+        match mv {
+            Move::Place(hex) if hex.q >= size || hex.r >= size => Err(ParseMoveError::OffBoard(mv.to_string())),
+            _ => Ok(mv),
+        }
+    }
+}
+
+// Column letters for `q`: 0 is "a", 25 is "z", 26 is "aa".
+pub fn column_name(q: i32) -> String {
+    let mut letters = Vec::new();
+    let mut n = q.max(0) as u32 + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    letters.iter().rev().map(|&b| b as char).collect()
+}
+
+// The inverse of `column_name`, case-insensitive. None if the column does not fit an i32.
+fn parse_column(letters: &str) -> Option<i32> {
+    let number = letters.bytes().try_fold(0i32, |acc, b| {
+        acc.checked_mul(26)?.checked_add((b.to_ascii_lowercase() - b'a') as i32 + 1)
+    })?;
+    Some(number - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_basic() {
+        assert_eq!("c5".parse::<Move>(), Ok(Move::Place(Hex { q: 2, r: 4 })));
+        assert_eq!("A1".parse::<Move>(), Ok(Move::Place(Hex { q: 0, r: 0 })));
+        assert_eq!(" k11 ".parse::<Move>(), Ok(Move::Place(Hex { q: 10, r: 10 })));
+        assert_eq!("swap".parse::<Move>(), Ok(Move::Swap));
+        assert_eq!("SWAP".parse::<Move>(), Ok(Move::Swap));
+
+        assert_eq!(Move::Place(Hex { q: 2, r: 4 }).to_string(), "c5");
+        assert_eq!(Move::Swap.to_string(), "swap");
+        assert_eq!("Pass".parse::<Move>(), Ok(Move::Pass));
+        assert_eq!(Move::Pass.to_string(), "pass");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Move>(), Err(ParseMoveError::Empty));
+        assert_eq!("5c".parse::<Move>(), Err(ParseMoveError::MissingColumn));
+        assert_eq!("c".parse::<Move>(), Err(ParseMoveError::MissingRow));
+        assert_eq!("c0".parse::<Move>(), Err(ParseMoveError::InvalidRow("0".to_string())));
+        assert_eq!("c5x".parse::<Move>(), Err(ParseMoveError::TrailingCharacters("x".to_string())));
+        assert_eq!(
+            "zzzzzzzzz1".parse::<Move>(),
+            Err(ParseMoveError::InvalidColumn("zzzzzzzzz".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_round_trip_up_to_size_26() {
+        for size in 1..=26 {
+            for q in 0..size {
+                for r in 0..size {
+                    let mv = Move::Place(Hex { q, r });
+                    let text = mv.to_string();
+                    assert_eq!(Move::parse_for_size(&text, size), Ok(mv), "{} on size {}", text, size);
+                }
+            }
+        }
+        assert_eq!(Move::Place(Hex { q: 25, r: 25 }).to_string(), "z26");
+    }
+
+    #[test]
+    fn test_columns_beyond_z() {
+        for (q, name) in [(0, "a"), (25, "z"), (26, "aa"), (27, "ab"), (51, "az"), (52, "ba"), (701, "zz"), (702, "aaa")] {
+            assert_eq!(column_name(q), name);
+            assert_eq!(parse_column(name), Some(q));
+        }
+        assert_eq!("AB30".parse::<Move>(), Ok(Move::Place(Hex { q: 27, r: 29 })));
+        for q in 0..40 {
+            for r in 0..40 {
+                let mv = Move::Place(Hex { q, r });
+                assert_eq!(Move::parse_for_size(&mv.to_string(), 40), Ok(mv));
+            }
+        }
+        assert_eq!(Move::parse_for_size("aa1", 26), Err(ParseMoveError::OffBoard("aa1".to_string())));
+    }
+
+    #[test]
+    fn test_parse_for_size_rejects_off_board() {
+        assert_eq!(Move::parse_for_size("l1", 11), Err(ParseMoveError::OffBoard("l1".to_string())));
+        assert_eq!(Move::parse_for_size("a12", 11), Err(ParseMoveError::OffBoard("a12".to_string())));
+        assert!(Move::parse_for_size("k11", 11).is_ok());
+        assert_eq!(Move::parse_for_size("swap", 11), Ok(Move::Swap));
+    }
+}
@@ -0,0 +1,396 @@
+use std::io;
+
+use coast_to_coast::board::{Board, CellState, Hex};
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::notation::Move;
+use coast_to_coast::record::{GameRecord, RecordError};
+use eframe::egui;
+use tiny_skia::{FillRule, LineCap, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+use crate::renderer::{cell_bounds, hexagon};
+
+const SQRT_3: f32 = 1.7320508; // Approximately sqrt(3)
+
+pub const EMPTY: egui::Color32 = egui::Color32::from_gray(200);
+pub const RED: egui::Color32 = egui::Color32::from_rgb(220, 50, 50);
+pub const BLUE: egui::Color32 = egui::Color32::from_rgb(50, 90, 220);
+const BLOCKED: egui::Color32 = egui::Color32::from_gray(30);
+const OUTLINE: egui::Color32 = egui::Color32::WHITE;
+const LAST_MOVE: egui::Color32 = egui::Color32::from_rgb(255, 200, 0);
+const PATH: egui::Color32 = egui::Color32::from_rgb(255, 200, 0);
+const CANDIDATE: egui::Color32 = egui::Color32::from_rgb(60, 200, 90);
+// Cell size in scene units, which is also the SVG's; images scale the scene to fit.
+const HEX_SIZE: f32 = 20.0;
+const OUTLINE_WIDTH: f32 = 1.5;
+const EDGE_WIDTH: f32 = 5.0;
+// Exported images' widths, their heights following the board's shape.
+pub const PNG_WIDTH: u32 = 1200;
+pub const GIF_WIDTH: u16 = 480;
+// A GIF's frames, in hundredths of a second; the final position stays up longer.
+const GIF_FRAME_DELAY: u16 = 80;
+const GIF_LAST_FRAME_DELAY: u16 = 300;
+
+// Something drawn, in scene units.
+enum Mark {
+    Cell { corners: Vec<egui::Pos2>, fill: egui::Color32 },
+    // A translucent wash over a cell, without an outline.
+    Tint { corners: Vec<egui::Pos2>, fill: egui::Color32 },
+    Outline { corners: Vec<egui::Pos2>, width: f32, color: egui::Color32 },
+    Edge { from: egui::Pos2, to: egui::Pos2, color: egui::Color32 },
+    Ring { center: egui::Pos2, radius: f32, width: f32, color: egui::Color32 },
+}
+
+// A board drawn without a window, for exports, thumbnails, the stream overlay and tests: cells,
+// board edges lined in the color of the player who connects them, and the last move ringed as
+// on screen, with whatever else is added on top. It comes out as SVG or as pixels.
+pub struct Scene {
+    size: egui::Vec2,
+    // The top left cell bound, which the scene starts a margin before.
+    min: egui::Pos2,
+    marks: Vec<Mark>,
+}
+
+impl Scene {
+    pub fn new(board: &Board, last: Option<Hex>) -> Self {
+        let (min, max) = cell_bounds(board);
+        let mut scene = Self { size: egui::Vec2::ZERO, min, marks: Vec::new() };
+        let center = |hex: &Hex| scene.center(*hex);
+        let size = egui::vec2((max.x - min.x + 2.0 * SQRT_3) * HEX_SIZE, (max.y - min.y + 3.0) * HEX_SIZE);
+        let mut cells: Vec<_> = board.cells.iter().collect();
+        cells.sort_by_key(|(hex, _)| (hex.r, hex.q));
+        let mut marks: Vec<Mark> = cells
+            .iter()
+            .map(|(hex, state)| {
+                let fill = match state {
+                    CellState::Empty => EMPTY,
+                    CellState::Red => RED,
+                    CellState::Blue => BLUE,
+                    CellState::Blocked => BLOCKED,
+                };
+                Mark::Cell { corners: hexagon(center(hex), HEX_SIZE), fill }
+            })
+            .collect();
+        // Side i of a cell runs from corner i to corner i + 1 and faces the neighbor in NEIGHBORS[i].
+        const NEIGHBORS: [(i32, i32); 6] = [(0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1), (1, 0)];
+        for (hex, _) in &cells {
+            let corners = hexagon(center(hex), HEX_SIZE);
+            for (side, (dq, dr)) in NEIGHBORS.iter().enumerate() {
+                let neighbor = Hex { q: hex.q + dq, r: hex.r + dr };
+                if board.get_cell(&neighbor).is_some() {
+                    continue;
+                }
+                // Red joins the sides across columns, Blue the sides across rows.
+                let color = if (0..board.size).contains(&neighbor.q) { BLUE } else { RED };
+                marks.push(Mark::Edge { from: corners[side], to: corners[(side + 1) % 6], color });
+            }
+        }
+        if let Some(hex) = last {
+            let (radius, width) = (HEX_SIZE * 0.55, HEX_SIZE * 0.12);
+            marks.push(Mark::Ring { center: center(&hex), radius, width, color: LAST_MOVE });
+        }
+        scene.size = size;
+        scene.marks = marks;
+        scene
+    }
+
+    // The game's position with its last move ringed and, once the board is won, the winning
+    // chain outlined.
+    pub fn of_game(game: &Game) -> Self {
+        let last = match game.history.last() {
+            Some(Move::Place(hex)) => Some(*hex),
+            _ => None,
+        };
+        let scene = Self::new(&game.board, last);
+        match game.state {
+            GameState::Finished { winner } => {
+                let chain = game.board.connecting_chain(winner, &game.board.shape().goal_sides(winner));
+                scene.with_path(&chain.unwrap_or_default())
+            }
+            _ => scene,
+        }
+    }
+
+    pub fn with_path(mut self, path: &[Hex]) -> Self {
+        for hex in path {
+            let corners = hexagon(self.center(*hex), HEX_SIZE * 0.85);
+            self.marks.push(Mark::Outline { corners, width: HEX_SIZE * 0.15, color: PATH });
+        }
+        self
+    }
+
+    // Ownership estimates, from 1 for Red's to -1 for Blue's, tinted as on screen.
+    pub fn with_ownership(mut self, ownership: &[(Hex, f32)]) -> Self {
+        for (hex, balance) in ownership {
+            let [r, g, b, _] = if *balance > 0.0 { RED } else { BLUE }.to_array();
+            let fill = egui::Color32::from_rgba_unmultiplied(r, g, b, (balance.abs().min(1.0) * 160.0) as u8);
+            self.marks.push(Mark::Tint { corners: hexagon(self.center(*hex), HEX_SIZE * 0.8), fill });
+        }
+        self
+    }
+
+    // Suggested moves, ringed in green as in teaching mode.
+    pub fn with_candidates(mut self, candidates: &[Hex]) -> Self {
+        for hex in candidates {
+            let (radius, width) = (HEX_SIZE * 0.55, HEX_SIZE * 0.1);
+            self.marks.push(Mark::Ring { center: self.center(*hex), radius, width, color: CANDIDATE });
+        }
+        self
+    }
+
+    fn center(&self, hex: Hex) -> egui::Pos2 {
+        let x = SQRT_3 * (hex.q as f32 + hex.r as f32 / 2.0) - self.min.x + SQRT_3;
+        egui::pos2(x * HEX_SIZE, (1.5 * hex.r as f32 - self.min.y + 1.5) * HEX_SIZE)
+    }
+
+    pub fn svg(&self) -> String {
+        // Colors unmultiplied, with any transparency given separately.
+        let color = |color: egui::Color32| {
+            let [r, g, b, _] = color.to_srgba_unmultiplied();
+            format!("#{:02x}{:02x}{:02x}", r, g, b)
+        };
+        let points = |corners: &[egui::Pos2]| {
+            corners.iter().map(|p| format!("{:.1},{:.1}", p.x, p.y)).collect::<Vec<String>>().join(" ")
+        };
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {:.0} {:.0}\">",
+            self.size.x.ceil(),
+            self.size.y.ceil()
+        );
+        for mark in &self.marks {
+            svg.push_str(&match mark {
+                Mark::Cell { corners, fill } => format!(
+                    "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                    points(corners),
+                    color(*fill),
+                    color(OUTLINE),
+                    OUTLINE_WIDTH
+                ),
+                Mark::Tint { corners, fill } => format!(
+                    "<polygon points=\"{}\" fill=\"{}\" fill-opacity=\"{:.2}\"/>",
+                    points(corners),
+                    color(*fill),
+                    fill.a() as f32 / 255.0
+                ),
+                Mark::Outline { corners, width, color: outline } => format!(
+                    "<polygon points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\"/>",
+                    points(corners),
+                    color(*outline),
+                    width
+                ),
+                Mark::Edge { from, to, color: edge } => format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{}\" \
+                     stroke-linecap=\"round\"/>",
+                    from.x,
+                    from.y,
+                    to.x,
+                    to.y,
+                    color(*edge),
+                    EDGE_WIDTH
+                ),
+                Mark::Ring { center, radius, width, color: ring } => format!(
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\"/>",
+                    center.x,
+                    center.y,
+                    radius,
+                    color(*ring),
+                    width
+                ),
+            });
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    // The scene in a `[width, height]` image over `background`, scaled to fit and centered.
+    pub fn render(&self, [width, height]: [u32; 2], background: egui::Color32) -> Pixmap {
+        let mut pixmap = Pixmap::new(width.max(1), height.max(1)).expect("image size is not zero");
+        let [r, g, b, a] = background.to_srgba_unmultiplied();
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+        let scale = (width as f32 / self.size.x).min(height as f32 / self.size.y);
+        let offset = (egui::vec2(width as f32, height as f32) - self.size * scale) / 2.0;
+        let transform = Transform::from_scale(scale, scale).post_translate(offset.x, offset.y);
+        let paint = |color: egui::Color32| {
+            let mut paint = Paint::default();
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            paint.set_color_rgba8(r, g, b, a);
+            paint
+        };
+        let stroke = |width| Stroke { width, line_cap: LineCap::Round, ..Default::default() };
+        for mark in &self.marks {
+            match mark {
+                Mark::Cell { corners, fill } => {
+                    if let Some(path) = polygon(corners) {
+                        pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+                        pixmap.stroke_path(&path, &paint(OUTLINE), &stroke(OUTLINE_WIDTH), transform, None);
+                    }
+                }
+                Mark::Tint { corners, fill } => {
+                    if let Some(path) = polygon(corners) {
+                        pixmap.fill_path(&path, &paint(*fill), FillRule::Winding, transform, None);
+                    }
+                }
+                Mark::Outline { corners, width, color } => {
+                    if let Some(path) = polygon(corners) {
+                        pixmap.stroke_path(&path, &paint(*color), &stroke(*width), transform, None);
+                    }
+                }
+                Mark::Edge { from, to, color } => {
+                    let mut path = PathBuilder::new();
+                    path.move_to(from.x, from.y);
+                    path.line_to(to.x, to.y);
+                    if let Some(path) = path.finish() {
+                        pixmap.stroke_path(&path, &paint(*color), &stroke(EDGE_WIDTH), transform, None);
+                    }
+                }
+                Mark::Ring { center, radius, width, color } => {
+                    if let Some(path) = PathBuilder::from_circle(center.x, center.y, *radius) {
+                        pixmap.stroke_path(&path, &paint(*color), &stroke(*width), transform, None);
+                    }
+                }
+            }
+        }
+        pixmap
+    }
+
+    // How tall an image `width` wide fits the scene without margins.
+    fn height_for(&self, width: u32) -> u32 {
+        (width as f32 * self.size.y / self.size.x).round().max(1.0) as u32
+    }
+
+    // The scene as an egui image, e.g. for a texture.
+    pub fn image(&self, [width, height]: [usize; 2]) -> egui::ColorImage {
+        let pixmap = self.render([width as u32, height as u32], egui::Color32::TRANSPARENT);
+        egui::ColorImage::from_rgba_premultiplied([width, height], pixmap.data())
+    }
+}
+
+fn polygon(corners: &[egui::Pos2]) -> Option<tiny_skia::Path> {
+    let mut path = PathBuilder::new();
+    let (first, rest) = corners.split_first()?;
+    path.move_to(first.x, first.y);
+    for corner in rest {
+        path.line_to(corner.x, corner.y);
+    }
+    path.close();
+    path.finish()
+}
+
+// The scene as a PNG image `width` wide, on a transparent background.
+pub fn png(scene: &Scene, width: u32) -> io::Result<Vec<u8>> {
+    let size = [width, scene.height_for(width)];
+    scene.render(size, egui::Color32::TRANSPARENT).encode_png().map_err(io::Error::other)
+}
+
+// The game move by move as an animated GIF `width` wide, looping. GIFs have no partial
+// transparency, so the board is drawn on white.
+pub fn gif(record: &GameRecord, width: u16) -> io::Result<Vec<u8>> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    let mut start = record.clone();
+    start.moves.clear();
+    start.metadata.result = None;
+    let mut game = start.replay().map_err(|e| invalid(e.to_string()))?;
+    let height = Scene::new(&game.board, None).height_for(width.into()).min(u16::MAX.into()) as u16;
+    let mut encoder = gif::Encoder::new(Vec::new(), width, height, &[]).map_err(io::Error::other)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+    let mut frame = |game: &Game, delay: u16| {
+        let pixmap = Scene::of_game(game).render([width.into(), height.into()], egui::Color32::WHITE);
+        let mut pixels = pixmap.take();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)
+    };
+    frame(&game, GIF_FRAME_DELAY)?;
+    for (i, mv) in record.moves.iter().enumerate() {
+        game.play_move(*mv).map_err(|reason| invalid(RecordError::IllegalMove { number: i + 1, reason }.to_string()))?;
+        let delay = if i + 1 == record.moves.len() { GIF_LAST_FRAME_DELAY } else { GIF_FRAME_DELAY };
+        frame(&game, delay)?;
+    }
+    encoder.into_inner().map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::game::Game;
+    use coast_to_coast::record::GameMetadata;
+
+    #[test]
+    fn test_svg_draws_cells_edges_and_last_move() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 1, r: 1 }, CellState::Red);
+        let svg = Scene::new(&board, Some(Hex { q: 1, r: 1 })).svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<polygon").count(), 9);
+        assert_eq!(svg.matches("fill=\"#dc3232\"").count(), 1);
+        // The three cells along each of Red's edges have two sides on it.
+        assert_eq!(svg.matches("stroke=\"#dc3232\" stroke-width=\"5\"").count(), 12);
+        assert_eq!(svg.matches("stroke=\"#325adc\" stroke-width=\"5\"").count(), 10);
+        assert_eq!(svg.matches("<circle").count(), 1);
+    }
+
+    // Compares `scene` drawn `width` wide with tests/golden/<name>.png; run with
+    // HEX_UPDATE_GOLDEN=1 to redraw the references after a deliberate change. A channel may be
+    // off by a little, for floating point differences between machines.
+    fn assert_matches_golden(name: &str, scene: &Scene, width: u32) {
+        let png = png(scene, width).unwrap();
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("tests/golden/{}.png", name));
+        if std::env::var_os("HEX_UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &png).unwrap();
+        }
+        let expected = Pixmap::load_png(&path).unwrap();
+        let actual = Pixmap::decode_png(&png).unwrap();
+        assert_eq!((actual.width(), actual.height()), (expected.width(), expected.height()), "{}", name);
+        let off = actual.data().iter().zip(expected.data()).filter(|(a, b)| a.abs_diff(**b) > 2).count();
+        assert_eq!(off, 0, "{} channels differ from {}", off, path.display());
+    }
+
+    fn play(size: i32, moves: &str) -> Game {
+        let mut game = Game::with_size(size).with_pie_rule(false);
+        for mv in moves.split_whitespace() {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_empty_board_matches_golden_image() {
+        assert_matches_golden("empty", &Scene::of_game(&Game::with_size(11)), 400);
+    }
+
+    #[test]
+    fn test_game_in_progress_matches_golden_image() {
+        assert_matches_golden("midgame", &Scene::of_game(&play(5, "c3 b4 d2")), 240);
+    }
+
+    #[test]
+    fn test_finished_game_matches_golden_image() {
+        // Red wins along row 2; the winning chain is outlined.
+        let game = play(4, "a2 a1 b2 b1 c2 c1 d2");
+        assert!(matches!(game.state, GameState::Finished { winner: CellState::Red }));
+        assert_matches_golden("finished", &Scene::of_game(&game), 240);
+    }
+
+    #[test]
+    fn test_annotated_board_matches_golden_image() {
+        let game = play(5, "c3 c2");
+        let ownership = [(Hex { q: 2, r: 3 }, 0.8), (Hex { q: 1, r: 1 }, -0.5), (Hex { q: 3, r: 3 }, 0.2)];
+        let scene = Scene::of_game(&game).with_ownership(&ownership).with_candidates(&[Hex { q: 3, r: 1 }]);
+        assert_matches_golden("annotated", &scene, 240);
+    }
+
+    #[test]
+    fn test_gif_has_a_frame_per_move() {
+        let mut game = Game::with_size(4).with_pie_rule(false);
+        for mv in ["a1", "b2"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let gif = gif(&GameRecord::from_game(&game, &GameMetadata::default()), 120).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).unwrap();
+        assert_eq!(decoder.width(), 120);
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, [GIF_FRAME_DELAY, GIF_FRAME_DELAY, GIF_LAST_FRAME_DELAY]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Ownership estimates: which empty cells tend to end up in the winning connection.
+//!
+//! Each playout fills the rest of the board with random stones, the player to move first and
+//! one stone a turn, and finds the chain the winner joined their sides with. Counting how often
+//! each empty cell was part of that chain, and for whom, shows which areas decide the game:
+//! cells that matter to neither side stay near zero however the board fills up.
+//!
+//! ```
+//! use coast_to_coast::board::Hex;
+//! use coast_to_coast::game::Game;
+//! use coast_to_coast::ownership;
+//! use rand::SeedableRng;
+//!
+//! let mut game = Game::with_size(5);
+//! game.play_move("c3".parse().unwrap()).unwrap();
+//! let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+//! let map = ownership::estimate(&game, 200, &mut rng);
+//! // Red's centre stone makes the cells around it Red's more often than not, e.g. c2.
+//! assert!(map.balance(Hex { q: 2, r: 1 }) > 0.0);
+//! ```
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::board::{CellState, Hex};
+use crate::game::Game;
+
+/// How often each empty cell was in the winning chain, for Red and for Blue.
+pub struct Ownership {
+    playouts: u32,
+    // Per empty cell, the playouts it was in Red's and in Blue's winning chain.
+    counts: HashMap<Hex, [u32; 2]>,
+}
+
+impl Ownership {
+    pub fn playouts(&self) -> u32 {
+        self.playouts
+    }
+
+    /// How often `hex` was part of `player`'s winning chain, from 0 to 1.
+    pub fn share(&self, hex: Hex, player: CellState) -> f32 {
+        let index = match player {
+            CellState::Red => 0,
+            CellState::Blue => 1,
+            CellState::Empty | CellState::Blocked => return 0.0,
+        };
+        let count = self.counts.get(&hex).map_or(0, |counts| counts[index]);
+        count as f32 / self.playouts.max(1) as f32
+    }
+
+    /// From 1 for a cell always in Red's connection to -1 for one always in Blue's.
+    pub fn balance(&self, hex: Hex) -> f32 {
+        self.share(hex, CellState::Red) - self.share(hex, CellState::Blue)
+    }
+
+    /// Every cell that was empty, with its balance.
+    pub fn cells(&self) -> impl Iterator<Item = (Hex, f32)> + '_ {
+        self.counts.keys().map(|hex| (*hex, self.balance(*hex)))
+    }
+}
+
+/// Runs `playouts` random playouts from `game`'s position and counts where each one was won.
+pub fn estimate(game: &Game, playouts: u32, rng: &mut impl Rng) -> Ownership {
+    let mut empty = game.board.empty_cells();
+    let mut counts: HashMap<Hex, [u32; 2]> = empty.iter().map(|hex| (*hex, [0, 0])).collect();
+    let players = match game.current_player {
+        CellState::Blue => [CellState::Blue, CellState::Red],
+        _ => [CellState::Red, CellState::Blue],
+    };
+    for _ in 0..playouts {
+        let mut board = game.board.clone();
+        empty.shuffle(rng);
+        for (i, hex) in empty.iter().enumerate() {
+            board.set_cell(*hex, players[i % 2]);
+        }
+        for (index, player) in [CellState::Red, CellState::Blue].into_iter().enumerate() {
+            let Some(chain) = board.connecting_chain(player, &board.shape().goal_sides(player)) else {
+                continue;
+            };
+            for hex in chain {
+                if let Some(counts) = counts.get_mut(&hex) {
+                    counts[index] += 1;
+                }
+            }
+            break;
+        }
+    }
+    Ownership { playouts, counts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_shares_follow_the_winning_chains() {
+        // Red's a2-b2 reaches the far edge through c2 or c1; Blue's b1 and b3 have to go round.
+        let mut game = Game::with_size(3);
+        for mv in ["a2", "b1", "b2", "b3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let map = estimate(&game, 400, &mut rng);
+        assert_eq!(map.playouts(), 400);
+        assert_eq!(map.cells().count(), game.board.empty_cells().len());
+        for (hex, balance) in map.cells() {
+            // Each playout has one winner, so no cell is counted for both.
+            let shares = map.share(hex, CellState::Red) + map.share(hex, CellState::Blue);
+            assert!(shares <= 1.0 && (-1.0..=1.0).contains(&balance), "{:?}", hex);
+        }
+        // c2 and c1.
+        assert!(map.balance(Hex { q: 2, r: 1 }) > 0.0);
+        assert!(map.balance(Hex { q: 2, r: 0 }) > 0.0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::Instant;
+
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::eval;
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::record::GameMetadata;
+use eframe::egui;
+
+use crate::clock::{format_duration, GameClock};
+use crate::i18n::{player_name, resigned_text, tr, tr_args, Language};
+use crate::motion;
+use crate::renderer::BoardRenderer;
+
+pub const TOGGLE_KEY: egui::Key = egui::Key::F5;
+
+const RED: egui::Color32 = egui::Color32::from_rgb(220, 50, 50);
+const BLUE: egui::Color32 = egui::Color32::from_rgb(50, 90, 220);
+
+pub enum PresentationAction {
+    Play(Hex),
+    PieRule(bool),
+}
+
+pub struct PresentationView<'a> {
+    pub game: &'a Game,
+    pub metadata: &'a GameMetadata,
+    pub clock: &'a GameClock,
+    pub last_action: Option<&'a str>,
+    pub eval_bar: bool,
+}
+
+// Streaming / demonstration layout: nameplates with big clocks on top, the board filling the
+// window, and the last move called out underneath.
+pub fn show(
+    ctx: &egui::Context,
+    view: &PresentationView,
+    renderer: &mut BoardRenderer,
+    language: Language,
+) -> Option<PresentationAction> {
+    let mut action = None;
+    let now = Instant::now();
+    if let Some(tick) = view.clock.next_tick(now) {
+        motion::repaint_after(ctx, tick);
+    }
+
+    egui::TopBottomPanel::top("presentation_nameplates").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            nameplate(ui, view, CellState::Red, now, language);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                nameplate(ui, view, CellState::Blue, now, language);
+            });
+        });
+    });
+
+    egui::TopBottomPanel::bottom("presentation_callout").show(ctx, |ui| {
+        ui.vertical_centered(|ui| match view.game.state {
+            GameState::Finished { winner } => {
+                let text = tr_args(language, "game.winner", &[("winner", player_name(language, winner))]);
+                ui.label(egui::RichText::new(text).size(40.0).strong());
+            }
+            GameState::Resigned { winner } => {
+                ui.label(egui::RichText::new(resigned_text(language, winner)).size(40.0).strong());
+            }
+            GameState::Drawn => {
+                ui.label(egui::RichText::new(tr(language, "game.drawn")).size(40.0).strong());
+            }
+            GameState::Adjourned { .. } => {
+                ui.label(egui::RichText::new(tr(language, "game.adjourned")).size(40.0).strong());
+            }
+            GameState::WaitingForPieRuleChoice => {
+                ui.label(egui::RichText::new(tr(language, "pie_rule.prompt")).size(32.0));
+                ui.horizontal(|ui| {
+                    if ui.button(egui::RichText::new(tr(language, "pie_rule.apply")).size(24.0)).clicked() {
+                        action = Some(PresentationAction::PieRule(true));
+                    }
+                    if ui.button(egui::RichText::new(tr(language, "pie_rule.continue")).size(24.0)).clicked() {
+                        action = Some(PresentationAction::PieRule(false));
+                    }
+                });
+            }
+            GameState::InProgress => {
+                let text = view.last_action.unwrap_or_default();
+                ui.label(egui::RichText::new(text).size(40.0).strong());
+            }
+        });
+    });
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        if let Some(hex) = renderer.render_board(ui, view.game, language) {
+            action = Some(PresentationAction::Play(hex));
+        }
+    });
+
+    if view.eval_bar {
+        evaluation_bar(ctx, eval::evaluate(view.game));
+    }
+    action
+}
+
+fn nameplate(ui: &mut egui::Ui, view: &PresentationView, player: CellState, now: Instant, language: Language) {
+    let (color, name) = match player {
+        CellState::Red => (RED, &view.metadata.red),
+        _ => (BLUE, &view.metadata.blue),
+    };
+    let name = if name.is_empty() { player_name(language, player) } else { name.as_str() };
+    let to_move = !matches!(view.game.state, GameState::Finished { .. }) && view.game.current_player == player;
+    let stroke = egui::Stroke::new(if to_move { 4.0 } else { 1.0 }, color);
+
+    egui::Frame::group(ui.style()).stroke(stroke).inner_margin(12.0).show(ui, |ui| {
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new(name).size(28.0).strong().color(color));
+            let used = format_duration(view.clock.used(player, now));
+            ui.label(egui::RichText::new(used).size(56.0).monospace());
+        });
+    });
+}
+
+// Red's share of the bar grows from the bottom as Red's position improves. Drawn in the
+// foreground so it stays on top of the board.
+fn evaluation_bar(ctx: &egui::Context, red: f32) {
+    egui::Area::new(egui::Id::new("evaluation_bar"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::LEFT_CENTER, egui::vec2(8.0, 0.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            let height = ctx.content_rect().height() * 0.6;
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(20.0, height), egui::Sense::hover());
+            let painter = ui.painter();
+            painter.rect_filled(rect, 4.0, BLUE);
+            let split = rect.bottom() - rect.height() * red.clamp(0.0, 1.0);
+            let red_rect = egui::Rect::from_min_max(egui::pos2(rect.left(), split), rect.max);
+            painter.rect_filled(red_rect, 4.0, RED);
+            painter.rect_stroke(rect, 4.0, egui::Stroke::new(1.0, egui::Color32::WHITE), egui::StrokeKind::Outside);
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_no_repaints_while_idle() {
+        let ctx = egui::Context::default();
+        let mut renderer = BoardRenderer::new(&ctx);
+        let (game, metadata) = (Game::with_size(5), GameMetadata::default());
+        let mut clock = GameClock::default();
+        let mut repaint_delay = |clock: &GameClock| {
+            let view = PresentationView { game: &game, metadata: &metadata, clock, last_action: None, eval_bar: false };
+            let mut frame = || {
+                ctx.run(egui::RawInput::default(), |ctx| {
+                    show(ctx, &view, &mut renderer, Language::English);
+                })
+            };
+            // The first frame lays the panels out.
+            frame();
+            frame().viewport_output[&egui::ViewportId::ROOT].repaint_delay
+        };
+        assert_eq!(repaint_delay(&clock), Duration::MAX);
+
+        clock.switch_to(CellState::Red, Instant::now());
+        assert!(repaint_delay(&clock) <= Duration::from_secs(1));
+    }
+}
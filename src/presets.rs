@@ -0,0 +1,250 @@
+//! Match presets: an opponent, a board size, the pie rule and a time control, saved under a
+//! name such as "Blitz 9×9 vs MCTS hard", to start games from and to enter in tournaments.
+//!
+//! Presets are written as text; see `assets/presets.txt` for the format and the ones that ship
+//! built in. The user's own are kept in `<data_dir>/presets.txt`.
+//!
+//! ```
+//! use coast_to_coast::ai::GreedyPlayer;
+//! use coast_to_coast::presets;
+//! use coast_to_coast::tournament::{round_robin, Entrant};
+//!
+//! let text = "[Quick]\nopponent = easy\nsize = 4\npie_rule = no\n";
+//! let preset = presets::parse(text).unwrap().remove(0);
+//! let mut entrants = vec![preset.entrant(), Entrant::new("greedy", GreedyPlayer)];
+//! let results = round_robin(&mut entrants, 2, preset.size);
+//! assert_eq!(results.names, ["Quick", "greedy"]);
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::ai::{AiPlayer, GreedyPlayer, RandomPlayer};
+use crate::alphabeta::AlphaBetaPlayer;
+use crate::game::{Game, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE};
+use crate::mcts::{BridgePlayout, MctsPlayer};
+use crate::tournament::Entrant;
+
+// The user's presets live in `<data_dir>/presets.txt`.
+pub const PRESETS_FILE: &str = "presets.txt";
+
+const BUILTIN: &str = include_str!("../assets/presets.txt");
+
+// The app's hard and classic engines search this much per move.
+const HARD_ITERATIONS: usize = 10_000;
+const CLASSIC_TIME_LIMIT: Duration = Duration::from_secs(1);
+const CLASSIC_MAX_DEPTH: u32 = 64;
+
+/// The computer opponent, as the app's difficulty levels name them; each is its own engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    /// Random moves.
+    Easy,
+    /// The greedy one-ply player.
+    Medium,
+    /// MCTS with bridge-aware playouts.
+    Hard,
+    /// Alpha-beta with iterative deepening.
+    Classic,
+}
+
+impl Opponent {
+    pub const ALL: [Opponent; 4] = [Opponent::Easy, Opponent::Medium, Opponent::Hard, Opponent::Classic];
+
+    /// The name presets are written with.
+    pub fn key(self) -> &'static str {
+        match self {
+            Opponent::Easy => "easy",
+            Opponent::Medium => "medium",
+            Opponent::Hard => "hard",
+            Opponent::Classic => "classic",
+        }
+    }
+
+    /// The engine with the app's default search budget.
+    pub fn player(self) -> Box<dyn AiPlayer + Send> {
+        match self {
+            Opponent::Easy => Box::new(RandomPlayer::new()),
+            Opponent::Medium => Box::new(GreedyPlayer),
+            Opponent::Hard => Box::new(MctsPlayer::new(HARD_ITERATIONS, Box::new(BridgePlayout))),
+            Opponent::Classic => Box::new(AlphaBetaPlayer::new(CLASSIC_MAX_DEPTH).with_time_limit(CLASSIC_TIME_LIMIT)),
+        }
+    }
+}
+
+impl FromStr for Opponent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Opponent::ALL.into_iter().find(|opponent| opponent.key().eq_ignore_ascii_case(s.trim())).ok_or(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchPreset {
+    pub name: String,
+    pub opponent: Opponent,
+    pub size: i32,
+    pub pie_rule: bool,
+    /// As in game records, e.g. "300+5"; empty for none.
+    pub time_control: String,
+}
+
+impl MatchPreset {
+    /// A preset with the defaults for everything but its name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            opponent: Opponent::Medium,
+            size: DEFAULT_BOARD_SIZE,
+            pie_rule: true,
+            time_control: String::new(),
+        }
+    }
+
+    /// A new game on the preset's board, with its pie rule.
+    pub fn game(&self) -> Game {
+        Game::with_size(self.size).with_pie_rule(self.pie_rule)
+    }
+
+    /// The preset's opponent as a tournament entrant, under the preset's name.
+    pub fn entrant(&self) -> Entrant {
+        Entrant { name: self.name.clone(), player: self.opponent.player() }
+    }
+
+    /// The preset in the text format [`parse`] reads.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "[{}]\nopponent = {}\nsize = {}\npie_rule = {}\n",
+            self.name,
+            self.opponent.key(),
+            self.size,
+            if self.pie_rule { "yes" } else { "no" }
+        );
+        if !self.time_control.is_empty() {
+            text.push_str(&format!("time_control = {}\n", self.time_control));
+        }
+        text
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PresetError {
+    KeyWithoutName { line: usize },
+    NotAKey { line: usize },
+    UnknownKey { line: usize, key: String },
+    BadValue { line: usize, key: String, value: String },
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::KeyWithoutName { line } => write!(f, "line {}: setting before a [name]", line),
+            PresetError::NotAKey { line } => write!(f, "line {}: expected key = value", line),
+            PresetError::UnknownKey { line, key } => write!(f, "line {}: unknown key {:?}", line, key),
+            PresetError::BadValue { line, key, value } => write!(f, "line {}: bad {} {:?}", line, key, value),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+/// Reads every preset in `text`.
+pub fn parse(text: &str) -> Result<Vec<MatchPreset>, PresetError> {
+    let mut presets: Vec<MatchPreset> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            presets.push(MatchPreset::new(name.trim()));
+            continue;
+        }
+        let preset = presets.last_mut().ok_or(PresetError::KeyWithoutName { line: line_number })?;
+        let (key, value) = trimmed.split_once('=').ok_or(PresetError::NotAKey { line: line_number })?;
+        let (key, value) = (key.trim(), value.trim());
+        let bad_value = || PresetError::BadValue { line: line_number, key: key.to_string(), value: value.to_string() };
+        match key {
+            "opponent" => preset.opponent = value.parse().map_err(|_| bad_value())?,
+            "size" => {
+                preset.size = value.parse().ok().filter(|size| (1..=MAX_BOARD_SIZE).contains(size)).ok_or_else(bad_value)?
+            }
+            "pie_rule" => {
+                preset.pie_rule = match value {
+                    "yes" => true,
+                    "no" => false,
+                    _ => return Err(bad_value()),
+                }
+            }
+            "time_control" => preset.time_control = value.to_string(),
+            _ => return Err(PresetError::UnknownKey { line: line_number, key: key.to_string() }),
+        }
+    }
+    Ok(presets)
+}
+
+/// The presets that ship with the app.
+pub fn builtin() -> Vec<MatchPreset> {
+    parse(BUILTIN).expect("built-in presets parse")
+}
+
+/// The user's own presets from `<data_dir>/presets.txt`; none if it is missing or doesn't
+/// parse, with a warning for the latter.
+pub fn load_user(data_dir: &Path) -> Vec<MatchPreset> {
+    let path = data_dir.join(PRESETS_FILE);
+    let Ok(text) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    parse(&text).unwrap_or_else(|e| {
+        tracing::warn!(path = %path.display(), error = %e, "skipping unreadable presets");
+        Vec::new()
+    })
+}
+
+/// Writes the user's presets over `<data_dir>/presets.txt`.
+pub fn save_user(data_dir: &Path, presets: &[MatchPreset]) -> io::Result<()> {
+    fs::create_dir_all(data_dir)?;
+    let text: Vec<String> = presets.iter().map(MatchPreset::to_text).collect();
+    fs::write(data_dir.join(PRESETS_FILE), text.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_read_write_and_play() {
+        let builtin = builtin();
+        assert_eq!(builtin[0].name, "Blitz 9×9 vs MCTS hard");
+        assert_eq!((builtin[0].opponent, builtin[0].size, builtin[0].time_control.as_str()), (Opponent::Hard, 9, "180+2"));
+
+        let mut preset = MatchPreset::new("Mine");
+        assert_eq!(parse("[Mine]\n").unwrap(), vec![preset.clone()]);
+        preset.opponent = Opponent::Classic;
+        preset.size = 5;
+        preset.pie_rule = false;
+        preset.time_control = "60+1".to_string();
+        let dir = std::env::temp_dir().join(format!("hex-presets-test-{}", std::process::id()));
+        save_user(&dir, &[preset.clone(), MatchPreset::new("Other")]).unwrap();
+        assert_eq!(load_user(&dir), vec![preset.clone(), MatchPreset::new("Other")]);
+        fs::write(dir.join(PRESETS_FILE), "size = 9").unwrap();
+        assert!(load_user(&dir).is_empty());
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(parse("[A]\nsize = 0"), Err(PresetError::BadValue { line: 2, key: "size".into(), value: "0".into() }));
+        assert_eq!(parse("[A]\ncolour = red"), Err(PresetError::UnknownKey { line: 2, key: "colour".into() }));
+        assert_eq!(parse("[A]\nopponent"), Err(PresetError::NotAKey { line: 2 }));
+
+        let game = preset.game();
+        assert_eq!((game.board.size, game.pie_rule), (5, false));
+        let mut entrant = MatchPreset { opponent: Opponent::Medium, ..preset }.entrant();
+        assert_eq!(entrant.name, "Mine");
+        assert!(entrant.player.choose_move(&game).is_some());
+    }
+}
@@ -0,0 +1,171 @@
+//! Proves edge templates: that a stone is connected to its edge through the template's empty
+//! carrier whatever the opponent plays there, with the opponent moving first.
+//!
+//! The search tries every intrusion into the carrier and looks for an answer to each, caching
+//! positions it has settled. It stops early once the stone's chain touches the edge, or two
+//! empty edge cells so the opponent can only take one, and gives up after a node budget, as
+//! big carriers (the fourth-row templates and beyond) can take far longer than a user waits.
+//!
+//! ```
+//! use coast_to_coast::prover::{self, Proof};
+//! use coast_to_coast::templates;
+//!
+//! let ziggurat = templates::parse("[Ziggurat]\n. . . .\n . . .\n  x .").unwrap().remove(0);
+//! assert_eq!(prover::prove(&ziggurat, 100_000), Proof::Holds);
+//! // Without its last edge cell the opponent cuts it off.
+//! let short = templates::parse("[Short]\n. . .\n . . .\n  x .").unwrap().remove(0);
+//! assert_eq!(prover::prove(&short, 100_000), Proof::Fails);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::templates::Template;
+
+// Enough for every template up to the ziggurat many times over, in well under a second.
+pub const DEFAULT_BUDGET: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Proof {
+    /// The stone reaches the edge whatever the opponent does.
+    Holds,
+    /// The opponent has a way to cut it off.
+    Fails,
+    /// The search ran out of budget, or the template has more than 64 cells.
+    Unknown,
+}
+
+/// Searches at most `budget` positions for whether `template` holds. With several stones, the
+/// one furthest from the edge must reach it; the others help.
+pub fn prove(template: &Template, budget: usize) -> Proof {
+    let carrier = template.carrier();
+    let cells: Vec<(i32, i32)> = carrier.iter().chain(template.stones()).copied().collect();
+    if cells.len() > 64 {
+        return Proof::Unknown;
+    }
+    let mask = |keep: &dyn Fn(usize, (i32, i32)) -> bool| {
+        cells.iter().enumerate().filter(|(i, cell)| keep(*i, **cell)).fold(0u64, |mask, (i, _)| mask | 1 << i)
+    };
+    let neighbors = cells
+        .iter()
+        .map(|(along, depth)| {
+            let adjacent = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)].map(|(a, d)| (along + a, depth + d));
+            mask(&|_, cell| adjacent.contains(&cell))
+        })
+        .collect();
+    let target = (carrier.len()..cells.len()).max_by_key(|i| cells[*i].1).expect("a template has a stone");
+    let mut prover = Prover {
+        neighbors,
+        edge: mask(&|_, (_, depth)| depth == 0),
+        stones: mask(&|i, _| i >= carrier.len()),
+        target: 1 << target,
+        empty: mask(&|i, _| i < carrier.len()),
+        settled: HashMap::new(),
+        budget,
+    };
+    match prover.holds(0, 0) {
+        Some(true) => Proof::Holds,
+        Some(false) => Proof::Fails,
+        None => Proof::Unknown,
+    }
+}
+
+// Cells are bits in the order of the carrier, then the stones.
+struct Prover {
+    neighbors: Vec<u64>,
+    edge: u64,
+    stones: u64,
+    target: u64,
+    empty: u64,
+    // Positions as (cells the owner filled, cells the opponent took) already decided.
+    settled: HashMap<(u64, u64), bool>,
+    budget: usize,
+}
+
+impl Prover {
+    // The target stone's chain through the cells in `passable`.
+    fn chain(&self, passable: u64) -> u64 {
+        let mut chain = self.target;
+        loop {
+            let mut grown = chain;
+            let mut cells = chain;
+            while cells != 0 {
+                grown |= self.neighbors[cells.trailing_zeros() as usize] & passable;
+                cells &= cells - 1;
+            }
+            if grown == chain {
+                return chain;
+            }
+            chain = grown;
+        }
+    }
+
+    fn touching(&self, cells: u64) -> u64 {
+        let mut touching = 0;
+        let mut rest = cells;
+        while rest != 0 {
+            touching |= self.neighbors[rest.trailing_zeros() as usize];
+            rest &= rest - 1;
+        }
+        touching
+    }
+
+    // Whether the owner holds the connection with the opponent to move; None once out of budget.
+    fn holds(&mut self, own: u64, taken: u64) -> Option<bool> {
+        let free = self.empty & !own & !taken;
+        let chain = self.chain(own | self.stones);
+        if chain & self.edge != 0 || (self.touching(chain) & free & self.edge).count_ones() >= 2 {
+            return Some(true);
+        }
+        if self.chain(own | self.stones | free) & self.edge == 0 {
+            return Some(false);
+        }
+        if let Some(holds) = self.settled.get(&(own, taken)) {
+            return Some(*holds);
+        }
+        self.budget = self.budget.checked_sub(1)?;
+
+        let mut holds = true;
+        let mut intrusions = free;
+        'intrusions: while intrusions != 0 {
+            let intrusion = 1 << intrusions.trailing_zeros();
+            intrusions &= intrusions - 1;
+            let free = free & !intrusion;
+            // Answers next to the intrusion first; they are the likeliest to work.
+            let near = self.touching(intrusion) & free;
+            for answers in [near, free & !near] {
+                let mut answers = answers;
+                while answers != 0 {
+                    let answer = 1 << answers.trailing_zeros();
+                    answers &= answers - 1;
+                    if self.holds(own | answer, taken | intrusion)? {
+                        continue 'intrusions;
+                    }
+                }
+            }
+            holds = false;
+            break;
+        }
+        self.settled.insert((own, taken), holds);
+        Some(holds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::{builtin, parse};
+
+    #[test]
+    fn test_builtin_templates_hold() {
+        for template in builtin() {
+            assert_eq!(prove(&template, DEFAULT_BUDGET), Proof::Holds, "{}", template.name);
+        }
+        // A lone edge cell is one intrusion from nothing; a stone on the edge needs no carrier.
+        let templates = parse("[Half bridge]\n.\n x\n[On the edge]\nx").unwrap();
+        assert_eq!(prove(&templates[0], DEFAULT_BUDGET), Proof::Fails);
+        assert_eq!(prove(&templates[1], DEFAULT_BUDGET), Proof::Holds);
+        // Too big to settle in a handful of positions.
+        let ziggurat = &builtin()[1];
+        assert_eq!(prove(ziggurat, 2), Proof::Unknown);
+    }
+}
@@ -0,0 +1,208 @@
+//! Python bindings (`pyhex` feature), so the rules engine can serve as a fast Hex environment.
+//!
+//! Build with `maturin develop` (see `pyproject.toml`) and then:
+//!
+//! ```python
+//! import pyhex
+//! game = pyhex.Game(7)
+//! ai = pyhex.RandomAi(seed=1)
+//! while not game.is_over():
+//!     if game.waiting_for_pie_rule():
+//!         game.decide_pie_rule(ai.decide_pie_rule(game))
+//!     else:
+//!         game.play(*ai.choose_move(game))
+//! print(game.winner())
+//! ```
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::ai::{AiPlayer, RandomPlayer};
+use crate::board::{Board, CellState, Hex};
+use crate::game::{Game, GameState, DEFAULT_BOARD_SIZE};
+
+fn cell_code(state: CellState) -> i32 {
+    match state {
+        CellState::Empty => 0,
+        CellState::Red => 1,
+        CellState::Blue => 2,
+        CellState::Blocked => 3,
+    }
+}
+
+fn player_name(state: CellState) -> &'static str {
+    match state {
+        CellState::Red => "red",
+        CellState::Blue => "blue",
+        CellState::Empty => "empty",
+        CellState::Blocked => "blocked",
+    }
+}
+
+/// A Hex board. Cells are addressed by axial `(q, r)`; values are 0 (empty), 1 (red), 2 (blue),
+/// 3 (blocked).
+#[pyclass(name = "Board")]
+#[derive(Clone)]
+pub struct PyBoard {
+    inner: Board,
+}
+
+#[pymethods]
+impl PyBoard {
+    #[new]
+    #[pyo3(signature = (size = DEFAULT_BOARD_SIZE))]
+    fn new(size: i32) -> Self {
+        Self { inner: Board::new(size) }
+    }
+
+    #[getter]
+    fn size(&self) -> i32 {
+        self.inner.size
+    }
+
+    fn get(&self, q: i32, r: i32) -> PyResult<i32> {
+        self.inner
+            .get_cell(&Hex { q, r })
+            .map(|state| cell_code(*state))
+            .ok_or_else(|| PyValueError::new_err("Hex is out of bounds"))
+    }
+
+    /// Rows indexed `[r][q]`.
+    fn to_list(&self) -> Vec<Vec<i32>> {
+        (0..self.inner.size)
+            .map(|r| {
+                (0..self.inner.size)
+                    .map(|q| self.inner.get_cell(&Hex { q, r }).map_or(0, |s| cell_code(*s)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn empty_cells(&self) -> Vec<(i32, i32)> {
+        self.inner.empty_cells().iter().map(|h| (h.q, h.r)).collect()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+}
+
+/// A game in progress, including the pie-rule decision after the first move.
+#[pyclass(name = "Game")]
+#[derive(Clone)]
+pub struct PyGame {
+    inner: Game,
+}
+
+#[pymethods]
+impl PyGame {
+    #[new]
+    #[pyo3(signature = (size = DEFAULT_BOARD_SIZE))]
+    fn new(size: i32) -> Self {
+        Self { inner: Game::with_size(size) }
+    }
+
+    /// Places a stone for the current player. Raises `ValueError` on an illegal move.
+    fn play(&mut self, q: i32, r: i32) -> PyResult<()> {
+        let hex = Hex { q, r };
+        if self.inner.state != GameState::InProgress {
+            return Err(PyValueError::new_err("Game is not accepting moves"));
+        }
+        match self.inner.board.get_cell(&hex) {
+            None => Err(PyValueError::new_err("Hex is out of bounds")),
+            Some(CellState::Empty) => {
+                self.inner.handle_click(hex);
+                Ok(())
+            }
+            Some(_) => Err(PyValueError::new_err("Cell is not empty")),
+        }
+    }
+
+    fn decide_pie_rule(&mut self, swap: bool) -> PyResult<()> {
+        if !self.waiting_for_pie_rule() {
+            return Err(PyValueError::new_err("No pie-rule decision is pending"));
+        }
+        self.inner.handle_pie_rule_decision(swap);
+        Ok(())
+    }
+
+    fn legal_moves(&self) -> Vec<(i32, i32)> {
+        self.inner.legal_moves().map(|h| (h.q, h.r)).collect()
+    }
+
+    fn waiting_for_pie_rule(&self) -> bool {
+        self.inner.state == GameState::WaitingForPieRuleChoice
+    }
+
+    fn is_over(&self) -> bool {
+        matches!(self.inner.state, GameState::Finished { .. })
+    }
+
+    /// "red", "blue" or `None` while the game is running.
+    fn winner(&self) -> Option<&'static str> {
+        match self.inner.state {
+            GameState::Finished { winner } => Some(player_name(winner)),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn current_player(&self) -> &'static str {
+        player_name(self.inner.current_player)
+    }
+
+    #[getter]
+    fn turn_count(&self) -> u32 {
+        self.inner.turn_count
+    }
+
+    #[getter]
+    fn board(&self) -> PyBoard {
+        PyBoard { inner: self.inner.board.clone() }
+    }
+
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.board.to_string()
+    }
+}
+
+/// The crate's random baseline AI.
+#[pyclass(name = "RandomAi", unsendable)]
+pub struct PyRandomAi {
+    inner: RandomPlayer,
+}
+
+#[pymethods]
+impl PyRandomAi {
+    #[new]
+    #[pyo3(signature = (seed = None, swap_probability = 0.5))]
+    fn new(seed: Option<u64>, swap_probability: f64) -> Self {
+        let player = match seed {
+            Some(seed) => RandomPlayer::seeded(seed),
+            None => RandomPlayer::new(),
+        };
+        Self { inner: player.with_swap_probability(swap_probability) }
+    }
+
+    fn choose_move(&mut self, game: &PyGame) -> Option<(i32, i32)> {
+        self.inner.choose_move(&game.inner).map(|h| (h.q, h.r))
+    }
+
+    fn decide_pie_rule(&mut self, game: &PyGame) -> bool {
+        self.inner.decide_pie_rule(&game.inner)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "pyhex")]
+fn pyhex(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBoard>()?;
+    m.add_class::<PyGame>()?;
+    m.add_class::<PyRandomAi>()?;
+    m.add("DEFAULT_BOARD_SIZE", DEFAULT_BOARD_SIZE)?;
+    Ok(())
+}
@@ -0,0 +1,1007 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::ai::SearchTelemetry;
+use crate::board::{CellState, Hex};
+use crate::game::{Game, GameState, TurnStructure, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE};
+use crate::notation::{Move, ParseMoveError};
+
+// PGN-style information about a game, edited by the user and stored with saved games.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameMetadata {
+    pub red: String,
+    pub blue: String,
+    pub event: String,
+    pub date: String,
+    pub round: String,
+    pub result: Option<GameResult>,
+    pub time_control: String,
+    // The user's own labels, e.g. "study" or "blitz", for finding games again.
+    pub tags: Vec<String>,
+}
+
+// How a game ended, or that it was adjourned to be finished later. Draws and adjournments are
+// agreed by the players in match play; the board only ever produces wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    Win(CellState),
+    // A win because the other player resigned.
+    Resignation(CellState),
+    Draw,
+    Adjourned,
+}
+
+impl GameResult {
+    pub fn of(state: GameState) -> Option<Self> {
+        match state {
+            GameState::Finished { winner } => Some(GameResult::Win(winner)),
+            GameState::Resigned { winner } => Some(GameResult::Resignation(winner)),
+            GameState::Drawn => Some(GameResult::Draw),
+            GameState::Adjourned { .. } => Some(GameResult::Adjourned),
+            GameState::InProgress | GameState::WaitingForPieRuleChoice => None,
+        }
+    }
+
+    // The `Result` tag for this result.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            GameResult::Win(CellState::Red) => "Red",
+            GameResult::Win(CellState::Blue) => "Blue",
+            GameResult::Resignation(CellState::Red) => "Red+Resign",
+            GameResult::Resignation(CellState::Blue) => "Blue+Resign",
+            GameResult::Draw => "Draw",
+            GameResult::Adjourned => "Adjourned",
+            // Not a result a record can hold.
+            GameResult::Win(_) | GameResult::Resignation(_) => "*",
+        }
+    }
+
+    // Reads a tag written by `tag`; "*" and unknown tags are None.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "Red" => Some(GameResult::Win(CellState::Red)),
+            "Blue" => Some(GameResult::Win(CellState::Blue)),
+            "Red+Resign" => Some(GameResult::Resignation(CellState::Red)),
+            "Blue+Resign" => Some(GameResult::Resignation(CellState::Blue)),
+            "Draw" => Some(GameResult::Draw),
+            "Adjourned" => Some(GameResult::Adjourned),
+            _ => None,
+        }
+    }
+}
+
+impl GameMetadata {
+    pub fn result_tag(&self) -> &'static str {
+        self.result.map_or("*", |result| result.tag())
+    }
+}
+
+// The record format `to_text` writes, in its Format tag. Records from before the tag are
+// format 1; `parse` reads them as they were meant (see `migrate`).
+const FORMAT: u32 = 3;
+
+// A plain-text game record: a header of `[Tag "value"]` lines followed by numbered moves,
+//
+//   [Format "3"]
+//   [Red "Alice"]
+//   [Blue "Bob"]
+//   [Event "Club night"]
+//   [Round "3"]
+//   [Size "11"]
+//   [Date "2026.10.15"]
+//   [Result "Red"]        (or "Blue", "Red+Resign", "Blue+Resign", "Draw", "Adjourned", "*")
+//   [TimeControl "300+5"]
+//   [Tags "study, blitz"]
+//   [Blocked "b3 c1"]
+//   [Turns "1-2"]
+//   [Passing "allowed"]
+//   [PieRule "off"]
+//   [Swap "recolour"]
+//
+//   1. c5 {4.2s}
+//   2. swap {12.0s}
+//   3. d4 {0.9s} {nodes=10000 ms=874 depth=14 ponder=yes} {"the only move"}
+//
+// The whole game is here: who played and how it ended, the starting position (Size and
+// Blocked), the rules, then each action with what is known about it. Autosaves, saved games,
+// bookmarks, the journal's compaction and SGF export all go through this one struct.
+//
+// Event, Round, TimeControl, Tags, Blocked (the board's permanently blocked cells), Turns (stones
+// on the first turn and on later turns), Passing and PieRule are only written when they differ
+// from standard Hex; the last four are for variants. A `{...s}` comment after a move is
+// the time taken for it, a `{nodes=...}` comment the engine's telemetry for it (with `tt=`
+// for the table hit rate, in percent, when the engine has a table), and a quoted comment the
+// user's annotation, with `\`, `"` and `}` escaped by a backslash and line breaks as `\n`;
+// other comments are ignored.
+//
+// Format 2 added the Format tag and annotations. Format 3 made the swap Blue's whole turn, with
+// Red's stone mirrored (see `Game::swapped_cell`); before it the swap recoloured the stone where
+// it stood and Blue placed next. Older games with a swap keep that rule, written as Swap
+// "recolour" (see `TurnStructure::legacy_swap`). Tags a reader does not know are skipped, so a
+// newer record still loads with what this version understands of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub metadata: GameMetadata,
+    pub size: i32,
+    pub moves: Vec<Move>,
+    // Thinking time per move, aligned with `moves`; None where it was not recorded.
+    pub move_times: Vec<Option<Duration>>,
+    // Engine telemetry per move, aligned with `moves`; None for moves no engine searched.
+    pub telemetry: Vec<Option<SearchTelemetry>>,
+    // The user's comments per move, aligned with `moves`.
+    pub annotations: Vec<Option<String>>,
+    pub blocked: Vec<Hex>,
+    pub turns: TurnStructure,
+    pub allow_pass: bool,
+    pub pie_rule: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RecordError {
+    MalformedTag { line: usize },
+    InvalidSize { line: usize },
+    InvalidTurns { line: usize },
+    InvalidMove { line: usize, error: ParseMoveError },
+    IllegalMove { number: usize, reason: &'static str },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::MalformedTag { line } => write!(f, "line {}: malformed tag", line),
+            RecordError::InvalidSize { line } => write!(f, "line {}: invalid board size", line),
+            RecordError::InvalidTurns { line } => write!(f, "line {}: invalid turn structure", line),
+            RecordError::InvalidMove { line, error } => write!(f, "line {}: {}", line, error),
+            RecordError::IllegalMove { number, reason } => write!(f, "move {}: {}", number, reason),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl GameRecord {
+    // A finished, drawn or adjourned game's result overrides whatever the metadata carried.
+    pub fn from_game(game: &Game, metadata: &GameMetadata) -> Self {
+        let mut metadata = metadata.clone();
+        if let Some(result) = GameResult::of(game.state) {
+            metadata.result = Some(result);
+        }
+        Self {
+            metadata,
+            size: game.board.size,
+            moves: game.history.clone(),
+            move_times: vec![None; game.history.len()],
+            telemetry: vec![None; game.history.len()],
+            annotations: vec![None; game.history.len()],
+            blocked: game.board.blocked_cells(),
+            turns: game.turn_structure,
+            allow_pass: game.allow_pass,
+            pie_rule: game.pie_rule,
+        }
+    }
+
+    // Attaches per-move times, dropping extras and leaving missing ones unknown.
+    pub fn with_move_times(mut self, times: &[Option<Duration>]) -> Self {
+        self.move_times = (0..self.moves.len()).map(|i| times.get(i).copied().flatten()).collect();
+        self
+    }
+
+    // Attaches engine telemetry the same way.
+    pub fn with_telemetry(mut self, telemetry: &[Option<SearchTelemetry>]) -> Self {
+        self.telemetry = (0..self.moves.len()).map(|i| telemetry.get(i).copied().flatten()).collect();
+        self
+    }
+
+    // Attaches annotations the same way; blank ones are dropped.
+    pub fn with_annotations(mut self, annotations: &[Option<String>]) -> Self {
+        self.annotations = (0..self.moves.len())
+            .map(|i| annotations.get(i).cloned().flatten().filter(|text| !text.trim().is_empty()))
+            .collect();
+        self
+    }
+
+    // One row per move for spreadsheets and engine developers; telemetry columns are empty
+    // where no engine searched.
+    pub fn telemetry_csv(&self) -> String {
+        let mut csv = "move,player,notation,seconds,nodes,engine_ms,nps,depth,ponder_hit,tt_hit_rate\n".to_string();
+        let movers = self.turns.movers(&self.moves);
+        for (i, mv) in self.moves.iter().enumerate() {
+            let seconds = self.move_times.get(i).copied().flatten().map(|time| format!("{:.3}", time.as_secs_f64()));
+            let mut row = vec![
+                (i + 1).to_string(),
+                movers.get(i).map(|player| format!("{:?}", player)).unwrap_or_default(),
+                mv.to_string(),
+                seconds.unwrap_or_default(),
+            ];
+            match self.telemetry.get(i).copied().flatten() {
+                Some(telemetry) => row.extend([
+                    telemetry.nodes.to_string(),
+                    telemetry.elapsed.as_millis().to_string(),
+                    format!("{:.0}", telemetry.nodes_per_second()),
+                    telemetry.depth.to_string(),
+                    telemetry.ponder_hit.to_string(),
+                    telemetry.table_hit_rate.map(|rate| format!("{:.3}", rate)).unwrap_or_default(),
+                ]),
+                None => row.extend(std::iter::repeat_n(String::new(), 6)),
+            }
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn to_text(&self) -> String {
+        let metadata = &self.metadata;
+        let mut text = format!("[Format \"{}\"]\n[Red \"{}\"]\n[Blue \"{}\"]\n", FORMAT, metadata.red, metadata.blue);
+        if !metadata.event.is_empty() {
+            text.push_str(&format!("[Event \"{}\"]\n", metadata.event));
+        }
+        if !metadata.round.is_empty() {
+            text.push_str(&format!("[Round \"{}\"]\n", metadata.round));
+        }
+        text.push_str(&format!(
+            "[Size \"{}\"]\n[Date \"{}\"]\n[Result \"{}\"]\n",
+            self.size,
+            metadata.date,
+            metadata.result_tag()
+        ));
+        if !metadata.time_control.is_empty() {
+            text.push_str(&format!("[TimeControl \"{}\"]\n", metadata.time_control));
+        }
+        if !metadata.tags.is_empty() {
+            text.push_str(&format!("[Tags \"{}\"]\n", metadata.tags.join(", ")));
+        }
+        if !self.blocked.is_empty() {
+            let cells: Vec<String> = self.blocked.iter().map(Hex::notation).collect();
+            text.push_str(&format!("[Blocked \"{}\"]\n", cells.join(" ")));
+        }
+        if (self.turns.first, self.turns.then) != (1, 1) {
+            text.push_str(&format!("[Turns \"{}-{}\"]\n", self.turns.first, self.turns.then));
+        }
+        if self.allow_pass {
+            text.push_str("[Passing \"allowed\"]\n");
+        }
+        if !self.pie_rule {
+            text.push_str("[PieRule \"off\"]\n");
+        }
+        if self.turns.legacy_swap {
+            text.push_str("[Swap \"recolour\"]\n");
+        }
+        text.push('\n');
+        for (i, mv) in self.moves.iter().enumerate() {
+            text.push_str(&format!("{}. {}", i + 1, mv));
+            if let Some(time) = self.move_times.get(i).copied().flatten() {
+                text.push_str(&format!(" {{{:.1}s}}", time.as_secs_f64()));
+            }
+            if let Some(telemetry) = self.telemetry.get(i).copied().flatten() {
+                text.push_str(&format!(" {{{}}}", telemetry_comment(&telemetry)));
+            }
+            if let Some(annotation) = self.annotations.get(i).and_then(Option::as_deref) {
+                text.push_str(&format!(" {{\"{}\"}}", escape_annotation(annotation)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    // Reads a record written by `to_text`. Move numbers are optional and moves may share a
+    // line, so hand-typed lists such as "c5 swap d4" are accepted too. Moves are checked
+    // against the board size but not replayed; see `replay` for that.
+    pub fn parse(text: &str) -> Result<Self, RecordError> {
+        let mut record = GameRecord {
+            metadata: GameMetadata::default(),
+            size: DEFAULT_BOARD_SIZE,
+            moves: Vec::new(),
+            move_times: Vec::new(),
+            telemetry: Vec::new(),
+            annotations: Vec::new(),
+            blocked: Vec::new(),
+            turns: TurnStructure::STANDARD,
+            allow_pass: false,
+            pie_rule: true,
+        };
+        let mut format = 1;
+        let mut move_lines = Vec::new();
+        // Checked against the size once all tags are read, as Size may come later.
+        let mut blocked_tag = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                let (tag, value) = parse_tag(line).ok_or(RecordError::MalformedTag { line: line_number })?;
+                let metadata = &mut record.metadata;
+                match tag {
+                    // A format this version can't read is a newer one; what it shares still loads.
+                    "Format" => format = value.parse().unwrap_or(FORMAT),
+                    "Red" => metadata.red = value.to_string(),
+                    "Blue" => metadata.blue = value.to_string(),
+                    "Event" => metadata.event = value.to_string(),
+                    "Round" => metadata.round = value.to_string(),
+                    "Date" => metadata.date = value.to_string(),
+                    "TimeControl" => metadata.time_control = value.to_string(),
+                    "Tags" => metadata.tags = parse_tags(value),
+                    "Passing" => record.allow_pass = value == "allowed",
+                    "PieRule" => record.pie_rule = value != "off",
+                    "Swap" => record.turns.legacy_swap = value == "recolour",
+                    "Blocked" => blocked_tag = Some((line_number, value)),
+                    "Turns" => {
+                        let (first, then) = value.split_once('-').unwrap_or((value, ""));
+                        record.turns = match (first.parse(), then.parse()) {
+                            (Ok(first), Ok(then)) if first > 0 && then > 0 => TurnStructure { first, then, ..record.turns },
+                            _ => return Err(RecordError::InvalidTurns { line: line_number }),
+                        };
+                    }
+                    "Size" => {
+                        record.size = value
+                            .parse()
+                            .ok()
+                            .filter(|size| (1..=MAX_BOARD_SIZE).contains(size))
+                            .ok_or(RecordError::InvalidSize { line: line_number })?;
+                    }
+                    "Result" => {
+                        metadata.result = GameResult::from_tag(value);
+                    }
+                    _ => {} // Unknown tags are ignored so newer records still load.
+                }
+            } else {
+                move_lines.push((line_number, line));
+            }
+        }
+
+        if let Some((line_number, value)) = blocked_tag {
+            for cell in value.split_whitespace() {
+                match Move::parse_for_size(cell, record.size) {
+                    Ok(Move::Place(hex)) => record.blocked.push(hex),
+                    Ok(Move::Swap | Move::Pass) => return Err(RecordError::MalformedTag { line: line_number }),
+                    Err(error) => return Err(RecordError::InvalidMove { line: line_number, error }),
+                }
+            }
+        }
+
+        for (line_number, line) in move_lines {
+            for (piece, is_comment) in split_comments(line) {
+                if is_comment {
+                    if let (Some(time), Some(last)) = (parse_move_time(piece), record.move_times.last_mut()) {
+                        *last = Some(time);
+                    }
+                    if let (Some(telemetry), Some(last)) = (parse_telemetry(piece), record.telemetry.last_mut()) {
+                        *last = Some(telemetry);
+                    }
+                    if let (Some(annotation), Some(last)) = (parse_annotation(piece), record.annotations.last_mut()) {
+                        *last = Some(annotation);
+                    }
+                    continue;
+                }
+                for token in piece.split_whitespace() {
+                    // Strip move numbers, whether separate ("12.") or attached ("12.c5").
+                    let token = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+                    if token.is_empty() {
+                        continue;
+                    }
+                    let mv = Move::parse_for_size(token, record.size)
+                        .map_err(|error| RecordError::InvalidMove { line: line_number, error })?;
+                    record.moves.push(mv);
+                    record.move_times.push(None);
+                    record.telemetry.push(None);
+                    record.annotations.push(None);
+                }
+            }
+        }
+        Ok(migrate(record, format))
+    }
+
+    // Plays the moves through the rules engine, so only legal games load.
+    pub fn replay(&self) -> Result<Game, RecordError> {
+        let mut game = Game::with_size(self.size)
+            .with_pie_rule(self.pie_rule)
+            .with_turn_structure(self.turns)
+            .with_pass(self.allow_pass);
+        for hex in &self.blocked {
+            game.board.set_cell(*hex, CellState::Blocked);
+        }
+        for (i, mv) in self.moves.iter().enumerate() {
+            game.play_move(*mv)
+                .map_err(|reason| RecordError::IllegalMove { number: i + 1, reason })?;
+        }
+        // Agreed results are not on the board, so they come from the Result tag.
+        match self.metadata.result {
+            Some(GameResult::Draw) => game.agree_draw().ok(),
+            Some(GameResult::Resignation(CellState::Red)) => game.resign(CellState::Blue).ok(),
+            Some(GameResult::Resignation(_)) => game.resign(CellState::Red).ok(),
+            Some(GameResult::Adjourned) => game.adjourn().ok(),
+            _ => None,
+        };
+        Ok(game)
+    }
+}
+
+// Why a record does not stand up to replay.
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    IllegalMove { number: usize, reason: &'static str },
+    // The Result tag says one thing, the moves another; None is an unfinished game.
+    ResultMismatch { recorded: Option<GameResult>, actual: Option<GameResult> },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::IllegalMove { number, reason } => write!(f, "move {}: {}", number, reason),
+            ReplayError::ResultMismatch { recorded, actual } => write!(
+                f,
+                "the record gives the result as {} but the moves end in {}",
+                result_name(*recorded),
+                result_name(*actual)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+fn result_name(result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::Win(CellState::Red)) => "a win for Red",
+        Some(GameResult::Win(_)) => "a win for Blue",
+        Some(GameResult::Resignation(CellState::Red)) => "a win for Red by resignation",
+        Some(GameResult::Resignation(_)) => "a win for Blue by resignation",
+        Some(GameResult::Draw) => "a draw",
+        Some(GameResult::Adjourned) => "an adjournment",
+        None => "an unfinished game",
+    }
+}
+
+// Checks a record from elsewhere before trusting it: every move must be legal under its rules,
+// and the Result tag must be what the moves lead to. Draws and adjournments are agreed off the
+// board, so they only need a game that had not been won.
+pub fn verify_record(record: &GameRecord) -> Result<(), ReplayError> {
+    let game = record.replay().map_err(|error| match error {
+        RecordError::IllegalMove { number, reason } => ReplayError::IllegalMove { number, reason },
+        other => unreachable!("replay only fails on moves, got {:?}", other),
+    })?;
+    let actual = GameResult::of(game.state);
+    if actual != record.metadata.result {
+        return Err(ReplayError::ResultMismatch { recorded: record.metadata.result, actual });
+    }
+    Ok(())
+}
+
+// Why a pasted move list does not replay, with the token at fault and its place in the list,
+// counting moves from 1.
+#[derive(Debug, PartialEq)]
+pub enum MoveListError {
+    Empty,
+    Unreadable { number: usize, token: String, error: ParseMoveError },
+    Illegal { number: usize, token: String, reason: &'static str },
+}
+
+impl fmt::Display for MoveListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveListError::Empty => write!(f, "no moves found"),
+            MoveListError::Unreadable { number, token, error } => write!(f, "move {} \"{}\": {}", number, token, error),
+            MoveListError::Illegal { number, token, reason } => write!(f, "move {} \"{}\": {}", number, token, reason),
+        }
+    }
+}
+
+impl std::error::Error for MoveListError {}
+
+// Plays a bare list of moves, one per line or separated by spaces, onto `game`, which sets the
+// size and rules. Move numbers are skipped as `GameRecord::parse` skips them; tags and comments
+// are not moves and are reported as such.
+pub fn replay_move_list(mut game: Game, text: &str) -> Result<Game, MoveListError> {
+    let mut number = 0;
+    for token in text.split_whitespace() {
+        let mv = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+        if mv.is_empty() {
+            continue;
+        }
+        number += 1;
+        let token = token.to_string();
+        let mv = Move::parse_for_size(mv, game.board.size)
+            .map_err(|error| MoveListError::Unreadable { number, token: token.clone(), error })?;
+        game.play_move(mv).map_err(|reason| MoveListError::Illegal { number, token, reason })?;
+    }
+    if number == 0 {
+        return Err(MoveListError::Empty);
+    }
+    Ok(game)
+}
+
+// Brings a record read in an older format up to the current one.
+fn migrate(mut record: GameRecord, format: u32) -> GameRecord {
+    if format < 2 {
+        // Format 1 had no annotations: a quoted comment was the user's own note in the file,
+        // which that version's readers skipped, so it still is.
+        record.annotations.fill(None);
+    }
+    if format < 3 && record.moves.contains(&Move::Swap) {
+        // Blue kept the move after swapping, and the stone stayed on its cell.
+        record.turns.legacy_swap = true;
+    }
+    record
+}
+
+// Comma-separated tags as typed, trimmed, without blanks or repeats.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|known| known == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+fn parse_tag(line: &str) -> Option<(&str, &str)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (tag, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((tag, value))
+}
+
+// Splits a move line into plain text and `{comment}` pieces, in order. A backslash escapes the
+// character after it within a comment. An unclosed comment runs to the end of the line.
+fn split_comments(line: &str) -> Vec<(&str, bool)> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('{') {
+        pieces.push((&rest[..open], false));
+        let inner = &rest[open + 1..];
+        let mut escaped = false;
+        let close = inner
+            .char_indices()
+            .find(|&(_, c)| {
+                let close = c == '}' && !escaped;
+                escaped = c == '\\' && !escaped;
+                close
+            })
+            .map_or(inner.len(), |(close, _)| close);
+        pieces.push((&inner[..close], true));
+        rest = inner.get(close + 1..).unwrap_or("");
+    }
+    pieces.push((rest, false));
+    pieces
+}
+
+// "12.4s" to a duration, kept to the millisecond.
+fn parse_move_time(comment: &str) -> Option<Duration> {
+    let seconds: f64 = comment.trim().strip_suffix('s')?.parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_millis((seconds * 1000.0).round() as u64))
+}
+
+fn escape_annotation(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.trim().chars() {
+        match c {
+            '\\' | '"' | '}' => escaped.extend(['\\', c]),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Reads a quoted comment written by `to_text`.
+fn parse_annotation(comment: &str) -> Option<String> {
+    let quoted = comment.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut text = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some(escaped) => text.push(escaped),
+                None => {}
+            },
+            c => text.push(c),
+        }
+    }
+    Some(text)
+}
+
+fn telemetry_comment(telemetry: &SearchTelemetry) -> String {
+    let mut comment = format!(
+        "nodes={} ms={} depth={} ponder={}",
+        telemetry.nodes,
+        telemetry.elapsed.as_millis(),
+        telemetry.depth,
+        if telemetry.ponder_hit { "yes" } else { "no" }
+    );
+    if let Some(rate) = telemetry.table_hit_rate {
+        comment.push_str(&format!(" tt={:.1}", rate * 100.0));
+    }
+    comment
+}
+
+// Reads what `telemetry_comment` wrote; unknown keys are skipped.
+fn parse_telemetry(comment: &str) -> Option<SearchTelemetry> {
+    let comment = comment.trim();
+    if !comment.starts_with("nodes=") {
+        return None;
+    }
+    let mut telemetry = SearchTelemetry::default();
+    for pair in comment.split_whitespace() {
+        let (key, value) = pair.split_once('=')?;
+        match key {
+            "nodes" => telemetry.nodes = value.parse().ok()?,
+            "ms" => telemetry.elapsed = Duration::from_millis(value.parse().ok()?),
+            "depth" => telemetry.depth = value.parse().ok()?,
+            "ponder" => telemetry.ponder_hit = value == "yes",
+            "tt" => telemetry.table_hit_rate = Some(value.parse::<f32>().ok()? / 100.0),
+            _ => {}
+        }
+    }
+    Some(telemetry)
+}
+
+// Today's UTC date as "YYYY.MM.DD", the format used by the Date tag.
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+// Days since 1970-01-01 to a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Hex;
+
+    fn sample_game() -> Game {
+        let mut game = Game::with_size(3);
+        for mv in ["b2", "swap", "a1", "a2", "c1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        game
+    }
+
+    fn sample_metadata() -> GameMetadata {
+        GameMetadata {
+            red: "Alice".to_string(),
+            blue: "Bob".to_string(),
+            date: "2026.10.15".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_text() {
+        let record = GameRecord::from_game(&sample_game(), &sample_metadata());
+        assert_eq!(
+            record.to_text(),
+            "[Format \"3\"]\n[Red \"Alice\"]\n[Blue \"Bob\"]\n[Size \"3\"]\n[Date \"2026.10.15\"]\n[Result \"*\"]\n\n\
+             1. b2\n2. swap\n3. a1\n4. a2\n5. c1\n"
+        );
+    }
+
+    #[test]
+    fn test_optional_tags_round_trip() {
+        let metadata = GameMetadata {
+            event: "Club night".to_string(),
+            round: "3".to_string(),
+            time_control: "300+5".to_string(),
+            result: Some(GameResult::Win(CellState::Blue)),
+            tags: parse_tags(" study,blitz, , study"),
+            ..sample_metadata()
+        };
+        let record = GameRecord::from_game(&sample_game(), &metadata);
+        let text = record.to_text();
+        assert!(text.contains("[Event \"Club night\"]\n[Round \"3\"]\n"));
+        assert!(text.contains("[TimeControl \"300+5\"]\n[Tags \"study, blitz\"]\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap().metadata, metadata);
+    }
+
+    #[test]
+    fn test_finished_game_sets_result() {
+        let mut game = Game::with_size(2);
+        for mv in ["a1", "a2", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        assert_eq!(record.metadata.result, Some(GameResult::Win(CellState::Red)));
+    }
+
+    #[test]
+    fn test_agreed_results_round_trip() {
+        let mut game = sample_game();
+        game.adjourn().unwrap();
+        let text = GameRecord::from_game(&game, &sample_metadata()).to_text();
+        assert!(text.contains("[Result \"Adjourned\"]\n"));
+        let mut resumed = GameRecord::parse(&text).unwrap().replay().unwrap();
+        assert_eq!(resumed.state, GameState::Adjourned { pie_rule_pending: false });
+        resumed.resume();
+        assert_eq!(resumed.state, GameState::InProgress);
+
+        resumed.agree_draw().unwrap();
+        let record = GameRecord::parse(&GameRecord::from_game(&resumed, &sample_metadata()).to_text()).unwrap();
+        assert_eq!(record.metadata.result, Some(GameResult::Draw));
+        assert_eq!(record.replay().unwrap().state, GameState::Drawn);
+
+        let mut resigned = sample_game();
+        resigned.resign(CellState::Blue).unwrap();
+        assert_eq!(resigned.state, GameState::Resigned { winner: CellState::Red });
+        assert!(resigned.play_move(Move::Pass).is_err());
+        let text = GameRecord::from_game(&resigned, &sample_metadata()).to_text();
+        assert!(text.contains("[Result \"Red+Resign\"]\n"));
+        let record = GameRecord::parse(&text).unwrap();
+        assert_eq!(verify_record(&record), Ok(()));
+        assert_eq!(record.replay().unwrap().state, resigned.state);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let game = sample_game();
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        let parsed = GameRecord::parse(&record.to_text()).unwrap();
+        assert_eq!(parsed, record);
+
+        let replayed = parsed.replay().unwrap();
+        assert_eq!(replayed.history, game.history);
+        assert_eq!(replayed.board.to_string(), game.board.to_string());
+    }
+
+    #[test]
+    fn test_parse_loose_move_list() {
+        let record = GameRecord::parse("[Size \"5\"]\n1.c3 2.swap\nb2 d4").unwrap();
+        assert_eq!(record.size, 5);
+        assert_eq!(
+            record.moves,
+            vec![
+                Move::Place(Hex { q: 2, r: 2 }),
+                Move::Swap,
+                Move::Place(Hex { q: 1, r: 1 }),
+                Move::Place(Hex { q: 3, r: 3 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_move_times_round_trip() {
+        let times = [Some(Duration::from_millis(4_200)), None, Some(Duration::from_secs(12))];
+        let record = GameRecord::from_game(&sample_game(), &sample_metadata()).with_move_times(&times);
+        assert_eq!(record.move_times.len(), 5);
+        let text = record.to_text();
+        assert!(text.contains("1. b2 {4.2s}\n2. swap\n3. a1 {12.0s}\n4. a2\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+
+        // Other comments, even with spaces, are skipped.
+        let record = GameRecord::parse("[Size \"3\"]\n1. a1 {a good start} 2. b2 {1.5s}").unwrap();
+        assert_eq!(record.move_times, vec![None, Some(Duration::from_millis(1_500))]);
+    }
+
+    #[test]
+    fn test_telemetry_round_trip_and_csv() {
+        let telemetry = SearchTelemetry {
+            nodes: 10_000,
+            elapsed: Duration::from_millis(500),
+            depth: 14,
+            ponder_hit: true,
+            table_hit_rate: Some(0.25),
+        };
+        let record = GameRecord::from_game(&sample_game(), &sample_metadata())
+            .with_move_times(&[Some(Duration::from_millis(900))])
+            .with_telemetry(&[Some(telemetry), None, Some(SearchTelemetry { table_hit_rate: None, ..telemetry })]);
+        let text = record.to_text();
+        assert!(text.contains("1. b2 {0.9s} {nodes=10000 ms=500 depth=14 ponder=yes tt=25.0}\n2. swap\n"));
+        assert!(text.contains("3. a1 {nodes=10000 ms=500 depth=14 ponder=yes}\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+
+        let csv = record.telemetry_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[1], "1,Red,b2,0.900,10000,500,20000,14,true,0.250");
+        assert_eq!(lines[2], "2,Blue,swap,,,,,,,");
+    }
+
+    #[test]
+    fn test_annotations_round_trip_and_migrate() {
+        let annotations = [None, Some("swap {b2} is \"standard\"\\\nthen a1".to_string()), Some(" ".to_string())];
+        let record = GameRecord::from_game(&sample_game(), &sample_metadata()).with_annotations(&annotations);
+        assert_eq!(record.annotations, [None, annotations[1].clone(), None, None, None]);
+        let text = record.to_text();
+        assert!(text.contains("2. swap {\"swap {b2\\} is \\\"standard\\\"\\\\\\nthen a1\"}\n3. a1\n"));
+        assert_eq!(GameRecord::parse(&text).unwrap(), record);
+
+        // Format 1 records had no annotations; their comments stay skipped.
+        let old = GameRecord::parse("[Size \"3\"]\n1. a1 {\"a note\"} {1.5s}").unwrap();
+        assert_eq!((old.annotations, old.move_times), (vec![None], vec![Some(Duration::from_millis(1_500))]));
+        let newer = GameRecord::parse("[Format \"4\"]\n[Size \"3\"]\n[Clock \"red\"]\n1. a1 {\"a note\"}").unwrap();
+        assert_eq!(newer.annotations, [Some("a note".to_string())]);
+    }
+
+    #[test]
+    fn test_swap_in_older_formats_keeps_its_meaning() {
+        let (a1, a2, c1, a3) = (Hex { q: 0, r: 0 }, Hex { q: 0, r: 1 }, Hex { q: 2, r: 0 }, Hex { q: 0, r: 2 });
+        let moves = "1. c1\n2. swap\n3. a1\n4. a2\n";
+        // Before format 3 the swap recoloured c1 and Blue placed next.
+        for header in ["[Size \"3\"]\n", "[Format \"2\"]\n[Size \"3\"]\n"] {
+            let old = GameRecord::parse(&format!("{header}{moves}")).unwrap();
+            assert!(old.turns.legacy_swap);
+            let game = old.replay().unwrap();
+            let cells = [c1, a1, a2, a3].map(|hex| *game.board.get_cell(&hex).unwrap());
+            assert_eq!(cells, [CellState::Blue, CellState::Blue, CellState::Red, CellState::Empty]);
+            assert_eq!(game.current_player, CellState::Blue);
+            assert_eq!(old.turns.movers(&old.moves), [CellState::Red, CellState::Blue, CellState::Blue, CellState::Red]);
+
+            // Saved again in the current format, it still means the same.
+            let text = GameRecord::from_game(&game, &GameMetadata::default()).to_text();
+            assert!(text.contains("[Swap \"recolour\"]\n") && !text.contains("[Turns"));
+            let resaved = GameRecord::parse(&text).unwrap().replay().unwrap();
+            assert_eq!(resaved.board.to_string(), game.board.to_string());
+        }
+
+        // From format 3 the swap mirrors c1 to a3 and is Blue's turn.
+        let new = GameRecord::parse(&format!("[Format \"3\"]\n[Size \"3\"]\n{moves}")).unwrap();
+        assert!(!new.turns.legacy_swap);
+        let game = new.replay().unwrap();
+        let cells = [c1, a1, a2, a3].map(|hex| *game.board.get_cell(&hex).unwrap());
+        assert_eq!(cells, [CellState::Empty, CellState::Red, CellState::Blue, CellState::Blue]);
+        assert_eq!(game.current_player, CellState::Red);
+        // Old records without a swap need nothing kept.
+        assert!(!GameRecord::parse("[Size \"3\"]\n1. c1\n2. a1").unwrap().turns.legacy_swap);
+    }
+
+    #[test]
+    fn test_blocked_cells_round_trip() {
+        let mut game = Game::with_size(3);
+        game.board.set_cell(Hex { q: 1, r: 1 }, CellState::Blocked);
+        game.board.set_cell(Hex { q: 0, r: 2 }, CellState::Blocked);
+        game.play_move("a1".parse().unwrap()).unwrap();
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        let text = record.to_text();
+        assert!(text.contains("[Blocked \"b2 a3\"]\n"));
+
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.replay().unwrap().board.to_string(), game.board.to_string());
+        // Playing into a hole makes the record illegal.
+        let record = GameRecord::parse("[Blocked \"b2\"]\n[Size \"3\"]\na1 b2").unwrap();
+        assert!(matches!(record.replay(), Err(RecordError::IllegalMove { number: 2, .. })));
+    }
+
+    #[test]
+    fn test_turns_round_trip() {
+        let mut game = Game::with_size(3).with_turn_structure(TurnStructure::ONE_THEN_TWO);
+        for mv in ["a1", "b2", "c2", "a2"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        let text = record.to_text();
+        assert!(text.contains("[Turns \"1-2\"]\n"));
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.replay().unwrap().board.to_string(), game.board.to_string());
+        assert_eq!(GameRecord::parse("[Turns \"1-0\"]"), Err(RecordError::InvalidTurns { line: 1 }));
+    }
+
+    #[test]
+    fn test_passes_round_trip() {
+        let mut game = Game::with_size(3).with_pass(true);
+        for mv in ["a1", "b2", "pass", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        let text = record.to_text();
+        assert!(text.contains("[Passing \"allowed\"]\n"));
+        assert!(text.contains("3. pass\n"));
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.replay().unwrap().current_player, game.current_player);
+
+        // Without the tag the pass is illegal.
+        let record = GameRecord::parse("[Size \"3\"]\na1 b2 pass").unwrap();
+        assert!(matches!(record.replay(), Err(RecordError::IllegalMove { number: 3, .. })));
+    }
+
+    #[test]
+    fn test_pie_rule_round_trip() {
+        let mut game = Game::with_size(3).with_pie_rule(false);
+        game.play_move("b2".parse().unwrap()).unwrap();
+        let record = GameRecord::from_game(&game, &sample_metadata());
+        let text = record.to_text();
+        assert!(text.contains("[PieRule \"off\"]\n"));
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.replay().unwrap().state, GameState::InProgress);
+        assert!(!GameRecord::from_game(&Game::with_size(3), &sample_metadata()).to_text().contains("PieRule"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(GameRecord::parse("[Size 5]"), Err(RecordError::MalformedTag { line: 1 }));
+        assert_eq!(GameRecord::parse("[Size \"0\"]"), Err(RecordError::InvalidSize { line: 1 }));
+        // Replaying a huge board would only exhaust memory.
+        assert_eq!(GameRecord::parse("[Size \"2000000000\"]"), Err(RecordError::InvalidSize { line: 1 }));
+        assert_eq!(
+            GameRecord::parse("[Size \"3\"]\n\n1. a1\n2. d4"),
+            Err(RecordError::InvalidMove { line: 4, error: ParseMoveError::OffBoard("d4".to_string()) })
+        );
+    }
+
+    #[test]
+    fn test_replay_rejects_illegal_moves() {
+        let record = GameRecord::parse("[Size \"3\"]\na1 a1").unwrap();
+        assert!(matches!(record.replay(), Err(RecordError::IllegalMove { number: 2, .. })));
+    }
+
+    #[test]
+    fn test_replay_move_list() {
+        let game = replay_move_list(Game::with_size(3), "1. a1\n2. swap\nb2 c3\n").unwrap();
+        assert_eq!(game.history.len(), 4);
+        let error = |text| replay_move_list(Game::with_size(3), text).err();
+        assert_eq!(error(" \n1. "), Some(MoveListError::Empty));
+        assert_eq!(
+            error("a1 b2\n3.d4"),
+            Some(MoveListError::Unreadable {
+                number: 3,
+                token: "3.d4".to_string(),
+                error: ParseMoveError::OffBoard("d4".to_string())
+            })
+        );
+        let error = error("a1 b2 b2").unwrap();
+        assert!(matches!(&error, MoveListError::Illegal { number: 3, token, .. } if token == "b2"), "{:?}", error);
+        assert!(error.to_string().starts_with("move 3 \"b2\": "));
+    }
+
+    #[test]
+    fn test_verify_record() {
+        let mut game = Game::with_size(2);
+        for mv in ["a1", "a2", "b1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let mut record = GameRecord::from_game(&game, &sample_metadata());
+        assert_eq!(verify_record(&record), Ok(()));
+
+        record.metadata.result = Some(GameResult::Win(CellState::Blue));
+        assert_eq!(
+            verify_record(&record),
+            Err(ReplayError::ResultMismatch {
+                recorded: Some(GameResult::Win(CellState::Blue)),
+                actual: Some(GameResult::Win(CellState::Red)),
+            })
+        );
+        // A game that was won cannot have been drawn by agreement.
+        record.metadata.result = Some(GameResult::Draw);
+        assert!(matches!(verify_record(&record), Err(ReplayError::ResultMismatch { .. })));
+
+        record.moves.truncate(2);
+        assert_eq!(verify_record(&record), Ok(()));
+        record.metadata.result = None;
+        assert_eq!(verify_record(&record), Ok(()));
+        record.metadata.result = Some(GameResult::Win(CellState::Red));
+        assert!(matches!(verify_record(&record), Err(ReplayError::ResultMismatch { actual: None, .. })));
+
+        let illegal = GameRecord::parse("[Size \"3\"]\na1 a1").unwrap();
+        assert_eq!(verify_record(&illegal), Err(ReplayError::IllegalMove { number: 2, reason: "Illegal move" }));
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_741), (2026, 10, 15));
+    }
+}
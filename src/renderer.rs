@@ -1,63 +1,149 @@
+use std::collections::HashSet;
+
 use eframe::egui::{self, Context, Ui};
-use crate::board::{Board, CellState, Hex};
-use crate::game::{Game, HEX_DRAW_SIZE};
+use coast_to_coast::board::{Board, CellState, Hex};
+use coast_to_coast::game::Game;
+use coast_to_coast::notation::Move;
+use coast_to_coast::templates::TemplateMatch;
+use crate::blind::BlindStyle;
+#[cfg(feature = "gpu")]
+use crate::gpu_board::{self, GpuCells};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::motion;
+use crate::stones::{self, StonePainter};
+use crate::themes::Backdrop;
 
 const SQRT_3: f32 = 1.7320508; // Approximately sqrt(3)
+// Bounds on the cell radius in points before `zoom`. A board too big for its space goes below the
+// minimum rather than out of sight.
+const MIN_HEX_SIZE: f32 = 8.0;
+const MAX_HEX_SIZE: f32 = 40.0;
+// How far a theme's frame reaches around each cell, in cell radii.
+const FRAME_REACH: f32 = 1.35;
+pub const CELL_OUTLINE: egui::Color32 = egui::Color32::from_rgb(0x42, 0x87, 0xf5);
+const BLOCKED: egui::Color32 = egui::Color32::from_gray(30);
 
 pub struct BoardRenderer {
-    hex_size: f32, // Corresponds to HEX_DRAW_SIZE
+    hex_size: f32, // Worked out by `fit` for the space the board is given
     x_offset: f32,
     y_offset: f32,
-    hex_spacing: f32,
-    pixels_per_hex_row: f32,
+    // Where the board is centered, which it is turned about when `rotated`.
+    center: egui::Pos2,
+    // Grow the board to fill the space it is given (presentation mode).
+    pub fill: bool,
+    // Scales the board, though never past what fits.
+    pub zoom: f32,
+    // Ring the most recent placement.
+    pub highlight_last_move: bool,
+    // Turned 180°, for the player sitting across the board.
+    pub rotated: bool,
+    // Mirrored left to right, for the left-handed layout.
+    pub mirrored: bool,
+    // Stones masked by blind mode, drawn according to `blind_style`.
+    pub hidden: HashSet<Hex>,
+    pub blind_style: BlindStyle,
+    // Draws the stones in the style chosen in the settings.
+    pub stones: Box<dyn StonePainter>,
+    // The theme's background and board frame, drawn beneath the cells.
+    pub backdrop: Backdrop,
+    // The engine's suggestions in teaching mode, ringed in green.
+    pub candidates: Vec<Hex>,
+    // Ownership estimates, from 1 for Red's to -1 for Blue's, tinted in the players' colors.
+    pub ownership: Vec<(Hex, f32)>,
+    // How much each empty cell would shorten the player to move's two-distance, written on it.
+    pub distance_gains: Vec<(Hex, u32)>,
+    // Cells that would start a losing ladder; hovering one shows a warning.
+    pub ladder_warnings: Vec<Hex>,
+    // Edge templates on the board, their carriers outlined; hovering one names it.
+    pub templates: Vec<TemplateMatch>,
+    meshes: CellMeshes,
+    // Draws the cells on the GPU instead, when the app runs on wgpu.
+    #[cfg(feature = "gpu")]
+    pub gpu: Option<GpuCells>,
+}
+
+// What the cell meshes were built for: each cell's fill, if it is batched, and the layout.
+#[derive(PartialEq)]
+struct MeshKey {
+    cells: Vec<(Hex, Option<egui::Color32>)>,
+    layout: [f32; 6],
+    rotated: bool,
+    mirrored: bool,
+}
+
+// The cells' fills, one mesh per color, and their outlines in one more, tessellated once and
+// kept until the board or its layout changes. A shape per cell is what large boards spend their
+// frames on.
+#[derive(Default)]
+struct CellMeshes {
+    key: Option<MeshKey>,
+    shapes: Vec<egui::Shape>,
 }
 
 impl BoardRenderer {
     pub fn new(_cc: &Context) -> Self {
         Self {
-            hex_size: HEX_DRAW_SIZE,
+            hex_size: MIN_HEX_SIZE,
             x_offset: 0.0,
             y_offset: 0.0,
-            hex_spacing: HEX_DRAW_SIZE * 1.5,
-            pixels_per_hex_row: HEX_DRAW_SIZE * SQRT_3,
+            center: egui::Pos2::ZERO,
+            fill: false,
+            zoom: 1.0,
+            highlight_last_move: false,
+            rotated: false,
+            mirrored: false,
+            hidden: HashSet::new(),
+            blind_style: BlindStyle::default(),
+            stones: Box::new(stones::Flat),
+            backdrop: Backdrop::default(),
+            candidates: Vec::new(),
+            ownership: Vec::new(),
+            distance_gains: Vec::new(),
+            ladder_warnings: Vec::new(),
+            templates: Vec::new(),
+            meshes: CellMeshes::default(),
+            #[cfg(feature = "gpu")]
+            gpu: None,
         }
     }
 
-    pub fn calculate_offsets(&mut self, board: &Board) {
-        let mut min_x = f32::MAX;
-        let mut max_x = f32::MIN;
-        let mut min_y = f32::MAX;
-        let mut max_y = f32::MIN;
+    // Sizes and centers the board in `rect`, whatever its shape.
+    fn fit(&mut self, board: &Board, rect: egui::Rect) {
+        let (min, max) = cell_bounds(board);
+        // Outer extent in units of hex_size: a pointy-top cell is sqrt(3) wide and 2 tall.
+        let width = max.x - min.x + SQRT_3;
+        let height = max.y - min.y + 2.0;
+        let fitted = (rect.width() / width).min(rect.height() / height);
+        self.hex_size = if self.fill { fitted } else { (fitted.clamp(MIN_HEX_SIZE, MAX_HEX_SIZE) * self.zoom).min(fitted) };
 
-        for q in -board.size..=board.size {
-            for r in (-board.size).max(-q - board.size)..=board.size.min(-q + board.size) {
-                let hex = Hex { q, r };
-                let pixel_pos = self.transform_no_offset(hex);
-                min_x = min_x.min(pixel_pos.x);
-                max_x = max_x.max(pixel_pos.x);
-                min_y = min_y.min(pixel_pos.y);
-                max_y = max_y.max(pixel_pos.y);
-            }
-        }
-
-        let board_width = max_x - min_x + self.hex_size * SQRT_3;
-        let board_height = max_y - min_y + self.hex_size * 2.0;
-
-        let window_width = 800.0;
-        let window_height = 600.0;
-
-        self.x_offset = (window_width - board_width) / 2.0 - min_x;
-        self.y_offset = (window_height - board_height) / 2.0 - min_y;
+        let middle = min.lerp(max, 0.5);
+        self.x_offset = rect.center().x - middle.x * self.hex_size;
+        self.y_offset = rect.center().y - middle.y * self.hex_size;
+        self.center = rect.center();
     }
 
-    pub fn render_board(&mut self, ui: &mut Ui, game: &Game) -> Option<Hex> {
-        let (response, _painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click());
+    pub fn render_board(&mut self, ui: &mut Ui, game: &Game, language: Language) -> Option<Hex> {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click());
+        self.fit(&game.board, response.rect);
+        // Cell images are painted through `ui`; keep them inside the board's rect like the rest.
+        let clip_rect = ui.clip_rect();
+        ui.set_clip_rect(painter.clip_rect());
+        self.paint_backdrop(ui, &painter, response.rect, &game.board);
+        self.paint_cells(ui.ctx(), &painter, game);
         let mut clicked_hex: Option<Hex> = None;
 
+        let size = game.board.size as usize;
+        ui.ctx().accesskit_node_builder(response.id, |node| {
+            node.set_role(accesskit::Role::Grid);
+            node.set_label(tr(language, "app.title"));
+            node.set_row_count(size);
+            node.set_column_count(size);
+        });
+
         if response.clicked() {
             if let Some(mouse_pos) = ui.input(|i| i.pointer.latest_pos()) {
                 let hex = self.pixel_to_hex_no_offset(mouse_pos);
-                if game.board.cells.contains_key(&hex) {
+                if game.is_legal_move(&hex) {
                     clicked_hex = Some(hex);
                 }
             }
@@ -67,34 +153,272 @@ impl BoardRenderer {
             let center_pixel_pos = self.transform_no_offset(*hex);
             let center_pixel_pos_with_offset = self.transform(center_pixel_pos);
 
-            let image = match cell_state {
-                CellState::Empty => egui::Image::new(egui::include_image!("../assets/hexagon_empty.svg")),
-                CellState::Red => egui::Image::new(egui::include_image!("../assets/hexagon_red.svg")),
-                CellState::Blue => egui::Image::new(egui::include_image!("../assets/hexagon_blue.svg")),
-            };
-
+            let masked = self.hidden.contains(hex);
+            let shown_state = if masked { CellState::Empty } else { *cell_state };
             let image_size = egui::Vec2::splat(self.hex_size * 2.0); // Adjust size as needed
             let image_rect = egui::Rect::from_center_size(center_pixel_pos_with_offset, image_size);
 
-            ui.put(image_rect, image.fit_to_exact_size(image_size));
+            // Stones the cell meshes don't have are painted one by one.
+            if matches!(shown_state, CellState::Red | CellState::Blue) && self.stones.flat_color(shown_state).is_none() {
+                self.stones.paint(ui, center_pixel_pos_with_offset, self.hex_size, shown_state);
+            }
+            if masked && self.blind_style == BlindStyle::Neutral {
+                painter.circle_filled(center_pixel_pos_with_offset, self.hex_size * 0.5, egui::Color32::from_gray(140));
+            }
+
+            // Each cell gets its own AccessKit node so screen readers can walk the board and press cells.
+            let cell_id = response.id.with(hex);
+            let cell_label = match (masked, self.blind_style) {
+                (true, BlindStyle::Neutral) => {
+                    tr_args(language, "cell.label", &[("cell", &hex.notation()), ("state", tr(language, "cell.occupied"))])
+                }
+                _ => Self::cell_label(*hex, shown_state, language),
+            };
+            ui.ctx().accesskit_node_builder(cell_id, |node| {
+                node.set_role(accesskit::Role::Cell);
+                node.set_label(cell_label);
+                node.set_row_index(hex.r as usize);
+                node.set_column_index(hex.q as usize);
+                node.set_bounds(accesskit::Rect {
+                    x0: image_rect.min.x.into(),
+                    y0: image_rect.min.y.into(),
+                    x1: image_rect.max.x.into(),
+                    y1: image_rect.max.y.into(),
+                });
+                node.add_action(accesskit::Action::Click);
+            });
+            if ui.input(|i| i.has_accesskit_action_request(cell_id, accesskit::Action::Click))
+                && game.is_legal_move(hex)
+            {
+                clicked_hex = Some(*hex);
+            }
+        }
+        if let (true, Some(Move::Place(hex))) = (self.highlight_last_move, game.history.last()) {
+            let center = self.transform(self.transform_no_offset(*hex));
+            painter.circle_stroke(
+                center,
+                self.hex_size * 0.55,
+                egui::Stroke::new((self.hex_size * 0.12).max(2.0), egui::Color32::from_rgb(255, 200, 0)),
+            );
+        }
+        for (hex, balance) in &self.ownership {
+            let center = self.transform(self.transform_no_offset(*hex));
+            let (r, g, b) = if *balance > 0.0 { (230, 40, 40) } else { (40, 90, 230) };
+            let color = egui::Color32::from_rgba_unmultiplied(r, g, b, (balance.abs().min(1.0) * 160.0) as u8);
+            painter.add(egui::Shape::convex_polygon(hexagon(center, self.hex_size * 0.8), color, egui::Stroke::NONE));
         }
+        let gain_color = match game.current_player {
+            CellState::Blue => egui::Color32::from_rgb(40, 90, 230),
+            _ => egui::Color32::from_rgb(230, 40, 40),
+        };
+        for (hex, gain) in self.distance_gains.iter().filter(|(_, gain)| *gain > 0) {
+            let center = self.transform(self.transform_no_offset(*hex));
+            let font = egui::FontId::proportional(self.hex_size * 0.8);
+            painter.text(center, egui::Align2::CENTER_CENTER, gain.to_string(), font, gain_color);
+        }
+        for hex in &self.candidates {
+            let center = self.transform(self.transform_no_offset(*hex));
+            painter.circle_stroke(
+                center,
+                self.hex_size * 0.55,
+                egui::Stroke::new((self.hex_size * 0.1).max(2.0), egui::Color32::from_rgb(60, 200, 90)),
+            );
+        }
+        for found in &self.templates {
+            let color = match found.player {
+                CellState::Blue => egui::Color32::from_rgba_unmultiplied(40, 90, 230, 180),
+                _ => egui::Color32::from_rgba_unmultiplied(230, 40, 40, 180),
+            };
+            for hex in &found.carrier {
+                let center = self.transform(self.transform_no_offset(*hex));
+                let outline = hexagon(center, self.hex_size * 0.8);
+                painter.add(egui::Shape::closed_line(outline, egui::Stroke::new((self.hex_size * 0.06).max(1.0), color)));
+            }
+        }
+        let hovered = response.hover_pos().map(|pos| self.pixel_to_hex_no_offset(pos));
+        let mut tooltip = Vec::new();
+        if let Some(hex) = hovered.filter(|hex| self.ladder_warnings.contains(hex)) {
+            let center = self.transform(self.transform_no_offset(hex));
+            let font = egui::FontId::proportional(self.hex_size * 0.9);
+            painter.text(center, egui::Align2::CENTER_CENTER, "⚠", font, egui::Color32::from_rgb(255, 170, 0));
+            tooltip.push(tr(language, "ladder.warning").to_string());
+        }
+        if let Some(hex) = hovered {
+            for found in self.templates.iter().filter(|found| found.carrier.contains(&hex) || found.stones.contains(&hex)) {
+                let args = [
+                    ("name", found.name.as_str()),
+                    ("cell", &found.stones[0].notation()),
+                    ("player", player_name(language, found.player)),
+                ];
+                tooltip.push(tr_args(language, "templates.match", &args));
+            }
+        }
+        if !tooltip.is_empty() {
+            response.on_hover_text_at_pointer(tooltip.join("\n"));
+        }
+        ui.set_clip_rect(clip_rect);
         clicked_hex
     }
 
+    // Textures are loaded for the size they are drawn at, so they stay sharp at any scale, and
+    // the frame's tiles keep their size in points whatever the board's.
+    fn paint_backdrop(&self, ui: &Ui, painter: &egui::Painter, rect: egui::Rect, board: &Board) {
+        let backdrop = &self.backdrop;
+        let texture = |uri: &str, options| {
+            match egui::Image::new(uri).texture_options(options).load_for_size(ui.ctx(), rect.size()) {
+                Ok(egui::load::TexturePoll::Ready { texture }) => Some(texture),
+                _ => None,
+            }
+        };
+        if let Some(image) = backdrop.image.as_deref().and_then(|uri| texture(uri, egui::TextureOptions::LINEAR)) {
+            // Scaled to cover the area, cropping whichever way it is too long.
+            let scale = (rect.width() / image.size.x).max(rect.height() / image.size.y);
+            let uv = egui::Rect::from_center_size(egui::pos2(0.5, 0.5), rect.size() / (image.size * scale));
+            painter.image(image.id, rect, uv, egui::Color32::WHITE);
+        } else if let (Some((top, _)), true) = (backdrop.gradient, motion::reduced(ui.ctx())) {
+            painter.rect_filled(rect, 0.0, top);
+        } else if let Some((top, bottom)) = backdrop.gradient {
+            let mut mesh = egui::Mesh::default();
+            mesh.colored_vertex(rect.left_top(), top);
+            mesh.colored_vertex(rect.right_top(), top);
+            mesh.colored_vertex(rect.right_bottom(), bottom);
+            mesh.colored_vertex(rect.left_bottom(), bottom);
+            mesh.add_triangle(0, 1, 2);
+            mesh.add_triangle(0, 2, 3);
+            painter.add(mesh);
+        }
+
+        let tile = backdrop.frame.as_deref().and_then(|uri| texture(uri, egui::TextureOptions::LINEAR_REPEAT));
+        let (mut mesh, color) = match (tile, backdrop.frame_color) {
+            (Some(tile), _) => (egui::Mesh::with_texture(tile.id), egui::Color32::WHITE),
+            (None, Some(color)) => (egui::Mesh::default(), color),
+            (None, None) => return,
+        };
+        // Overlapping hexagons a little larger than the cells, which together outline the board.
+        for hex in board.cells.keys() {
+            let center = self.transform(self.transform_no_offset(*hex));
+            let first = mesh.vertices.len() as u32;
+            for corner in hexagon(center, self.hex_size * FRAME_REACH) {
+                let uv = match tile {
+                    Some(tile) => ((corner - rect.min) / tile.size).to_pos2(),
+                    None => egui::epaint::WHITE_UV,
+                };
+                mesh.vertices.push(egui::epaint::Vertex { pos: corner, uv, color });
+            }
+            for i in 1..5 {
+                mesh.add_triangle(first, first + i, first + i + 1);
+            }
+        }
+        painter.add(mesh);
+    }
+
+    fn paint_cells(&mut self, ctx: &Context, painter: &egui::Painter, game: &Game) {
+        let fill = |hex: &Hex, state: CellState| match state {
+            _ if self.hidden.contains(hex) => None,
+            // Blocked cells are dark voids.
+            CellState::Blocked => Some(BLOCKED),
+            CellState::Red | CellState::Blue => self.stones.flat_color(state),
+            CellState::Empty => None,
+        };
+        let key = MeshKey {
+            cells: game.board.cells.iter().map(|(hex, state)| (*hex, fill(hex, *state))).collect(),
+            layout: [self.hex_size, self.x_offset, self.y_offset, self.center.x, self.center.y, ctx.pixels_per_point()],
+            rotated: self.rotated,
+            mirrored: self.mirrored,
+        };
+        if self.meshes.key.as_ref() != Some(&key) {
+            self.meshes.shapes = self.cell_shapes(ctx, &key);
+            self.meshes.key = Some(key);
+        }
+        // Cloning a mesh shape only shares it.
+        painter.extend(self.meshes.shapes.iter().cloned());
+    }
+
+    fn cell_shapes(&self, ctx: &Context, key: &MeshKey) -> Vec<egui::Shape> {
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &self.gpu {
+            let mut bounds = egui::Rect::NOTHING;
+            let instances: Vec<gpu_board::Instance> = key
+                .cells
+                .iter()
+                .map(|(hex, fill)| {
+                    let center = self.transform(self.transform_no_offset(*hex));
+                    bounds.extend_with(center);
+                    let outline = (*fill != Some(BLOCKED)).then_some(CELL_OUTLINE);
+                    gpu_board::Instance::new(center, *fill, outline)
+                })
+                .collect();
+            let rect = bounds.expand(self.hex_size);
+            return vec![gpu.shape(rect, instances, self.hex_size, outline_width(self.hex_size))];
+        }
+        self.cell_meshes(ctx, key)
+    }
+
+    fn cell_meshes(&self, ctx: &Context, key: &MeshKey) -> Vec<egui::Shape> {
+        let options = ctx.tessellation_options(|options| *options);
+        let font_size = ctx.fonts(|fonts| fonts.font_image_size());
+        let mut tessellator = egui::epaint::Tessellator::new(ctx.pixels_per_point(), options, font_size, Vec::new());
+        let mut fills: Vec<(egui::Color32, egui::Mesh)> = Vec::new();
+        let mut outlines = egui::Mesh::default();
+        let stroke = egui::Stroke::new(outline_width(self.hex_size), CELL_OUTLINE);
+        for (hex, color) in &key.cells {
+            let corners = hexagon(self.transform(self.transform_no_offset(*hex)), self.hex_size);
+            if let Some(color) = *color {
+                let at = match fills.iter().position(|(fill, _)| *fill == color) {
+                    Some(at) => at,
+                    None => {
+                        fills.push((color, egui::Mesh::default()));
+                        fills.len() - 1
+                    }
+                };
+                let polygon = egui::Shape::convex_polygon(corners.clone(), color, egui::Stroke::NONE);
+                tessellator.tessellate_shape(polygon, &mut fills[at].1);
+            }
+            if *color != Some(BLOCKED) {
+                tessellator.tessellate_shape(egui::Shape::closed_line(corners, stroke), &mut outlines);
+            }
+        }
+        fills.into_iter().map(|(_, mesh)| mesh).chain([outlines]).map(egui::Shape::mesh).collect()
+    }
+
+    fn cell_label(hex: Hex, cell_state: CellState, language: Language) -> String {
+        let state = match cell_state {
+            CellState::Empty => tr(language, "cell.empty"),
+            CellState::Red => tr(language, "player.red"),
+            CellState::Blue => tr(language, "player.blue"),
+            CellState::Blocked => tr(language, "cell.blocked"),
+        };
+        tr_args(language, "cell.label", &[("cell", &hex.notation()), ("state", state)])
+    }
+
     fn transform_no_offset(&self, hex: Hex) -> egui::Pos2 {
         let x = self.hex_size * (SQRT_3 * hex.q as f32 + SQRT_3 / 2.0 * hex.r as f32);
         let y = self.hex_size * (3.0 / 2.0 * hex.r as f32);
         egui::pos2(x, y)
     }
 
+    // Everything drawn or clicked goes through these two, so turning the board here turns all of it.
     fn transform(&self, pos: egui::Pos2) -> egui::Pos2 {
-        egui::Pos2::new(pos.x + self.x_offset, pos.y + self.y_offset)
+        self.orient(egui::Pos2::new(pos.x + self.x_offset, pos.y + self.y_offset))
     }
 
     fn inverse_transform(&self, pixel_pos: egui::Pos2) -> egui::Pos2 {
+        let pixel_pos = self.orient(pixel_pos);
         egui::Pos2::new(pixel_pos.x - self.x_offset, pixel_pos.y - self.y_offset)
     }
 
+    // Rotates and mirrors about the board's center; doing it twice gives back where it started.
+    fn orient(&self, pos: egui::Pos2) -> egui::Pos2 {
+        let mut offset = pos - self.center;
+        if self.rotated {
+            offset = -offset;
+        }
+        if self.mirrored {
+            offset.x = -offset.x;
+        }
+        self.center + offset
+    }
+
     fn pixel_to_hex_no_offset(&self, pixel_pos: egui::Pos2) -> Hex {
         let no_offset_pixel = self.inverse_transform(pixel_pos);
         let q_float = (no_offset_pixel.x * SQRT_3 / 3.0 - no_offset_pixel.y / 3.0) / self.hex_size;
@@ -121,3 +445,191 @@ impl BoardRenderer {
         Hex { q: q as i32, r: r as i32 }
     }
 }
+
+// The range of cell centers for a hex_size of 1.
+pub fn cell_bounds(board: &Board) -> (egui::Pos2, egui::Pos2) {
+    let mut bounds: Option<(egui::Pos2, egui::Pos2)> = None;
+    for hex in board.cells.keys() {
+        let center = egui::pos2(SQRT_3 * (hex.q as f32 + hex.r as f32 / 2.0), 1.5 * hex.r as f32);
+        bounds = Some(match bounds {
+            Some((min, max)) => (min.min(center), max.max(center)),
+            None => (center, center),
+        });
+    }
+    bounds.unwrap_or((egui::Pos2::ZERO, egui::Pos2::ZERO))
+}
+
+// How wide cells are outlined, for cells of the given radius.
+pub fn outline_width(radius: f32) -> f32 {
+    (radius * 0.08).max(1.0)
+}
+
+// The corners of a pointy-top hexagon of the given radius.
+pub fn hexagon(center: egui::Pos2, radius: f32) -> Vec<egui::Pos2> {
+    (0..6)
+        .map(|i| {
+            let angle = std::f32::consts::FRAC_PI_3 * i as f32 + std::f32::consts::FRAC_PI_6;
+            center + egui::vec2(angle.cos(), angle.sin()) * radius
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+
+    #[test]
+    fn test_tiny_boards_are_centered() {
+        let ctx = Context::default();
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(400.0, 300.0));
+        for size in 1..=3 {
+            let mut renderer = BoardRenderer::new(&ctx);
+            let board = Board::new(size);
+            renderer.fit(&board, rect);
+            // The middle of the rhombus is halfway between its two far corners.
+            let first = renderer.transform(renderer.transform_no_offset(Hex { q: 0, r: 0 }));
+            let last = renderer.transform(renderer.transform_no_offset(Hex { q: size - 1, r: size - 1 }));
+            let middle = first.lerp(last, 0.5);
+            assert!((middle - rect.center()).length() < 0.01, "size {} centered at {:?}", size, middle);
+            assert!(renderer.hex_size <= MAX_HEX_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_hex_size_follows_the_space_and_zoom() {
+        let ctx = Context::default();
+        let board = Board::new(5);
+        let mut renderer = BoardRenderer::new(&ctx);
+        let size_in = |renderer: &mut BoardRenderer, width: f32, height: f32| {
+            renderer.fit(&board, egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width, height)));
+            renderer.hex_size
+        };
+        let small = size_in(&mut renderer, 200.0, 150.0);
+        let medium = size_in(&mut renderer, 400.0, 300.0);
+        assert!((medium - 2.0 * small).abs() < 0.01, "{} then {}", small, medium);
+        assert_eq!(size_in(&mut renderer, 4000.0, 3000.0), MAX_HEX_SIZE);
+        renderer.zoom = 0.5;
+        assert_eq!(size_in(&mut renderer, 4000.0, 3000.0), MAX_HEX_SIZE / 2.0);
+        // Zooming in never pushes the board out of its space.
+        renderer.zoom = 2.0;
+        assert_eq!(size_in(&mut renderer, 400.0, 300.0), medium);
+        renderer.fill = true;
+        assert!(size_in(&mut renderer, 4000.0, 3000.0) > MAX_HEX_SIZE);
+    }
+
+    #[test]
+    fn test_other_shapes_are_centered() {
+        let ctx = Context::default();
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(300.0, 300.0));
+        // Both are symmetric about their middle cell's center.
+        for (board, middle) in [
+            (Board::with_shape(coast_to_coast::shape::Hexagon { side: 3 }), Hex { q: 2, r: 2 }),
+            (Board::with_shape(coast_to_coast::shape::Triangle { side: 1 }), Hex { q: 0, r: 0 }),
+        ] {
+            let mut renderer = BoardRenderer::new(&ctx);
+            renderer.fit(&board, rect);
+            let center = renderer.transform(renderer.transform_no_offset(middle));
+            assert!((center - rect.center()).length() < 0.01, "{:?} centered at {:?}", board.shape(), center);
+        }
+    }
+
+    #[test]
+    fn test_clicks_reach_the_cell_under_the_pointer() {
+        // Each with whether it is rotated and mirrored.
+        let boards = [
+            (Board::new(5), false, false),
+            (Board::with_shape(coast_to_coast::shape::Hexagon { side: 3 }), false, true),
+            (Board::new(4), true, false),
+            (Board::new(4), true, true),
+        ];
+        for (board, rotated, mirrored) in boards {
+            let mut game = Game::with_board(board);
+            game.play_move("c3".parse().unwrap()).unwrap();
+            game.handle_pie_rule_decision(false);
+            let mut harness = UiHarness::new(500.0, 400.0);
+            let mut renderer = BoardRenderer::new(&harness.ctx);
+            renderer.rotated = rotated;
+            renderer.mirrored = mirrored;
+            let clicked = std::cell::Cell::new(None);
+            // Content above and beside the board shifts it away from the panel's corner.
+            let mut show = |ui: &mut Ui| {
+                ui.label("above");
+                egui::Frame::new().inner_margin(egui::Margin { left: 70, ..Default::default() }).show(ui, |ui| {
+                    if let Some(hex) = renderer.render_board(ui, &game, Language::English) {
+                        clicked.set(Some(hex));
+                    }
+                });
+            };
+            harness.run(&mut show);
+            let position = |hex: Hex| {
+                let state = *game.board.cells.get(&hex)?;
+                harness.find(&BoardRenderer::cell_label(hex, state, Language::English))
+            };
+            let corner = [Hex { q: 0, r: 0 }, Hex { q: 1, r: 0 }, Hex { q: 0, r: 1 }].map(position);
+            if let [Some(a1), Some(b1), Some(a2)] = corner {
+                // a1 is top left: at the bottom when rotated and on the right when turned either way.
+                assert_eq!(a1.y > a2.y, rotated);
+                assert_eq!(a1.x > b1.x, rotated != mirrored);
+            }
+            let cells: Vec<Hex> = game.board.cells.keys().copied().collect();
+            for hex in cells {
+                let label = BoardRenderer::cell_label(hex, game.board.cells[&hex], Language::English);
+                let pos = harness.find(&label).unwrap();
+                harness.click_at(pos, &mut show);
+                let expected = (hex != Hex { q: 2, r: 2 }).then_some(hex);
+                assert_eq!(clicked.take(), expected, "{:?} at {:?}", hex, pos);
+            }
+        }
+    }
+    #[test]
+    fn test_cells_are_batched_until_the_board_changes() {
+        let mut game = Game::with_size(19);
+        game.play_move("c3".parse().unwrap()).unwrap();
+        game.handle_pie_rule_decision(false);
+        game.play_move("d4".parse().unwrap()).unwrap();
+        let mut harness = UiHarness::new(800.0, 800.0);
+        let mut renderer = BoardRenderer::new(&harness.ctx);
+        let meshes = |renderer: &BoardRenderer| -> Vec<*const egui::Mesh> {
+            let mesh = |shape: &egui::Shape| match shape {
+                egui::Shape::Mesh(mesh) => Some(std::sync::Arc::as_ptr(mesh)),
+                _ => None,
+            };
+            renderer.meshes.shapes.iter().filter_map(mesh).collect()
+        };
+        harness.run(|ui| {
+            renderer.render_board(ui, &game, Language::English);
+        });
+        // Red's and Blue's stones, and every cell's outline.
+        let first = meshes(&renderer);
+        assert_eq!(first.len(), 3);
+        harness.run(|ui| {
+            renderer.render_board(ui, &game, Language::English);
+        });
+        assert_eq!(meshes(&renderer), first);
+        game.play_move("e5".parse().unwrap()).unwrap();
+        harness.run(|ui| {
+            renderer.render_board(ui, &game, Language::English);
+        });
+        assert_ne!(meshes(&renderer), first);
+    }
+
+    #[test]
+    fn test_board_shrinks_into_a_small_window() {
+        let game = Game::with_size(11);
+        let mut harness = UiHarness::new(360.0, 200.0);
+        let mut renderer = BoardRenderer::new(&harness.ctx);
+        harness.run(|ui| {
+            ui.label("above");
+            renderer.render_board(ui, &game, Language::English);
+        });
+        let board = harness.bounds("Hex Game").unwrap();
+        assert!(renderer.hex_size < MAX_HEX_SIZE);
+        for (hex, state) in game.board.cells.iter() {
+            let cell = harness.bounds(&BoardRenderer::cell_label(*hex, *state, Language::English)).unwrap();
+            // Images are square, a little wider than the hexagon they show.
+            let hexagon = cell.shrink2(egui::vec2(cell.width() * (1.0 - SQRT_3 / 2.0) / 2.0, 0.0));
+            assert!(board.expand(0.01).contains_rect(hexagon), "{:?} at {:?} outside {:?}", hex, cell, board);
+        }
+    }
+}
@@ -0,0 +1,406 @@
+use std::cell::OnceCell;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use coast_to_coast::board::{Board, CellState};
+use coast_to_coast::bookmarks::{self, Bookmark};
+use coast_to_coast::record::GameResult;
+use coast_to_coast::saves::{self, SavedGame};
+use eframe::egui;
+
+use crate::bookmark_panel::{show_bookmark, BookmarkClick};
+use crate::detached;
+use crate::game_info::{result_text, tags_edit};
+use crate::i18n::{tr, tr_args, Language};
+use crate::thumbnail::{ThumbnailCache, THUMBNAIL_SIZE};
+
+// Thumbnail plus spacing and the separator below each row.
+const ROW_HEIGHT: f32 = THUMBNAIL_SIZE[1] as f32 + 12.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Newest,
+    Oldest,
+    Result,
+}
+
+struct Entry {
+    saved: SavedGame,
+    // Final position, or None if the moves no longer replay. Filled in the first time the
+    // row scrolls into view, so opening the browser stays cheap with hundreds of saves.
+    board: OnceCell<Option<Board>>,
+}
+
+impl Entry {
+    fn board(&self) -> Option<&Board> {
+        self.board
+            .get_or_init(|| self.saved.record.replay().ok().map(|game| game.board))
+            .as_ref()
+    }
+}
+
+pub enum BrowserAction {
+    Load(Box<SavedGame>),
+    Bookmark(Box<Bookmark>),
+    NewGame,
+}
+
+// The "Continue / Recent games" screen shown at startup and from the Saved games button.
+pub struct GamesBrowser {
+    pub open: bool,
+    // In a window of its own, leaving the board in the main one.
+    pub detached: bool,
+    entries: Vec<Entry>,
+    bookmarks: Vec<Bookmark>,
+    sort: SortOrder,
+    // Only games with this tag are listed.
+    tag_filter: Option<String>,
+    thumbnails: ThumbnailCache,
+    // The save whose Delete was clicked, asking to be clicked again: deleting can't be undone.
+    confirm_delete: Option<PathBuf>,
+}
+
+impl Default for GamesBrowser {
+    fn default() -> Self {
+        Self {
+            open: false,
+            detached: false,
+            entries: Vec::new(),
+            bookmarks: Vec::new(),
+            sort: SortOrder::Newest,
+            tag_filter: None,
+            thumbnails: ThumbnailCache::default(),
+            confirm_delete: None,
+        }
+    }
+}
+
+impl GamesBrowser {
+    pub fn open(&mut self, data_dir: Option<&Path>) {
+        self.entries = data_dir
+            .map(saves::list)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|saved| Entry { saved, board: OnceCell::new() })
+            .collect();
+        self.bookmarks = data_dir.map(bookmarks::list).unwrap_or_default();
+        let entries = &self.entries;
+        self.thumbnails.retain(|id| entries.iter().any(|entry| entry.saved.path == id));
+        self.sort_entries();
+        if self.tag_filter.as_ref().is_some_and(|tag| !self.tags().contains(tag)) {
+            self.tag_filter = None;
+        }
+        self.open = true;
+    }
+
+    // Whether the browser takes the main window's place of the board.
+    pub fn covers_board(&self) -> bool {
+        self.open && !self.detached
+    }
+
+    // Shows the browser in its own window if it is open and detached.
+    pub fn show_detached(&mut self, ctx: &egui::Context, language: Language) -> Option<BrowserAction> {
+        if !self.open || !self.detached {
+            return None;
+        }
+        let mut action = None;
+        if detached::show(ctx, "games_browser", tr(language, "browser.title"), [640.0, 720.0], |ui| {
+            action = self.show(ui, language);
+        }) {
+            self.open = false;
+        }
+        action
+    }
+
+    pub fn has_games(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    // Every tag in use, in alphabetical order.
+    fn tags(&self) -> BTreeSet<String> {
+        self.entries.iter().flat_map(|entry| entry.saved.record.metadata.tags.iter().cloned()).collect()
+    }
+
+    // Indices of the entries the tag filter lets through, in sort order.
+    fn visible(&self) -> Vec<usize> {
+        (0..self.entries.len())
+            .filter(|index| {
+                let tags = &self.entries[*index].saved.record.metadata.tags;
+                self.tag_filter.as_ref().is_none_or(|tag| tags.contains(tag))
+            })
+            .collect()
+    }
+
+    fn sort_entries(&mut self) {
+        match self.sort {
+            SortOrder::Newest => self.entries.sort_by_key(|entry| Reverse(entry.saved.modified)),
+            SortOrder::Oldest => self.entries.sort_by_key(|entry| entry.saved.modified),
+            SortOrder::Result => self.entries.sort_by_key(|entry| {
+                let rank = match entry.saved.record.metadata.result {
+                    Some(GameResult::Win(CellState::Red) | GameResult::Resignation(CellState::Red)) => 0,
+                    Some(GameResult::Win(CellState::Blue) | GameResult::Resignation(CellState::Blue)) => 1,
+                    Some(GameResult::Draw) => 2,
+                    _ => 3,
+                };
+                (rank, Reverse(entry.saved.modified))
+            }),
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, language: Language) -> Option<BrowserAction> {
+        let mut action = None;
+        ui.heading(tr(language, "browser.title"));
+        ui.horizontal(|ui| {
+            if let Some(autosave) = self.entries.iter().find(|entry| entry.saved.is_autosave()) {
+                if ui.button(tr(language, "browser.continue")).clicked() {
+                    action = Some(BrowserAction::Load(Box::new(autosave.saved.clone())));
+                }
+            }
+            if ui.button(tr(language, "browser.new_game")).clicked() {
+                action = Some(BrowserAction::NewGame);
+            }
+            if !self.detached && ui.button(tr(language, "browser.back")).clicked() {
+                self.open = false;
+            }
+            let (place, hint) = match self.detached {
+                true => ("browser.attach", "browser.attach_hint"),
+                false => ("browser.detach", "browser.detach_hint"),
+            };
+            if ui.button(tr(language, place)).on_hover_text(tr(language, hint)).clicked() {
+                self.detached = !self.detached;
+            }
+            let previous = self.sort;
+            egui::ComboBox::from_label(tr(language, "browser.sort"))
+                .selected_text(sort_text(language, self.sort))
+                .show_ui(ui, |ui| {
+                    for order in [SortOrder::Newest, SortOrder::Oldest, SortOrder::Result] {
+                        ui.selectable_value(&mut self.sort, order, sort_text(language, order));
+                    }
+                });
+            if self.sort != previous {
+                self.sort_entries();
+            }
+            let tags = self.tags();
+            if !tags.is_empty() {
+                egui::ComboBox::from_label(tr(language, "browser.tag"))
+                    .selected_text(self.tag_filter.as_deref().unwrap_or(tr(language, "browser.all_tags")))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.tag_filter, None, tr(language, "browser.all_tags"));
+                        for tag in tags {
+                            let text = tag.clone();
+                            ui.selectable_value(&mut self.tag_filter, Some(tag), text);
+                        }
+                    });
+            }
+        });
+        ui.separator();
+
+        if !self.bookmarks.is_empty() {
+            let mut deleted = None;
+            let count = self.bookmarks.len().to_string();
+            egui::CollapsingHeader::new(tr_args(language, "browser.bookmarks", &[("count", &count)]))
+                .id_salt("browser_bookmarks")
+                .show(ui, |ui| {
+                    for (index, bookmark) in self.bookmarks.iter().enumerate() {
+                        match show_bookmark(ui, language, bookmark) {
+                            Some(BookmarkClick::Open) => action = Some(BrowserAction::Bookmark(Box::new(bookmark.clone()))),
+                            Some(BookmarkClick::Delete) => deleted = Some(index),
+                            None => {}
+                        }
+                    }
+                });
+            if let Some(index) = deleted {
+                let bookmark = self.bookmarks.remove(index);
+                if let Err(e) = bookmarks::delete(&bookmark.path) {
+                    tracing::warn!(path = %bookmark.path.display(), error = %e, "failed to delete bookmark");
+                }
+            }
+            ui.separator();
+        }
+
+        if self.entries.is_empty() {
+            ui.label(tr(language, "browser.empty"));
+        }
+        let mut deleted = None;
+        let mut retagged = None;
+        let visible = self.visible();
+        let thumbnails = &mut self.thumbnails;
+        let confirm_delete = &mut self.confirm_delete;
+        egui::ScrollArea::vertical().show_rows(ui, ROW_HEIGHT, visible.len(), |ui, rows| {
+            for index in rows.map(|row| visible[row]) {
+                let entry = &self.entries[index];
+                let metadata = &entry.saved.record.metadata;
+                let board = entry.board();
+                ui.horizontal(|ui| {
+                    let size = egui::vec2(THUMBNAIL_SIZE[0] as f32, THUMBNAIL_SIZE[1] as f32);
+                    let thumbnail = match board {
+                        Some(board) => {
+                            let texture = thumbnails.get(ui.ctx(), &entry.saved.path, board);
+                            ui.add(egui::Image::new(&texture).fit_to_exact_size(size).sense(egui::Sense::click()))
+                        }
+                        None => ui.add_sized(size, egui::Label::new("?")),
+                    };
+                    ui.vertical(|ui| {
+                        let players = format!(
+                            "{} – {}",
+                            non_empty(&metadata.red, tr(language, "player.red")),
+                            non_empty(&metadata.blue, tr(language, "player.blue"))
+                        );
+                        if entry.saved.is_autosave() {
+                            ui.strong(format!("{} ({})", players, tr(language, "browser.autosave")));
+                        } else {
+                            ui.strong(players);
+                        }
+                        let mut details = vec![
+                            format!("{}×{}", entry.saved.record.size, entry.saved.record.size),
+                            metadata.date.clone(),
+                            result_text(language, metadata.result).to_string(),
+                        ];
+                        if !metadata.event.is_empty() {
+                            details.insert(0, metadata.event.clone());
+                        }
+                        ui.label(details.join(" · "));
+                        ui.horizontal(|ui| {
+                            let load = ui.add_enabled(board.is_some(), egui::Button::new(tr(language, "browser.load")));
+                            if load.clicked() || (thumbnail.clicked() && board.is_some()) {
+                                action = Some(BrowserAction::Load(Box::new(entry.saved.clone())));
+                            }
+                            if confirm_delete.as_ref() == Some(&entry.saved.path) {
+                                ui.label(tr(language, "browser.delete_confirm"));
+                                if ui.button(tr(language, "browser.delete")).clicked() {
+                                    deleted = Some(index);
+                                }
+                                if ui.button(tr(language, "browser.keep")).clicked() {
+                                    *confirm_delete = None;
+                                }
+                            } else if ui.button(tr(language, "browser.delete")).clicked() {
+                                *confirm_delete = Some(entry.saved.path.clone());
+                            }
+                            let mut tags = metadata.tags.clone();
+                            let id = egui::Id::new(("browser_tags", &entry.saved.path));
+                            ui.label(tr(language, "browser.tags"));
+                            if tags_edit(ui, id, &mut tags, 160.0) {
+                                retagged = Some((index, tags));
+                            }
+                        });
+                    });
+                });
+                ui.separator();
+            }
+        });
+
+        if let Some((index, tags)) = retagged {
+            let saved = &mut self.entries[index].saved;
+            saved.record.metadata.tags = tags;
+            if let Err(e) = saves::update(&saved.path, &saved.record) {
+                tracing::warn!(path = %saved.path.display(), error = %e, "failed to save tags");
+            }
+        }
+        if let Some(index) = deleted {
+            self.confirm_delete = None;
+            let entry = self.entries.remove(index);
+            match saves::delete(&entry.saved.path) {
+                Ok(()) => tracing::info!(path = %entry.saved.path.display(), "saved game deleted"),
+                Err(e) => tracing::warn!(path = %entry.saved.path.display(), error = %e, "failed to delete saved game"),
+            }
+        }
+        // A detached browser stays open, to go through one game after another.
+        if action.is_some() && !self.detached {
+            self.open = false;
+        }
+        action
+    }
+}
+
+fn sort_text(language: Language, order: SortOrder) -> &'static str {
+    match order {
+        SortOrder::Newest => tr(language, "browser.sort_newest"),
+        SortOrder::Oldest => tr(language, "browser.sort_oldest"),
+        SortOrder::Result => tr(language, "browser.sort_result"),
+    }
+}
+
+fn non_empty<'a>(name: &'a str, fallback: &'a str) -> &'a str {
+    if name.is_empty() {
+        fallback
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+    use coast_to_coast::game::Game;
+    use coast_to_coast::record::{GameMetadata, GameRecord};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn entry(name: &str, age: u64, winner: Option<CellState>) -> Entry {
+        let tags = if winner.is_some() { vec!["finished".to_string()] } else { Vec::new() };
+        let metadata =
+            GameMetadata { red: name.to_string(), result: winner.map(GameResult::Win), tags, ..Default::default() };
+        let saved = SavedGame {
+            path: name.into(),
+            record: GameRecord::from_game(&Game::with_size(3), &metadata),
+            modified: UNIX_EPOCH + Duration::from_secs(1_000 - age),
+        };
+        Entry { saved, board: OnceCell::new() }
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let mut browser = GamesBrowser {
+            entries: vec![
+                entry("old-blue", 30, Some(CellState::Blue)),
+                entry("unfinished", 10, None),
+                entry("new-red", 0, Some(CellState::Red)),
+                entry("old-red", 20, Some(CellState::Red)),
+            ],
+            ..Default::default()
+        };
+        let names = |browser: &GamesBrowser| -> Vec<String> {
+            browser.entries.iter().map(|entry| entry.saved.record.metadata.red.clone()).collect()
+        };
+
+        browser.sort_entries();
+        assert_eq!(names(&browser), ["new-red", "unfinished", "old-red", "old-blue"]);
+        browser.sort = SortOrder::Oldest;
+        browser.sort_entries();
+        assert_eq!(names(&browser), ["old-blue", "old-red", "unfinished", "new-red"]);
+        browser.sort = SortOrder::Result;
+        browser.sort_entries();
+        assert_eq!(names(&browser), ["new-red", "old-red", "old-blue", "unfinished"]);
+
+        assert_eq!(browser.tags().into_iter().collect::<Vec<_>>(), ["finished"]);
+        assert_eq!(browser.visible(), [0, 1, 2, 3]);
+        browser.tag_filter = Some("finished".to_string());
+        assert_eq!(browser.visible(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_delete_asks_first() {
+        let dir = std::env::temp_dir().join(format!("hex-saved-games-test-{}", std::process::id()));
+        let path = saves::save(&dir, &GameRecord::from_game(&Game::with_size(3), &GameMetadata::default())).unwrap();
+        let mut browser = GamesBrowser::default();
+        browser.open(Some(&dir));
+        let mut harness = UiHarness::new(800.0, 600.0);
+        let mut frame = |ui: &mut egui::Ui| {
+            browser.show(ui, Language::English);
+        };
+        harness.run(&mut frame);
+
+        // The first click only asks; keeping the game puts Delete back.
+        harness.click("Delete", &mut frame);
+        assert!(path.exists() && harness.find("Keep").is_some());
+        harness.click("Keep", &mut frame);
+        assert!(path.exists() && harness.find("Keep").is_none());
+
+        harness.click("Delete", &mut frame);
+        harness.click("Delete", &mut frame);
+        assert!(!path.exists());
+        assert!(harness.find("Delete").is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
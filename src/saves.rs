@@ -0,0 +1,163 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::record::GameRecord;
+
+// Saved games live as text records (see `GameRecord::to_text`) in `<data_dir>/games/`.
+pub const SAVES_DIR: &str = "games";
+// The unfinished game kept between sessions, rewritten on every autosave.
+pub const AUTOSAVE_FILE: &str = "autosave.txt";
+
+#[derive(Clone, Debug)]
+pub struct SavedGame {
+    pub path: PathBuf,
+    pub record: GameRecord,
+    pub modified: SystemTime,
+}
+
+impl SavedGame {
+    pub fn is_autosave(&self) -> bool {
+        self.path.file_name().is_some_and(|name| name == AUTOSAVE_FILE)
+    }
+}
+
+// Writes `record` to a new `game-<timestamp>.txt`, adding a suffix if that name is taken.
+pub fn save(data_dir: &Path, record: &GameRecord) -> io::Result<PathBuf> {
+    let dir = data_dir.join(SAVES_DIR);
+    fs::create_dir_all(&dir)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut path = dir.join(format!("game-{}.txt", timestamp));
+    let mut suffix = 1;
+    while path.exists() {
+        suffix += 1;
+        path = dir.join(format!("game-{}-{}.txt", timestamp, suffix));
+    }
+    fs::write(&path, record.to_text())?;
+    Ok(path)
+}
+
+// Overwrites an existing save, e.g. after its metadata was edited.
+pub fn update(path: &Path, record: &GameRecord) -> io::Result<()> {
+    fs::write(path, record.to_text())
+}
+
+pub fn autosave(data_dir: &Path, record: &GameRecord) -> io::Result<()> {
+    let dir = data_dir.join(SAVES_DIR);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(AUTOSAVE_FILE), record.to_text())
+}
+
+// Deletes a save and its notes.
+pub fn delete(path: &Path) -> io::Result<()> {
+    fs::remove_file(path)?;
+    remove_if_present(&notes_path(path))
+}
+
+// A save's notes, Markdown in a file beside it: `game-<timestamp>.md` for `game-<timestamp>.txt`.
+pub fn notes_path(save: &Path) -> PathBuf {
+    save.with_extension("md")
+}
+
+// Empty when the save has none.
+pub fn read_notes(save: &Path) -> String {
+    fs::read_to_string(notes_path(save)).unwrap_or_default()
+}
+
+// Blank notes leave no file behind.
+pub fn write_notes(save: &Path, notes: &str) -> io::Result<()> {
+    if notes.trim().is_empty() {
+        remove_if_present(&notes_path(save))
+    } else {
+        fs::write(notes_path(save), notes)
+    }
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// All readable saves, most recently modified first. Files that fail to parse are skipped.
+pub fn list(data_dir: &Path) -> Vec<SavedGame> {
+    let Ok(entries) = fs::read_dir(data_dir.join(SAVES_DIR)) else {
+        return Vec::new();
+    };
+    let mut games: Vec<SavedGame> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .filter_map(|path| {
+            let text = fs::read_to_string(&path).ok()?;
+            let record = match GameRecord::parse(&text) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipping unreadable save");
+                    return None;
+                }
+            };
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).unwrap_or(UNIX_EPOCH);
+            Some(SavedGame { path, record, modified })
+        })
+        .collect();
+    games.sort_by_key(|game| std::cmp::Reverse(game.modified));
+    games
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::record::GameMetadata;
+
+    #[test]
+    fn test_save_and_list() {
+        let dir = std::env::temp_dir().join(format!("hex-saves-test-{}", std::process::id()));
+        let mut game = Game::with_size(3);
+        game.play_move("a1".parse().unwrap()).unwrap();
+        let metadata = GameMetadata { event: "Club night".to_string(), ..Default::default() };
+        let record = GameRecord::from_game(&game, &metadata);
+
+        let first = save(&dir, &record).unwrap();
+        let second = save(&dir, &record).unwrap();
+        assert_ne!(first, second);
+        fs::write(dir.join(SAVES_DIR).join("broken.txt"), "[Size 3]").unwrap();
+
+        let saves = list(&dir);
+        assert_eq!(saves.len(), 2);
+        assert!(saves.iter().all(|saved| saved.record == record && !saved.is_autosave()));
+
+        delete(&first).unwrap();
+        autosave(&dir, &record).unwrap();
+        autosave(&dir, &record).unwrap();
+        let saves = list(&dir);
+        assert_eq!(saves.len(), 2);
+        assert_eq!(saves.iter().filter(|saved| saved.is_autosave()).count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_notes_beside_a_save() {
+        let dir = std::env::temp_dir().join(format!("hex-notes-test-{}", std::process::id()));
+        let path = save(&dir, &GameRecord::from_game(&Game::with_size(3), &GameMetadata::default())).unwrap();
+        assert_eq!(read_notes(&path), "");
+
+        write_notes(&path, "# Takeaways\n- block early").unwrap();
+        assert_eq!(read_notes(&path), "# Takeaways\n- block early");
+        assert_eq!(list(&dir).len(), 1);
+        write_notes(&path, "  \n").unwrap();
+        assert!(!notes_path(&path).exists());
+
+        write_notes(&path, "again").unwrap();
+        delete(&path).unwrap();
+        assert!(!notes_path(&path).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use std::thread;
+use std::time::Duration;
+
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::record::{GameRecord, GameResult};
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST};
+
+use crate::events::GameEvent;
+
+// User scripts live as `.rhai` files in `<data_dir>/scripts/`.
+pub const SCRIPTS_DIR: &str = "scripts";
+
+// Keeps a runaway loop in a script from freezing the app.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Rhai scripts hooked to game events, run on the UI thread as the events happen. A script
+// defines any of these functions:
+//
+//   fn on_game_started(event)   fn on_move_played(event)   fn on_game_finished(event)
+//   fn on_result_agreed(event)  fn on_taken_back(event)
+//   fn allow_move(cell, player)  // return false to refuse a move, for trying out rule changes
+//
+// `event` is a map of the event's fields plus `record`, the game as a text record.
+// Scripts can call log(text), write_file(path, text), append_file(path, text) and
+// http_post(url, body); posts are sent in the background.
+#[derive(Clone, Default)]
+pub struct Scripts {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    engine: Engine,
+    scripts: Vec<(String, AST)>,
+}
+
+impl Scripts {
+    // Compiles every script in `<data_dir>/scripts/`; ones that don't compile are skipped with a
+    // warning.
+    pub fn load(data_dir: &Path) -> Self {
+        let engine = engine();
+        let mut paths: Vec<_> = fs::read_dir(data_dir.join(SCRIPTS_DIR))
+            .map(|entries| entries.filter_map(|entry| Some(entry.ok()?.path())).collect())
+            .unwrap_or_default();
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "rhai"));
+        paths.sort();
+        let scripts = paths
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().into_owned();
+                match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| {
+                    engine.compile(&text).map_err(|e| e.to_string())
+                }) {
+                    Ok(ast) => {
+                        tracing::info!(script = %name, "script loaded");
+                        Some((name, ast))
+                    }
+                    Err(e) => {
+                        tracing::warn!(script = %name, error = %e, "skipping script");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { inner: Rc::new(RefCell::new(Inner { engine, scripts })) }
+    }
+
+    pub fn handle(&self, event: &GameEvent, record: &GameRecord) {
+        let inner = self.inner.borrow();
+        let (hook, mut map) = event_map(event);
+        if inner.scripts.iter().any(|(_, ast)| ast.iter_functions().any(|function| function.name == hook)) {
+            map.insert("record".into(), record.to_text().into());
+            inner.call(hook, || (map.clone(),));
+        }
+    }
+
+    // Whether every script's allow_move lets `player` play `hex`.
+    pub fn allow_move(&self, hex: Hex, player: CellState) -> bool {
+        let inner = self.inner.borrow();
+        let answers = inner.call("allow_move", || (hex.notation(), player_name(player).to_string()));
+        answers.into_iter().all(|answer| answer.as_bool().unwrap_or(true))
+    }
+}
+
+impl Inner {
+    // Calls `hook` in each script that defines it with as many parameters as `args` has.
+    fn call<A: rhai::FuncArgs>(&self, hook: &str, args: impl Fn() -> A) -> Vec<Dynamic> {
+        let mut answers = Vec::new();
+        for (name, ast) in &self.scripts {
+            if !ast.iter_functions().any(|function| function.name == hook) {
+                continue;
+            }
+            let options = CallFnOptions::new().eval_ast(false);
+            match self.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, hook, args()) {
+                Ok(answer) => answers.push(answer),
+                Err(e) => tracing::warn!(script = %name, hook, error = %e, "script failed"),
+            }
+        }
+        answers
+    }
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| tracing::info!(target: "script", "{}", text));
+    engine.register_fn("log", |text: &str| tracing::info!(target: "script", "{}", text));
+    engine.register_fn("write_file", |path: &str, text: &str| match fs::write(path, text) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(path, error = %e, "script could not write a file");
+            false
+        }
+    });
+    engine.register_fn("append_file", |path: &str, text: &str| {
+        let appended = fs::OpenOptions::new().create(true).append(true).open(path);
+        match appended.and_then(|mut file| file.write_all(text.as_bytes())) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(path, error = %e, "script could not append to a file");
+                false
+            }
+        }
+    });
+    engine.register_fn("http_post", |url: &str, body: &str| {
+        let (url, body) = (url.to_string(), body.to_string());
+        thread::spawn(move || {
+            let content_type = if body.trim_start().starts_with(['{', '[']) { "application/json" } else { "text/plain" };
+            match ureq::post(&url).timeout(HTTP_TIMEOUT).set("Content-Type", content_type).send_string(&body) {
+                Ok(_) => tracing::debug!(url = %url, "script posted"),
+                Err(e) => tracing::warn!(url = %url, error = %e, "script post failed"),
+            }
+        });
+    });
+    engine
+}
+
+// The hook an event goes to and its fields.
+fn event_map(event: &GameEvent) -> (&'static str, Map) {
+    let mut map = Map::new();
+    let hook = match event {
+        GameEvent::GameStarted { size, moves, opponent } => {
+            map.insert("size".into(), (*size as i64).into());
+            map.insert("moves".into(), (*moves as i64).into());
+            map.insert("opponent".into(), opponent.clone().map_or(Dynamic::UNIT, Dynamic::from));
+            "on_game_started"
+        }
+        GameEvent::MovePlayed { player, mv, move_number } => {
+            map.insert("player".into(), player_name(*player).into());
+            map.insert("move".into(), mv.to_string().into());
+            map.insert("move_number".into(), (*move_number as i64).into());
+            "on_move_played"
+        }
+        GameEvent::GameFinished { winner } => {
+            map.insert("winner".into(), player_name(*winner).into());
+            "on_game_finished"
+        }
+        GameEvent::ResultAgreed { result } => {
+            let (result, winner) = match result {
+                GameResult::Win(winner) => ("win", Some(*winner)),
+                GameResult::Resignation(winner) => ("resignation", Some(*winner)),
+                GameResult::Draw => ("draw", None),
+                GameResult::Adjourned => ("adjourned", None),
+            };
+            map.insert("result".into(), result.into());
+            if let Some(winner) = winner {
+                map.insert("winner".into(), player_name(winner).into());
+            }
+            "on_result_agreed"
+        }
+        GameEvent::TakenBack { moves } => {
+            map.insert("moves".into(), (*moves as i64).into());
+            "on_taken_back"
+        }
+    };
+    (hook, map)
+}
+
+fn player_name(player: CellState) -> &'static str {
+    match player {
+        CellState::Red => "red",
+        CellState::Blue => "blue",
+        CellState::Empty | CellState::Blocked => "none",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::game::Game;
+
+    #[test]
+    fn test_scripts_see_events_and_veto_moves() {
+        let dir = std::env::temp_dir().join(format!("hex-scripts-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join(SCRIPTS_DIR)).unwrap();
+        let log = dir.join("finished.txt");
+        let script = format!(
+            "fn on_game_finished(event) {{ append_file({:?}, event.winner + \"\\n\" + event.record); }}\n\
+             fn allow_move(cell, player) {{ cell != \"a1\" }}",
+            log.display().to_string()
+        );
+        fs::write(dir.join(SCRIPTS_DIR).join("rules.rhai"), script).unwrap();
+        fs::write(dir.join(SCRIPTS_DIR).join("broken.rhai"), "fn (").unwrap();
+
+        let scripts = Scripts::load(&dir);
+        assert!(!scripts.allow_move(Hex { q: 0, r: 0 }, CellState::Red));
+        assert!(scripts.allow_move(Hex { q: 1, r: 0 }, CellState::Red));
+        let record = GameRecord::from_game(&Game::with_size(3), &Default::default());
+        scripts.handle(&GameEvent::GameFinished { winner: CellState::Blue }, &record);
+        assert_eq!(fs::read_to_string(&log).unwrap(), format!("blue\n{}", record.to_text()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::time::Duration;
+
+use coast_to_coast::mcts::{SearchSnapshot, SnapshotNode};
+use eframe::egui;
+
+use crate::{detached, motion};
+
+// Snapshots waiting to be shown; engines drop theirs rather than wait when it is full.
+const SNAPSHOT_BACKLOG: usize = 8;
+
+// A developer window on the latest search tree an engine sent, to see why it chose a move.
+// Toggled with F10. With several engines thinking at once it shows whichever reported last.
+pub struct SearchInspector {
+    pub open: bool,
+    // In a window of its own rather than over the board.
+    detached: bool,
+    receiver: Receiver<SearchSnapshot>,
+    latest: Option<SearchSnapshot>,
+}
+
+impl SearchInspector {
+    // The inspector and the sender engines report to.
+    pub fn new() -> (Self, SyncSender<SearchSnapshot>) {
+        let (sender, receiver) = mpsc::sync_channel(SNAPSHOT_BACKLOG);
+        (Self { open: false, detached: false, receiver, latest: None }, sender)
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F10)) {
+            self.open = !self.open;
+        }
+        // Drained even while closed, so engines always have room to report.
+        if let Some(snapshot) = self.receiver.try_iter().last() {
+            self.latest = Some(snapshot);
+        }
+        if !self.open {
+            return;
+        }
+        motion::repaint_after(ctx, Duration::from_millis(250));
+
+        let (latest, separate) = (&self.latest, &mut self.detached);
+        if *separate {
+            let size = [420.0, 360.0];
+            if detached::show(ctx, "search_inspector", "Search Inspector", size, |ui| contents(ui, latest, separate)) {
+                self.open = false;
+            }
+            return;
+        }
+        egui::Window::new("Search Inspector")
+            .open(&mut self.open)
+            .default_size([420.0, 360.0])
+            .show(ctx, |ui| contents(ui, latest, separate));
+    }
+}
+
+fn contents(ui: &mut egui::Ui, latest: &Option<SearchSnapshot>, separate: &mut bool) {
+    ui.checkbox(separate, "Separate window");
+    let Some(snapshot) = latest else {
+        ui.label("No search yet. Let a Hard engine think.");
+        return;
+    };
+    ui.monospace(format!("iterations {}", snapshot.iterations));
+    ui.monospace(format!("pv {}", principal_variation(&snapshot.root)));
+    ui.separator();
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        for (i, child) in snapshot.root.children.iter().enumerate() {
+            show_node(ui, child, egui::Id::new("inspector").with(i));
+        }
+    });
+}
+
+fn show_node(ui: &mut egui::Ui, node: &SnapshotNode, id: egui::Id) {
+    if node.children.is_empty() {
+        ui.monospace(node_label(node));
+        return;
+    }
+    egui::CollapsingHeader::new(egui::RichText::new(node_label(node)).monospace()).id_salt(id).show(ui, |ui| {
+        for (i, child) in node.children.iter().enumerate() {
+            show_node(ui, child, id.with(i));
+        }
+    });
+}
+
+fn node_label(node: &SnapshotNode) -> String {
+    let mv = node.mv.map_or_else(|| "root".to_string(), |hex| hex.notation());
+    format!("{:<4} {:?}  visits {:>6}  value {:.3}", mv, node.player, node.visits, node.value)
+}
+
+fn principal_variation(root: &SnapshotNode) -> String {
+    let line: Vec<String> = root.principal_variation().iter().map(|hex| hex.notation()).collect();
+    line.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::board::{CellState, Hex};
+
+    #[test]
+    fn test_labels_and_principal_variation() {
+        let node = |mv, player, visits, value, children| SnapshotNode { mv, player, visits, value, children };
+        let leaf = node(Some(Hex { q: 1, r: 2 }), CellState::Blue, 40, 0.25, vec![]);
+        let child = node(Some(Hex { q: 2, r: 2 }), CellState::Red, 90, 0.6, vec![leaf]);
+        let root = node(None, CellState::Blue, 100, 0.4, vec![child]);
+        assert_eq!(principal_variation(&root), "c3 b3");
+        assert_eq!(node_label(&root.children[0]), "c3   Red  visits     90  value 0.600");
+    }
+}
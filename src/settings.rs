@@ -0,0 +1,316 @@
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::{Game, TurnStructure, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::ai_opponent::{AiConfig, PlayoutChoice, ReplacementChoice};
+use crate::blind::BlindStyle;
+use crate::board_bridge::BridgeConfig;
+use crate::cloud_sync::SyncConfig;
+use crate::i18n::{tr, tr_args, Language};
+use crate::keymap::{self, Keymap};
+use crate::ladders::LadderWarnings;
+use crate::match_presets::MatchPresets;
+use crate::new_game::Seats;
+use crate::stones::StoneStyle;
+use crate::themes::ThemeLibrary;
+use crate::teaching::CANDIDATE_COUNT;
+use crate::updater::DEFAULT_UPDATE_URL;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    // The name the user enters for themselves in game info; tells their side in the statistics.
+    pub player_name: String,
+    // Used by "New game" and Dark Hex.
+    pub board_size: i32,
+    // Whether new games offer the swap after the first move. Dark Hex never does.
+    pub pie_rule: bool,
+    // Stones each turn places after the one-stone opening; 2 is the "12*" variant.
+    pub stones_per_turn: u32,
+    // Standard Hex forbids passing.
+    pub allow_pass: bool,
+    // Recorded with new games; the clocks count up either way.
+    pub time_control: String,
+    // The match preset last chosen, by name; empty for none. See `match_presets`.
+    pub match_preset: String,
+    // Who plays new games, as last set up in the New game dialog.
+    pub seats: Seats,
+    // Off unless the user opts in; "never ask again" on the update notice turns it back off.
+    pub check_for_updates: bool,
+    pub update_url: String,
+    pub sync: SyncConfig,
+    // Finished games are posted here as JSON; empty for none.
+    pub webhook_url: String,
+    // A physical board that moves can be read from, see Game → Connect board.
+    pub bridge: BridgeConfig,
+    // A speech recognizer command that prints a line per phrase heard; only has an effect in
+    // builds with the `voice` feature.
+    pub voice_command: String,
+    // Only has an effect in builds with the `discord` feature.
+    pub discord_presence: bool,
+    pub presentation_eval_bar: bool,
+    // Ring the last move; presentation mode always does.
+    pub highlight_last_move: bool,
+    // Turn the board 180°, for the player on the far side of the table.
+    pub rotate_board: bool,
+    // Left-handed layout: the board mirrored left to right and the side panels swapped. Moves
+    // keep their names, so records read the same either way.
+    pub left_handed: bool,
+    // Scales the board within the window; it is sized to the window either way.
+    pub board_zoom: f32,
+    // No animations or gradients and fewer repaints; see `motion`.
+    pub reduced_motion: bool,
+    pub stone_style: StoneStyle,
+    // The theme pack, by its folder in `<data_dir>/themes/`; empty for none.
+    pub theme: String,
+    // Window mode, e.g. for club displays and projectors. F11 toggles fullscreen by default.
+    pub fullscreen: bool,
+    pub borderless: bool,
+    // Blind (memory training) mode: stones are masked `blind_delay_secs` after they are placed.
+    pub blind_mode: bool,
+    pub blind_delay_secs: f32,
+    pub blind_style: BlindStyle,
+    // Pause the clocks and dim the board once the window has been out of focus this long; 0 for
+    // never.
+    pub idle_pause_secs: f32,
+    // Teaching mode: these colors may only play one of the engine's top candidates.
+    pub teach_red: bool,
+    pub teach_blue: bool,
+    // Warn on hover before a move that starts a ladder with no escape.
+    pub ladder_warnings: LadderWarnings,
+    pub ai: AiConfig,
+    pub keymap: Keymap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            player_name: String::new(),
+            board_size: DEFAULT_BOARD_SIZE,
+            pie_rule: true,
+            stones_per_turn: 1,
+            allow_pass: false,
+            time_control: String::new(),
+            match_preset: String::new(),
+            seats: Seats::default(),
+            check_for_updates: false,
+            update_url: DEFAULT_UPDATE_URL.to_string(),
+            sync: SyncConfig::default(),
+            webhook_url: String::new(),
+            bridge: BridgeConfig::default(),
+            voice_command: String::new(),
+            discord_presence: false,
+            presentation_eval_bar: false,
+            highlight_last_move: false,
+            rotate_board: false,
+            left_handed: false,
+            board_zoom: 1.0,
+            reduced_motion: false,
+            stone_style: StoneStyle::default(),
+            theme: String::new(),
+            fullscreen: false,
+            borderless: false,
+            blind_mode: false,
+            blind_delay_secs: 3.0,
+            blind_style: BlindStyle::default(),
+            idle_pause_secs: 60.0,
+            teach_red: false,
+            teach_blue: false,
+            ladder_warnings: LadderWarnings::default(),
+            ai: AiConfig::default(),
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Settings {
+    // A new game as configured: size, pie rule, turn structure and passing.
+    pub fn new_game(&self) -> Game {
+        Game::with_size(self.board_size)
+            .with_pie_rule(self.pie_rule)
+            .with_turn_structure(TurnStructure { first: 1, then: self.stones_per_turn.max(1), ..TurnStructure::STANDARD })
+            .with_pass(self.allow_pass)
+    }
+
+    pub fn teaches(&self, player: CellState) -> bool {
+        match player {
+            CellState::Red => self.teach_red,
+            CellState::Blue => self.teach_blue,
+            CellState::Empty | CellState::Blocked => false,
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, open: &mut bool, themes: &mut ThemeLibrary, presets: &mut MatchPresets) {
+        let language = self.language;
+        egui::Window::new(tr(language, "settings.title"))
+            .open(open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label(tr(language, "settings.language"))
+                    .selected_text(self.language.native_name())
+                    .show_ui(ui, |ui| {
+                        for option in Language::ALL {
+                            ui.selectable_value(&mut self.language, option, option.native_name());
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    ui.label(tr(language, "settings.player_name"));
+                    ui.text_edit_singleline(&mut self.player_name);
+                });
+                ui.add(egui::Slider::new(&mut self.board_size, 1..=MAX_BOARD_SIZE).text(tr(language, "settings.board_size")));
+                ui.checkbox(&mut self.pie_rule, tr(language, "settings.pie_rule"));
+                ui.add(egui::Slider::new(&mut self.stones_per_turn, 1..=3).text(tr(language, "settings.stones_per_turn")));
+                ui.checkbox(&mut self.allow_pass, tr(language, "settings.allow_pass"));
+                presets.show(ui, language, self);
+                ui.checkbox(&mut self.check_for_updates, tr(language, "settings.check_updates"));
+                ui.add_enabled_ui(self.check_for_updates, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(language, "settings.update_url"));
+                        ui.text_edit_singleline(&mut self.update_url);
+                    });
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr(language, "settings.webhook_url"))
+                        .on_hover_text(tr(language, "settings.webhook_url_hint"));
+                    ui.text_edit_singleline(&mut self.webhook_url);
+                });
+                ui.checkbox(&mut self.presentation_eval_bar, tr(language, "settings.eval_bar"));
+                ui.checkbox(&mut self.fullscreen, tr(language, "settings.fullscreen"));
+                ui.checkbox(&mut self.borderless, tr(language, "settings.borderless"));
+                ui.checkbox(&mut self.left_handed, tr(language, "settings.left_handed"));
+                ui.add(
+                    egui::Slider::new(&mut self.board_zoom, 0.5..=2.0)
+                        .fixed_decimals(2)
+                        .suffix("×")
+                        .text(tr(language, "settings.board_zoom")),
+                );
+                ui.checkbox(&mut self.reduced_motion, tr(language, "settings.reduced_motion"))
+                    .on_hover_text(tr(language, "settings.reduced_motion_hint"));
+                ui.horizontal(|ui| {
+                    ui.label(tr(language, "settings.stones"));
+                    ui.radio_value(&mut self.stone_style, StoneStyle::Flat, tr(language, "settings.stones_flat"));
+                    ui.radio_value(&mut self.stone_style, StoneStyle::Shaded, tr(language, "settings.stones_shaded"));
+                    ui.radio_value(&mut self.stone_style, StoneStyle::Textured, tr(language, "settings.stones_textured"));
+                });
+                ui.horizontal(|ui| {
+                    let none = tr(language, "settings.theme_none");
+                    let selected = themes.get(&self.theme).map_or(none, |pack| pack.name());
+                    egui::ComboBox::from_label(tr(language, "settings.theme")).selected_text(selected).show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.theme, String::new(), none);
+                        for pack in &themes.packs {
+                            ui.selectable_value(&mut self.theme, pack.id.clone(), pack.name())
+                                .on_hover_text(&pack.manifest.author);
+                        }
+                    });
+                    let reload = ui.button(tr(language, "settings.theme_reload"));
+                    if reload.on_hover_text(tr(language, "settings.theme_hint")).clicked() {
+                        themes.reload();
+                    }
+                });
+                ui.checkbox(&mut self.blind_mode, tr(language, "settings.blind_mode"));
+                ui.add_enabled_ui(self.blind_mode, |ui| {
+                    ui.add(
+                        egui::Slider::new(&mut self.blind_delay_secs, 0.0..=30.0)
+                            .suffix(" s")
+                            .text(tr(language, "settings.blind_delay")),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.blind_style, BlindStyle::Hidden, tr(language, "settings.blind_hidden"));
+                        ui.radio_value(&mut self.blind_style, BlindStyle::Neutral, tr(language, "settings.blind_neutral"));
+                    });
+                });
+                ui.add(
+                    egui::Slider::new(&mut self.idle_pause_secs, 0.0..=600.0)
+                        .suffix(" s")
+                        .text(tr(language, "settings.idle_pause")),
+                )
+                .on_hover_text(tr(language, "settings.idle_pause_hint"));
+                ui.horizontal(|ui| {
+                    ui.label(tr_args(language, "settings.teaching", &[("count", &CANDIDATE_COUNT.to_string())]));
+                    ui.checkbox(&mut self.teach_red, tr(language, "player.red"));
+                    ui.checkbox(&mut self.teach_blue, tr(language, "player.blue"));
+                });
+                egui::ComboBox::from_label(tr(language, "settings.ladder_warnings"))
+                    .selected_text(self.ladder_warnings.name(language))
+                    .show_ui(ui, |ui| {
+                        for option in LadderWarnings::ALL {
+                            ui.selectable_value(&mut self.ladder_warnings, option, option.name(language));
+                        }
+                    });
+                ui.add(
+                    egui::Slider::new(&mut self.ai.min_response_delay_secs, 0.0..=5.0)
+                        .suffix(" s")
+                        .text(tr(language, "settings.ai_delay")),
+                );
+                egui::ComboBox::from_label(tr(language, "settings.playout"))
+                    .selected_text(self.ai.playout.name(language))
+                    .show_ui(ui, |ui| {
+                        for option in PlayoutChoice::ALL {
+                            ui.selectable_value(&mut self.ai.playout, option, option.name(language));
+                        }
+                    });
+                ui.checkbox(&mut self.ai.reuse_tree, tr(language, "settings.reuse_tree"));
+                ui.add(
+                    egui::Slider::new(&mut self.ai.tree_memory_mb, 1..=1024)
+                        .logarithmic(true)
+                        .suffix(" MB")
+                        .text(tr(language, "settings.tree_memory")),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.ai.table_memory_mb, 1..=1024)
+                        .logarithmic(true)
+                        .suffix(" MB")
+                        .text(tr(language, "settings.table_memory")),
+                );
+                egui::ComboBox::from_label(tr(language, "settings.table_replacement"))
+                    .selected_text(self.ai.table_replacement.name(language))
+                    .show_ui(ui, |ui| {
+                        for option in ReplacementChoice::ALL {
+                            ui.selectable_value(&mut self.ai.table_replacement, option, option.name(language));
+                        }
+                    });
+                #[cfg(feature = "discord")]
+                ui.checkbox(&mut self.discord_presence, tr(language, "settings.discord_presence"));
+                #[cfg(feature = "voice")]
+                ui.horizontal(|ui| {
+                    ui.label(tr(language, "settings.voice_command"))
+                        .on_hover_text(tr(language, "settings.voice_command_hint"));
+                    ui.text_edit_singleline(&mut self.voice_command);
+                });
+                egui::CollapsingHeader::new(tr(language, "settings.sync")).id_salt("sync").show(ui, |ui| {
+                    egui::Grid::new("sync_settings").num_columns(2).show(ui, |ui| {
+                        ui.label(tr(language, "settings.sync_url"));
+                        ui.text_edit_singleline(&mut self.sync.url);
+                        ui.end_row();
+                        ui.label(tr(language, "settings.sync_username"));
+                        ui.text_edit_singleline(&mut self.sync.username);
+                        ui.end_row();
+                        ui.label(tr(language, "settings.sync_password"));
+                        ui.add(egui::TextEdit::singleline(&mut self.sync.password).password(true));
+                        ui.end_row();
+                    });
+                    ui.label(tr(language, "settings.sync_hint"));
+                });
+                egui::CollapsingHeader::new(tr(language, "settings.bridge")).id_salt("bridge").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tr(language, "settings.bridge_device"));
+                        ui.text_edit_singleline(&mut self.bridge.device);
+                    });
+                    ui.checkbox(&mut self.bridge.transpose, tr(language, "settings.bridge_transpose"));
+                    ui.checkbox(&mut self.bridge.mirror_x, tr(language, "settings.bridge_mirror_x"));
+                    ui.checkbox(&mut self.bridge.mirror_y, tr(language, "settings.bridge_mirror_y"));
+                    ui.label(tr(language, "settings.bridge_hint"));
+                });
+                egui::CollapsingHeader::new(tr(language, "keymap.title")).id_salt("keymap").show(ui, |ui| {
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| self.keymap.show_editor(ui, language));
+                });
+            });
+        if !*open {
+            keymap::stop_recording(ctx);
+        }
+    }
+}
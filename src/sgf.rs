@@ -0,0 +1,162 @@
+use crate::board::CellState;
+use crate::notation::Move;
+use crate::record::{GameRecord, GameResult};
+
+// Writes a record as SGF in the convention HexGui uses: GM[11], moves in letter-number
+// notation and the pie rule as "swap-pieces". SGF's black and white map to Red (who moves
+// first) and Blue. Passes are written as "pass".
+//
+// Tags go in TAGS, a property of this program's own with one value per tag.
+//
+// Annotations become the move's C comment.
+//
+// Move times become BL/WL, the time each side has left after the move, when the time control
+// gives a main time ("300" or Fischer "300+5"). SGF has no property for time spent, so
+// without one the times are left out.
+pub fn to_sgf(record: &GameRecord) -> String {
+    let metadata = &record.metadata;
+    let mut sgf = format!("(;FF[4]GM[11]AP[coast-to-coast]SZ[{}]", record.size);
+    let mut property = |name: &str, value: &str| {
+        if !value.is_empty() {
+            sgf.push_str(&format!("{}[{}]", name, escape(value)));
+        }
+    };
+    property("PB", &metadata.red);
+    property("PW", &metadata.blue);
+    property("EV", &metadata.event);
+    property("RO", &metadata.round);
+    property("DT", &metadata.date.replace('.', "-"));
+    property("OT", &metadata.time_control);
+    property(
+        "RE",
+        match metadata.result {
+            Some(GameResult::Win(CellState::Red)) => "B+",
+            Some(GameResult::Win(CellState::Blue)) => "W+",
+            Some(GameResult::Resignation(CellState::Red)) => "B+R",
+            Some(GameResult::Resignation(_)) => "W+R",
+            Some(GameResult::Draw) => "0",
+            // SGF's "Void" is a game without a result, such as a suspended one.
+            Some(GameResult::Adjourned) => "Void",
+            _ => "",
+        },
+    );
+    if !metadata.tags.is_empty() {
+        let values: Vec<String> = metadata.tags.iter().map(|tag| format!("[{}]", escape(tag))).collect();
+        sgf.push_str(&format!("TAGS{}", values.concat()));
+    }
+    // SGF's TM holds the main time in seconds.
+    let time_control = parse_time_control(&metadata.time_control);
+    if let Some((main, _)) = time_control {
+        sgf.push_str(&format!("TM[{}]", main));
+    }
+
+    // Seconds left per side, until a move with no recorded time makes it unknown.
+    let mut left = time_control.map(|(main, _)| [Some(main as f64); 2]);
+    for (i, (mv, mover)) in record.moves.iter().zip(record.turns.movers(&record.moves)).enumerate() {
+        let (color, side) = match mover {
+            CellState::Red => ("B", 0),
+            _ => ("W", 1),
+        };
+        let value = match mv {
+            Move::Place(hex) => hex.notation(),
+            Move::Swap => "swap-pieces".to_string(),
+            Move::Pass => "pass".to_string(),
+        };
+        sgf.push_str(&format!(";{}[{}]", color, value));
+        if let (Some(left), Some((_, increment))) = (left.as_mut(), time_control) {
+            let time = record.move_times.get(i).copied().flatten();
+            left[side] = left[side].zip(time).map(|(left, time)| left - time.as_secs_f64() + increment as f64);
+            if let Some(seconds) = left[side] {
+                sgf.push_str(&format!("{}L[{:.1}]", color, seconds));
+            }
+        }
+        if let Some(annotation) = record.annotations.get(i).and_then(Option::as_deref) {
+            sgf.push_str(&format!("C[{}]", escape(annotation)));
+        }
+    }
+    sgf.push_str(")\n");
+    sgf
+}
+
+// "300+5" to (300, 5); a bare "300" has no increment.
+fn parse_time_control(text: &str) -> Option<(u32, u32)> {
+    let mut parts = text.split('+');
+    let main = parts.next()?.trim().parse().ok()?;
+    let increment = match parts.next() {
+        Some(increment) => increment.trim().parse().ok()?,
+        None => 0,
+    };
+    Some((main, increment))
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, TurnStructure};
+    use crate::record::GameMetadata;
+    use std::time::Duration;
+
+    #[test]
+    fn test_to_sgf() {
+        let mut game = Game::with_size(3);
+        for mv in ["c1", "swap", "a1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        // Other readers take swap-pieces to mirror c1 to a3, and so does the game.
+        assert_eq!(game.board.get_cell(&crate::board::Hex { q: 0, r: 2 }), Some(&CellState::Blue));
+        let metadata = GameMetadata {
+            red: "Alice".to_string(),
+            blue: "Bob [guest]".to_string(),
+            event: "Club night".to_string(),
+            round: "3".to_string(),
+            date: "2026.10.15".to_string(),
+            result: Some(GameResult::Win(CellState::Red)),
+            time_control: "300+5".to_string(),
+            tags: vec!["study".to_string(), "vs-engine".to_string()],
+        };
+        let record = GameRecord::from_game(&game, &metadata);
+        assert_eq!(
+            to_sgf(&record),
+            "(;FF[4]GM[11]AP[coast-to-coast]SZ[3]PB[Alice]PW[Bob [guest\\]]EV[Club night]RO[3]\
+             DT[2026-10-15]OT[300+5]RE[B+]TAGS[study][vs-engine]TM[300];B[c1];W[swap-pieces];B[a1])\n"
+        );
+    }
+
+    #[test]
+    fn test_move_times_become_time_left() {
+        let mut game = Game::with_size(3);
+        for mv in ["b2", "a1", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let metadata = GameMetadata { time_control: "60+2".to_string(), ..Default::default() };
+        let times = [Some(Duration::from_secs(10)), None, Some(Duration::from_millis(5_500))];
+        let record = GameRecord::from_game(&game, &metadata).with_move_times(&times);
+        // Blue's time is unknown after its first move, so WL is left out from then on.
+        assert!(to_sgf(&record).ends_with("TM[60];B[b2]BL[52.0];W[a1];B[c3]BL[48.5])\n"));
+
+        let record = GameRecord::from_game(&game, &GameMetadata::default()).with_move_times(&times);
+        assert!(to_sgf(&record).ends_with(";B[b2];W[a1];B[c3])\n"));
+    }
+
+    #[test]
+    fn test_pass_and_turns() {
+        let mut game = Game::with_size(3).with_pass(true).with_turn_structure(TurnStructure::ONE_THEN_TWO);
+        for mv in ["a1", "b2", "pass", "c3"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let record = GameRecord::from_game(&game, &GameMetadata::default());
+        assert!(to_sgf(&record).ends_with(";B[a1];W[b2];W[pass];B[c3])\n"));
+        let record = record.with_annotations(&[None, Some("[sic]".to_string())]);
+        assert!(to_sgf(&record).ends_with(";B[a1];W[b2]C[[sic\\]];W[pass];B[c3])\n"));
+    }
+
+    #[test]
+    fn test_to_sgf_omits_empty_metadata() {
+        let record = GameRecord::from_game(&Game::with_size(5), &GameMetadata::default());
+        assert_eq!(to_sgf(&record), "(;FF[4]GM[11]AP[coast-to-coast]SZ[5])\n");
+    }
+}
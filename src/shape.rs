@@ -0,0 +1,169 @@
+//! Board shapes: which cells a board has and which of its sides each cell touches.
+//!
+//! Hex is played on a [`Rhombus`]. Other connection games and puzzles can use a
+//! [`Hexagon`], a [`Triangle`] (the game of Y), or their own [`BoardShape`]. All cells use
+//! non-negative axial coordinates, so notation and rendering work unchanged.
+
+use std::fmt;
+
+use crate::board::{CellState, Hex};
+
+pub trait BoardShape: fmt::Debug + Send + Sync {
+    /// Every cell on the board.
+    fn cells(&self) -> Vec<Hex>;
+
+    /// Number of sides, numbered from 0.
+    fn side_count(&self) -> usize;
+
+    /// Whether `hex` lies on side `side`. Corner cells lie on two sides.
+    fn on_side(&self, hex: Hex, side: usize) -> bool;
+
+    /// The sides `player` wins by joining with one chain. Defaults to Hex's pairing: Red
+    /// joins sides 0 and 1, Blue sides 2 and 3.
+    fn goal_sides(&self, player: CellState) -> Vec<usize> {
+        match player {
+            CellState::Red => vec![0, 1],
+            CellState::Blue => vec![2, 3],
+            CellState::Empty | CellState::Blocked => Vec::new(),
+        }
+    }
+
+    /// One more than the largest `q` or `r` of any cell.
+    fn extent(&self) -> i32;
+}
+
+/// The Hex board: `size` by `size` cells. Sides 0 and 1 are `q = 0` and `q = size - 1`
+/// (Red's), sides 2 and 3 are `r = 0` and `r = size - 1` (Blue's).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rhombus {
+    pub size: i32,
+}
+
+impl BoardShape for Rhombus {
+    fn cells(&self) -> Vec<Hex> {
+        (0..self.size).flat_map(|r| (0..self.size).map(move |q| Hex { q, r })).collect()
+    }
+
+    fn side_count(&self) -> usize {
+        4
+    }
+
+    fn on_side(&self, hex: Hex, side: usize) -> bool {
+        match side {
+            0 => hex.q == 0,
+            1 => hex.q == self.size - 1,
+            2 => hex.r == 0,
+            3 => hex.r == self.size - 1,
+            _ => false,
+        }
+    }
+
+    fn extent(&self) -> i32 {
+        self.size
+    }
+}
+
+/// A regular hexagon with `side` cells per side. Sides 0 to 3 are the two `q` and two `r`
+/// sides, as on the rhombus, so Red and Blue keep their directions. Sides 4 and 5 (the
+/// `q + r` sides) belong to nobody.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hexagon {
+    pub side: i32,
+}
+
+impl BoardShape for Hexagon {
+    fn cells(&self) -> Vec<Hex> {
+        let (n, max) = (self.side, 2 * self.side - 2);
+        (0..=max)
+            .flat_map(|r| (0..=max).map(move |q| Hex { q, r }))
+            .filter(|hex| (n - 1..=3 * n - 3).contains(&(hex.q + hex.r)))
+            .collect()
+    }
+
+    fn side_count(&self) -> usize {
+        6
+    }
+
+    fn on_side(&self, hex: Hex, side: usize) -> bool {
+        let (n, max) = (self.side, 2 * self.side - 2);
+        match side {
+            0 => hex.q == 0,
+            1 => hex.q == max,
+            2 => hex.r == 0,
+            3 => hex.r == max,
+            4 => hex.q + hex.r == n - 1,
+            5 => hex.q + hex.r == 3 * n - 3,
+            _ => false,
+        }
+    }
+
+    fn extent(&self) -> i32 {
+        2 * self.side - 1
+    }
+}
+
+/// A triangle with `side` cells per side, the board of Y: sides 0, 1 and 2 are `q = 0`,
+/// `r = 0` and `q + r = side - 1`, and both players win by touching all three.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Triangle {
+    pub side: i32,
+}
+
+impl BoardShape for Triangle {
+    fn cells(&self) -> Vec<Hex> {
+        (0..self.side).flat_map(|r| (0..self.side - r).map(move |q| Hex { q, r })).collect()
+    }
+
+    fn side_count(&self) -> usize {
+        3
+    }
+
+    fn on_side(&self, hex: Hex, side: usize) -> bool {
+        match side {
+            0 => hex.q == 0,
+            1 => hex.r == 0,
+            2 => hex.q + hex.r == self.side - 1,
+            _ => false,
+        }
+    }
+
+    fn goal_sides(&self, player: CellState) -> Vec<usize> {
+        match player {
+            CellState::Red | CellState::Blue => vec![0, 1, 2],
+            _ => Vec::new(),
+        }
+    }
+
+    fn extent(&self) -> i32 {
+        self.side
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sides_touched(shape: &dyn BoardShape) -> Vec<usize> {
+        (0..shape.side_count())
+            .map(|side| shape.cells().into_iter().filter(|hex| shape.on_side(*hex, side)).count())
+            .collect()
+    }
+
+    #[test]
+    fn test_shapes_have_expected_cells_and_sides() {
+        let rhombus = Rhombus { size: 4 };
+        assert_eq!(rhombus.cells().len(), 16);
+        assert_eq!(sides_touched(&rhombus), vec![4; 4]);
+
+        // 3n(n-1)+1 cells, n per side.
+        let hexagon = Hexagon { side: 3 };
+        assert_eq!(hexagon.cells().len(), 19);
+        assert_eq!(sides_touched(&hexagon), vec![3; 6]);
+        assert!(hexagon.cells().iter().all(|hex| hex.q < hexagon.extent() && hex.r < hexagon.extent()));
+
+        let triangle = Triangle { side: 4 };
+        assert_eq!(triangle.cells().len(), 10);
+        assert_eq!(sides_touched(&triangle), vec![4; 3]);
+        assert_eq!(triangle.goal_sides(CellState::Blue), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,254 @@
+use std::time::Instant;
+
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::game::{Game, GameState};
+use eframe::egui;
+
+use crate::ai_opponent::{thinking_overlay, AiConfig, BackgroundEngine, EngineShared, Decision, Difficulty, PendingReply};
+use crate::clock::{format_duration, GameClock};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::motion;
+use crate::renderer::BoardRenderer;
+
+// The user plays Red on every board.
+const HUMAN: CellState = CellState::Red;
+
+struct SimulBoard {
+    game: Game,
+    clock: GameClock,
+    engine: BackgroundEngine,
+}
+
+impl SimulBoard {
+    fn new(size: i32, difficulty: Difficulty, ai: &AiConfig, shared: &EngineShared, now: Instant) -> Self {
+        let mut clock = GameClock::default();
+        clock.switch_to(HUMAN, now);
+        Self { game: Game::with_size(size), clock, engine: BackgroundEngine::new(difficulty, ai, shared) }
+    }
+
+    fn human_to_move(&self) -> bool {
+        self.game.current_player == HUMAN && self.game.state == GameState::InProgress
+    }
+
+    fn engine_to_move(&self) -> bool {
+        self.game.current_player != HUMAN
+            && matches!(self.game.state, GameState::InProgress | GameState::WaitingForPieRuleChoice)
+    }
+
+    fn is_over(&self) -> bool {
+        matches!(self.game.state, GameState::Finished { .. })
+    }
+
+    // Applies a finished engine decision, and sends the engine off again if it is still to move.
+    fn update(&mut self, ctx: &egui::Context, ai: &AiConfig, now: Instant) {
+        if let Some(decision) = self.engine.poll(ctx, now) {
+            self.apply(decision, now);
+        }
+        if self.engine_to_move() {
+            self.engine.think(ctx, &self.game, PendingReply::new(ai, now));
+        }
+    }
+
+    fn apply(&mut self, decision: Decision, now: Instant) {
+        match decision {
+            Decision::PieRule(swap) => {
+                self.game.handle_pie_rule_decision(swap);
+                if swap {
+                    self.clock.record_move(now);
+                }
+            }
+            Decision::Move(Some(hex)) => self.play(hex, now),
+            Decision::Move(None) => tracing::warn!(difficulty = ?self.engine.difficulty(), "simul engine found no move"),
+        }
+        self.sync_clock(now);
+    }
+
+    fn play(&mut self, hex: Hex, now: Instant) {
+        let before = self.game.history.len();
+        self.game.handle_click(hex);
+        if self.game.history.len() > before {
+            self.clock.record_move(now);
+        }
+        self.sync_clock(now);
+    }
+
+    fn sync_clock(&mut self, now: Instant) {
+        match self.game.state {
+            GameState::InProgress | GameState::WaitingForPieRuleChoice => {
+                if self.clock.running() != Some(self.game.current_player) {
+                    self.clock.switch_to(self.game.current_player, now);
+                }
+            }
+            _ => self.clock.stop(now),
+        }
+    }
+
+    fn status(&self, language: Language) -> String {
+        match self.game.state {
+            GameState::Finished { winner } => {
+                tr_args(language, "game.winner", &[("winner", player_name(language, winner))])
+            }
+            _ if self.human_to_move() => tr(language, "simul.your_move").to_string(),
+            _ => tr(language, "ai.thinking").to_string(),
+        }
+    }
+}
+
+// One user against several computer opponents at once, each on its own board with its own
+// clocks. Engines think on their own threads, so the user can move elsewhere meanwhile.
+pub struct SimulSession {
+    size: i32,
+    // The opponents chosen before the start; the boards are created from them.
+    setup: Vec<Difficulty>,
+    boards: Vec<SimulBoard>,
+    current: usize,
+}
+
+impl SimulSession {
+    pub fn new(size: i32) -> Self {
+        Self { size, setup: vec![Difficulty::Easy, Difficulty::Medium], boards: Vec::new(), current: 0 }
+    }
+
+    fn start(&mut self, ai: &AiConfig, shared: &EngineShared, now: Instant) {
+        tracing::info!(boards = self.setup.len(), size = self.size, "simul started");
+        self.boards = self
+            .setup
+            .iter()
+            .map(|difficulty| SimulBoard::new(self.size, *difficulty, ai, shared, now))
+            .collect();
+        self.current = 0;
+    }
+
+    // The next board after the current one where the user is to move, if any.
+    fn next_waiting_board(&self) -> Option<usize> {
+        let count = self.boards.len();
+        (1..=count).map(|offset| (self.current + offset) % count).find(|i| self.boards[*i].human_to_move())
+    }
+
+    // Returns true once the user asks to leave.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        renderer: &mut BoardRenderer,
+        ai: &AiConfig,
+        shared: &EngineShared,
+        language: Language,
+    ) -> bool {
+        let mut leave = false;
+        ui.horizontal(|ui| {
+            ui.heading(tr(language, "simul.title"));
+            leave = ui.button(tr(language, "simul.leave")).clicked();
+        });
+        if self.boards.is_empty() {
+            self.show_setup(ui, ai, shared, language);
+        } else {
+            self.show_boards(ui, renderer, ai, language);
+        }
+        leave
+    }
+
+    fn show_setup(&mut self, ui: &mut egui::Ui, ai: &AiConfig, shared: &EngineShared, language: Language) {
+        ui.label(tr(language, "simul.hint"));
+        let mut remove = None;
+        for (i, difficulty) in self.setup.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(tr_args(language, "simul.board", &[("number", &(i + 1).to_string())]));
+                egui::ComboBox::from_id_salt(("simul_difficulty", i))
+                    .selected_text(difficulty.name(language))
+                    .show_ui(ui, |ui| {
+                        for option in Difficulty::ALL {
+                            ui.selectable_value(difficulty, option, option.name(language));
+                        }
+                    });
+                if ui.button(tr(language, "simul.remove")).clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            self.setup.remove(i);
+        }
+        ui.horizontal(|ui| {
+            if ui.button(tr(language, "simul.add")).clicked() {
+                self.setup.push(Difficulty::Easy);
+            }
+            if ui.add_enabled(!self.setup.is_empty(), egui::Button::new(tr(language, "simul.start"))).clicked() {
+                self.start(ai, shared, Instant::now());
+            }
+        });
+    }
+
+    fn show_boards(&mut self, ui: &mut egui::Ui, renderer: &mut BoardRenderer, ai: &AiConfig, language: Language) {
+        let now = Instant::now();
+        for board in &mut self.boards {
+            board.update(ui.ctx(), ai, now);
+        }
+        // The engines wake the window when they have moved; only the clocks need to tick.
+        if let Some(tick) = self.boards.iter().filter_map(|board| board.clock.next_tick(now)).min() {
+            motion::repaint_after(ui.ctx(), tick);
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            for (i, board) in self.boards.iter().enumerate() {
+                let title = tr_args(
+                    language,
+                    "simul.board_title",
+                    &[("number", &(i + 1).to_string()), ("difficulty", board.engine.difficulty().name(language))],
+                );
+                let text = format!("{} · {}", title, board.status(language));
+                let text = if board.human_to_move() { egui::RichText::new(text).strong() } else { text.into() };
+                if ui.selectable_label(i == self.current, text).clicked() {
+                    self.current = i;
+                }
+            }
+        });
+        let won = self.boards.iter().filter(|board| board.game.state == GameState::Finished { winner: HUMAN }).count();
+        if self.boards.iter().all(SimulBoard::is_over) {
+            ui.strong(tr_args(
+                language,
+                "simul.score",
+                &[("won", &won.to_string()), ("total", &self.boards.len().to_string())],
+            ));
+        }
+
+        let board = &mut self.boards[self.current];
+        ui.label(format!(
+            "{} {}  ·  {} {}",
+            player_name(language, CellState::Red),
+            format_duration(board.clock.used(CellState::Red, now)),
+            player_name(language, CellState::Blue),
+            format_duration(board.clock.used(CellState::Blue, now)),
+        ));
+        ui.label(board.status(language));
+        let board_rect = ui.available_rect_before_wrap();
+        let clicked = renderer.render_board(ui, &board.game, language);
+        if board.engine.is_thinking() {
+            thinking_overlay(ui.ctx(), board_rect, language);
+        } else if let Some(hex) = clicked.filter(|_| board.human_to_move()) {
+            board.play(hex, now);
+            // Simul etiquette: move on to the next board waiting for the user.
+            if let Some(next) = self.next_waiting_board().filter(|_| !self.boards[self.current].human_to_move()) {
+                self.current = next;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_waiting_board_skips_engine_turns() {
+        let now = Instant::now();
+        let mut session = SimulSession::new(3);
+        session.setup = vec![Difficulty::Easy; 3];
+        let ai = AiConfig::default();
+        session.start(&ai, &EngineShared::new(&ai, std::sync::mpsc::sync_channel(1).0), now);
+        session.boards[1].play(Hex { q: 1, r: 1 }, now);
+        assert!(session.boards[1].engine_to_move());
+        assert_eq!(session.next_waiting_board(), Some(2));
+        session.current = 2;
+        assert_eq!(session.next_waiting_board(), Some(0));
+    }
+}
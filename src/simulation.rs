@@ -0,0 +1,250 @@
+//! Headless game simulation for studying Hex strategies programmatically.
+//!
+//! Games are played entirely through [`Game`]'s rules engine with no UI involved.
+//!
+//! ```
+//! use coast_to_coast::ai::RandomPlayer;
+//! use coast_to_coast::simulation::simulate;
+//!
+//! let mut red = RandomPlayer::seeded(1);
+//! let mut blue = RandomPlayer::seeded(2);
+//! let report = simulate(&mut red, &mut blue, 10, 5);
+//! assert_eq!(report.games.len(), 10);
+//! assert_eq!(report.red_wins + report.blue_wins, 10);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::ai::{AiPlayer, SearchTelemetry};
+use crate::board::{CellState, Hex};
+use crate::game::{Game, GameState};
+
+/// Knobs for [`simulate_with_options`].
+#[derive(Clone, Debug)]
+pub struct SimulationOptions {
+    pub n_games: usize,
+    pub size: i32,
+    /// Keep every game's move list in [`GameOutcome::record`].
+    pub keep_records: bool,
+}
+
+/// Result of a single simulated game.
+#[derive(Clone, Debug)]
+pub struct GameOutcome {
+    pub winner: CellState,
+    /// Set when the loser's policy returned no move or an illegal one.
+    pub forfeit: bool,
+    /// Whether the second player applied the pie rule.
+    pub swapped: bool,
+    /// Number of stones placed.
+    pub moves: usize,
+    /// Time each policy call took, in play order (the pie-rule decision included).
+    pub move_times: Vec<Duration>,
+    /// Who made each policy call in `move_times`.
+    pub movers: Vec<CellState>,
+    /// What each policy call's search reported, aligned with `move_times`.
+    pub telemetry: Vec<Option<SearchTelemetry>>,
+    /// Cells in play order, present when [`SimulationOptions::keep_records`] is set.
+    pub record: Option<Vec<Hex>>,
+}
+
+/// Aggregated results returned by [`simulate`].
+#[derive(Clone, Debug, Default)]
+pub struct SimulationReport {
+    pub games: Vec<GameOutcome>,
+    pub red_wins: usize,
+    pub blue_wins: usize,
+    pub total_time: Duration,
+}
+
+impl SimulationReport {
+    /// Fraction of games won by Red, or 0 if no games were played.
+    pub fn red_win_rate(&self) -> f64 {
+        if self.games.is_empty() {
+            0.0
+        } else {
+            self.red_wins as f64 / self.games.len() as f64
+        }
+    }
+
+    /// Mean time of a single policy call across all games.
+    pub fn average_move_time(&self) -> Duration {
+        let times: Vec<Duration> = self.games.iter().flat_map(|g| g.move_times.iter().copied()).collect();
+        if times.is_empty() {
+            Duration::ZERO
+        } else {
+            times.iter().sum::<Duration>() / times.len() as u32
+        }
+    }
+}
+
+/// Plays `n_games` games on a `size`×`size` board, `policy_red` moving for Red and
+/// `policy_blue` for Blue (including the pie-rule decision). Records are not kept.
+pub fn simulate(
+    policy_red: &mut dyn AiPlayer,
+    policy_blue: &mut dyn AiPlayer,
+    n_games: usize,
+    size: i32,
+) -> SimulationReport {
+    let options = SimulationOptions { n_games, size, keep_records: false };
+    simulate_with_options(policy_red, policy_blue, &options)
+}
+
+/// Like [`simulate`], with full control over [`SimulationOptions`].
+pub fn simulate_with_options(
+    policy_red: &mut dyn AiPlayer,
+    policy_blue: &mut dyn AiPlayer,
+    options: &SimulationOptions,
+) -> SimulationReport {
+    let started = Instant::now();
+    let mut report = SimulationReport::default();
+    for _ in 0..options.n_games {
+        let outcome = play_game(policy_red, policy_blue, options);
+        match outcome.winner {
+            CellState::Red => report.red_wins += 1,
+            CellState::Blue => report.blue_wins += 1,
+            CellState::Empty | CellState::Blocked => {}
+        }
+        report.games.push(outcome);
+    }
+    report.total_time = started.elapsed();
+    report
+}
+
+fn play_game(policy_red: &mut dyn AiPlayer, policy_blue: &mut dyn AiPlayer, options: &SimulationOptions) -> GameOutcome {
+    let mut game = Game::with_size(options.size);
+    let mut outcome = GameOutcome {
+        winner: CellState::Empty,
+        forfeit: false,
+        swapped: false,
+        moves: 0,
+        move_times: Vec::new(),
+        movers: Vec::new(),
+        telemetry: Vec::new(),
+        record: options.keep_records.then(Vec::new),
+    };
+
+    loop {
+        let player = game.current_player;
+        let policy: &mut dyn AiPlayer = match player {
+            CellState::Red => &mut *policy_red,
+            _ => &mut *policy_blue,
+        };
+        match game.state {
+            GameState::Finished { winner } => {
+                outcome.winner = winner;
+                return outcome;
+            }
+            // Only players agree draws, resign or adjourn; the policies here never do.
+            GameState::Drawn | GameState::Resigned { .. } | GameState::Adjourned { .. } => return outcome,
+            GameState::WaitingForPieRuleChoice => {
+                let started = Instant::now();
+                let swap = policy.decide_pie_rule(&game);
+                outcome.move_times.push(started.elapsed());
+                outcome.movers.push(player);
+                outcome.telemetry.push(policy.telemetry());
+                outcome.swapped = swap;
+                game.handle_pie_rule_decision(swap);
+            }
+            GameState::InProgress => {
+                let started = Instant::now();
+                let choice = policy.choose_move(&game);
+                outcome.move_times.push(started.elapsed());
+                outcome.movers.push(player);
+                outcome.telemetry.push(policy.telemetry());
+                match choice.filter(|hex| game.is_legal_move(hex)) {
+                    Some(hex) => {
+                        game.handle_click(hex);
+                        outcome.moves += 1;
+                        if let Some(record) = outcome.record.as_mut() {
+                            record.push(hex);
+                        }
+                    }
+                    None => {
+                        outcome.forfeit = true;
+                        outcome.winner = match player {
+                            CellState::Red => CellState::Blue,
+                            _ => CellState::Red,
+                        };
+                        return outcome;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::RandomPlayer;
+
+    struct NoMovePlayer;
+
+    impl AiPlayer for NoMovePlayer {
+        fn choose_move(&mut self, _game: &Game) -> Option<Hex> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_simulate_counts_outcomes() {
+        let mut red = RandomPlayer::seeded(1);
+        let mut blue = RandomPlayer::seeded(2);
+        let report = simulate(&mut red, &mut blue, 20, 4);
+
+        assert_eq!(report.games.len(), 20);
+        assert_eq!(report.red_wins + report.blue_wins, 20);
+        for game in &report.games {
+            assert_ne!(game.winner, CellState::Empty);
+            assert!(!game.forfeit);
+            assert!(game.record.is_none());
+            assert!(game.moves <= 16);
+            // One timing per placement plus the pie-rule decision.
+            assert_eq!(game.move_times.len(), game.moves + 1);
+            assert_eq!(game.movers.len(), game.move_times.len());
+            assert!(game.telemetry.len() == game.move_times.len() && game.telemetry.iter().all(Option::is_none));
+            assert_eq!(&game.movers[..2], [CellState::Red, CellState::Blue]);
+        }
+    }
+
+    #[test]
+    fn test_simulate_keeps_records() {
+        let mut red = RandomPlayer::seeded(3);
+        let mut blue = RandomPlayer::seeded(4);
+        let options = SimulationOptions { n_games: 5, size: 3, keep_records: true };
+        let report = simulate_with_options(&mut red, &mut blue, &options);
+
+        for game in &report.games {
+            let record = game.record.as_ref().unwrap();
+            assert_eq!(record.len(), game.moves);
+        }
+    }
+
+    #[test]
+    fn test_simulate_is_reproducible_with_seeds() {
+        let options = SimulationOptions { n_games: 5, size: 5, keep_records: true };
+        let run = || {
+            let mut red = RandomPlayer::seeded(10);
+            let mut blue = RandomPlayer::seeded(11);
+            simulate_with_options(&mut red, &mut blue, &options)
+        };
+        let a = run();
+        let b = run();
+        for (x, y) in a.games.iter().zip(&b.games) {
+            assert_eq!(x.record, y.record);
+            assert_eq!(x.winner, y.winner);
+        }
+    }
+
+    #[test]
+    fn test_policy_without_move_forfeits() {
+        let mut red = NoMovePlayer;
+        let mut blue = RandomPlayer::seeded(5);
+        let report = simulate(&mut red, &mut blue, 1, 3);
+
+        assert_eq!(report.blue_wins, 1);
+        assert!(report.games[0].forfeit);
+        assert_eq!(report.games[0].moves, 0);
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use coast_to_coast::insights::{self, GameInsight};
+use coast_to_coast::saves;
+use eframe::egui;
+
+use crate::i18n::{tr, tr_args, Language};
+use crate::motion;
+
+const CHART_HEIGHT: f32 = 90.0;
+// Only the most played openings get a bar.
+const OPENINGS_SHOWN: usize = 8;
+
+// "My statistics": charts of the user's own play, worked out from the saved games on disk.
+// Replaying every game with the evaluation takes a while, so it runs in the background each
+// time the window opens.
+#[derive(Default)]
+pub struct StatsWindow {
+    pub open: bool,
+    pending: Option<Receiver<Vec<GameInsight>>>,
+    insights: Vec<GameInsight>,
+}
+
+impl StatsWindow {
+    pub fn toggle(&mut self, ctx: &egui::Context, data_dir: Option<&Path>, own_name: &str) {
+        self.open = !self.open;
+        let Some(data_dir) = data_dir.filter(|_| self.open) else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let data_dir = data_dir.to_path_buf();
+        let own_name = own_name.to_string();
+        thread::spawn(move || {
+            let insights: Vec<GameInsight> = saves::list(&data_dir)
+                .iter()
+                .filter_map(|saved| insights::analyse(&saved.record, &own_name))
+                .collect();
+            tracing::debug!(games = insights.len(), "play statistics computed");
+            let _ = sender.send(insights);
+            ctx.request_repaint();
+        });
+        self.pending = Some(receiver);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) {
+        if let Some(insights) = self.pending.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            self.insights = insights;
+            self.pending = None;
+        }
+        let mut open = self.open;
+        egui::Window::new(tr(language, "stats.title"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if self.pending.is_some() {
+                    ui.horizontal(|ui| {
+                        motion::busy(ui);
+                        ui.label(tr(language, "stats.computing"));
+                    });
+                    return;
+                }
+                let months = insights::by_month(&self.insights);
+                let moves: usize = self.insights.iter().map(|insight| insight.moves).sum();
+                let loss: f32 = self.insights.iter().map(|insight| insight.loss).sum();
+                ui.label(tr_args(
+                    language,
+                    "stats.summary",
+                    &[
+                        ("games", &self.insights.len().to_string()),
+                        ("moves", &moves.to_string()),
+                        ("loss", &format!("{:.1}", 100.0 * loss / moves.max(1) as f32)),
+                    ],
+                ));
+                ui.label(tr(language, "stats.local"));
+                ui.separator();
+
+                ui.strong(tr(language, "stats.blunder_rate"));
+                let bars: Vec<Bar> = months
+                    .iter()
+                    .map(|(month, totals)| Bar {
+                        label: month.clone(),
+                        value: totals.blunder_rate(),
+                        hover: format!(
+                            "{}: {:.0}% ({} / {})",
+                            month,
+                            100.0 * totals.blunder_rate(),
+                            totals.blunders,
+                            totals.moves
+                        ),
+                    })
+                    .collect();
+                bar_chart(ui, language, &bars);
+
+                ui.strong(tr(language, "stats.average_loss"));
+                let bars: Vec<Bar> = months
+                    .iter()
+                    .map(|(month, totals)| Bar {
+                        label: month.clone(),
+                        value: totals.average_loss(),
+                        hover: format!("{}: {:.1}%", month, 100.0 * totals.average_loss()),
+                    })
+                    .collect();
+                bar_chart(ui, language, &bars);
+
+                ui.strong(tr(language, "stats.openings"));
+                let bars: Vec<Bar> = insights::by_opening(&self.insights)
+                    .into_iter()
+                    .take(OPENINGS_SHOWN)
+                    .map(|opening| Bar {
+                        label: opening.opening.notation(),
+                        value: opening.score,
+                        hover: tr_args(
+                            language,
+                            "stats.opening_score",
+                            &[
+                                ("opening", &opening.opening.notation()),
+                                ("score", &format!("{:.0}", 100.0 * opening.score)),
+                                ("games", &opening.games.to_string()),
+                            ],
+                        ),
+                    })
+                    .collect();
+                bar_chart(ui, language, &bars);
+            });
+        self.open = open;
+    }
+}
+
+struct Bar {
+    label: String,
+    value: f32,
+    hover: String,
+}
+
+// Bars scaled to the tallest, each labelled underneath and explained on hover.
+fn bar_chart(ui: &mut egui::Ui, language: Language, bars: &[Bar]) {
+    if bars.is_empty() {
+        ui.weak(tr(language, "stats.no_data"));
+        return;
+    }
+    let label_height = 14.0;
+    let (response, painter) =
+        ui.allocate_painter(egui::vec2(ui.available_width(), CHART_HEIGHT + label_height), egui::Sense::hover());
+    let rect = response.rect;
+    let chart = egui::Rect::from_min_size(rect.min, egui::vec2(rect.width(), CHART_HEIGHT));
+    painter.rect_filled(chart, 2.0, ui.visuals().extreme_bg_color);
+    let tallest = bars.iter().map(|bar| bar.value).fold(0.0, f32::max).max(0.001);
+    let width = rect.width() / bars.len() as f32;
+    let mut hovered = None;
+    for (i, bar) in bars.iter().enumerate() {
+        let left = rect.left() + width * i as f32;
+        let height = CHART_HEIGHT * bar.value / tallest;
+        let column = egui::Rect::from_min_max(egui::pos2(left, chart.top()), egui::pos2(left + width, rect.bottom()));
+        let shape = egui::Rect::from_min_max(
+            egui::pos2(left + width * 0.15, chart.bottom() - height),
+            egui::pos2(left + width * 0.85, chart.bottom()),
+        );
+        let over = response.hover_pos().is_some_and(|pos| column.contains(pos));
+        let color = if over { ui.visuals().selection.bg_fill } else { ui.visuals().widgets.inactive.fg_stroke.color };
+        painter.rect_filled(shape, 0.0, color);
+        painter.text(
+            egui::pos2(left + width / 2.0, chart.bottom() + 1.0),
+            egui::Align2::CENTER_TOP,
+            &bar.label,
+            egui::FontId::proportional(10.0),
+            ui.visuals().text_color(),
+        );
+        if over {
+            hovered = Some(&bar.hover);
+        }
+    }
+    if let Some(hover) = hovered {
+        response.on_hover_text(hover);
+    }
+}
@@ -0,0 +1,167 @@
+use coast_to_coast::board::CellState;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::motion;
+use crate::renderer::{hexagon, outline_width, CELL_OUTLINE};
+use crate::themes::{file_uri, ThemePack};
+
+// Images a theme pack's stones are looked for under when its manifest doesn't name them.
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "svg", "jpg", "jpeg"];
+const RED: egui::Color32 = egui::Color32::from_rgb(230, 40, 30);
+const BLUE: egui::Color32 = egui::Color32::from_rgb(40, 100, 230);
+const FLAT_RED: egui::Color32 = egui::Color32::from_rgb(255, 0, 0);
+const FLAT_BLUE: egui::Color32 = egui::Color32::from_rgb(0, 0, 255);
+
+// How stones are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StoneStyle {
+    // Plain hexagons in the players' colors.
+    #[default]
+    Flat,
+    // Lit from the top left, so stones look rounded.
+    Shaded,
+    // Images from a theme pack.
+    Textured,
+}
+
+// Draws a stone of `player` in the cell centered at `center`, `radius` being the cell's.
+pub trait StonePainter {
+    fn paint(&self, ui: &egui::Ui, center: egui::Pos2, radius: f32, player: CellState);
+
+    // A plain color to fill the stone with, if that is all it is; the renderer then batches it
+    // with the other cells rather than calling `paint`.
+    fn flat_color(&self, _player: CellState) -> Option<egui::Color32> {
+        None
+    }
+}
+
+pub struct Flat;
+
+impl StonePainter for Flat {
+    fn paint(&self, ui: &egui::Ui, center: egui::Pos2, radius: f32, player: CellState) {
+        let Some(color) = self.flat_color(player) else {
+            return;
+        };
+        let stroke = egui::Stroke::new(outline_width(radius), CELL_OUTLINE);
+        ui.painter().add(egui::Shape::convex_polygon(hexagon(center, radius), color, stroke));
+    }
+
+    fn flat_color(&self, player: CellState) -> Option<egui::Color32> {
+        match player {
+            CellState::Red => Some(FLAT_RED),
+            CellState::Blue => Some(FLAT_BLUE),
+            CellState::Empty | CellState::Blocked => None,
+        }
+    }
+}
+
+// Stones in the players' colors, or a theme pack's.
+pub struct Shaded {
+    red: egui::Color32,
+    blue: egui::Color32,
+}
+
+impl StonePainter for Shaded {
+    // A fan from a highlight off-center to the corners, which egui shades as a gradient.
+    fn paint(&self, ui: &egui::Ui, center: egui::Pos2, radius: f32, player: CellState) {
+        let color = match player {
+            CellState::Red => self.red,
+            CellState::Blue => self.blue,
+            CellState::Empty | CellState::Blocked => return,
+        };
+        let light = color.lerp_to_gamma(egui::Color32::WHITE, 0.55);
+        let dark = color.lerp_to_gamma(egui::Color32::BLACK, 0.4);
+        let corners = hexagon(center, radius);
+        // Reduced motion does without gradients.
+        if motion::reduced(ui.ctx()) {
+            ui.painter().add(egui::Shape::convex_polygon(corners, color, egui::Stroke::new((radius * 0.06).max(1.0), dark)));
+            return;
+        }
+        let mut mesh = egui::Mesh::default();
+        mesh.colored_vertex(center + egui::vec2(-0.3, -0.35) * radius, light);
+        for corner in &corners {
+            mesh.colored_vertex(*corner, dark);
+        }
+        for i in 0..6 {
+            mesh.add_triangle(0, 1 + i, 1 + (i + 1) % 6);
+        }
+        let painter = ui.painter();
+        painter.add(mesh);
+        painter.add(egui::Shape::closed_line(corners, egui::Stroke::new((radius * 0.06).max(1.0), dark)));
+    }
+}
+
+// Stone images from a theme pack, shaped like the cells and transparent outside them. A
+// player without an image is drawn flat.
+pub struct Textured {
+    red: Option<String>,
+    blue: Option<String>,
+}
+
+impl Textured {
+    // The images the pack's manifest names, or its `red` and `blue` images.
+    pub fn load(pack: &ThemePack) -> Self {
+        let image = |named: &Option<String>, name| {
+            let path = match named {
+                Some(named) => pack.file(named),
+                None => IMAGE_EXTENSIONS.iter().find_map(|ext| pack.file(&format!("{}.{}", name, ext))),
+            };
+            if path.is_none() {
+                tracing::warn!(theme = pack.id, name, "theme has no stone image");
+            }
+            path.as_deref().map(file_uri)
+        };
+        let stones = &pack.manifest.stones;
+        Self { red: image(&stones.red, "red"), blue: image(&stones.blue, "blue") }
+    }
+}
+
+impl StonePainter for Textured {
+    fn paint(&self, ui: &egui::Ui, center: egui::Pos2, radius: f32, player: CellState) {
+        let uri = match player {
+            CellState::Red => &self.red,
+            CellState::Blue => &self.blue,
+            CellState::Empty | CellState::Blocked => return,
+        };
+        match uri {
+            Some(uri) => egui::Image::new(uri.as_str())
+                .paint_at(ui, egui::Rect::from_center_size(center, egui::Vec2::splat(radius * 2.0))),
+            None => Flat.paint(ui, center, radius, player),
+        }
+    }
+}
+
+// The painter for `style`, with the theme pack's images and colors if one is chosen. Textured
+// stones are drawn flat without one.
+pub fn painter(style: StoneStyle, pack: Option<&ThemePack>) -> Box<dyn StonePainter> {
+    match (style, pack) {
+        (StoneStyle::Flat, _) | (StoneStyle::Textured, None) => Box::new(Flat),
+        (StoneStyle::Shaded, pack) => {
+            let (red, blue) = pack.map(ThemePack::colors).unwrap_or_default();
+            Box::new(Shaded { red: red.unwrap_or(RED), blue: blue.unwrap_or(BLUE) })
+        }
+        (StoneStyle::Textured, Some(pack)) => Box::new(Textured::load(pack)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::themes::ThemeManifest;
+
+    #[test]
+    fn test_theme_packs_supply_stone_images() {
+        let dir = std::env::temp_dir().join(format!("hex-stones-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("red.png"), b"").unwrap();
+        let mut pack = ThemePack { id: "wood".to_string(), dir: dir.clone(), manifest: ThemeManifest::default() };
+        let textured = Textured::load(&pack);
+        assert!(textured.red.as_deref().is_some_and(|uri| uri.starts_with("file://") && uri.ends_with("red.png")));
+        assert_eq!(textured.blue, None);
+        // Named in the manifest instead.
+        pack.manifest.stones.blue = Some("red.png".to_string());
+        assert_eq!(Textured::load(&pack).blue, textured.red);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use coast_to_coast::board::Hex;
+use coast_to_coast::game::Game;
+use coast_to_coast::insights::{self, Mistake};
+use coast_to_coast::notation::Move;
+use coast_to_coast::saves;
+use eframe::egui;
+
+use crate::drills::{self, Deck};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::motion;
+use crate::renderer::BoardRenderer;
+
+// What a search of the saved games turned up: the files searched and the mistakes in them.
+type Collected = (Vec<String>, Vec<Mistake>);
+
+// The card on the board, and the user's answer once given.
+struct Drill {
+    card: usize,
+    game: Game,
+    answer: Option<(Hex, bool)>,
+}
+
+// Study mode: the user's blunders from their saved games, drilled as flashcards. New saves are
+// searched for mistakes in the background each time it opens.
+pub struct StudySession {
+    data_dir: PathBuf,
+    deck: Deck,
+    collecting: Option<Receiver<Collected>>,
+    // Cards added by this session's search.
+    added: usize,
+    drill: Option<Drill>,
+}
+
+impl StudySession {
+    pub fn new(ctx: &egui::Context, data_dir: &Path, own_name: &str) -> Self {
+        let deck = Deck::load(data_dir);
+        let (sender, receiver) = mpsc::channel();
+        let (ctx, data_dir_owned, own_name) = (ctx.clone(), data_dir.to_path_buf(), own_name.to_string());
+        let collected = deck.collected.clone();
+        thread::spawn(move || {
+            let mut names = Vec::new();
+            let mut mistakes = Vec::new();
+            // The autosave is still being played.
+            for saved in saves::list(&data_dir_owned).into_iter().filter(|saved| !saved.is_autosave()) {
+                let Some(name) = saved.path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if !collected.iter().any(|known| known == name) {
+                    mistakes.extend(insights::mistakes(&saved.record, &own_name));
+                    names.push(name.to_string());
+                }
+            }
+            tracing::debug!(games = names.len(), mistakes = mistakes.len(), "saved games searched for mistakes");
+            let _ = sender.send((names, mistakes));
+            ctx.request_repaint();
+        });
+        Self { data_dir: data_dir.to_path_buf(), deck, collecting: Some(receiver), added: 0, drill: None }
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.deck.save(&self.data_dir) {
+            tracing::warn!(error = %e, "failed to save the study deck");
+        }
+    }
+
+    // Puts the next card due on the board, dropping any whose position no longer replays.
+    fn next(&mut self, today: u64) {
+        self.drill = None;
+        while let Some(card) = self.deck.next_due(today) {
+            match self.deck.cards[card].game() {
+                Some(game) => {
+                    self.drill = Some(Drill { card, game, answer: None });
+                    return;
+                }
+                None => {
+                    self.deck.cards.remove(card);
+                    self.save();
+                }
+            }
+        }
+    }
+
+    // Returns true once the user asks to leave.
+    pub fn show(&mut self, ui: &mut egui::Ui, renderer: &mut BoardRenderer, language: Language) -> bool {
+        let today = drills::today();
+        if let Some((names, mistakes)) = self.collecting.as_ref().and_then(|receiver| receiver.try_recv().ok()) {
+            self.collecting = None;
+            self.added = self.deck.add(&mistakes, today);
+            self.deck.collected.extend(names);
+            self.save();
+        }
+        if self.drill.is_none() {
+            self.next(today);
+        }
+
+        let mut leave = false;
+        ui.horizontal(|ui| {
+            ui.heading(tr(language, "study.title"));
+            leave = ui.button(tr(language, "watch.leave")).clicked();
+        });
+        ui.horizontal(|ui| {
+            let due = self.deck.due(today).to_string();
+            ui.label(tr_args(language, "study.summary", &[("due", &due), ("total", &self.deck.cards.len().to_string())]));
+            if self.collecting.is_some() {
+                motion::busy(ui);
+                ui.label(tr(language, "study.collecting"));
+            } else if self.added > 0 {
+                ui.label(tr_args(language, "study.added", &[("count", &self.added.to_string())]));
+            }
+        });
+        ui.separator();
+
+        let Some(drill) = &mut self.drill else {
+            let empty = if self.deck.cards.is_empty() { "study.empty" } else { "study.done" };
+            ui.label(tr(language, empty));
+            return leave;
+        };
+        let card = &mut self.deck.cards[drill.card];
+        match drill.answer {
+            None => {
+                let player = player_name(language, drill.game.current_player);
+                ui.strong(tr_args(language, "study.prompt", &[("player", player)]));
+            }
+            Some((answer, right)) => {
+                let key = if right { "study.right" } else { "study.wrong" };
+                let args = [("answer", answer.notation()), ("best", card.best.clone()), ("played", card.played.clone())];
+                let args: Vec<(&str, &str)> = args.iter().map(|(name, value)| (*name, value.as_str())).collect();
+                ui.horizontal(|ui| {
+                    ui.strong(tr_args(language, key, &args));
+                    if ui.button(tr(language, "study.next")).clicked() {
+                        self.drill = None;
+                    }
+                });
+            }
+        }
+        let (reviews, correct) = (card.reviews.to_string(), card.correct.to_string());
+        ui.weak(tr_args(language, "study.card_stats", &[("reviews", &reviews), ("correct", &correct)]));
+
+        let Some(drill) = &mut self.drill else {
+            return leave;
+        };
+        if drill.answer.is_some() {
+            if let Ok(Move::Place(best)) = card.best.parse() {
+                renderer.candidates = vec![best];
+            }
+        }
+        if let Some(hex) = renderer.render_board(ui, &drill.game, language) {
+            if drill.answer.is_none() {
+                let right = drills::is_best(&drill.game, hex);
+                card.review(right, today);
+                drill.answer = Some((hex, right));
+                self.save();
+            }
+        }
+        leave
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use coast_to_coast::transposition::{Replacement, TableStats, TranspositionTable};
+use eframe::egui;
+
+use crate::motion;
+
+// A developer window on the engines' shared transposition table, toggled with F9 like the
+// debug console is with F12.
+#[derive(Default)]
+pub struct TablePanel {
+    pub open: bool,
+}
+
+impl TablePanel {
+    pub fn show(&mut self, ctx: &egui::Context, table: &Arc<TranspositionTable>) {
+        if ctx.input(|i| i.key_pressed(egui::Key::F9)) {
+            self.open = !self.open;
+        }
+        if !self.open {
+            return;
+        }
+        // Engines fill the table from their own threads; keep the numbers moving.
+        motion::repaint_after(ctx, Duration::from_millis(500));
+
+        let stats = table.stats();
+        egui::Window::new("Transposition Table").open(&mut self.open).resizable(false).show(ctx, |ui| {
+            for line in summary(&stats, table.memory_bytes(), table.replacement()) {
+                ui.monospace(line);
+            }
+            ui.add(egui::ProgressBar::new(stats.fill_rate()).text(format!("fill {:.1}%", stats.fill_rate() * 100.0)));
+            ui.add(egui::ProgressBar::new(stats.hit_rate()).text(format!("hits {:.1}%", stats.hit_rate() * 100.0)));
+            if ui.button("Clear").clicked() {
+                table.clear();
+            }
+        });
+    }
+}
+
+fn summary(stats: &TableStats, memory_bytes: usize, replacement: Replacement) -> [String; 3] {
+    [
+        format!("memory    {} MB, replacement {:?}", memory_bytes >> 20, replacement),
+        format!("entries   {} / {}", stats.filled, stats.capacity),
+        format!("probes    {} ({} hits)", stats.probes, stats.hits),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lines() {
+        let stats = TableStats { capacity: 1000, filled: 250, probes: 40, hits: 10 };
+        let lines = summary(&stats, 8 << 20, Replacement::Always);
+        assert_eq!(lines[0], "memory    8 MB, replacement Always");
+        assert_eq!(lines[1], "entries   250 / 1000");
+        assert_eq!(lines[2], "probes    40 (10 hits)");
+        assert_eq!(stats.fill_rate(), 0.25);
+    }
+}
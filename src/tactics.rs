@@ -0,0 +1,284 @@
+//! Tactics positions with known best moves, for measuring how strong an engine is: how often
+//! it finds the move that wins or the only one that doesn't lose, such as the answer to a bridge
+//! intrusion or an edge template defence.
+//!
+//! Positions are written as text; see `assets/tactics.txt` for the format and the ones that
+//! ship built in. `cargo test --features engine-tests` runs the app's engines through them,
+//! and `--tactics` on the command line prints each engine's score.
+//!
+//! ```
+//! use coast_to_coast::ai::GreedyPlayer;
+//! use coast_to_coast::tactics;
+//!
+//! // Blue to move has to stop Red's a2-b2 reaching c2.
+//! let text = "[Block]\nsize = 3\nmoves = a2 c1 b2\nbest = c2";
+//! let positions = tactics::parse(text).unwrap();
+//! let report = tactics::run(&mut GreedyPlayer, &positions);
+//! assert_eq!(report.solved(), 1);
+//! ```
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::ai::AiPlayer;
+use crate::board::Hex;
+use crate::game::{Game, MAX_BOARD_SIZE};
+use crate::notation::Move;
+use crate::presets::Opponent;
+
+const BUILTIN: &str = include_str!("../assets/tactics.txt");
+
+/// A position and the moves that solve it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tactic {
+    pub name: String,
+    pub size: i32,
+    /// The moves leading to the position, played without the pie rule.
+    pub moves: Vec<Move>,
+    /// Any of these solves it.
+    pub best: Vec<Hex>,
+}
+
+impl Tactic {
+    fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), size: 0, moves: Vec::new(), best: Vec::new() }
+    }
+
+    /// The position, with the player to move to find the best move. [`parse`] has checked
+    /// that the moves are legal.
+    pub fn game(&self) -> Game {
+        let mut game = Game::with_size(self.size).with_pie_rule(false);
+        for mv in &self.moves {
+            game.play_move(*mv).expect("parse checked the moves");
+        }
+        game
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TacticsError {
+    KeyWithoutName { line: usize },
+    NotAKey { line: usize },
+    UnknownKey { line: usize, key: String },
+    BadValue { line: usize, key: String, value: String },
+    // Whole positions that don't stand up, named as in the file.
+    MissingSize { name: String },
+    IllegalMove { name: String, number: usize },
+    NoBestMove { name: String },
+}
+
+impl fmt::Display for TacticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TacticsError::KeyWithoutName { line } => write!(f, "line {}: setting before a [name]", line),
+            TacticsError::NotAKey { line } => write!(f, "line {}: expected key = value", line),
+            TacticsError::UnknownKey { line, key } => write!(f, "line {}: unknown key {:?}", line, key),
+            TacticsError::BadValue { line, key, value } => write!(f, "line {}: bad {} {:?}", line, key, value),
+            TacticsError::MissingSize { name } => write!(f, "{}: no size", name),
+            TacticsError::IllegalMove { name, number } => write!(f, "{}: move {} is illegal", name, number),
+            TacticsError::NoBestMove { name } => write!(f, "{}: no legal best move", name),
+        }
+    }
+}
+
+impl std::error::Error for TacticsError {}
+
+/// Reads every position in `text`, checking that its moves can be played and its best moves
+/// are legal after them.
+pub fn parse(text: &str) -> Result<Vec<Tactic>, TacticsError> {
+    let mut tactics: Vec<Tactic> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            tactics.push(Tactic::new(name.trim()));
+            continue;
+        }
+        let tactic = tactics.last_mut().ok_or(TacticsError::KeyWithoutName { line: line_number })?;
+        let (key, value) = trimmed.split_once('=').ok_or(TacticsError::NotAKey { line: line_number })?;
+        let (key, value) = (key.trim(), value.trim());
+        let bad_value = || TacticsError::BadValue { line: line_number, key: key.to_string(), value: value.to_string() };
+        match key {
+            "size" => {
+                tactic.size = value.parse().ok().filter(|size| (1..=MAX_BOARD_SIZE).contains(size)).ok_or_else(bad_value)?
+            }
+            "moves" => {
+                tactic.moves = value.split_whitespace().map(str::parse).collect::<Result<_, _>>().map_err(|_| bad_value())?
+            }
+            "best" => {
+                tactic.best = value
+                    .split_whitespace()
+                    .map(|cell| match cell.parse() {
+                        Ok(Move::Place(hex)) => Ok(hex),
+                        _ => Err(bad_value()),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            _ => return Err(TacticsError::UnknownKey { line: line_number, key: key.to_string() }),
+        }
+    }
+    for tactic in &tactics {
+        check(tactic)?;
+    }
+    Ok(tactics)
+}
+
+fn check(tactic: &Tactic) -> Result<(), TacticsError> {
+    if tactic.size == 0 {
+        return Err(TacticsError::MissingSize { name: tactic.name.clone() });
+    }
+    let mut game = Game::with_size(tactic.size).with_pie_rule(false);
+    for (i, mv) in tactic.moves.iter().enumerate() {
+        game.play_move(*mv).map_err(|_| TacticsError::IllegalMove { name: tactic.name.clone(), number: i + 1 })?;
+    }
+    if tactic.best.is_empty() || !tactic.best.iter().all(|hex| game.is_legal_move(hex)) {
+        return Err(TacticsError::NoBestMove { name: tactic.name.clone() });
+    }
+    Ok(())
+}
+
+/// The positions that ship with the app.
+pub fn builtin() -> Vec<Tactic> {
+    parse(BUILTIN).expect("built-in tactics parse")
+}
+
+/// How an engine did on one position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TacticResult {
+    pub name: String,
+    /// What the engine played; None if it found nothing.
+    pub chosen: Option<Hex>,
+    pub solved: bool,
+    pub elapsed: Duration,
+}
+
+/// How an engine did on a set of positions; displays as a line per position and a score.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TacticsReport {
+    pub results: Vec<TacticResult>,
+}
+
+impl TacticsReport {
+    pub fn solved(&self) -> usize {
+        self.results.iter().filter(|result| result.solved).count()
+    }
+
+    /// The share of positions solved, from 0 to 1; 1 for no positions.
+    pub fn score(&self) -> f32 {
+        if self.results.is_empty() {
+            return 1.0;
+        }
+        self.solved() as f32 / self.results.len() as f32
+    }
+}
+
+impl fmt::Display for TacticsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.results.iter().map(|result| result.name.chars().count()).max().unwrap_or(0);
+        for result in &self.results {
+            let chosen = result.chosen.map_or("-".to_string(), |hex| hex.notation());
+            let mark = if result.solved { "ok" } else { "MISS" };
+            let seconds = result.elapsed.as_secs_f32();
+            writeln!(f, "{mark:<4} {:<width$}  {chosen:<4} {seconds:.2}s", result.name)?;
+        }
+        writeln!(f, "solved {} of {} ({:.0}%)", self.solved(), self.results.len(), self.score() * 100.0)
+    }
+}
+
+/// Asks `player` for a move in each of `tactics`.
+pub fn run(player: &mut dyn AiPlayer, tactics: &[Tactic]) -> TacticsReport {
+    let results = tactics
+        .iter()
+        .map(|tactic| {
+            let game = tactic.game();
+            let started = Instant::now();
+            let chosen = player.choose_move(&game);
+            let solved = chosen.is_some_and(|hex| tactic.best.contains(&hex));
+            tracing::debug!(position = %tactic.name, ?chosen, solved, "tactics position tried");
+            TacticResult { name: tactic.name.clone(), chosen, solved, elapsed: started.elapsed() }
+        })
+        .collect();
+    TacticsReport { results }
+}
+
+/// The engines to score from the command line: `--tactics` for all of them, or e.g.
+/// `--tactics=hard,classic`. None without the flag; the unknown name if there is one.
+pub fn opponents_from_args(args: impl IntoIterator<Item = String>) -> Option<Result<Vec<Opponent>, String>> {
+    args.into_iter().find_map(|arg| match arg.strip_prefix("--tactics")? {
+        "" => Some(Ok(Opponent::ALL.to_vec())),
+        rest => {
+            let names = rest.strip_prefix('=')?;
+            Some(names.split(',').map(|name| name.parse().map_err(|_| name.to_string())).collect())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{GreedyPlayer, RandomPlayer};
+    #[cfg(feature = "engine-tests")]
+    use crate::{alphabeta::AlphaBetaPlayer, mcts::{BridgePlayout, MctsPlayer}};
+
+    #[test]
+    fn test_parse_and_score() {
+        let builtin = builtin();
+        assert!(builtin.len() >= 8);
+        for tactic in &builtin {
+            assert!(tactic.game().legal_moves().count() > tactic.best.len(), "{}", tactic.name);
+        }
+
+        let text = "[Block]\nsize = 3\nmoves = a2 c1 b2\nbest = c2\n\n[Centre]\nsize = 3\nbest = b2";
+        let tactics = parse(text).unwrap();
+        assert_eq!(tactics[0].best, vec![Hex { q: 2, r: 1 }]);
+        let report = run(&mut RandomPlayer::seeded(1), &tactics[1..]);
+        assert_eq!(report.results.len(), 1);
+        let report = run(&mut GreedyPlayer, &tactics);
+        assert_eq!((report.solved(), report.score()), (2, 1.0));
+        assert!(report.to_string().ends_with("solved 2 of 2 (100%)\n"));
+
+        assert_eq!(parse("[A]\nmoves = a1"), Err(TacticsError::MissingSize { name: "A".into() }));
+        assert_eq!(
+            parse("[A]\nsize = 2\nmoves = a1 a1\nbest = b2"),
+            Err(TacticsError::IllegalMove { name: "A".into(), number: 2 })
+        );
+        assert_eq!(parse("[A]\nsize = 2\nmoves = a1\nbest = a1"), Err(TacticsError::NoBestMove { name: "A".into() }));
+        assert_eq!(
+            parse("[A]\nbest = swap"),
+            Err(TacticsError::BadValue { line: 2, key: "best".into(), value: "swap".into() })
+        );
+    }
+
+    // Plays the engines at the app's strength, so it is slow; add `--release -- --nocapture`
+    // to see the reports.
+    #[cfg(feature = "engine-tests")]
+    #[test]
+    fn test_engine_strength() {
+        let tactics = builtin();
+        let engines: [(&str, Box<dyn AiPlayer>, f32); 3] = [
+            ("medium", Box::new(GreedyPlayer), 0.75),
+            ("hard", Box::new(MctsPlayer::new(10_000, Box::new(BridgePlayout)).seeded(1)), 0.9),
+            ("classic", Box::new(AlphaBetaPlayer::new(6)), 0.9),
+        ];
+        for (name, mut engine, floor) in engines {
+            let report = run(engine.as_mut(), &tactics);
+            println!("{name}\n{report}");
+            assert!(report.score() >= floor, "{name} solved only {:.0}%", report.score() * 100.0);
+        }
+    }
+
+    #[test]
+    fn test_opponents_from_args() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(opponents_from_args(args(&["hex"])), None);
+        assert_eq!(opponents_from_args(args(&["hex", "--tactics"])), Some(Ok(Opponent::ALL.to_vec())));
+        assert_eq!(
+            opponents_from_args(args(&["hex", "--tactics=hard,classic"])),
+            Some(Ok(vec![Opponent::Hard, Opponent::Classic]))
+        );
+        assert_eq!(opponents_from_args(args(&["--tactics=hard,best"])), Some(Err("best".to_string())));
+    }
+}
@@ -0,0 +1,96 @@
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::Game;
+use eframe::egui;
+
+use crate::i18n::{player_name, tr, tr_args, Language};
+
+// The takeback exchange between two players: one asks to undo moves, the other answers. In a
+// local game both sides answer through `TakebackDialog`; a network game would carry the same
+// messages between the clients, each applying an accepted takeback to its game and clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TakebackMessage {
+    Request { requester: CellState, moves: usize },
+    Accept,
+    Decline,
+}
+
+// Asks the opponent of the player who made the last move whether they may take it back.
+#[derive(Default)]
+pub struct TakebackDialog {
+    pending: Option<(CellState, usize)>,
+}
+
+impl TakebackDialog {
+    // The request for undoing `game`'s last move, or None before any move.
+    pub fn request_for(game: &Game) -> Option<TakebackMessage> {
+        let requester = *game.turn_structure.movers(&game.history).last()?;
+        Some(TakebackMessage::Request { requester, moves: 1 })
+    }
+
+    pub fn receive(&mut self, message: TakebackMessage) {
+        if let TakebackMessage::Request { requester, moves } = message {
+            self.pending = Some((requester, moves));
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    // Shows the pending request and returns the opponent's answer with the number of moves
+    // requested, once given.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) -> Option<(TakebackMessage, usize)> {
+        let (requester, moves) = self.pending?;
+        let opponent = match requester {
+            CellState::Red => CellState::Blue,
+            _ => CellState::Red,
+        };
+        let mut answer = None;
+        egui::Window::new(tr(language, "takeback.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr_args(
+                    language,
+                    "takeback.message",
+                    &[("player", player_name(language, requester)), ("opponent", player_name(language, opponent))],
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "takeback.accept")).clicked() {
+                        answer = Some(TakebackMessage::Accept);
+                    }
+                    if ui.button(tr(language, "takeback.decline")).clicked() {
+                        answer = Some(TakebackMessage::Decline);
+                    }
+                });
+            });
+        if answer.is_some() {
+            self.pending = None;
+        }
+        answer.map(|answer| (answer, moves))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_comes_from_last_mover() {
+        let mut game = Game::with_size(3);
+        assert_eq!(TakebackDialog::request_for(&game), None);
+        game.play_move("b2".parse().unwrap()).unwrap();
+        game.play_move("swap".parse().unwrap()).unwrap();
+        assert_eq!(
+            TakebackDialog::request_for(&game),
+            Some(TakebackMessage::Request { requester: CellState::Blue, moves: 1 })
+        );
+
+        let mut dialog = TakebackDialog::default();
+        dialog.receive(TakebackMessage::Decline);
+        assert!(!dialog.is_pending());
+        dialog.receive(TakebackDialog::request_for(&game).unwrap());
+        assert!(dialog.is_pending());
+    }
+}
@@ -0,0 +1,43 @@
+use coast_to_coast::board::{CellState, Hex};
+use coast_to_coast::eval;
+use coast_to_coast::game::Game;
+
+// How many of the engine's best moves the student may choose from.
+pub const CANDIDATE_COUNT: usize = 3;
+
+// The engine's candidates for the current position, ranked once per position rather than
+// every frame.
+#[derive(Default)]
+pub struct TeachingCandidates {
+    position: Option<(u64, CellState)>,
+    moves: Vec<Hex>,
+}
+
+impl TeachingCandidates {
+    pub fn for_game(&mut self, game: &Game) -> &[Hex] {
+        let position = (game.board.position_hash(), game.current_player);
+        if self.position != Some(position) {
+            self.position = Some(position);
+            self.moves = eval::rank_moves(game).into_iter().take(CANDIDATE_COUNT).map(|(hex, _)| hex).collect();
+        }
+        &self.moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates_follow_the_position() {
+        let mut game = Game::with_size(5);
+        let mut candidates = TeachingCandidates::default();
+        let first = candidates.for_game(&game).to_vec();
+        assert_eq!(first.len(), CANDIDATE_COUNT);
+        assert_eq!(first[0], Hex { q: 2, r: 2 });
+
+        game.handle_click(first[0]);
+        game.handle_pie_rule_decision(false);
+        assert!(!candidates.for_game(&game).contains(&first[0]));
+    }
+}
@@ -0,0 +1,256 @@
+//! Edge templates: a stone of a player's and the empty cells (its carrier) that together
+//! connect the stone to one of their edges whatever the opponent plays, as long as the
+//! carrier stays empty.
+//!
+//! Templates are written as text; see `assets/templates.txt` for the format and the ones that
+//! ship built in. More can be added as `.txt` files in `<data_dir>/templates/`.
+//!
+//! ```
+//! use coast_to_coast::board::{Board, CellState, Hex};
+//! use coast_to_coast::templates;
+//!
+//! let mut board = Board::new(5);
+//! board.set_cell(Hex { q: 2, r: 1 }, CellState::Blue);
+//! let matches = templates::find(&board, &templates::builtin());
+//! // c2 is on the second row from Blue's top edge, with c1 and d1 free in front of it.
+//! assert_eq!(matches[0].name, "Template II");
+//! assert_eq!(matches[0].carrier, vec![Hex { q: 2, r: 0 }, Hex { q: 3, r: 0 }]);
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::board::{Board, CellState, Hex};
+use crate::ladder::Rows;
+use crate::prover::{self, Proof};
+
+// User templates live as text files in `<data_dir>/templates/`.
+pub const TEMPLATES_DIR: &str = "templates";
+
+const BUILTIN: &str = include_str!("../assets/templates.txt");
+
+/// A template as drawn for side 0 or 2 of the board: cells as `(along, depth)`, with depth 0
+/// the edge row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    stones: Vec<(i32, i32)>,
+    carrier: Vec<(i32, i32)>,
+}
+
+impl Template {
+    /// The template's stones, as `(along, depth)`.
+    pub fn stones(&self) -> &[(i32, i32)] {
+        &self.stones
+    }
+
+    /// The empty cells the template needs, as `(along, depth)`.
+    pub fn carrier(&self) -> &[(i32, i32)] {
+        &self.carrier
+    }
+
+    // The same template reflected along the edge.
+    fn mirrored(&self) -> Template {
+        let mirror = |(along, depth): &(i32, i32)| (-along - depth, *depth);
+        Template {
+            name: self.name.clone(),
+            stones: self.stones.iter().map(mirror).collect(),
+            carrier: self.carrier.iter().map(mirror).collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    RowsWithoutName { line: usize },
+    Misaligned { line: usize },
+    UnknownCell { line: usize, cell: char },
+    NoStone { name: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::RowsWithoutName { line } => write!(f, "line {}: rows before a [name]", line),
+            TemplateError::Misaligned { line } => write!(f, "line {}: cell between two columns", line),
+            TemplateError::UnknownCell { line, cell } => write!(f, "line {}: unknown cell {:?}", line, cell),
+            TemplateError::NoStone { name } => write!(f, "template {}: no stone", name),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Reads every template in `text`.
+pub fn parse(text: &str) -> Result<Vec<Template>, TemplateError> {
+    let mut templates: Vec<Template> = Vec::new();
+    let mut depth = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            templates.push(Template { name: name.trim().to_string(), stones: Vec::new(), carrier: Vec::new() });
+            depth = 0;
+            continue;
+        }
+        let Some(template) = templates.last_mut() else {
+            return Err(TemplateError::RowsWithoutName { line: line_number });
+        };
+        for (column, cell) in line.chars().enumerate().filter(|(_, cell)| !cell.is_whitespace()) {
+            let offset = column as i32 - depth;
+            if offset % 2 != 0 {
+                return Err(TemplateError::Misaligned { line: line_number });
+            }
+            let position = (offset / 2, depth);
+            match cell {
+                'x' => template.stones.push(position),
+                '.' => template.carrier.push(position),
+                cell => return Err(TemplateError::UnknownCell { line: line_number, cell }),
+            }
+        }
+        depth += 1;
+    }
+    if let Some(template) = templates.iter().find(|template| template.stones.is_empty()) {
+        return Err(TemplateError::NoStone { name: template.name.clone() });
+    }
+    Ok(templates)
+}
+
+/// The templates that ship with the game.
+pub fn builtin() -> Vec<Template> {
+    parse(BUILTIN).expect("built-in templates parse")
+}
+
+/// The user's own templates from `<data_dir>/templates/*.txt`. Files that don't parse, and
+/// templates the prover shows don't hold, are skipped with a warning. Templates too big for it
+/// to settle are kept, with a warning that they are unchecked.
+pub fn load_user(data_dir: &Path) -> Vec<Template> {
+    let Ok(entries) = fs::read_dir(data_dir.join(TEMPLATES_DIR)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let text = fs::read_to_string(&path).ok()?;
+            match parse(&text) {
+                Ok(templates) => Some(templates),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %e, "skipping unreadable templates");
+                    None
+                }
+            }
+        })
+        .flatten()
+        .filter(|template| match prover::prove(template, prover::DEFAULT_BUDGET) {
+            Proof::Holds => true,
+            Proof::Fails => {
+                tracing::warn!(name = %template.name, "skipping a template the opponent can cut");
+                false
+            }
+            Proof::Unknown => {
+                tracing::warn!(name = %template.name, "using a template too big to check");
+                true
+            }
+        })
+        .collect()
+}
+
+/// A template found on the board: `stones` are connected to `player`'s edge `side` for as long
+/// as the `carrier` cells stay empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateMatch {
+    pub name: String,
+    pub player: CellState,
+    pub side: usize,
+    pub stones: Vec<Hex>,
+    pub carrier: Vec<Hex>,
+}
+
+/// Every place on `board` where one of `templates` holds, for either player. Only the Hex
+/// rhombus's four sides are searched.
+pub fn find(board: &Board, templates: &[Template]) -> Vec<TemplateMatch> {
+    let shape = board.shape();
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    for player in [CellState::Red, CellState::Blue] {
+        for side in shape.goal_sides(player).into_iter().filter(|side| *side < 4) {
+            let rows = Rows { side, size: board.size };
+            // Templates are drawn for sides whose rows lean the other way from these.
+            let lean = |(a, d): (i32, i32)| if rows.step() > 0 { (a, d) } else { (a + d, d) };
+            for template in templates.iter().flat_map(|template| [template.clone(), template.mirrored()]) {
+                let (anchor_along, anchor_depth) = lean(template.stones[0]);
+                for (stone, _) in board.cells.iter().filter(|(_, state)| **state == player) {
+                    let (depth, along) = rows.position(*stone);
+                    if depth != anchor_depth {
+                        continue;
+                    }
+                    let place = |cell: &(i32, i32)| {
+                        let (a, d) = lean(*cell);
+                        rows.hex(d, a - anchor_along + along)
+                    };
+                    let stones: Vec<Hex> = template.stones.iter().map(place).collect();
+                    let mut carrier: Vec<Hex> = template.carrier.iter().map(place).collect();
+                    let holds = stones.iter().all(|hex| board.get_cell(hex) == Some(&player))
+                        && carrier.iter().all(|hex| board.get_cell(hex) == Some(&CellState::Empty))
+                        && template.carrier.iter().zip(&carrier).all(|((_, d), hex)| *d > 0 || shape.on_side(*hex, side));
+                    carrier.sort_by_key(|hex| (hex.r, hex.q));
+                    // Symmetric templates match their own mirror image.
+                    if holds && seen.insert((template.name.clone(), side, carrier.clone())) {
+                        found.push(TemplateMatch { name: template.name.clone(), player, side, stones, carrier });
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_find_templates() {
+        let templates = builtin();
+        assert_eq!(templates.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["Template II", "Ziggurat"]);
+        assert_eq!(templates[1].stones, vec![(0, 2)]);
+        assert_eq!(templates[1].carrier.len(), 8);
+        assert_eq!(parse("[Bad]\n. .\n x\n.x"), Err(TemplateError::Misaligned { line: 4 }));
+        assert_eq!(parse(". .\n x"), Err(TemplateError::RowsWithoutName { line: 1 }));
+        assert_eq!(parse("[Empty]\n. ."), Err(TemplateError::NoStone { name: "Empty".to_string() }));
+
+        // Red's c3 has a ziggurat to each of its edges on 5x5; the mirror images run off the
+        // board. Blue's a5 spoils the one to the left edge.
+        let mut board = Board::new(5);
+        board.set_cell(Hex { q: 2, r: 2 }, CellState::Red);
+        let found = find(&board, &templates);
+        let names: Vec<(&str, usize)> = found.iter().map(|found| (found.name.as_str(), found.side)).collect();
+        assert_eq!(names, [("Ziggurat", 0), ("Ziggurat", 1)]);
+        assert!(found[0].carrier.contains(&Hex { q: 0, r: 4 }) && found[1].carrier.contains(&Hex { q: 4, r: 0 }));
+        board.set_cell(Hex { q: 0, r: 4 }, CellState::Blue);
+        let found = find(&board, &templates);
+        assert_eq!((found.len(), found[0].side, found[0].stones.as_slice()), (1, 1, [Hex { q: 2, r: 2 }].as_slice()));
+    }
+
+    #[test]
+    fn test_user_templates_are_proved() {
+        let dir = std::env::temp_dir().join(format!("hex-templates-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join(TEMPLATES_DIR)).unwrap();
+        fs::write(dir.join(TEMPLATES_DIR).join("mine.txt"), "[Wide bridge]\n. . .\n x\n[Half bridge]\n.\n x").unwrap();
+        fs::write(dir.join(TEMPLATES_DIR).join("broken.txt"), ". x").unwrap();
+        let names: Vec<String> = load_user(&dir).into_iter().map(|template| template.name).collect();
+        assert_eq!(names, ["Wide bridge"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use eframe::egui;
+use serde::Deserialize;
+
+// Theme packs live in `<data_dir>/themes/<id>/`, each described by a manifest:
+//
+//   name = "Walnut"
+//   author = "..."
+//   dark = true               # a dark interface; leave out to keep the user's choice
+//   [stones]
+//   red = "red.png"           # images for textured stones, in the pack's folder
+//   blue = "blue.png"
+//   red_color = "#d03a2a"     # colors for shaded stones
+//   blue_color = "#2860d0"
+//   [background]              # behind the board: an image, scaled to cover, or a gradient
+//   image = "felt.jpg"
+//   gradient = ["#203040", "#101820"]
+//   [board]
+//   frame = "wood.png"        # a texture tiled around the cells, or a plain color
+//   frame_color = "#6b4a2b"
+pub const THEMES_DIR: &str = "themes";
+pub const MANIFEST_FILE: &str = "theme.toml";
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ThemeManifest {
+    pub name: String,
+    pub author: String,
+    pub dark: Option<bool>,
+    pub stones: StonesManifest,
+    pub background: BackgroundManifest,
+    pub board: BoardManifest,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct StonesManifest {
+    pub red: Option<String>,
+    pub blue: Option<String>,
+    pub red_color: Option<String>,
+    pub blue_color: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BackgroundManifest {
+    pub image: Option<String>,
+    // Top and bottom colors.
+    pub gradient: Option<[String; 2]>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BoardManifest {
+    pub frame: Option<String>,
+    pub frame_color: Option<String>,
+}
+
+// What a theme draws beneath the cells, as the renderer takes it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Backdrop {
+    // An image URI, else a top-to-bottom gradient.
+    pub image: Option<String>,
+    pub gradient: Option<(egui::Color32, egui::Color32)>,
+    // A texture URI tiled around the cells, else a color.
+    pub frame: Option<String>,
+    pub frame_color: Option<egui::Color32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThemePack {
+    // The pack's folder name, which the settings refer to it by.
+    pub id: String,
+    pub dir: PathBuf,
+    pub manifest: ThemeManifest,
+}
+
+impl ThemePack {
+    pub fn name(&self) -> &str {
+        if self.manifest.name.trim().is_empty() {
+            &self.id
+        } else {
+            &self.manifest.name
+        }
+    }
+
+    // A file the manifest names, as long as it is inside the pack.
+    pub fn file(&self, name: &str) -> Option<PathBuf> {
+        let relative = Path::new(name.trim());
+        let inside = relative.components().all(|component| matches!(component, Component::Normal(_)));
+        if !inside {
+            tracing::warn!(theme = self.id, name, "theme file outside its pack");
+            return None;
+        }
+        Some(self.dir.join(relative)).filter(|path| path.is_file())
+    }
+
+    // Colors for shaded stones, where the manifest gives them.
+    pub fn colors(&self) -> (Option<egui::Color32>, Option<egui::Color32>) {
+        let stones = &self.manifest.stones;
+        (stones.red_color.as_deref().and_then(parse_color), stones.blue_color.as_deref().and_then(parse_color))
+    }
+
+    pub fn backdrop(&self) -> Backdrop {
+        let uri = |name: &Option<String>| name.as_deref().and_then(|name| self.file(name)).map(|path| file_uri(&path));
+        let (background, board) = (&self.manifest.background, &self.manifest.board);
+        let gradient =
+            background.gradient.as_ref().and_then(|[top, bottom]| Some((parse_color(top)?, parse_color(bottom)?)));
+        Backdrop {
+            image: uri(&background.image),
+            gradient,
+            frame: uri(&board.frame),
+            frame_color: board.frame_color.as_deref().and_then(parse_color),
+        }
+    }
+}
+
+pub fn file_uri(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+// "#rrggbb", with or without the "#".
+fn parse_color(text: &str) -> Option<egui::Color32> {
+    let hex = text.trim().trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    match hex.len() {
+        6 => Some(egui::Color32::from_rgb(channel(0)?, channel(2)?, channel(4)?)),
+        _ => None,
+    }
+}
+
+// The theme packs found in the data directory, read again on `reload` so a pack being worked
+// on can be tried without restarting.
+#[derive(Default)]
+pub struct ThemeLibrary {
+    dir: Option<PathBuf>,
+    pub packs: Vec<ThemePack>,
+    // Counts reloads, so whoever applied a pack knows to apply it again.
+    pub generation: u64,
+}
+
+impl ThemeLibrary {
+    pub fn load(data_dir: Option<&Path>) -> Self {
+        let mut library = Self { dir: data_dir.map(|dir| dir.join(THEMES_DIR)), ..Default::default() };
+        library.reload();
+        library
+    }
+
+    pub fn reload(&mut self) {
+        self.packs = self.dir.as_deref().map(discover).unwrap_or_default();
+        self.generation += 1;
+        tracing::debug!(packs = self.packs.len(), "theme packs loaded");
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ThemePack> {
+        self.packs.iter().find(|pack| pack.id == id.trim())
+    }
+}
+
+// Every folder in `dir` with a readable manifest, by name. Packs whose manifest doesn't read
+// are skipped with a warning.
+fn discover(dir: &Path) -> Vec<ThemePack> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<ThemePack> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let text = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+            let manifest = match toml::from_str(&text) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    tracing::warn!(theme = %dir.display(), error = %e, "skipping unreadable theme pack");
+                    return None;
+                }
+            };
+            Some(ThemePack { id: entry.file_name().to_string_lossy().into_owned(), dir, manifest })
+        })
+        .collect();
+    packs.sort_by_key(|pack| pack.name().to_lowercase());
+    packs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packs_are_discovered_and_reloaded() {
+        let dir = std::env::temp_dir().join(format!("hex-themes-test-{}", std::process::id()));
+        let walnut = dir.join(THEMES_DIR).join("walnut");
+        fs::create_dir_all(&walnut).unwrap();
+        fs::create_dir_all(dir.join(THEMES_DIR).join("no-manifest")).unwrap();
+        let manifest = "name = \"Walnut\"\ndark = true\n\
+                        [stones]\nred = \"red.png\"\nblue = \"../blue.png\"\nred_color = \"#d03a2a\"\n\
+                        [background]\ngradient = [\"#203040\", \"000000\"]\n[board]\nframe = \"red.png\"\n";
+        fs::write(walnut.join(MANIFEST_FILE), manifest).unwrap();
+        fs::write(walnut.join("red.png"), b"").unwrap();
+
+        let mut library = ThemeLibrary::load(Some(&dir));
+        assert_eq!(library.packs.len(), 1);
+        let pack = library.get("walnut").unwrap();
+        assert_eq!((pack.name(), pack.manifest.dark), ("Walnut", Some(true)));
+        assert_eq!(pack.file("red.png"), Some(walnut.join("red.png")));
+        assert_eq!(pack.file("../blue.png"), None);
+        assert_eq!(pack.colors(), (Some(egui::Color32::from_rgb(0xd0, 0x3a, 0x2a)), None));
+        let backdrop = pack.backdrop();
+        assert_eq!(backdrop.frame, Some(file_uri(&walnut.join("red.png"))));
+        assert_eq!(backdrop.gradient, Some((egui::Color32::from_rgb(0x20, 0x30, 0x40), egui::Color32::BLACK)));
+        assert_eq!((backdrop.image, backdrop.frame_color), (None, None));
+
+        fs::write(walnut.join(MANIFEST_FILE), "name = [").unwrap();
+        let generation = library.generation;
+        library.reload();
+        assert!(library.packs.is_empty() && library.generation > generation);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
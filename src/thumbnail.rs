@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use coast_to_coast::board::Board;
+use eframe::egui;
+
+use crate::offscreen::Scene;
+
+pub const THUMBNAIL_SIZE: [usize; 2] = [96, 64];
+
+// Rasterized thumbnails for the games browser, keyed by game id (its save path). Each entry
+// remembers the position hash it was drawn from and is redrawn only when that changes.
+#[derive(Default)]
+pub struct ThumbnailCache {
+    textures: HashMap<PathBuf, (u64, egui::TextureHandle)>,
+}
+
+impl ThumbnailCache {
+    pub fn get(&mut self, ctx: &egui::Context, id: &Path, board: &Board) -> egui::TextureHandle {
+        let hash = board.position_hash();
+        if let Some((cached_hash, texture)) = self.textures.get(id) {
+            if *cached_hash == hash {
+                return texture.clone();
+            }
+        }
+        let texture = ctx.load_texture(
+            format!("thumbnail-{}", id.display()),
+            rasterize(board, THUMBNAIL_SIZE),
+            egui::TextureOptions::LINEAR,
+        );
+        self.textures.insert(id.to_path_buf(), (hash, texture.clone()));
+        texture
+    }
+
+    // Drops thumbnails of games that are no longer listed, e.g. after a delete.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        self.textures.retain(|id, _| keep(id));
+    }
+}
+
+// Draws `board` into a `[width, height]` image, scaled to fit and centered, as exports draw it.
+pub fn rasterize(board: &Board, size: [usize; 2]) -> egui::ColorImage {
+    Scene::new(board, None).image(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::board::{CellState, Hex};
+    use crate::offscreen::{BLUE, EMPTY, RED};
+
+    #[test]
+    fn test_rasterize_colors_cells() {
+        let mut board = Board::new(3);
+        board.set_cell(Hex { q: 1, r: 0 }, CellState::Red);
+        board.set_cell(Hex { q: 1, r: 2 }, CellState::Blue);
+        let image = rasterize(&board, THUMBNAIL_SIZE);
+
+        assert_eq!(image.size, THUMBNAIL_SIZE);
+        let count = |color| image.pixels.iter().filter(|pixel| **pixel == color).count();
+        let (red, blue, empty) = (count(RED), count(BLUE), count(EMPTY));
+        // Red and Blue also line the board's edges.
+        assert!(red > 0 && blue > 0 && empty > red && empty > blue);
+        // The rhombus leaves the corners of the image transparent.
+        assert_eq!(image.pixels[0], egui::Color32::TRANSPARENT);
+    }
+}
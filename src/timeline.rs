@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use coast_to_coast::board::CellState;
+use coast_to_coast::notation::Move;
+use coast_to_coast::record::GameResult;
+use eframe::egui;
+
+use crate::clock::format_duration;
+use crate::events::GameEvent;
+use crate::game_info::result_text;
+use crate::i18n::{player_name, tr, tr_args, Language};
+
+struct Entry {
+    at: Instant,
+    event: GameEvent,
+    // Moves in the game just after the event; where clicking the entry takes the board.
+    moves: usize,
+    // Taken back since, so its position is no longer in the game's history.
+    undone: bool,
+}
+
+// Everything that happened in the current game, in order, as the event bus reported it. Clones
+// share one timeline, so one can subscribe to the bus while the app draws another.
+#[derive(Clone, Default)]
+pub struct Timeline {
+    entries: Rc<RefCell<Vec<Entry>>>,
+}
+
+impl Timeline {
+    pub fn handle(&self, event: &GameEvent) {
+        let mut entries = self.entries.borrow_mut();
+        let last = entries.iter().rev().find(|entry| !entry.undone).map_or(0, |entry| entry.moves);
+        let moves = match *event {
+            GameEvent::GameStarted { moves, .. } => {
+                entries.clear();
+                moves
+            }
+            GameEvent::MovePlayed { move_number, .. } => move_number,
+            GameEvent::TakenBack { moves } => {
+                for entry in entries.iter_mut().filter(|entry| entry.moves > moves) {
+                    entry.undone = true;
+                }
+                moves
+            }
+            GameEvent::GameFinished { .. } | GameEvent::ResultAgreed { .. } => last,
+        };
+        entries.push(Entry { at: Instant::now(), event: event.clone(), moves, undone: false });
+    }
+
+    // The timeline, newest at the bottom. Returns the number of moves to show the board after
+    // when an entry is clicked.
+    pub fn show(&self, ui: &mut egui::Ui, language: Language) -> Option<usize> {
+        let entries = self.entries.borrow();
+        let start = entries.first().map(|entry| entry.at);
+        let mut clicked = None;
+        ui.heading(tr(language, "timeline.title"));
+        egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+            for entry in entries.iter() {
+                let elapsed = start.map(|start| format_duration(entry.at - start)).unwrap_or_default();
+                let mut text = egui::RichText::new(format!("{}  {}", elapsed, describe(&entry.event, language)));
+                if entry.undone {
+                    text = text.strikethrough().weak();
+                }
+                let response = ui.add_enabled(!entry.undone, egui::Button::new(text).frame(false));
+                if response.clicked() {
+                    clicked = Some(entry.moves);
+                }
+            }
+        });
+        clicked
+    }
+}
+
+fn describe(event: &GameEvent, language: Language) -> String {
+    match event {
+        GameEvent::GameStarted { moves: 0, .. } => tr(language, "timeline.started").to_string(),
+        GameEvent::GameStarted { moves, .. } => tr_args(language, "timeline.resumed", &[("moves", &moves.to_string())]),
+        GameEvent::MovePlayed { player, mv, move_number } => {
+            let player = player_name(language, *player);
+            let text = match mv {
+                Move::Place(hex) => tr_args(language, "announce.move", &[("player", player), ("cell", &hex.notation())]),
+                Move::Swap => tr_args(language, "announce.swap", &[("player", player)]),
+                Move::Pass => tr_args(language, "announce.pass", &[("player", player)]),
+            };
+            format!("{}. {}", move_number, text)
+        }
+        GameEvent::TakenBack { moves } => tr_args(language, "timeline.taken_back", &[("moves", &moves.to_string())]),
+        GameEvent::GameFinished { winner } => {
+            tr_args(language, "game.winner", &[("winner", player_name(language, *winner))])
+        }
+        GameEvent::ResultAgreed { result: GameResult::Win(winner) } => {
+            tr_args(language, "game.winner", &[("winner", player_name(language, *winner))])
+        }
+        GameEvent::ResultAgreed { result: GameResult::Resignation(winner) } => {
+            let loser = if *winner == CellState::Red { CellState::Blue } else { CellState::Red };
+            tr_args(language, "timeline.resigned", &[("player", player_name(language, loser))])
+        }
+        GameEvent::ResultAgreed { result } => result_text(language, Some(*result)).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::board::Hex;
+
+    #[test]
+    fn test_takebacks_strike_out_later_entries() {
+        let timeline = Timeline::default();
+        timeline.handle(&GameEvent::GameStarted { size: 5, moves: 0, opponent: None });
+        for (n, q) in [(1, 0), (2, 1), (3, 2)] {
+            let mv = Move::Place(Hex { q, r: 0 });
+            timeline.handle(&GameEvent::MovePlayed { player: CellState::Red, mv, move_number: n });
+        }
+        timeline.handle(&GameEvent::TakenBack { moves: 1 });
+        timeline.handle(&GameEvent::ResultAgreed { result: GameResult::Draw });
+
+        let entries = timeline.entries.borrow();
+        let summary: Vec<(usize, bool)> = entries.iter().map(|entry| (entry.moves, entry.undone)).collect();
+        assert_eq!(summary, vec![(0, false), (1, false), (2, true), (3, true), (1, false), (1, false)]);
+        assert_eq!(describe(&entries[5].event, Language::English), "Draw");
+    }
+}
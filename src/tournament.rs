@@ -0,0 +1,444 @@
+//! Round-robin tournaments between [`AiPlayer`]s, and a report comparing them.
+//!
+//! [`round_robin`] plays every pair of entrants against each other, alternating colors.
+//! [`TournamentResults::write_report`] then fills a results directory with `report.md` and
+//! `report.html`, which hold a cross-table, Elo estimates with error bars and average move
+//! times, plus every game as SGF under `games/`, linked from the reports, with a CSV of its
+//! engine telemetry beside it.
+//!
+//! ```
+//! use coast_to_coast::ai::{GreedyPlayer, RandomPlayer};
+//! use coast_to_coast::tournament::{round_robin, Entrant};
+//!
+//! let mut entrants = vec![Entrant::new("random", RandomPlayer::seeded(1)), Entrant::new("greedy", GreedyPlayer)];
+//! let results = round_robin(&mut entrants, 4, 4);
+//! assert_eq!(results.games.len(), 4);
+//!
+//! let dir = std::env::temp_dir().join("hex-tournament-doctest");
+//! let report = results.write_report(&dir).unwrap();
+//! assert!(report.ends_with("report.md"));
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::ai::AiPlayer;
+use crate::board::CellState;
+use crate::game::Game;
+use crate::notation::Move;
+use crate::record::{self, GameMetadata, GameRecord, GameResult};
+use crate::sgf;
+use crate::simulation::{simulate_with_options, GameOutcome, SimulationOptions};
+
+/// Virtual games added between every pair of entrants that met, half won by each side, so a
+/// perfect score still gets a finite rating. BayesElo's prior works the same way.
+const PRIOR_GAMES: f64 = 2.0;
+
+/// Fixed-point iterations for the ratings; plenty for tournaments of a few engines.
+const RATING_ITERATIONS: usize = 500;
+
+/// A named player taking part in a tournament.
+pub struct Entrant {
+    pub name: String,
+    pub player: Box<dyn AiPlayer>,
+}
+
+impl Entrant {
+    pub fn new(name: impl Into<String>, player: impl AiPlayer + 'static) -> Self {
+        Self { name: name.into(), player: Box::new(player) }
+    }
+}
+
+/// One game of a tournament; `red` and `blue` index [`TournamentResults::names`].
+#[derive(Clone, Debug)]
+pub struct TournamentGame {
+    pub red: usize,
+    pub blue: usize,
+    pub outcome: GameOutcome,
+}
+
+impl TournamentGame {
+    /// The index of the entrant who won.
+    pub fn winner(&self) -> Option<usize> {
+        match self.outcome.winner {
+            CellState::Red => Some(self.red),
+            CellState::Blue => Some(self.blue),
+            CellState::Empty | CellState::Blocked => None,
+        }
+    }
+}
+
+/// An Elo estimate and the half-width of its 95% confidence interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub elo: f64,
+    pub error: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct TournamentResults {
+    pub names: Vec<String>,
+    pub size: i32,
+    pub games: Vec<TournamentGame>,
+}
+
+/// Plays `games_per_pair` games between every pair of entrants on a `size`×`size` board,
+/// the colors alternating from game to game. Every game's moves are kept for the report.
+pub fn round_robin(entrants: &mut [Entrant], games_per_pair: usize, size: i32) -> TournamentResults {
+    let options = SimulationOptions { n_games: 1, size, keep_records: true };
+    let mut games = Vec::new();
+    for a in 0..entrants.len() {
+        for b in a + 1..entrants.len() {
+            for n in 0..games_per_pair {
+                let (red, blue) = if n % 2 == 0 { (a, b) } else { (b, a) };
+                let (policy_red, policy_blue) = pair(entrants, red, blue);
+                let report = simulate_with_options(policy_red, policy_blue, &options);
+                games.extend(report.games.into_iter().map(|outcome| TournamentGame { red, blue, outcome }));
+            }
+        }
+    }
+    tracing::info!(entrants = entrants.len(), games = games.len(), "round robin finished");
+    TournamentResults { names: entrants.iter().map(|entrant| entrant.name.clone()).collect(), size, games }
+}
+
+fn pair(entrants: &mut [Entrant], first: usize, second: usize) -> (&mut dyn AiPlayer, &mut dyn AiPlayer) {
+    let (low, high) = entrants.split_at_mut(first.max(second));
+    let (lower, higher) = (low[first.min(second)].player.as_mut(), high[0].player.as_mut());
+    if first < second {
+        (lower, higher)
+    } else {
+        (higher, lower)
+    }
+}
+
+impl TournamentResults {
+    /// `wins[a][b]` is how many games entrant `a` won against entrant `b`.
+    pub fn cross_table(&self) -> Vec<Vec<usize>> {
+        let mut wins = vec![vec![0; self.names.len()]; self.names.len()];
+        for game in &self.games {
+            if let Some(winner) = game.winner() {
+                let loser = if winner == game.red { game.blue } else { game.red };
+                wins[winner][loser] += 1;
+            }
+        }
+        wins
+    }
+
+    /// Bradley–Terry maximum-likelihood ratings with [`PRIOR_GAMES`] virtual games per pair,
+    /// on the Elo scale and centered on 0. Error bars come from the curvature of the
+    /// likelihood at the estimate.
+    pub fn ratings(&self) -> Vec<Rating> {
+        let count = self.names.len();
+        let wins = self.cross_table();
+        let mut games = vec![vec![0.0; count]; count];
+        let mut won = vec![0.0; count];
+        for a in 0..count {
+            for b in 0..count {
+                let played = (wins[a][b] + wins[b][a]) as f64;
+                if a != b && played > 0.0 {
+                    games[a][b] = played + PRIOR_GAMES;
+                    won[a] += wins[a][b] as f64 + PRIOR_GAMES / 2.0;
+                }
+            }
+        }
+
+        // Hunter's MM iteration for the strengths `gamma`, renormalised to a geometric mean of 1.
+        let mut gamma = vec![1.0_f64; count];
+        for _ in 0..RATING_ITERATIONS {
+            for a in 0..count {
+                let denominator: f64 = (0..count).map(|b| games[a][b] / (gamma[a] + gamma[b])).sum();
+                if denominator > 0.0 {
+                    gamma[a] = won[a] / denominator;
+                }
+            }
+            let mean = gamma.iter().map(|g| g.ln()).sum::<f64>() / count as f64;
+            gamma.iter_mut().for_each(|g| *g /= mean.exp());
+        }
+
+        let scale = 400.0 / std::f64::consts::LN_10;
+        (0..count)
+            .map(|a| {
+                let information: f64 = (0..count)
+                    .map(|b| {
+                        let p = gamma[a] / (gamma[a] + gamma[b]);
+                        games[a][b] * p * (1.0 - p)
+                    })
+                    .sum();
+                let error = if information > 0.0 { 1.96 * scale / information.sqrt() } else { f64::INFINITY };
+                Rating { elo: scale * gamma[a].ln(), error }
+            })
+            .collect()
+    }
+
+    /// Mean time per decision for each entrant.
+    pub fn average_move_times(&self) -> Vec<Duration> {
+        let mut totals = vec![(Duration::ZERO, 0u32); self.names.len()];
+        for game in &self.games {
+            let outcome = &game.outcome;
+            for (time, mover) in outcome.move_times.iter().zip(&outcome.movers) {
+                let entrant = if *mover == CellState::Red { game.red } else { game.blue };
+                totals[entrant].0 += *time;
+                totals[entrant].1 += 1;
+            }
+        }
+        totals.into_iter().map(|(total, calls)| if calls == 0 { total } else { total / calls }).collect()
+    }
+
+    /// The game as a record, with move times and engine telemetry, for SGF and CSV export.
+    /// Forfeits keep their result although the board shows no connection.
+    pub fn record(&self, game: &TournamentGame) -> GameRecord {
+        let mut moves = Vec::new();
+        for (i, hex) in game.outcome.record.iter().flatten().enumerate() {
+            moves.push(Move::Place(*hex));
+            if i == 0 && game.outcome.swapped {
+                moves.push(Move::Swap);
+            }
+        }
+        let mut replay = Game::with_size(self.size);
+        for mv in moves {
+            if replay.play_move(mv).is_err() {
+                break;
+            }
+        }
+        let metadata = GameMetadata {
+            red: self.names[game.red].clone(),
+            blue: self.names[game.blue].clone(),
+            event: "Round robin".to_string(),
+            date: record::today(),
+            result: matches!(game.outcome.winner, CellState::Red | CellState::Blue)
+                .then_some(GameResult::Win(game.outcome.winner)),
+            ..Default::default()
+        };
+        // Policy calls line up with moves, except that the pie decision after the first stone
+        // is only a move when it swapped.
+        let outcome = &game.outcome;
+        let calls = (0..outcome.move_times.len()).filter(|i| *i != 1 || outcome.swapped);
+        let (times, telemetry): (Vec<_>, Vec<_>) =
+            calls.map(|i| (Some(outcome.move_times[i]), outcome.telemetry.get(i).copied().flatten())).unzip();
+        GameRecord::from_game(&replay, &metadata).with_move_times(&times).with_telemetry(&telemetry)
+    }
+
+    /// Writes `report.md`, `report.html` and `games/*.sgf` under `dir`, creating it if needed,
+    /// and returns the Markdown report's path.
+    pub fn write_report(&self, dir: &Path) -> io::Result<PathBuf> {
+        let games_dir = dir.join("games");
+        fs::create_dir_all(&games_dir)?;
+        let mut links = Vec::new();
+        for (i, game) in self.games.iter().enumerate() {
+            let file = format!(
+                "{:03}-{}-vs-{}.sgf",
+                i + 1,
+                file_safe(&self.names[game.red]),
+                file_safe(&self.names[game.blue])
+            );
+            let record = self.record(game);
+            fs::write(games_dir.join(&file), sgf::to_sgf(&record))?;
+            fs::write(games_dir.join(&file).with_extension("csv"), record.telemetry_csv())?;
+            links.push((self.game_summary(i, game), format!("games/{}", file)));
+        }
+
+        let report = self.report();
+        fs::write(dir.join("report.html"), report.to_html(&links))?;
+        let path = dir.join("report.md");
+        fs::write(&path, report.to_markdown(&links))?;
+        tracing::info!(path = %path.display(), "tournament report written");
+        Ok(path)
+    }
+
+    fn game_summary(&self, index: usize, game: &TournamentGame) -> String {
+        let result = match game.winner() {
+            Some(winner) if game.outcome.forfeit => format!("{} won by forfeit", self.names[winner]),
+            Some(winner) => format!("{} won", self.names[winner]),
+            None => "no result".to_string(),
+        };
+        format!(
+            "{}. {} (Red) vs {} (Blue): {}, {} moves",
+            index + 1,
+            self.names[game.red],
+            self.names[game.blue],
+            result,
+            game.outcome.moves
+        )
+    }
+
+    // The report's tables as plain cells, shared by the Markdown and HTML output.
+    fn report(&self) -> Report {
+        let ratings = self.ratings();
+        let times = self.average_move_times();
+        let wins = self.cross_table();
+        let mut order: Vec<usize> = (0..self.names.len()).collect();
+        order.sort_by(|a, b| ratings[*b].elo.total_cmp(&ratings[*a].elo));
+
+        let standings = order
+            .iter()
+            .map(|&a| {
+                let played = self.games.iter().filter(|game| game.red == a || game.blue == a).count();
+                vec![
+                    self.names[a].clone(),
+                    format!("{:+.0} ± {:.0}", ratings[a].elo, ratings[a].error),
+                    format!("{}/{}", wins[a].iter().sum::<usize>(), played),
+                    format!("{:.1} ms", times[a].as_secs_f64() * 1000.0),
+                ]
+            })
+            .collect();
+        let cross_table = order
+            .iter()
+            .map(|&a| {
+                let mut row = vec![self.names[a].clone()];
+                row.extend(order.iter().map(|&b| {
+                    if a == b {
+                        "—".to_string()
+                    } else {
+                        format!("{}-{}", wins[a][b], wins[b][a])
+                    }
+                }));
+                row
+            })
+            .collect();
+        let mut cross_header = vec![String::new()];
+        cross_header.extend(order.iter().map(|&a| self.names[a].clone()));
+        Report { title: format!("Round robin on {0}×{0}", self.size), standings, cross_header, cross_table }
+    }
+}
+
+struct Report {
+    title: String,
+    standings: Vec<Vec<String>>,
+    cross_header: Vec<String>,
+    cross_table: Vec<Vec<String>>,
+}
+
+const STANDINGS_HEADER: [&str; 4] = ["Engine", "Elo (95%)", "Wins", "Avg. move time"];
+
+impl Report {
+    fn to_markdown(&self, games: &[(String, String)]) -> String {
+        let table = |header: &[String], rows: &[Vec<String>]| {
+            let mut text = format!("| {} |\n|{}\n", header.join(" | "), "---|".repeat(header.len()));
+            for row in rows {
+                text.push_str(&format!("| {} |\n", row.join(" | ")));
+            }
+            text
+        };
+        let standings_header: Vec<String> = STANDINGS_HEADER.iter().map(|cell| cell.to_string()).collect();
+        let mut text = format!("# {}\n\n", self.title);
+        text.push_str(&table(&standings_header, &self.standings));
+        text.push_str("\n## Cross-table\n\nWins of the row's engine against the column's.\n\n");
+        text.push_str(&table(&self.cross_header, &self.cross_table));
+        text.push_str("\n## Games\n\n");
+        for (summary, link) in games {
+            text.push_str(&format!("- [{}]({})\n", summary, link));
+        }
+        text
+    }
+
+    fn to_html(&self, games: &[(String, String)]) -> String {
+        let table = |header: &[String], rows: &[Vec<String>]| {
+            let cells = |tag: &str, row: &[String]| {
+                row.iter().map(|cell| format!("<{0}>{1}</{0}>", tag, escape_html(cell))).collect::<String>()
+            };
+            let mut html = format!("<table>\n<tr>{}</tr>\n", cells("th", header));
+            for row in rows {
+                html.push_str(&format!("<tr>{}</tr>\n", cells("td", row)));
+            }
+            html.push_str("</table>\n");
+            html
+        };
+        let standings_header: Vec<String> = STANDINGS_HEADER.iter().map(|cell| cell.to_string()).collect();
+        let title = escape_html(&self.title);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+             <style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #999; padding: 2px 8px; }}</style>\n\
+             </head>\n<body>\n<h1>{0}</h1>\n",
+            title
+        );
+        html.push_str(&table(&standings_header, &self.standings));
+        html.push_str("<h2>Cross-table</h2>\n<p>Wins of the row's engine against the column's.</p>\n");
+        html.push_str(&table(&self.cross_header, &self.cross_table));
+        html.push_str("<h2>Games</h2>\n<ul>\n");
+        for (summary, link) in games {
+            html.push_str(&format!("<li><a href=\"{}\">{}</a></li>\n", escape_html(link), escape_html(summary)));
+        }
+        html.push_str("</ul>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn file_safe(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{GreedyPlayer, RandomPlayer};
+    use crate::board::Hex;
+
+    fn results(red_wins: &[bool]) -> TournamentResults {
+        let games = red_wins
+            .iter()
+            .enumerate()
+            .map(|(n, red_won)| {
+                let (red, blue) = if n % 2 == 0 { (0, 1) } else { (1, 0) };
+                let outcome = GameOutcome {
+                    winner: if *red_won { CellState::Red } else { CellState::Blue },
+                    forfeit: false,
+                    swapped: false,
+                    moves: 1,
+                    move_times: vec![Duration::from_millis(2), Duration::from_millis(4)],
+                    movers: vec![CellState::Red, CellState::Blue],
+                    telemetry: vec![None, None],
+                    record: Some(vec![Hex { q: 0, r: 0 }]),
+                };
+                TournamentGame { red, blue, outcome }
+            })
+            .collect();
+        TournamentResults { names: vec!["a".to_string(), "b".to_string()], size: 3, games }
+    }
+
+    #[test]
+    fn test_ratings_follow_the_score() {
+        // a wins three of four games.
+        let results = results(&[true, false, false, false]);
+        assert_eq!(results.cross_table(), vec![vec![0, 3], vec![1, 0]]);
+        let ratings = results.ratings();
+        assert!(ratings[0].elo > 0.0);
+        assert!((ratings[0].elo + ratings[1].elo).abs() < 1e-6);
+        assert!(ratings[0].error.is_finite() && ratings[0].error > 0.0);
+        assert_eq!(results.average_move_times(), vec![Duration::from_millis(3); 2]);
+
+        // A perfect score still has a finite rating thanks to the prior.
+        assert!(self::results(&[true, false]).ratings()[0].elo.is_finite());
+    }
+
+    #[test]
+    fn test_round_robin_writes_report() {
+        let mut entrants = vec![
+            Entrant::new("random", RandomPlayer::seeded(3)),
+            Entrant::new("greedy <1>", GreedyPlayer),
+            Entrant::new("random 2", RandomPlayer::seeded(4)),
+        ];
+        let results = round_robin(&mut entrants, 2, 3);
+        assert_eq!(results.games.len(), 6);
+        assert_eq!(results.games.iter().filter(|game| game.red == 1).count(), 2);
+
+        let dir = std::env::temp_dir().join(format!("hex-tournament-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = results.write_report(&dir).unwrap();
+        let markdown = fs::read_to_string(path).unwrap();
+        assert!(markdown.contains("## Cross-table"));
+        assert!(markdown.contains("](games/001-random-vs-greedy__1_.sgf)"));
+        let html = fs::read_to_string(dir.join("report.html")).unwrap();
+        assert!(html.contains("greedy &lt;1&gt;"));
+        assert_eq!(fs::read_dir(dir.join("games")).unwrap().count(), 12);
+        let csv = fs::read_to_string(dir.join("games/001-random-vs-greedy__1_.csv")).unwrap();
+        assert!(csv.starts_with("move,player,notation,seconds,"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,264 @@
+//! A transposition table for search engines: positions already searched, keyed by Zobrist hash.
+//!
+//! The table has a fixed number of slots worked out from a memory budget, split into shards
+//! behind their own locks so engine threads rarely wait on each other. Each position maps to
+//! one slot; when two compete for it the [`Replacement`] policy decides which stays.
+//!
+//! ```
+//! use coast_to_coast::board::{Board, CellState, Hex};
+//! use coast_to_coast::transposition::{zobrist, Bound, Replacement, TableEntry, TranspositionTable};
+//!
+//! let table = TranspositionTable::with_memory(1 << 20, Replacement::DepthPreferred);
+//! let mut board = Board::new(5);
+//! board.set_cell(Hex { q: 2, r: 2 }, CellState::Red);
+//! let key = zobrist(&board, CellState::Blue);
+//! table.store(key, TableEntry { depth: 3, score: 0.7, bound: Bound::Exact, best: Some(Hex { q: 1, r: 3 }) });
+//! assert_eq!(table.probe(key).map(|entry| entry.depth), Some(3));
+//! ```
+
+use std::mem;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::board::{Board, CellState, Hex};
+
+const SHARDS: usize = 16;
+
+/// The key for `state` on `hex`, the same in every run. Empty cells have none.
+pub fn zobrist_key(hex: Hex, state: CellState) -> u64 {
+    let color = match state {
+        CellState::Empty => return 0,
+        CellState::Red => 1,
+        CellState::Blue => 2,
+        CellState::Blocked => 3,
+    };
+    splitmix((hex.q as u32 as u64) << 34 | (hex.r as u32 as u64) << 2 | color)
+}
+
+/// Flipped in when Blue is to move.
+pub fn zobrist_side_key() -> u64 {
+    splitmix(u64::MAX)
+}
+
+/// The hash of `board` with `to_move` to play. Placing or removing a stone changes it by
+/// [`zobrist_key`], so searches can update it as they go.
+pub fn zobrist(board: &Board, to_move: CellState) -> u64 {
+    let side = if to_move == CellState::Blue { zobrist_side_key() } else { 0 };
+    board.cells.iter().fold(side, |hash, (hex, state)| hash ^ zobrist_key(*hex, *state))
+}
+
+fn splitmix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce5_e9b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// How a stored score relates to the position's true value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The search failed high: the true value is at least the score.
+    Lower,
+    /// The search failed low: the true value is at most the score.
+    Upper,
+}
+
+/// What a search learned about a position, from the side to move's point of view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableEntry {
+    /// Plies searched below the position; deeper results are worth more.
+    pub depth: u32,
+    pub score: f32,
+    pub bound: Bound,
+    pub best: Option<Hex>,
+}
+
+/// Which entry keeps a contested slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replacement {
+    /// The newest store always wins.
+    Always,
+    /// Shallower results don't push out deeper ones from the same search.
+    DepthPreferred,
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    key: u64,
+    generation: u32,
+    entry: TableEntry,
+}
+
+/// A snapshot of a table's counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TableStats {
+    pub capacity: usize,
+    pub filled: usize,
+    pub probes: u64,
+    pub hits: u64,
+}
+
+impl TableStats {
+    pub fn fill_rate(&self) -> f32 {
+        self.filled as f32 / self.capacity.max(1) as f32
+    }
+
+    pub fn hit_rate(&self) -> f32 {
+        self.hits as f32 / self.probes.max(1) as f32
+    }
+}
+
+pub struct TranspositionTable {
+    shards: Vec<Mutex<Vec<Option<Slot>>>>,
+    per_shard: usize,
+    memory_bytes: usize,
+    replacement: Replacement,
+    generation: AtomicU32,
+    filled: AtomicUsize,
+    probes: AtomicU64,
+    hits: AtomicU64,
+}
+
+impl TranspositionTable {
+    /// As many slots as fit in `memory_bytes`, at least one per shard.
+    pub fn with_memory(memory_bytes: usize, replacement: Replacement) -> Self {
+        let per_shard = (memory_bytes / mem::size_of::<Option<Slot>>() / SHARDS).max(1);
+        Self {
+            shards: (0..SHARDS).map(|_| Mutex::new(vec![None; per_shard])).collect(),
+            per_shard,
+            memory_bytes,
+            replacement,
+            generation: AtomicU32::new(0),
+            filled: AtomicUsize::new(0),
+            probes: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+        }
+    }
+
+    /// The budget the table was made with.
+    pub fn memory_bytes(&self) -> usize {
+        self.memory_bytes
+    }
+
+    pub fn replacement(&self) -> Replacement {
+        self.replacement
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shards.len() * self.per_shard
+    }
+
+    // The low bits pick the shard, the high bits the slot within it.
+    fn locate(&self, key: u64) -> (usize, usize) {
+        let shard = key as usize % self.shards.len();
+        (shard, (key >> 32) as usize % self.per_shard)
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        self.probes.fetch_add(1, Ordering::Relaxed);
+        let (shard, index) = self.locate(key);
+        let slots = self.shards[shard].lock().ok()?;
+        let entry = slots[index].filter(|slot| slot.key == key).map(|slot| slot.entry);
+        if entry.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        entry
+    }
+
+    pub fn store(&self, key: u64, entry: TableEntry) {
+        let (shard, index) = self.locate(key);
+        let generation = self.generation.load(Ordering::Relaxed);
+        let Ok(mut slots) = self.shards[shard].lock() else {
+            return;
+        };
+        let keep_old = match slots[index] {
+            None => {
+                self.filled.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            Some(old) => {
+                self.replacement == Replacement::DepthPreferred
+                    && old.key != key
+                    && old.generation == generation
+                    && old.entry.depth > entry.depth
+            }
+        };
+        if !keep_old {
+            slots[index] = Some(Slot { key, generation, entry });
+        }
+    }
+
+    /// Marks everything stored so far as from an older search, free to be replaced.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            if let Ok(mut slots) = shard.lock() {
+                slots.fill(None);
+            }
+        }
+        self.filled.store(0, Ordering::Relaxed);
+        self.probes.store(0, Ordering::Relaxed);
+        self.hits.store(0, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> TableStats {
+        TableStats {
+            capacity: self.capacity(),
+            filled: self.filled.load(Ordering::Relaxed),
+            probes: self.probes.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(depth: u32) -> TableEntry {
+        TableEntry { depth, score: 0.5, bound: Bound::Exact, best: None }
+    }
+
+    #[test]
+    fn test_zobrist_updates_incrementally() {
+        let mut board = Board::new(4);
+        let empty = zobrist(&board, CellState::Red);
+        let hex = Hex { q: 1, r: 2 };
+        board.set_cell(hex, CellState::Red);
+        let placed = zobrist(&board, CellState::Blue);
+        assert_eq!(placed, empty ^ zobrist_key(hex, CellState::Red) ^ zobrist_side_key());
+        assert_ne!(zobrist_key(hex, CellState::Red), zobrist_key(hex, CellState::Blue));
+        board.set_cell(hex, CellState::Empty);
+        assert_eq!(zobrist(&board, CellState::Red), empty);
+    }
+
+    #[test]
+    fn test_replacement_and_stats() {
+        // One slot per shard: keys with equal low bits collide.
+        let table = TranspositionTable::with_memory(0, Replacement::DepthPreferred);
+        let (deep, shallow) = (SHARDS as u64, 2 * SHARDS as u64);
+        table.store(deep, entry(5));
+        table.store(shallow, entry(1));
+        assert_eq!(table.probe(deep), Some(entry(5)));
+        assert_eq!(table.probe(shallow), None);
+        // A later search may overwrite anything.
+        table.new_search();
+        table.store(shallow, entry(1));
+        assert_eq!(table.probe(shallow), Some(entry(1)));
+
+        let stats = table.stats();
+        assert_eq!((stats.capacity, stats.filled, stats.probes, stats.hits), (SHARDS, 1, 3, 2));
+        assert_eq!(stats.fill_rate(), 1.0 / SHARDS as f32);
+
+        let always = TranspositionTable::with_memory(0, Replacement::Always);
+        always.store(deep, entry(5));
+        always.store(shallow, entry(1));
+        assert_eq!(always.probe(deep), None);
+        always.clear();
+        assert_eq!(always.stats(), TableStats { capacity: SHARDS, ..TableStats::default() });
+    }
+}
@@ -0,0 +1,70 @@
+use std::mem;
+
+use eframe::egui;
+
+// Runs UI code frame by frame for tests, feeding it pointer events the way a user's mouse
+// would and finding widgets through the AccessKit tree, by the labels screen readers see.
+pub struct UiHarness {
+    pub ctx: egui::Context,
+    screen: egui::Rect,
+    events: Vec<egui::Event>,
+    nodes: Vec<accesskit::Node>,
+}
+
+impl UiHarness {
+    pub fn new(width: f32, height: f32) -> Self {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width, height));
+        Self { ctx, screen, events: Vec::new(), nodes: Vec::new() }
+    }
+
+    // One frame of `ui` in a central panel, with whatever input is queued.
+    pub fn run(&mut self, mut ui: impl FnMut(&mut egui::Ui)) {
+        let input =
+            egui::RawInput { screen_rect: Some(self.screen), events: mem::take(&mut self.events), ..Default::default() };
+        let output = self.ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |panel| ui(panel));
+        });
+        if let Some(update) = output.platform_output.accesskit_update {
+            self.nodes = update.nodes.into_iter().map(|(_, node)| node).collect();
+        }
+    }
+
+    // Presses and releases the primary button at `pos`, a frame each, as a click does, then runs
+    // one more frame so what the click changed is on screen.
+    pub fn click_at(&mut self, pos: egui::Pos2, mut ui: impl FnMut(&mut egui::Ui)) {
+        let button = |pressed| egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::NONE,
+        };
+        self.events.extend([egui::Event::PointerMoved(pos), button(true)]);
+        self.run(&mut ui);
+        self.events.push(button(false));
+        self.run(&mut ui);
+        self.run(&mut ui);
+    }
+
+    // Where the widget labelled `label` was in the last frame.
+    pub fn bounds(&self, label: &str) -> Option<egui::Rect> {
+        let node = self.nodes.iter().find(|node| node.label() == Some(label))?;
+        let bounds = node.bounds()?;
+        Some(egui::Rect::from_min_max(
+            egui::pos2(bounds.x0 as f32, bounds.y0 as f32),
+            egui::pos2(bounds.x1 as f32, bounds.y1 as f32),
+        ))
+    }
+
+    // The middle of the widget labelled `label` in the last frame.
+    pub fn find(&self, label: &str) -> Option<egui::Pos2> {
+        self.bounds(label).map(|bounds| bounds.center())
+    }
+
+    // Clicks the widget labelled `label`, which the last frame must have shown.
+    pub fn click(&mut self, label: &str, ui: impl FnMut(&mut egui::Ui)) {
+        let pos = self.find(label).unwrap_or_else(|| panic!("no widget labelled {:?}", label));
+        self.click_at(pos, ui);
+    }
+}
@@ -0,0 +1,160 @@
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use eframe::egui;
+use serde::Deserialize;
+
+use crate::i18n::{tr, tr_args, Language};
+
+// GitHub's "latest release" endpoint. Any URL answering with the same JSON, or with a bare
+// tag such as "v1.2.0" as plain text, works too.
+pub const DEFAULT_UPDATE_URL: &str = "https://api.github.com/repos/echirui/coast-to-coast/releases/latest";
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Release {
+    pub tag: String,
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: Option<String>,
+}
+
+pub enum UpdateAction {
+    NeverAskAgain,
+}
+
+// Polls a background check started at launch and shows a small notice if it finds a newer
+// release. Nothing here blocks the UI; failures are only logged.
+#[derive(Default)]
+pub struct UpdateChecker {
+    pending: Option<Receiver<Option<Release>>>,
+    available: Option<Release>,
+}
+
+impl UpdateChecker {
+    pub fn start(ctx: &egui::Context, url: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        let url = url.to_string();
+        thread::spawn(move || {
+            let release = match fetch_latest(&url) {
+                Ok(release) => Some(release),
+                Err(e) => {
+                    tracing::warn!(url = %url, error = %e, "update check failed");
+                    None
+                }
+            };
+            let _ = sender.send(release);
+            ctx.request_repaint();
+        });
+        Self { pending: Some(receiver), available: None }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, language: Language) -> Option<UpdateAction> {
+        if let Some(receiver) = &self.pending {
+            if let Ok(release) = receiver.try_recv() {
+                self.pending = None;
+                self.available = release.filter(|release| is_newer(&release.tag, env!("CARGO_PKG_VERSION")));
+                if let Some(release) = &self.available {
+                    tracing::info!(tag = %release.tag, "update available");
+                }
+            }
+        }
+
+        let release = self.available.as_ref()?;
+        let mut action = None;
+        let mut dismissed = false;
+        egui::Window::new(tr(language, "update.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .show(ctx, |ui| {
+                ui.label(tr_args(language, "update.message", &[("version", &release.tag)]));
+                ui.hyperlink_to(tr(language, "update.open"), &release.url);
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "update.dismiss")).clicked() {
+                        dismissed = true;
+                    }
+                    if ui.button(tr(language, "update.never")).clicked() {
+                        action = Some(UpdateAction::NeverAskAgain);
+                    }
+                });
+            });
+        if dismissed || action.is_some() {
+            self.available = None;
+        }
+        action
+    }
+}
+
+fn fetch_latest(url: &str) -> Result<Release, Box<dyn std::error::Error>> {
+    let body = ureq::get(url)
+        .timeout(TIMEOUT)
+        .set("User-Agent", concat!("coast-to-coast/", env!("CARGO_PKG_VERSION")))
+        .call()?
+        .into_string()?;
+    parse_release(&body, url).ok_or_else(|| "response holds no release tag".into())
+}
+
+fn parse_release(body: &str, url: &str) -> Option<Release> {
+    let body = body.trim();
+    if body.starts_with('{') {
+        let release: GithubRelease = ureq::serde_json::from_str(body).ok()?;
+        Some(Release { tag: release.tag_name, url: release.html_url.unwrap_or_else(|| url.to_string()) })
+    } else {
+        let tag = body.lines().next()?.trim();
+        (!tag.is_empty()).then(|| Release { tag: tag.to_string(), url: url.to_string() })
+    }
+}
+
+// Compares dotted numeric versions, ignoring a leading "v" and any "-suffix". Tags that don't
+// parse are never reported as newer.
+pub fn is_newer(tag: &str, current: &str) -> bool {
+    match (parse_version(tag), parse_version(current)) {
+        (Some(tag), Some(current)) => tag > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.2.0", "0.1.0"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("v0.1.0", "0.1.0"));
+        assert!(!is_newer("0.0.9", "0.1.0"));
+        assert!(!is_newer("nightly", "0.1.0"));
+        assert!(!is_newer("v0.1.0-rc1", "0.1.0"));
+    }
+
+    #[test]
+    fn test_parse_release() {
+        let json = r#"{"tag_name": "v0.3.0", "html_url": "https://example.com/v0.3.0", "draft": false}"#;
+        assert_eq!(
+            parse_release(json, "https://api.example.com"),
+            Some(Release { tag: "v0.3.0".to_string(), url: "https://example.com/v0.3.0".to_string() })
+        );
+        assert_eq!(
+            parse_release("v0.3.0\n", "https://example.com/latest.txt"),
+            Some(Release { tag: "v0.3.0".to_string(), url: "https://example.com/latest.txt".to_string() })
+        );
+        assert_eq!(parse_release("{}", "https://example.com"), None);
+        assert_eq!(parse_release("", "https://example.com"), None);
+    }
+}
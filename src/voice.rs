@@ -0,0 +1,275 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use coast_to_coast::notation::Move;
+use eframe::egui;
+
+use crate::i18n::{tr, tr_args, Language};
+use crate::menu_bar::MenuAction;
+
+const NATO: [&str; 26] = [
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet", "kilo", "lima", "mike",
+    "november", "oscar", "papa", "quebec", "romeo", "sierra", "tango", "uniform", "victor", "whiskey", "xray", "yankee",
+    "zulu",
+];
+// How recognizers tend to write a letter said on its own.
+const SPOKEN_LETTERS: [(&str, char); 24] = [
+    ("ay", 'a'),
+    ("be", 'b'),
+    ("bee", 'b'),
+    ("see", 'c'),
+    ("sea", 'c'),
+    ("dee", 'd'),
+    ("ee", 'e'),
+    ("ef", 'f'),
+    ("eff", 'f'),
+    ("gee", 'g'),
+    ("aitch", 'h'),
+    ("eye", 'i'),
+    ("jay", 'j'),
+    ("kay", 'k'),
+    ("el", 'l'),
+    ("em", 'm'),
+    ("en", 'n'),
+    ("oh", 'o'),
+    ("pea", 'p'),
+    ("pee", 'p'),
+    ("are", 'r'),
+    ("ess", 's'),
+    ("tea", 't'),
+    ("tee", 't'),
+];
+const NUMBERS: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve", "thirteen",
+    "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+const SPOKEN_NUMBERS: [(&str, u32); 5] = [("won", 1), ("to", 2), ("too", 2), ("for", 4), ("ate", 8)];
+const TENS: [&str; 5] = ["twenty", "thirty", "forty", "fifty", "sixty"];
+
+// What a spoken phrase asks for: a move, or one of the menu actions that can be said.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VoiceCommand {
+    Move(Move),
+    Action(MenuAction),
+}
+
+// Reads a transcribed phrase such as "c five", "charlie 5", "C5", "pass", "undo" or "resign".
+// Moves go through the notation parser, so they are checked against the board size.
+pub fn parse_command(text: &str, size: i32) -> Option<VoiceCommand> {
+    let text = text.to_lowercase();
+    let words: Vec<&str> = text.split(|c: char| !c.is_ascii_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    match words.as_slice() {
+        ["undo"] | ["take", "back"] | ["takeback"] => return Some(VoiceCommand::Action(MenuAction::Undo)),
+        ["resign"] => return Some(VoiceCommand::Action(MenuAction::Resign)),
+        ["pass"] => return Some(VoiceCommand::Move(Move::Pass)),
+        ["swap"] => return Some(VoiceCommand::Move(Move::Swap)),
+        _ => {}
+    }
+    let (first, rest) = words.split_first()?;
+    let notation = match letter(first) {
+        Some(letter) => format!("{}{}", letter, number(rest)?),
+        // Already written as a cell, e.g. "c5".
+        None if rest.is_empty() => first.to_string(),
+        None => return None,
+    };
+    Move::parse_for_size(&notation, size).ok().map(VoiceCommand::Move)
+}
+
+fn letter(word: &str) -> Option<char> {
+    if let Some(i) = NATO.iter().position(|name| *name == word) {
+        return Some((b'a' + i as u8) as char);
+    }
+    if let Some((_, letter)) = SPOKEN_LETTERS.iter().find(|(spoken, _)| *spoken == word) {
+        return Some(*letter);
+    }
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_lowercase() => Some(letter),
+        _ => None,
+    }
+}
+
+// "five", "5", "twenty one" or digits said one by one, "one one" for 11.
+fn number(words: &[&str]) -> Option<u32> {
+    let small = |word: &str| {
+        NUMBERS
+            .iter()
+            .position(|name| *name == word)
+            .map(|n| n as u32)
+            .or_else(|| SPOKEN_NUMBERS.iter().find(|(spoken, _)| *spoken == word).map(|(_, n)| *n))
+    };
+    let mut digits = String::new();
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i];
+        if let Some(tens) = TENS.iter().position(|name| *name == word) {
+            let mut value = (tens as u32 + 2) * 10;
+            if let Some(unit) = words.get(i + 1).and_then(|next| small(next)).filter(|unit| *unit < 10) {
+                value += unit;
+                i += 1;
+            }
+            digits.push_str(&value.to_string());
+        } else if let Some(value) = small(word) {
+            digits.push_str(&value.to_string());
+        } else if word.bytes().all(|byte| byte.is_ascii_digit()) {
+            digits.push_str(word);
+        } else {
+            return None;
+        }
+        i += 1;
+    }
+    digits.parse().ok()
+}
+
+// Yes or no to the confirmation, said rather than clicked.
+fn parse_reply(text: &str) -> Option<bool> {
+    match text.trim().trim_end_matches(['.', '!']).to_lowercase().as_str() {
+        "yes" | "yeah" | "yep" | "ok" | "okay" | "confirm" | "play" => Some(true),
+        "no" | "nope" | "cancel" => Some(false),
+        _ => None,
+    }
+}
+
+// Moves and commands said aloud. Speech is turned into text by an external recognizer the
+// user sets up, e.g. a Vosk or Whisper streaming transcriber, run as a command that prints a
+// line per phrase heard. Every command is shown for confirmation, by click or by saying "yes"
+// or "no", before it is carried out.
+#[derive(Default)]
+pub struct VoiceInput {
+    command: String,
+    recognizer: Option<Child>,
+    lines: Option<Receiver<String>>,
+    // The phrase heard and what it was understood as, waiting to be confirmed.
+    pending: Option<(String, VoiceCommand)>,
+}
+
+impl VoiceInput {
+    // (Re)starts the recognizer when the command changes; cheap to call every frame. An empty
+    // command stops it.
+    pub fn set_command(&mut self, ctx: &egui::Context, command: &str) {
+        let command = command.trim();
+        if command == self.command {
+            return;
+        }
+        self.stop();
+        self.command = command.to_string();
+        let mut words = command.split_whitespace();
+        let Some(program) = words.next() else {
+            return;
+        };
+        let spawned = Command::new(program)
+            .args(words)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::warn!(command, error = %e, "could not start the speech recognizer");
+                return;
+            }
+        };
+        let (sender, receiver) = mpsc::channel();
+        let stdout = child.stdout.take();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            for line in stdout.map(BufReader::new).into_iter().flat_map(|stdout| stdout.lines()).map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+            }
+        });
+        tracing::info!(command, "listening for spoken moves");
+        self.recognizer = Some(child);
+        self.lines = Some(receiver);
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.recognizer.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        self.lines = None;
+        self.pending = None;
+    }
+
+    // Takes in what was heard and asks to confirm it; returns a command once confirmed.
+    pub fn show(&mut self, ctx: &egui::Context, language: Language, size: i32) -> Option<VoiceCommand> {
+        let heard: Vec<String> = self.lines.as_ref().map(|lines| lines.try_iter().collect()).unwrap_or_default();
+        let mut confirmed = None;
+        for text in heard {
+            match (&self.pending, parse_reply(&text)) {
+                (Some((_, command)), Some(yes)) => {
+                    confirmed = yes.then_some(*command);
+                    self.pending = None;
+                }
+                _ => match parse_command(&text, size) {
+                    Some(command) => self.pending = Some((text, command)),
+                    None => tracing::debug!(text, "not a spoken command"),
+                },
+            }
+        }
+        let (heard, command) = self.pending.as_ref()?;
+        let action = match command {
+            VoiceCommand::Move(mv) => tr_args(language, "voice.play", &[("move", &mv.to_string())]),
+            VoiceCommand::Action(action) => tr(language, action.label()).to_string(),
+        };
+        let mut answer = None;
+        egui::Window::new(tr(language, "voice.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -40.0))
+            .show(ctx, |ui| {
+                ui.weak(tr_args(language, "voice.heard", &[("text", heard)]));
+                ui.strong(action);
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "voice.confirm")).clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button(tr(language, "voice.cancel")).clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+        if let Some(yes) = answer {
+            confirmed = yes.then_some(*command);
+            self.pending = None;
+        }
+        confirmed
+    }
+}
+
+impl Drop for VoiceInput {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::board::Hex;
+
+    #[test]
+    fn test_spoken_commands() {
+        let place = |q, r| Some(VoiceCommand::Move(Move::Place(Hex { q, r })));
+        assert_eq!(parse_command("c five", 11), place(2, 4));
+        assert_eq!(parse_command("See 5.", 11), place(2, 4));
+        assert_eq!(parse_command("Charlie eleven", 11), place(2, 10));
+        assert_eq!(parse_command("k one one", 11), place(10, 10));
+        assert_eq!(parse_command("D4", 11), place(3, 3));
+        assert_eq!(parse_command("a twenty one", 25), place(0, 20));
+        assert_eq!(parse_command("Take back", 11), Some(VoiceCommand::Action(MenuAction::Undo)));
+        assert_eq!(parse_command("resign", 11), Some(VoiceCommand::Action(MenuAction::Resign)));
+        assert_eq!(parse_command("pass", 11), Some(VoiceCommand::Move(Move::Pass)));
+        // Off the board, or not a move at all.
+        assert_eq!(parse_command("c twelve", 11), None);
+        assert_eq!(parse_command("what a game", 11), None);
+        assert_eq!(parse_reply("Yes."), Some(true));
+        assert_eq!(parse_reply("cancel"), Some(false));
+    }
+}
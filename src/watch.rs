@@ -0,0 +1,344 @@
+use std::time::Instant;
+
+use coast_to_coast::ai::SearchTelemetry;
+use coast_to_coast::board::CellState;
+use coast_to_coast::game::{Game, GameState};
+use coast_to_coast::record::{GameMetadata, GameRecord};
+use eframe::egui;
+
+use crate::ai_opponent::{AiConfig, BackgroundEngine, EngineShared, Decision, Difficulty, PendingReply};
+use crate::clock::{format_duration, GameClock};
+use crate::i18n::{player_name, tr, tr_args, Language};
+use crate::motion;
+use crate::renderer::BoardRenderer;
+
+const MAX_MOVE_DELAY_SECS: f32 = 5.0;
+const TELEMETRY_COLUMNS: [&str; 6] =
+    ["telemetry.move", "telemetry.nodes", "telemetry.nps", "telemetry.depth", "telemetry.ponder", "telemetry.table"];
+
+struct Side {
+    engine: BackgroundEngine,
+    // The user has taken this side over from the engine.
+    human: bool,
+}
+
+// Two engines playing each other on screen, for demos and for comparing them. The user sets
+// the pace, can pause, and can take over either side at any point.
+pub struct WatchSession {
+    size: i32,
+    game: Game,
+    clock: GameClock,
+    // What the engines' searches reported, aligned with the clock's move times; None for the
+    // user's moves.
+    telemetry: Vec<Option<SearchTelemetry>>,
+    red: Side,
+    blue: Side,
+    move_delay_secs: f32,
+    paused: bool,
+}
+
+impl WatchSession {
+    pub fn new(size: i32, opponent: Difficulty, ai: &AiConfig, shared: &EngineShared) -> Self {
+        let side = |difficulty| Side { engine: BackgroundEngine::new(difficulty, ai, shared), human: false };
+        let mut session = Self {
+            size,
+            game: Game::with_size(size),
+            clock: GameClock::default(),
+            telemetry: Vec::new(),
+            red: side(Difficulty::Medium),
+            blue: side(opponent),
+            move_delay_secs: 1.0,
+            paused: false,
+        };
+        session.restart(Instant::now());
+        session
+    }
+
+    // A new game with the same engines, pace and sides.
+    fn restart(&mut self, now: Instant) {
+        self.red.engine.cancel();
+        self.blue.engine.cancel();
+        self.game = Game::with_size(self.size);
+        self.clock = GameClock::default();
+        self.telemetry.clear();
+        self.sync_clock(now);
+    }
+
+    fn side_mut(&mut self, player: CellState) -> &mut Side {
+        match player {
+            CellState::Blue => &mut self.blue,
+            _ => &mut self.red,
+        }
+    }
+
+    fn in_progress(&self) -> bool {
+        matches!(self.game.state, GameState::InProgress | GameState::WaitingForPieRuleChoice)
+    }
+
+    fn set_paused(&mut self, paused: bool, now: Instant) {
+        self.paused = paused;
+        if paused {
+            self.clock.stop(now);
+        } else {
+            self.sync_clock(now);
+        }
+    }
+
+    fn sync_clock(&mut self, now: Instant) {
+        if !self.in_progress() || self.paused {
+            self.clock.stop(now);
+        } else if self.clock.running() != Some(self.game.current_player) {
+            self.clock.switch_to(self.game.current_player, now);
+        }
+    }
+
+    // Lets the engine to move play once its decision is in and the move delay has passed.
+    fn update(&mut self, ctx: &egui::Context, now: Instant) {
+        if self.paused || !self.in_progress() {
+            return;
+        }
+        let player = self.game.current_player;
+        let delay = self.move_delay_secs;
+        let side = self.side_mut(player);
+        if side.human {
+            return;
+        }
+        let Some(decision) = side.engine.poll(ctx, now) else {
+            let game = self.game.clone();
+            self.side_mut(player).engine.think(ctx, &game, PendingReply::after(delay, now));
+            return;
+        };
+        let telemetry = self.side_mut(player).engine.telemetry();
+        match decision {
+            Decision::PieRule(swap) => self.decide_pie_rule(swap, telemetry, now),
+            Decision::Move(Some(hex)) => {
+                self.game.handle_click(hex);
+                self.clock.record_move(now);
+                self.telemetry.push(telemetry);
+            }
+            Decision::Move(None) => tracing::warn!(?player, "watched engine found no move"),
+        }
+        self.sync_clock(now);
+    }
+
+    fn decide_pie_rule(&mut self, swap: bool, telemetry: Option<SearchTelemetry>, now: Instant) {
+        self.game.handle_pie_rule_decision(swap);
+        if swap {
+            self.clock.record_move(now);
+            self.telemetry.push(telemetry);
+        }
+        self.sync_clock(now);
+    }
+
+    // Returns true once the user asks to leave.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        renderer: &mut BoardRenderer,
+        ai: &AiConfig,
+        shared: &EngineShared,
+        language: Language,
+    ) -> bool {
+        let now = Instant::now();
+        self.update(ui.ctx(), now);
+        // The engines wake the window when they have moved; only the clocks need to tick.
+        if let Some(tick) = self.clock.next_tick(now) {
+            motion::repaint_after(ui.ctx(), tick);
+        }
+
+        let mut leave = false;
+        ui.horizontal(|ui| {
+            ui.heading(tr(language, "watch.title"));
+            leave = ui.button(tr(language, "watch.leave")).clicked();
+        });
+        ui.horizontal_wrapped(|ui| {
+            let pause_key = if self.paused { "watch.resume" } else { "watch.pause" };
+            if ui.button(tr(language, pause_key)).clicked() {
+                self.set_paused(!self.paused, now);
+            }
+            if ui.button(tr(language, "watch.restart")).clicked() {
+                self.restart(now);
+            }
+            ui.add(
+                egui::Slider::new(&mut self.move_delay_secs, 0.0..=MAX_MOVE_DELAY_SECS)
+                    .suffix(" s")
+                    .text(tr(language, "watch.delay")),
+            );
+        });
+        for (i, player) in [CellState::Red, CellState::Blue].into_iter().enumerate() {
+            ui.horizontal(|ui| {
+                let side = self.side_mut(player);
+                ui.label(player_name(language, player));
+                let mut difficulty = side.engine.difficulty();
+                egui::ComboBox::from_id_salt(("watch_difficulty", i))
+                    .selected_text(difficulty.name(language))
+                    .show_ui(ui, |ui| {
+                        for option in Difficulty::ALL {
+                            ui.selectable_value(&mut difficulty, option, option.name(language));
+                        }
+                    });
+                if difficulty != side.engine.difficulty() {
+                    side.engine = BackgroundEngine::new(difficulty, ai, shared);
+                }
+                if ui.checkbox(&mut side.human, tr(language, "watch.take_over")).changed() && side.human {
+                    side.engine.cancel();
+                }
+            });
+        }
+
+        ui.label(format!(
+            "{} {}  ·  {} {}",
+            player_name(language, CellState::Red),
+            format_duration(self.clock.used(CellState::Red, now)),
+            player_name(language, CellState::Blue),
+            format_duration(self.clock.used(CellState::Blue, now)),
+        ));
+        let to_move = self.game.current_player;
+        let human_to_move = self.side_mut(to_move).human;
+        match self.game.state {
+            GameState::Finished { winner } => {
+                ui.strong(tr_args(language, "game.winner", &[("winner", player_name(language, winner))]));
+            }
+            GameState::WaitingForPieRuleChoice if human_to_move => {
+                ui.label(tr(language, "pie_rule.prompt"));
+                ui.horizontal(|ui| {
+                    if ui.button(tr(language, "pie_rule.apply")).clicked() {
+                        self.decide_pie_rule(true, None, now);
+                    }
+                    if ui.button(tr(language, "pie_rule.continue")).clicked() {
+                        self.decide_pie_rule(false, None, now);
+                    }
+                });
+            }
+            _ if self.paused => {
+                ui.label(tr(language, "watch.paused"));
+            }
+            _ => {
+                ui.label(tr_args(language, "announce.to_move", &[("player", player_name(language, to_move))]));
+            }
+        }
+
+        if let Some(hex) = renderer.render_board(ui, &self.game, language) {
+            // Clicks don't answer the pie rule; the buttons above do.
+            if human_to_move && self.game.state == GameState::InProgress && self.game.is_legal_move(&hex) {
+                self.game.handle_click(hex);
+                self.clock.record_move(now);
+                self.telemetry.push(None);
+                self.sync_clock(now);
+            }
+        }
+        self.show_telemetry(ui, language);
+        leave
+    }
+
+    fn record(&self) -> GameRecord {
+        let metadata = GameMetadata {
+            red: self.red.engine.difficulty().name(Language::English).to_string(),
+            blue: self.blue.engine.difficulty().name(Language::English).to_string(),
+            ..Default::default()
+        };
+        GameRecord::from_game(&self.game, &metadata)
+            .with_move_times(self.clock.move_times())
+            .with_telemetry(&self.telemetry)
+    }
+
+    // Per-move search figures for the engines' moves, copyable for a spreadsheet.
+    fn show_telemetry(&self, ui: &mut egui::Ui, language: Language) {
+        egui::CollapsingHeader::new(tr(language, "watch.telemetry")).id_salt("watch_telemetry").show(ui, |ui| {
+            if self.telemetry.iter().all(Option::is_none) {
+                ui.label(tr(language, "watch.no_telemetry"));
+                return;
+            }
+            let record = self.record();
+            if ui.button(tr(language, "watch.copy_csv")).clicked() {
+                ui.ctx().copy_text(record.telemetry_csv());
+            }
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("watch_telemetry_grid").striped(true).show(ui, |ui| {
+                    for key in TELEMETRY_COLUMNS {
+                        ui.strong(tr(language, key));
+                    }
+                    ui.end_row();
+                    for (i, (mv, telemetry)) in record.moves.iter().zip(&record.telemetry).enumerate() {
+                        let Some(telemetry) = telemetry else {
+                            continue;
+                        };
+                        for cell in telemetry_row(i + 1, &mv.to_string(), telemetry) {
+                            ui.monospace(cell);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+    }
+}
+
+fn telemetry_row(number: usize, notation: &str, telemetry: &SearchTelemetry) -> [String; 6] {
+    [
+        format!("{}. {}", number, notation),
+        telemetry.nodes.to_string(),
+        format!("{:.0}", telemetry.nodes_per_second()),
+        telemetry.depth.to_string(),
+        if telemetry.ponder_hit { "✔" } else { "" }.to_string(),
+        telemetry.table_hit_rate.map(|rate| format!("{:.1}%", rate * 100.0)).unwrap_or_default(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui_test::UiHarness;
+    use coast_to_coast::board::Hex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pause_stops_the_clock() {
+        let start = Instant::now();
+        let ai = AiConfig::default();
+        let shared = EngineShared::new(&ai, std::sync::mpsc::sync_channel(1).0);
+        let mut session = WatchSession::new(3, Difficulty::Easy, &ai, &shared);
+        session.restart(start);
+        assert_eq!(session.clock.running(), Some(CellState::Red));
+        session.set_paused(true, start + Duration::from_secs(2));
+        assert_eq!(session.clock.running(), None);
+        assert_eq!(session.clock.used(CellState::Red, start + Duration::from_secs(60)), Duration::from_secs(2));
+        session.set_paused(false, start + Duration::from_secs(60));
+        assert_eq!(session.clock.running(), Some(CellState::Red));
+    }
+
+    #[test]
+    fn test_pie_rule_buttons_for_a_human_side() {
+        let ai = AiConfig::default();
+        let shared = EngineShared::new(&ai, std::sync::mpsc::sync_channel(1).0);
+        let mut session = WatchSession::new(3, Difficulty::Easy, &ai, &shared);
+        session.red.human = true;
+        session.blue.human = true;
+        let mut harness = UiHarness::new(600.0, 500.0);
+        let mut renderer = BoardRenderer::new(&harness.ctx);
+        let mut show = |ui: &mut egui::Ui| {
+            session.show(ui, &mut renderer, &ai, &shared, Language::English);
+        };
+        harness.run(&mut show);
+        harness.click("b2, empty", &mut show);
+        harness.click("Apply Pie Rule", &mut show);
+        harness.click("a1, empty", &mut show);
+        assert_eq!(session.game.history, ["b2", "swap", "a1"].map(|mv| mv.parse().unwrap()));
+        assert_eq!(session.game.board.get_cell(&Hex { q: 1, r: 1 }), Some(&CellState::Blue));
+        assert_eq!(session.telemetry, vec![None; 3]);
+    }
+
+    #[test]
+    fn test_telemetry_rows() {
+        let telemetry = SearchTelemetry {
+            nodes: 5_000,
+            elapsed: Duration::from_millis(250),
+            depth: 7,
+            ponder_hit: true,
+            table_hit_rate: Some(0.125),
+        };
+        assert_eq!(telemetry_row(3, "c5", &telemetry), ["3. c5", "5000", "20000", "7", "✔", "12.5%"].map(String::from));
+        let fresh = SearchTelemetry { ponder_hit: false, table_hit_rate: None, ..telemetry };
+        assert_eq!(telemetry_row(1, "a1", &fresh)[4..], [String::new(), String::new()]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use coast_to_coast::board::CellState;
+use coast_to_coast::record::{GameRecord, GameResult};
+use coast_to_coast::sgf;
+use ureq::serde_json::{json, Value};
+
+use crate::events::GameEvent;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+// Attempts per game, waiting 1s, 2s, 4s, ... between them.
+const ATTEMPTS: u32 = 5;
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+
+enum Command {
+    Url(String),
+    Post(Value),
+}
+
+// Posts every finished game as JSON to a user-configured URL, e.g. for a club leaderboard or a
+// chat bot. Posting happens on a worker thread, one game at a time, retrying with backoff.
+#[derive(Clone)]
+pub struct Webhook {
+    sender: Sender<Command>,
+    url: String,
+}
+
+impl Webhook {
+    pub fn start() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || run(receiver));
+        Self { sender, url: String::new() }
+    }
+
+    pub fn handle(&self, event: &GameEvent, record: &GameRecord) {
+        if let Some(result) = finished(event) {
+            let _ = self.sender.send(Command::Post(payload(record, result)));
+        }
+    }
+
+    // Cheap to call every frame; only changes reach the worker. An empty URL turns it off.
+    pub fn set_url(&mut self, url: &str) {
+        if url.trim() != self.url {
+            self.url = url.trim().to_string();
+            let _ = self.sender.send(Command::Url(self.url.clone()));
+        }
+    }
+}
+
+// How the game ended, for the events that end one. Adjourned games aren't over yet.
+fn finished(event: &GameEvent) -> Option<GameResult> {
+    match event {
+        GameEvent::GameFinished { winner } => Some(GameResult::Win(*winner)),
+        GameEvent::ResultAgreed { result: GameResult::Adjourned } => None,
+        GameEvent::ResultAgreed { result } => Some(*result),
+        _ => None,
+    }
+}
+
+// The record may not carry the result yet when the event is published.
+fn payload(record: &GameRecord, result: GameResult) -> Value {
+    let mut record = record.clone();
+    record.metadata.result = Some(result);
+    let metadata = &record.metadata;
+    let winner = match result {
+        GameResult::Win(winner) | GameResult::Resignation(winner) => match winner {
+            CellState::Red => Value::from("red"),
+            CellState::Blue => Value::from("blue"),
+            CellState::Empty | CellState::Blocked => Value::Null,
+        },
+        GameResult::Draw | GameResult::Adjourned => Value::Null,
+    };
+    json!({
+        "red": metadata.red,
+        "blue": metadata.blue,
+        "event": metadata.event,
+        "round": metadata.round,
+        "date": metadata.date,
+        "size": record.size,
+        "result": metadata.result_tag(),
+        "winner": winner,
+        "moves": record.moves.iter().map(|mv| mv.to_string()).collect::<Vec<_>>(),
+        "sgf": sgf::to_sgf(&record),
+    })
+}
+
+fn run(receiver: Receiver<Command>) {
+    let mut url = String::new();
+    for command in receiver {
+        match command {
+            Command::Url(new) => url = new,
+            Command::Post(_) if url.is_empty() => {}
+            Command::Post(payload) => post(&url, &payload.to_string()),
+        }
+    }
+}
+
+fn post(url: &str, body: &str) {
+    let mut backoff = FIRST_BACKOFF;
+    for attempt in 1..=ATTEMPTS {
+        let response = ureq::post(url).timeout(TIMEOUT).set("Content-Type", "application/json").send_string(body);
+        match response {
+            Ok(_) => {
+                tracing::info!(url, "game posted to the webhook");
+                return;
+            }
+            // The server understood and refused; sending the same body again won't help.
+            Err(ureq::Error::Status(code, _)) if (400..500).contains(&code) && code != 429 => {
+                tracing::warn!(url, code, "webhook refused the game");
+                return;
+            }
+            Err(e) => tracing::debug!(url, attempt, error = %e, "webhook post failed"),
+        }
+        if attempt < ATTEMPTS {
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+    tracing::warn!(url, attempts = ATTEMPTS, "gave up posting the game to the webhook");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coast_to_coast::game::Game;
+    use coast_to_coast::record::GameMetadata;
+
+    #[test]
+    fn test_payload_of_finished_game() {
+        let mut game = Game::with_size(3).with_pie_rule(false);
+        for mv in ["a1", "a2", "b1", "b2", "c1"] {
+            game.play_move(mv.parse().unwrap()).unwrap();
+        }
+        let metadata = GameMetadata { red: "Alice".to_string(), blue: "Bob".to_string(), ..Default::default() };
+        let record = GameRecord::from_game(&game, &metadata);
+        let event = GameEvent::GameFinished { winner: CellState::Red };
+        let payload = payload(&record, finished(&event).unwrap());
+        assert_eq!(payload["red"], "Alice");
+        assert_eq!(payload["result"], "Red");
+        assert_eq!(payload["winner"], "red");
+        assert_eq!(payload["moves"], json!(["a1", "a2", "b1", "b2", "c1"]));
+        assert!(payload["sgf"].as_str().unwrap().contains("RE[B+]"));
+        assert_eq!(finished(&GameEvent::ResultAgreed { result: GameResult::Adjourned }), None);
+    }
+}